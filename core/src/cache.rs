@@ -0,0 +1,167 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use directories_next::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+/// crates.io metadata of one crate, as kept in `CRATE_CACHE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CrateInfo {
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    /// Newest version, possibly a pre-release
+    pub max_version: Option<String>,
+    pub max_stable_version: Option<String>,
+    /// Unix time of the lookup, in seconds
+    pub fetched_at: u64,
+}
+
+/// crates.io lookups shared across runs, so opening the Installed page does
+/// not query crates.io for every installed crate each time.
+pub(crate) static CRATE_CACHE: LazyLock<CrateCache> = LazyLock::new(|| {
+    CrateCache::new(
+        ProjectDirs::from("com", "ayi", "updater")
+            .map(|dirs| dirs.cache_dir().join("crates_io.json")),
+    )
+});
+
+/// On-disk JSON map of crate name to `CrateInfo`. The file is read on first
+/// use and rewritten after every insert; without a path it only lives in
+/// memory.
+pub(crate) struct CrateCache {
+    path: Option<PathBuf>,
+    /// `None` until the file has been read.
+    entries: Mutex<Option<HashMap<String, CrateInfo>>>,
+    /// Serializes file writes so concurrent inserts never interleave.
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+impl CrateCache {
+    pub(crate) fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Mutex::new(None),
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Option<HashMap<String, CrateInfo>>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Info for `crate_name` looked up less than `ttl` ago.
+    pub(crate) async fn get(&self, crate_name: &str, ttl: Duration) -> Option<CrateInfo> {
+        self.load().await;
+
+        let entries = self.entries();
+        let info = entries.as_ref()?.get(crate_name)?;
+        (unix_now().saturating_sub(info.fetched_at) < ttl.as_secs()).then(|| info.clone())
+    }
+
+    pub(crate) async fn insert(&self, crate_name: &str, info: CrateInfo) {
+        self.load().await;
+        self.entries()
+            .get_or_insert_default()
+            .insert(crate_name.to_owned(), info);
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        let _write = self.write_lock.lock().await;
+        let serialized = {
+            let entries = self.entries();
+            serde_json::to_string(entries.as_ref().unwrap_or(&HashMap::new()))
+        };
+        let json = match serialized {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("Could not serialize the crates.io cache: {}", e);
+                return;
+            }
+        };
+        if let Some(dir) = path.parent()
+            && let Err(e) = tokio::fs::create_dir_all(dir).await
+        {
+            log::warn!("Could not create {}: {}", dir.display(), e);
+            return;
+        }
+        if let Err(e) = tokio::fs::write(path, json).await {
+            log::warn!("Could not write {}: {}", path.display(), e);
+        }
+    }
+
+    /// Forget every entry, in memory and on disk.
+    pub(crate) fn clear(&self) {
+        *self.entries() = Some(HashMap::new());
+
+        if let Some(path) = &self.path
+            && let Err(e) = std::fs::remove_file(path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            log::warn!("Could not remove {}: {}", path.display(), e);
+        }
+    }
+
+    /// Read the cache file once; a missing or unreadable file starts empty.
+    async fn load(&self) {
+        if self.entries().is_some() {
+            return;
+        }
+
+        let loaded = match &self.path {
+            Some(path) => tokio::fs::read_to_string(path)
+                .await
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            None => HashMap::new(),
+        };
+        self.entries().get_or_insert(loaded);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(fetched_at: u64) -> CrateInfo {
+        CrateInfo {
+            description: Some("Fast line-oriented regex search tool".to_owned()),
+            homepage: Some("https://github.com/BurntSushi/ripgrep".to_owned()),
+            max_version: Some("14.1.1".to_owned()),
+            max_stable_version: Some("14.1.1".to_owned()),
+            fetched_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn entries_survive_a_reload_until_they_expire() {
+        let path =
+            std::env::temp_dir().join(format!("updater-crate-cache-{}.json", std::process::id()));
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = unix_now();
+
+        let cache = CrateCache::new(Some(path.clone()));
+        cache.insert("ripgrep", info(now)).await;
+        cache.insert("bat", info(now - 2 * day.as_secs())).await;
+
+        let reloaded = CrateCache::new(Some(path.clone()));
+        assert_eq!(reloaded.get("ripgrep", day).await, Some(info(now)));
+        assert_eq!(reloaded.get("bat", day).await, None);
+        assert_eq!(reloaded.get("fd-find", day).await, None);
+
+        reloaded.clear();
+        assert_eq!(reloaded.get("ripgrep", day).await, None);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use tokio::sync::Notify;
+
+tokio::task_local! {
+    static CURRENT: CancelToken;
+}
+
+/// Cooperative cancellation of package operations. Clones share one state,
+/// so the UI keeps a clone and calls `cancel` while the operation runs.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    state: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the operation: the running command is killed and the operation
+    /// returns `CoreError::Cancelled`. Commands not yet started never run.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once `cancel` has been called.
+    pub async fn cancelled(&self) {
+        let notified = self.state.notify.notified();
+        tokio::pin!(notified);
+        // Register before checking the flag so a `cancel` in between is not missed
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Run `operation` so that the commands it spawns are killed when `token`
+/// is cancelled. Without a token, operations run to completion.
+pub async fn with_cancel_token<F: Future>(token: CancelToken, operation: F) -> F::Output {
+    CURRENT.scope(token, operation).await
+}
+
+/// Token of the enclosing `with_cancel_token`, if any.
+pub(crate) fn current_token() -> Option<CancelToken> {
+    CURRENT.try_with(CancelToken::clone).ok()
+}
+
+/// Resolves when `token` is cancelled; never without a token.
+pub(crate) async fn cancelled(token: Option<&CancelToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
@@ -5,6 +5,11 @@ pub enum CoreError {
     #[error("Failed to execute command: {0}")]
     CommandError(String),
 
+    /// The program could not be started because it does not exist, e.g. a
+    /// manager uninstalled after it was configured
+    #[error("Executable not found: {0}")]
+    ExecutableNotFound(String),
+
     #[error("Invalid UTF-8 output: {0}")]
     Utf8Error(String),
 
@@ -19,6 +24,25 @@ pub enum CoreError {
 
     #[error("request error: {0}")]
     RequestError(String),
+
+    #[error("Binary directory unavailable: {0}")]
+    BinDirError(String),
+
+    #[error("Could not get administrator rights: {0}")]
+    PrivilegeError(String),
+
+    #[error("Repository metadata unavailable: {0}")]
+    MetadataError(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[cfg(feature = "sqlite-index")]
+    #[error("Package index error: {0}")]
+    IndexError(String),
 }
 
 impl From<reqwest::Error> for CoreError {
@@ -44,3 +68,10 @@ impl From<serde_json::Error> for CoreError {
         CoreError::SerializationError(e.to_string())
     }
 }
+
+#[cfg(feature = "sqlite-index")]
+impl From<rusqlite::Error> for CoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        CoreError::IndexError(e.to_string())
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::{PackageManagerType, PackageUpdate};
+
+/// Download rate assumed when a manager reports download sizes.
+const ASSUMED_BYTES_PER_SEC: u64 = 5 * 1024 * 1024;
+
+/// Typical seconds per package, excluding downloads the manager reports.
+/// Cargo and Go build from source; system managers install prebuilt packages
+/// in one transaction.
+fn seconds_per_package(manager: PackageManagerType) -> u64 {
+    match manager {
+        PackageManagerType::Cargo => 180,
+        PackageManagerType::Go => 30,
+        PackageManagerType::Flatpak | PackageManagerType::Snap | PackageManagerType::Homebrew => 20,
+        PackageManagerType::Chocolatey => 15,
+        PackageManagerType::Npm
+        | PackageManagerType::Pnpm
+        | PackageManagerType::Pip
+        | PackageManagerType::Pipx => 8,
+        PackageManagerType::Apt
+        | PackageManagerType::Dnf
+        | PackageManagerType::Pacman
+        | PackageManagerType::Zypper => 3,
+    }
+}
+
+/// Fixed cost of one manager run, e.g. loading repository metadata.
+fn seconds_per_run(manager: PackageManagerType) -> u64 {
+    if manager.is_system_manager() { 20 } else { 2 }
+}
+
+/// Rough time for `manager` to update `updates`, from the package count and
+/// the download sizes the manager reported. Only meant to set expectations.
+pub fn estimate_update_time(manager: PackageManagerType, updates: &[&PackageUpdate]) -> Duration {
+    if updates.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let install_secs = seconds_per_run(manager)
+        + seconds_per_package(manager).saturating_mul(updates.len() as u64);
+    let download_bytes: u64 = updates
+        .iter()
+        .filter_map(|update| update.download_size)
+        .sum();
+
+    Duration::from_secs(install_secs + download_bytes / ASSUMED_BYTES_PER_SEC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(name: &str, download_size: Option<u64>) -> PackageUpdate {
+        PackageUpdate {
+            name: name.to_owned(),
+            current_version: "1.0".to_owned(),
+            new_version: "1.1".to_owned(),
+            security: false,
+            download_size,
+        }
+    }
+
+    #[test]
+    fn compiling_managers_are_estimated_slower() {
+        let updates = [update("ripgrep", None), update("bat", None)];
+        let updates: Vec<&PackageUpdate> = updates.iter().collect();
+
+        let cargo = estimate_update_time(PackageManagerType::Cargo, &updates);
+        let dnf = estimate_update_time(PackageManagerType::Dnf, &updates);
+
+        assert!(cargo > dnf);
+        assert_eq!(
+            estimate_update_time(PackageManagerType::Cargo, &[]),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn reported_download_sizes_add_to_the_estimate() {
+        let small = update("org.gnome.Calculator", Some(0));
+        let large = update("org.gnome.Calculator", Some(600 * 1024 * 1024));
+
+        let small = estimate_update_time(PackageManagerType::Flatpak, &[&small]);
+        let large = estimate_update_time(PackageManagerType::Flatpak, &[&large]);
+
+        assert_eq!(large - small, Duration::from_secs(120));
+    }
+}
@@ -0,0 +1,448 @@
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+
+use crate::{Config, CoreResult, cache::CRATE_CACHE};
+
+/// User-Agent sent to crates.io and PyPI unless `Config::user_agent` overrides
+/// it; crates.io's crawler policy asks for a name, version and contact.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "PackageGet/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Yiki21/PackageGet)"
+);
+
+/// The `Config` fields a client is built from.
+#[derive(Debug, Clone, PartialEq)]
+struct ClientSettings {
+    user_agent: String,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+}
+
+impl ClientSettings {
+    fn new(config: &Config) -> Self {
+        let user_agent = config
+            .user_agent
+            .as_deref()
+            .map(str::trim)
+            .filter(|user_agent| !user_agent.is_empty())
+            .unwrap_or(DEFAULT_USER_AGENT);
+        let proxy = config
+            .http_proxy
+            .as_deref()
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty());
+
+        Self {
+            user_agent: user_agent.to_owned(),
+            timeout: (config.http_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.http_timeout_secs)),
+            proxy: proxy.map(str::to_owned),
+        }
+    }
+
+    /// Client builder with the User-Agent, `Config::http_timeout_secs` and
+    /// `Config::http_proxy` applied.
+    fn builder(&self) -> reqwest::ClientBuilder {
+        let mut builder = reqwest::Client::builder().user_agent(&self.user_agent);
+        if let Some(timeout) = self.timeout {
+            builder = builder.connect_timeout(timeout).timeout(timeout);
+        }
+        // Without an override reqwest follows HTTP_PROXY/HTTPS_PROXY/NO_PROXY
+        if let Some(proxy) = &self.proxy {
+            match reqwest::Proxy::all(proxy) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => log::warn!("Ignoring invalid HTTP proxy {:?}: {}", proxy, e),
+            }
+        }
+        builder
+    }
+}
+
+/// Client every outgoing API call uses, kept while the settings it was built
+/// from stay the same so its connection pool is reused across lookups.
+static SHARED_CLIENT: Mutex<Option<(ClientSettings, reqwest::Client)>> = Mutex::new(None);
+
+/// The shared client for `config`, rebuilt only after its HTTP settings change.
+pub(crate) fn shared_client(config: &Config) -> CoreResult<reqwest::Client> {
+    let settings = ClientSettings::new(config);
+    let mut shared = SHARED_CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some((built_from, client)) = shared.as_ref()
+        && *built_from == settings
+    {
+        return Ok(client.clone());
+    }
+
+    let client = settings.builder().build()?;
+    *shared = Some((settings, client.clone()));
+    Ok(client)
+}
+
+/// Wait before the first retry of `get_with_retry`, doubled for each further one.
+const RETRY_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// GET `url`, retried up to `Config::http_retries` times when the connection
+/// fails or times out or the server answers 5xx. Any other answer, 404
+/// included, is returned at once, as is the last one once retries run out.
+pub(crate) async fn get_with_retry<U>(
+    config: &Config,
+    client: &reqwest::Client,
+    url: U,
+) -> CoreResult<reqwest::Response>
+where
+    U: reqwest::IntoUrl + Clone,
+{
+    send_with_retry(
+        || client.get(url.clone()),
+        config.http_retries,
+        RETRY_INITIAL_DELAY,
+    )
+    .await
+}
+
+async fn send_with_retry(
+    request: impl Fn() -> reqwest::RequestBuilder,
+    retries: u32,
+    initial_delay: Duration,
+) -> CoreResult<reqwest::Response> {
+    let mut delay = initial_delay;
+    let mut attempt = 0;
+
+    loop {
+        let out_of_retries = attempt >= retries;
+        match request().send().await {
+            Ok(resp) if out_of_retries || !resp.status().is_server_error() => return Ok(resp),
+            Ok(resp) => log::debug!(
+                "{} answered {}, retrying in {:?}",
+                resp.url(),
+                resp.status(),
+                delay
+            ),
+            Err(e) if out_of_retries || !is_transient(&e) => return Err(e.into()),
+            Err(e) => log::debug!("Request failed ({}), retrying in {:?}", e, delay),
+        }
+
+        tokio::time::sleep(delay).await;
+        delay = delay.saturating_mul(2);
+        attempt += 1;
+    }
+}
+
+/// Connection failures and timeouts, which a later attempt may not hit.
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
+/// Session cache of crates.io `/api/v1/crates/{name}` responses, shared by
+/// `list_installed` and `list_updates` so one run fetches each crate once.
+pub(crate) static CRATES_IO_CACHE: LazyLock<JsonCache> =
+    LazyLock::new(|| JsonCache::new(256, Duration::from_secs(5 * 60)));
+
+/// Drop cached API responses, including the on-disk crates.io cache, so
+/// the next lookups hit the network.
+pub fn clear_api_cache() {
+    CRATES_IO_CACHE.clear();
+    CRATE_CACHE.clear();
+}
+
+/// Bounded LRU of parsed JSON responses that expire after `ttl`.
+pub(crate) struct JsonCache {
+    entries: Mutex<LruCache<String, (Instant, serde_json::Value)>>,
+    ttl: Duration,
+}
+
+impl JsonCache {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        match entries.get(key) {
+            Some((fetched_at, value)) if fetched_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    fn insert(&self, key: &str, value: serde_json::Value) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .put(key.to_owned(), (Instant::now(), value));
+    }
+
+    /// Cached value for `key`, or the result of `fetch`. Only `Ok(Some(_))`
+    /// is cached, so missing entries and errors are retried next time.
+    /// The lock is not held while fetching; concurrent misses may both fetch.
+    pub(crate) async fn get_or_fetch<F, Fut>(
+        &self,
+        key: &str,
+        fetch: F,
+    ) -> CoreResult<Option<serde_json::Value>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = CoreResult<Option<serde_json::Value>>>,
+    {
+        if let Some(value) = self.get(key) {
+            return Ok(Some(value));
+        }
+
+        let value = fetch().await?;
+        if let Some(value) = &value {
+            self.insert(key, value.clone());
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    async fn lookup(cache: &JsonCache, key: &str, requests: &AtomicUsize) -> serde_json::Value {
+        cache
+            .get_or_fetch(key, || async {
+                requests.fetch_add(1, Ordering::SeqCst);
+                Ok(Some(serde_json::json!({ "crate": { "name": key } })))
+            })
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn second_lookup_within_ttl_is_served_from_cache() {
+        let cache = JsonCache::new(8, Duration::from_secs(60));
+        let requests = AtomicUsize::new(0);
+
+        let first = lookup(&cache, "ripgrep", &requests).await;
+        let second = lookup(&cache, "ripgrep", &requests).await;
+
+        assert_eq!(first, second);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_and_evicted_entries_are_fetched_again() {
+        let requests = AtomicUsize::new(0);
+
+        let expired = JsonCache::new(8, Duration::ZERO);
+        lookup(&expired, "ripgrep", &requests).await;
+        lookup(&expired, "ripgrep", &requests).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let small = JsonCache::new(1, Duration::from_secs(60));
+        lookup(&small, "ripgrep", &requests).await;
+        lookup(&small, "bat", &requests).await;
+        lookup(&small, "ripgrep", &requests).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+    }
+
+    /// Headers of one request sent by a client built from `config`'s `ClientSettings`.
+    async fn captured_request(config: &Config) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(accept_one(listener));
+
+        let client = ClientSettings::new(config)
+            .builder()
+            .no_proxy()
+            .build()
+            .unwrap();
+        client.get(url).send().await.unwrap();
+        server.await.unwrap()
+    }
+
+    /// Request head of the first connection to `listener`, answered with 204.
+    async fn accept_one(listener: tokio::net::TcpListener) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        answer(&mut socket, 204).await
+    }
+
+    /// Request head read from `socket`, answered with an empty `status` response.
+    async fn answer(socket: &mut tokio::net::TcpStream, status: u16) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let read = socket.read(&mut buf).await.unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..read]);
+        }
+        let response = format!(
+            "HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            status
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    /// URL of a server answering its requests with `statuses` in order, and
+    /// the number of requests it has received.
+    async fn status_server(statuses: &'static [u16]) -> (String, std::sync::Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let received = std::sync::Arc::new(AtomicUsize::new(0));
+
+        let counter = received.clone();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                answer(&mut socket, *status).await;
+            }
+        });
+
+        (url, received)
+    }
+
+    async fn get_retrying(url: &str, retries: u32) -> CoreResult<reqwest::Response> {
+        let client = ClientSettings::new(&Config::default())
+            .builder()
+            .no_proxy()
+            .build()
+            .unwrap();
+        send_with_retry(|| client.get(url), retries, Duration::from_millis(1)).await
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried_until_success() {
+        let (url, received) = status_server(&[503, 502, 200]).await;
+
+        let resp = get_retrying(&url, 3).await.unwrap();
+
+        assert_eq!(resp.status(), 200);
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn last_server_error_is_returned_once_retries_run_out() {
+        let (url, received) = status_server(&[503, 503, 200]).await;
+
+        let resp = get_retrying(&url, 1).await.unwrap();
+
+        assert_eq!(resp.status(), 503);
+        assert_eq!(received.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn not_found_is_returned_without_retrying() {
+        let (url, received) = status_server(&[404, 200]).await;
+
+        let resp = get_retrying(&url, 3).await.unwrap();
+
+        assert_eq!(resp.status(), 404);
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn connection_errors_fail_after_retries() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        assert!(get_retrying(&url, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn configured_proxy_receives_requests() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = Config {
+            http_proxy: Some(format!("http://{}", listener.local_addr().unwrap())),
+            ..Config::default()
+        };
+        let server = tokio::spawn(accept_one(listener));
+
+        let client = shared_client(&config).unwrap();
+        client
+            .get("http://crates.io.invalid/api/v1/crates/ripgrep")
+            .send()
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET http://crates.io.invalid/api/v1/crates/ripgrep "));
+    }
+
+    #[test]
+    fn settings_follow_timeout_and_blank_overrides() {
+        let settings = ClientSettings::new(&Config::default());
+        assert_eq!(settings.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(settings.proxy, None);
+
+        let config = Config {
+            http_timeout_secs: 0,
+            http_proxy: Some("  ".to_owned()),
+            ..Config::default()
+        };
+        let settings = ClientSettings::new(&config);
+        assert_eq!(settings.timeout, None);
+        assert_eq!(settings.proxy, None);
+    }
+
+    #[tokio::test]
+    async fn requests_carry_default_or_configured_user_agent() {
+        let request = captured_request(&Config::default()).await.to_lowercase();
+        assert!(DEFAULT_USER_AGENT.contains("Yiki21/PackageGet"));
+        assert!(request.contains(&format!(
+            "user-agent: {}\r\n",
+            DEFAULT_USER_AGENT.to_lowercase()
+        )));
+
+        let config = Config {
+            user_agent: Some("my-mirror/1.0 (ops@example.com)".to_owned()),
+            ..Config::default()
+        };
+        let request = captured_request(&config).await;
+        assert!(request.contains("my-mirror/1.0 (ops@example.com)\r\n"));
+    }
+
+    #[tokio::test]
+    async fn missing_entries_are_not_cached() {
+        let cache = JsonCache::new(8, Duration::from_secs(60));
+        let requests = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            let value = cache
+                .get_or_fetch("missing", || async {
+                    requests.fetch_add(1, Ordering::SeqCst);
+                    Ok(None)
+                })
+                .await
+                .unwrap();
+            assert!(value.is_none());
+        }
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
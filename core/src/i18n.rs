@@ -0,0 +1,361 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// UI language, stored in `Config::language`. English is the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    English,
+    Chinese,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::English, Lang::Chinese];
+
+    /// Language name in its own language, for the selector.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lang::English => "English",
+            Lang::Chinese => "中文",
+        }
+    }
+}
+
+/// User-facing strings that have translations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    TabFinding,
+    TabUpdates,
+    TabInstalled,
+    TabSettings,
+    InProgress,
+    SettingsTitle,
+    SystemManager,
+    AppManager,
+    AddOtherManager,
+    Installation,
+    IncludePrereleases,
+    PreferBinstall,
+    HideDnfNoise,
+    Diagnostics,
+    InventorySnapshots,
+    PackageManifest,
+    HomebrewBundle,
+    TransactionHistory,
+    Language,
+    Appearance,
+    ColorStandard,
+    ColorHighContrast,
+    ListDensity,
+    DensityComfortable,
+    DensityCompact,
+    SortBy,
+    GroupBy,
+    GroupByManager,
+    GroupByFlat,
+    FilterManagers,
+    HideEmptyManagers,
+    RefreshSelected,
+    RefreshAll,
+    RefreshEverything,
+    Refreshing,
+    QuitWhileRunning,
+    KeepRunning,
+    QuitAnyway,
+    Cancel,
+    Cancelling,
+    OperationCancelled,
+    Confirm,
+    Close,
+    Loading,
+    CommandOutput,
+    LoadingPackageInfo,
+    WaitingPackageInfo,
+    SelectManagerToView,
+    NoSearchMatches,
+    PackageCount,
+    LoadInstalledFailed,
+    ConfirmRemovePackages,
+    DetailVersion,
+    DetailDescription,
+    DetailSize,
+    DetailInstalled,
+    DetailHomepage,
+    NoDescription,
+    Unknown,
+    NoHomepage,
+    LoadingDetails,
+    LoadDetailsFailed,
+    OpenHomepageFailed,
+    NotDetected,
+    PathValue,
+    PathSystemDefault,
+    RunAsAdminWith,
+    PrivilegePkexec,
+    PrivilegeSudoAskpass,
+    SudoAskpassHint,
+    NoAppManagers,
+    Unload,
+    ScanningPath,
+    ScanPathTip,
+    ScanPath,
+    SaveConfiguration,
+    Saving,
+    OpenConfigFolder,
+    DetectedManagers,
+    AllManagersAdded,
+    Add,
+    SelectPath,
+    DetectedInPath,
+    ExtraArguments,
+    GoBinAutoDetect,
+    GoBinDir,
+    ChooseBinaryDir,
+    AddAnotherDir,
+    ResetAutoDetect,
+    AlsoScanned,
+    Remove,
+    Off,
+    Never,
+    Hours,
+    Minutes,
+    Clear,
+    LeaveOutOfUpdateAll,
+    ParallelInstalls,
+    ConcurrencyHonoredBy,
+    CrateCacheTtl,
+    ConfirmThreshold,
+    ConfirmSystemAlways,
+    SystemManagerReadonly,
+    ConfirmCloseDuringOperation,
+    DryRun,
+    IdleTimeout,
+    IdleTimeoutHint,
+    ExtraPath,
+    ExtraPathHint,
+}
+
+/// Translate `key` into `lang`.
+pub fn tr(lang: Lang, key: Key) -> &'static str {
+    let (english, chinese) = match key {
+        Key::TabFinding => ("Finding", "查找"),
+        Key::TabUpdates => ("Updates", "更新"),
+        Key::TabInstalled => ("Installed", "已安装"),
+        Key::TabSettings => ("Settings", "设置"),
+        Key::InProgress => ("In progress", "进行中"),
+        Key::SettingsTitle => ("Package Manager Settings", "包管理器设置"),
+        Key::SystemManager => ("System Package Manager", "系统包管理器"),
+        Key::AppManager => ("App Package Manager", "应用包管理器"),
+        Key::AddOtherManager => ("Add Other Package Manager", "添加其他包管理器"),
+        Key::Installation => ("Installation", "安装"),
+        Key::IncludePrereleases => (
+            "Include pre-release versions in Cargo and Go updates",
+            "Cargo 和 Go 更新中包含预发布版本",
+        ),
+        Key::PreferBinstall => (
+            "Use cargo-binstall prebuilt binaries when available",
+            "可用时使用 cargo-binstall 预编译二进制",
+        ),
+        Key::HideDnfNoise => (
+            "Hide -debuginfo, -debugsource and -devel packages",
+            "隐藏 -debuginfo、-debugsource 和 -devel 包",
+        ),
+        Key::Diagnostics => ("Diagnostics", "诊断"),
+        Key::InventorySnapshots => ("Inventory Snapshots", "已安装包快照"),
+        Key::PackageManifest => ("Package Manifest", "软件包清单"),
+        Key::HomebrewBundle => ("Homebrew Bundle", "Homebrew Bundle 清单"),
+        Key::TransactionHistory => ("DNF History", "DNF 事务历史"),
+        Key::Language => ("Language", "语言"),
+        Key::Appearance => ("Appearance", "外观"),
+        Key::ColorStandard => ("Standard", "标准"),
+        Key::ColorHighContrast => ("High contrast", "高对比度"),
+        Key::ListDensity => ("List density", "列表密度"),
+        Key::DensityComfortable => ("Comfortable", "宽松"),
+        Key::DensityCompact => ("Compact", "紧凑"),
+        Key::SortBy => ("Sort By", "排序"),
+        Key::GroupBy => ("Group By", "分组"),
+        Key::GroupByManager => ("Manager", "按包管理器"),
+        Key::GroupByFlat => ("Flat", "不分组"),
+        Key::FilterManagers => ("Filter Package Managers", "筛选包管理器"),
+        Key::HideEmptyManagers => ("Hide managers with a count of 0", "隐藏数量为 0 的包管理器"),
+        Key::RefreshSelected => ("Refresh Selected", "刷新所选"),
+        Key::RefreshAll => ("Refresh All", "全部刷新"),
+        Key::RefreshEverything => ("Refresh all managers", "刷新全部包管理器"),
+        Key::Refreshing => ("Refreshing", "正在刷新"),
+        Key::QuitWhileRunning => (
+            "An operation is running ({}) — quit anyway?",
+            "有操作正在进行（{}），仍要退出吗？",
+        ),
+        Key::KeepRunning => ("Keep running", "继续运行"),
+        Key::QuitAnyway => ("Quit anyway", "仍要退出"),
+        Key::Cancel => ("Cancel", "取消"),
+        Key::Cancelling => ("Cancelling...", "正在取消..."),
+        Key::OperationCancelled => ("Operation cancelled", "操作已取消"),
+        Key::Confirm => ("Confirm", "确认"),
+        Key::Close => ("Close", "关闭"),
+        Key::Loading => ("Loading", "加载中"),
+        Key::CommandOutput => ("Command Output", "命令输出"),
+        Key::LoadingPackageInfo => ("Loading package information", "正在加载包信息"),
+        Key::WaitingPackageInfo => ("Waiting to load package information", "等待加载包信息"),
+        Key::SelectManagerToView => (
+            "Please select a package manager to view",
+            "请选择要查看的包管理器",
+        ),
+        Key::NoSearchMatches => ("No packages match your search", "没有匹配搜索的包"),
+        Key::PackageCount => ("{} packages", "{} 个包"),
+        Key::LoadInstalledFailed => ("Failed to load installed packages", "加载已安装的包失败"),
+        Key::ConfirmRemovePackages => (
+            "Remove {} package(s) from {}?{}",
+            "移除 {} 个包（来自 {}）？{}",
+        ),
+        Key::DetailVersion => ("Version", "版本"),
+        Key::DetailDescription => ("Description", "描述"),
+        Key::DetailSize => ("Size", "大小"),
+        Key::DetailInstalled => ("Installed", "安装时间"),
+        Key::DetailHomepage => ("Homepage", "主页"),
+        Key::NoDescription => ("No description available", "暂无描述"),
+        Key::Unknown => ("Unknown", "未知"),
+        Key::NoHomepage => ("None", "无"),
+        Key::LoadingDetails => ("Loading details", "正在加载详情"),
+        Key::LoadDetailsFailed => ("Failed to load details: {}", "加载详情失败：{}"),
+        Key::OpenHomepageFailed => ("Could not open the homepage: {}", "无法打开主页：{}"),
+        Key::NotDetected => ("Not detected", "未检测到"),
+        Key::PathValue => ("Path: {}", "路径：{}"),
+        Key::PathSystemDefault => ("Path: $PATH (System Default)", "路径：$PATH（系统默认）"),
+        Key::RunAsAdminWith => ("Run as administrator with", "以管理员身份运行时使用"),
+        Key::PrivilegePkexec => (
+            "pkexec (polkit authentication agent)",
+            "pkexec（polkit 认证代理）",
+        ),
+        Key::PrivilegeSudoAskpass => ("sudo -A (askpass helper)", "sudo -A（askpass 辅助程序）"),
+        Key::SudoAskpassHint => (
+            "Leave empty to use SUDO_ASKPASS from the environment.",
+            "留空则使用环境变量中的 SUDO_ASKPASS。",
+        ),
+        Key::NoAppManagers => (
+            "No application package managers in UI management",
+            "界面中尚未管理任何应用包管理器",
+        ),
+        Key::Unload => ("Unload", "移出"),
+        Key::ScanningPath => ("Scanning $PATH...", "正在扫描 $PATH..."),
+        Key::ScanPathTip => (
+            "Click \"Scan $PATH\" to discover available managers.",
+            "点击“扫描 $PATH”查找可用的包管理器。",
+        ),
+        Key::ScanPath => ("Scan $PATH", "扫描 $PATH"),
+        Key::SaveConfiguration => ("Save Configuration", "保存配置"),
+        Key::Saving => ("Saving...", "正在保存..."),
+        Key::OpenConfigFolder => ("Open Config Folder", "打开配置目录"),
+        Key::DetectedManagers => (
+            "Detected {} manager(s) in $PATH. They will only be added after manual Add.",
+            "在 $PATH 中检测到 {} 个包管理器，手动添加后才会启用。",
+        ),
+        Key::AllManagersAdded => (
+            "All available package managers have been added",
+            "所有可用的包管理器都已添加",
+        ),
+        Key::Add => ("Add", "添加"),
+        Key::SelectPath => ("Select Path", "选择路径"),
+        Key::DetectedInPath => (
+            "Detected in $PATH. Click Add to use system default path.",
+            "已在 $PATH 中检测到，点击“添加”使用系统默认路径。",
+        ),
+        Key::ExtraArguments => ("Extra arguments", "额外参数"),
+        Key::GoBinAutoDetect => (
+            "Auto Detect (GOBIN, GOPATH/bin and ~/go/bin)",
+            "自动检测（GOBIN、GOPATH/bin 和 ~/go/bin）",
+        ),
+        Key::GoBinDir => ("Binary Dir:", "二进制目录："),
+        Key::ChooseBinaryDir => ("Choose Binary Dir", "选择二进制目录"),
+        Key::AddAnotherDir => ("Add Another Dir", "添加其他目录"),
+        Key::ResetAutoDetect => ("Reset As Auto Detect", "重置为自动检测"),
+        Key::AlsoScanned => ("Also scanned: {}", "同时扫描：{}"),
+        Key::Remove => ("Remove", "移除"),
+        Key::Off => ("Off", "关闭"),
+        Key::Never => ("Never", "从不"),
+        Key::Hours => ("{} h", "{} 小时"),
+        Key::Minutes => ("{} min", "{} 分钟"),
+        Key::Clear => ("Clear", "清除"),
+        Key::LeaveOutOfUpdateAll => ("Leave {} out of Update All", "“全部更新”时跳过 {}"),
+        Key::ParallelInstalls => ("Parallel installs", "并行安装数"),
+        Key::ConcurrencyHonoredBy => (
+            "Only used by {}; other managers install one package at a time.",
+            "仅 {} 使用；其他包管理器一次安装一个包。",
+        ),
+        Key::CrateCacheTtl => ("Reuse crates.io info for", "crates.io 信息复用时长"),
+        Key::ConfirmThreshold => ("Confirm removing at least", "移除包数达到此值时确认"),
+        Key::ConfirmSystemAlways => (
+            "Always confirm removing system packages",
+            "移除系统包时总是确认",
+        ),
+        Key::SystemManagerReadonly => (
+            "Read-only system manager (browse and search only)",
+            "系统包管理器只读（仅浏览和搜索）",
+        ),
+        Key::ConfirmCloseDuringOperation => (
+            "Ask before quitting while an operation is running",
+            "有操作进行时退出前询问",
+        ),
+        Key::DryRun => (
+            "Show the exact commands before installing, updating or removing",
+            "安装、更新或移除前显示将执行的命令",
+        ),
+        Key::IdleTimeout => ("Stop commands silent for", "命令无输出多久后停止"),
+        Key::IdleTimeoutHint => (
+            "A command that prints nothing this long is usually waiting for input.",
+            "命令长时间无输出通常是在等待输入。",
+        ),
+        Key::ExtraPath => ("Extra PATH directories", "额外的 PATH 目录"),
+        Key::ExtraPathHint => (
+            "Comma-separated, searched before the inherited PATH when running managers.",
+            "以逗号分隔，运行包管理器时先于继承的 PATH 搜索。",
+        ),
+    };
+
+    match lang {
+        Lang::English => english,
+        Lang::Chinese => chinese,
+    }
+}
+
+/// `tr(lang, key)` with each `{}` replaced by the next of `args`.
+pub fn tr_args(lang: Lang, key: Key, args: &[&dyn Display]) -> String {
+    let mut parts = tr(lang, key).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_owned();
+    let mut args = args.iter();
+
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_language_is_english() {
+        assert_eq!(Lang::default(), Lang::English);
+        assert_eq!(tr(Lang::default(), Key::TabSettings), "Settings");
+        assert_eq!(tr(Lang::Chinese, Key::TabSettings), "设置");
+    }
+
+    #[test]
+    fn tr_args_fills_placeholders_in_order() {
+        assert_eq!(
+            tr_args(
+                Lang::English,
+                Key::ConfirmRemovePackages,
+                &[&3, &"DNF", &""]
+            ),
+            "Remove 3 package(s) from DNF?"
+        );
+        assert_eq!(tr_args(Lang::Chinese, Key::PackageCount, &[&12]), "12 个包");
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use rusqlite::{Connection, params};
+
+use crate::{ALL_PACKAGE_MANAGERS, Config, CoreResult, Inventory, PackageInfo, PackageManagerType};
+
+/// Installed packages of every manager in SQLite, rebuilt per manager on
+/// refresh. Search goes through an FTS5 trigram table, so substring queries
+/// stay fast with thousands of packages.
+#[derive(Debug)]
+pub struct PackageIndex {
+    conn: Connection,
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS packages (
+        manager TEXT NOT NULL,
+        name TEXT NOT NULL,
+        version TEXT NOT NULL,
+        description TEXT,
+        PRIMARY KEY (manager, name)
+    );
+    CREATE VIRTUAL TABLE IF NOT EXISTS packages_fts USING fts5(
+        manager UNINDEXED,
+        name,
+        description,
+        tokenize = 'trigram'
+    );
+";
+
+impl PackageIndex {
+    /// `packages.sqlite3` next to the config file.
+    pub fn default_path() -> CoreResult<PathBuf> {
+        Ok(Config::config_dir()?.join("packages.sqlite3"))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> CoreResult<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> CoreResult<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> CoreResult<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Replace everything indexed for `manager` with `packages`.
+    pub fn replace_manager(
+        &mut self,
+        manager: PackageManagerType,
+        packages: &[PackageInfo],
+    ) -> CoreResult<()> {
+        let key = manager_key(manager);
+        let tx = self.conn.transaction()?;
+
+        tx.execute("DELETE FROM packages WHERE manager = ?1", params![key])?;
+        tx.execute("DELETE FROM packages_fts WHERE manager = ?1", params![key])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO packages (manager, name, version, description)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            let mut insert_fts = tx.prepare(
+                "INSERT INTO packages_fts (manager, name, description) VALUES (?1, ?2, ?3)",
+            )?;
+
+            for package in packages {
+                insert.execute(params![
+                    key,
+                    package.name,
+                    package.version,
+                    package.description
+                ])?;
+                insert_fts.execute(params![key, package.name, package.description])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Packages whose name or description contains `query`, ignoring case.
+    pub fn search(&self, query: &str) -> CoreResult<Vec<(PackageManagerType, String)>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // trigram 分词器只能匹配至少 3 个字符，更短的查询退回 LIKE
+        let (sql, pattern) = if query.chars().count() >= 3 {
+            (
+                "SELECT manager, name FROM packages_fts WHERE packages_fts MATCH ?1",
+                format!("\"{}\"", query.replace('"', "\"\"")),
+            )
+        } else {
+            (
+                "SELECT manager, name FROM packages_fts \
+                 WHERE name LIKE ?1 ESCAPE '\\' OR description LIKE ?1 ESCAPE '\\'",
+                format!("%{}%", escape_like(query)),
+            )
+        };
+
+        let mut statement = self.conn.prepare(sql)?;
+        let rows = statement.query_map(params![pattern], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut matches = Vec::new();
+        for row in rows {
+            let (key, name) = row?;
+            if let Some(manager) = manager_from_key(&key) {
+                matches.push((manager, name));
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Indexed packages as an `Inventory`, to diff against snapshots.
+    pub fn inventory(&self) -> CoreResult<Inventory> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT manager, name, version FROM packages")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut managers: BTreeMap<PackageManagerType, BTreeMap<String, String>> = BTreeMap::new();
+        for row in rows {
+            let (key, name, version) = row?;
+            if let Some(manager) = manager_from_key(&key) {
+                managers.entry(manager).or_default().insert(name, version);
+            }
+        }
+
+        Ok(Inventory {
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            managers,
+        })
+    }
+}
+
+fn manager_key(manager: PackageManagerType) -> String {
+    format!("{:?}", manager)
+}
+
+fn manager_from_key(key: &str) -> Option<PackageManagerType> {
+    ALL_PACKAGE_MANAGERS
+        .iter()
+        .copied()
+        .find(|manager| manager_key(*manager) == key)
+}
+
+/// `text` with the `LIKE` wildcards `%` and `_`, and the `\` escape itself,
+/// escaped so they match literally.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, version: &str, description: Option<&str>) -> PackageInfo {
+        PackageInfo {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            source: PackageManagerType::Dnf,
+            description: description.map(str::to_owned),
+            size: None,
+            install_date: None,
+            homepage: None,
+            popularity: None,
+            has_update_info: true,
+            eol: None,
+        }
+    }
+
+    #[test]
+    fn search_matches_substrings_of_names_and_descriptions() {
+        let mut index = PackageIndex::open_in_memory().unwrap();
+        index
+            .replace_manager(
+                PackageManagerType::Dnf,
+                &[
+                    package("glibc", "2.39-5.fc40", Some("The GNU libc libraries")),
+                    package(
+                        "vim-enhanced",
+                        "9.1.0-1.fc40",
+                        Some("A version of the VIM editor"),
+                    ),
+                ],
+            )
+            .unwrap();
+        index
+            .replace_manager(
+                PackageManagerType::Cargo,
+                &[package("ripgrep", "14.1.0", None)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            index.search("LIBC").unwrap(),
+            [(PackageManagerType::Dnf, "glibc".to_owned())]
+        );
+        assert_eq!(
+            index.search("editor").unwrap(),
+            [(PackageManagerType::Dnf, "vim-enhanced".to_owned())]
+        );
+        assert_eq!(
+            index.search("GR").unwrap(),
+            [(PackageManagerType::Cargo, "ripgrep".to_owned())]
+        );
+        assert!(index.search("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn short_queries_match_wildcards_literally() {
+        let mut index = PackageIndex::open_in_memory().unwrap();
+        index
+            .replace_manager(
+                PackageManagerType::Pip,
+                &[
+                    package("a_b", "1.0", None),
+                    package("axb", "1.0", None),
+                    package("100%", "1.0", None),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            index.search("_").unwrap(),
+            [(PackageManagerType::Pip, "a_b".to_owned())]
+        );
+        assert_eq!(
+            index.search("0%").unwrap(),
+            [(PackageManagerType::Pip, "100%".to_owned())]
+        );
+        assert!(index.search("\\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn replace_manager_drops_packages_no_longer_installed() {
+        let mut index = PackageIndex::open_in_memory().unwrap();
+        index
+            .replace_manager(PackageManagerType::Go, &[package("gopls", "v0.15.0", None)])
+            .unwrap();
+        index
+            .replace_manager(PackageManagerType::Go, &[package("dlv", "v1.22.1", None)])
+            .unwrap();
+
+        assert!(index.search("gopls").unwrap().is_empty());
+
+        let inventory = index.inventory().unwrap();
+        assert_eq!(inventory.managers.len(), 1);
+        assert_eq!(
+            inventory.managers[&PackageManagerType::Go]["dlv"],
+            "v1.22.1"
+        );
+    }
+}
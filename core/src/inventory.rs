@@ -0,0 +1,266 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Config, CoreResult, PackageManagerType, version::compare_versions};
+
+/// Snapshot of installed packages across managers, `manager -> name -> version`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    /// Local time the snapshot was taken.
+    pub created_at: String,
+    pub managers: BTreeMap<PackageManagerType, BTreeMap<String, String>>,
+}
+
+/// Changes from one inventory to another, grouped by manager.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InventoryDiff {
+    pub managers: BTreeMap<PackageManagerType, Vec<PackageChange>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageChange {
+    Added {
+        name: String,
+        version: String,
+    },
+    Removed {
+        name: String,
+        version: String,
+    },
+    Upgraded {
+        name: String,
+        from: String,
+        to: String,
+    },
+    Downgraded {
+        name: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl PackageChange {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Added { name, .. }
+            | Self::Removed { name, .. }
+            | Self::Upgraded { name, .. }
+            | Self::Downgraded { name, .. } => name,
+        }
+    }
+}
+
+impl InventoryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.managers.is_empty()
+    }
+}
+
+impl Inventory {
+    /// Collect installed packages from every configured manager.
+    /// Managers that fail to list are left out of the snapshot.
+    pub async fn collect(config: &Config) -> Self {
+        let managers: Vec<PackageManagerType> = config
+            .system_manager
+            .iter()
+            .chain(config.app_managers.iter())
+            .map(|manager| manager.manager_type)
+            .collect();
+
+        let results = futures::future::join_all(
+            managers
+                .into_iter()
+                .map(|pm_type| async move { (pm_type, pm_type.list_installed(config).await) }),
+        )
+        .await;
+
+        let mut inventory = Self {
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            managers: BTreeMap::new(),
+        };
+
+        for (pm_type, result) in results {
+            match result {
+                Ok(packages) => {
+                    inventory.managers.insert(
+                        pm_type,
+                        packages
+                            .into_iter()
+                            .map(|package| (package.name, package.version))
+                            .collect(),
+                    );
+                }
+                Err(e) => log::warn!("Skipping {} in inventory: {}", pm_type.name(), e),
+            }
+        }
+
+        inventory
+    }
+
+    pub async fn load(path: impl AsRef<Path>) -> CoreResult<Self> {
+        let json = tokio::fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub async fn save(&self, path: impl AsRef<Path>) -> CoreResult<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+
+    /// What changed going from `self` (older) to `other` (newer).
+    pub fn diff(&self, other: &Inventory) -> InventoryDiff {
+        let empty = BTreeMap::new();
+        let mut diff = InventoryDiff::default();
+
+        let managers: BTreeSet<_> = self.managers.keys().chain(other.managers.keys()).collect();
+        for pm_type in managers {
+            let before = self.managers.get(pm_type).unwrap_or(&empty);
+            let after = other.managers.get(pm_type).unwrap_or(&empty);
+            let changes = diff_packages(before, after);
+
+            if !changes.is_empty() {
+                diff.managers.insert(*pm_type, changes);
+            }
+        }
+
+        diff
+    }
+}
+
+fn diff_packages(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> Vec<PackageChange> {
+    let mut changes = Vec::new();
+
+    for (name, from) in before {
+        match after.get(name) {
+            None => changes.push(PackageChange::Removed {
+                name: name.clone(),
+                version: from.clone(),
+            }),
+            Some(to) if to != from => {
+                let (name, from, to) = (name.clone(), from.clone(), to.clone());
+                changes.push(match compare_versions(&from, &to) {
+                    Ordering::Greater => PackageChange::Downgraded { name, from, to },
+                    // 格式不同但比较相等时也当作升级（例如重新构建的同版本）
+                    _ => PackageChange::Upgraded { name, from, to },
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (name, version) in after {
+        if !before.contains_key(name) {
+            changes.push(PackageChange::Added {
+                name: name.clone(),
+                version: version.clone(),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.name().cmp(b.name()));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory(entries: &[(PackageManagerType, &str, &str)]) -> Inventory {
+        let mut inventory = Inventory::default();
+        for (pm_type, name, version) in entries {
+            inventory
+                .managers
+                .entry(*pm_type)
+                .or_default()
+                .insert((*name).to_owned(), (*version).to_owned());
+        }
+        inventory
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_version_changes() {
+        let before = inventory(&[
+            (PackageManagerType::Dnf, "firefox", "126.0-1.fc40"),
+            (PackageManagerType::Dnf, "kernel", "6.8.9-300.fc40"),
+            (PackageManagerType::Dnf, "vim", "9.1.0-1.fc40"),
+            (PackageManagerType::Cargo, "ripgrep", "14.1.0"),
+            (PackageManagerType::Cargo, "bat", "0.24.0"),
+        ]);
+        let after = inventory(&[
+            (PackageManagerType::Dnf, "firefox", "125.0-1.fc40"),
+            (PackageManagerType::Dnf, "kernel", "6.8.10-100.fc40"),
+            (PackageManagerType::Dnf, "vim", "9.1.0-1.fc40"),
+            (PackageManagerType::Cargo, "ripgrep", "14.1.0"),
+            (PackageManagerType::Cargo, "eza", "0.18.0"),
+        ]);
+
+        let diff = before.diff(&after);
+
+        assert_eq!(
+            diff.managers[&PackageManagerType::Dnf],
+            vec![
+                PackageChange::Downgraded {
+                    name: "firefox".into(),
+                    from: "126.0-1.fc40".into(),
+                    to: "125.0-1.fc40".into(),
+                },
+                PackageChange::Upgraded {
+                    name: "kernel".into(),
+                    from: "6.8.9-300.fc40".into(),
+                    to: "6.8.10-100.fc40".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.managers[&PackageManagerType::Cargo],
+            vec![
+                PackageChange::Removed {
+                    name: "bat".into(),
+                    version: "0.24.0".into(),
+                },
+                PackageChange::Added {
+                    name: "eza".into(),
+                    version: "0.18.0".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_handles_managers_missing_on_one_side() {
+        let before = inventory(&[(PackageManagerType::Go, "gopls", "v0.15.0")]);
+        let after = inventory(&[(PackageManagerType::Npm, "eslint", "9.0.0")]);
+
+        let diff = before.diff(&after);
+
+        assert_eq!(diff.managers.len(), 2);
+        assert!(matches!(
+            diff.managers[&PackageManagerType::Go][..],
+            [PackageChange::Removed { .. }]
+        ));
+        assert!(matches!(
+            diff.managers[&PackageManagerType::Npm][..],
+            [PackageChange::Added { .. }]
+        ));
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn inventory_round_trips_through_json() {
+        let inventory = inventory(&[(PackageManagerType::Flatpak, "org.gimp.GIMP", "2.10.38")]);
+
+        let json = serde_json::to_string(&inventory).unwrap();
+        let parsed: Inventory = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, inventory);
+    }
+}
@@ -1,35 +1,77 @@
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    future::Future,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     error::CoreError,
+    i18n::Lang,
     pm::{
         apt::AptManager,
         cargo::CargoManager,
+        chocolatey::ChocolateyManager,
+        common::{
+            dedup_updates, drop_skipped_versions, new_command, poll_with_backoff,
+            run_with_concurrency_limit,
+        },
         dnf::DnfManager,
         flatpak::FlatpakManager,
         go::GoManager,
         homebrew::HomebrewManager,
         npm::{NpmManager, PnpmManager},
         pacman::PacmanManager,
+        pip::PipManager,
+        pipx::PipxManager,
         progress::CommandProgressEvent,
+        snap::SnapManager,
         zypper::ZypperManager,
     },
 };
 
+mod cache;
+mod cancel;
 pub mod error;
+mod estimate;
+mod http;
+pub mod i18n;
+#[cfg(feature = "sqlite-index")]
+mod index;
+mod inventory;
+mod manifest;
 mod pm;
+mod script;
 mod storage;
+mod version;
 
-pub use storage::{Config, PackageManagerConfig};
+pub use cancel::{CancelToken, with_cancel_token};
+pub use estimate::estimate_update_time;
+pub use http::{DEFAULT_USER_AGENT, clear_api_cache};
+#[cfg(feature = "sqlite-index")]
+pub use index::PackageIndex;
+pub use inventory::{Inventory, InventoryDiff, PackageChange};
+pub use manifest::{Manifest, ManifestEntry, ManifestImport};
+pub use script::{install_command, install_script};
+pub use storage::{
+    ColorScheme, Config, ListDensity, PackageManagerConfig, PagePreferences, PrivilegeEscalation,
+    UiPreferences,
+};
+pub use version::{compare_versions, display_version};
 
 #[derive(Debug, Clone)]
 pub struct PackageUpdate {
     pub name: String,
     pub current_version: String,
     pub new_version: String,
+    /// Fixes a security advisory (only DNF reports this)
+    pub security: bool,
+    /// Bytes to download, when the manager reports it (only Flatpak)
+    pub download_size: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +83,98 @@ pub struct PackageInfo {
     pub size: Option<u64>,
     pub install_date: Option<String>,
     pub homepage: Option<String>,
+    /// Download count or similar popularity metric, when the registry reports one
+    pub popularity: Option<u64>,
+    /// `false` when the manager cannot check this package for updates
+    /// (e.g. Go binaries built without module info)
+    pub has_update_info: bool,
+    /// End-of-life notice, with the replacement app ID when there is one
+    /// (only Flatpak reports this)
+    pub eol: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    /// Matches kept after applying `Config::search_result_limit`.
+    pub packages: Vec<PackageInfo>,
+    /// Number of matches the manager reported before truncation.
+    pub total: usize,
+}
+
+/// A broken or partially installed package reported by diagnostics.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub manager: PackageManagerType,
+    pub package: String,
+    pub description: String,
+    /// Command or action likely to fix the problem.
+    pub suggested_fix: Option<String>,
+}
+
+/// Whether applied updates need a reboot to take effect.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestartAdvice {
+    pub reboot_recommended: bool,
+    /// Updated core packages/services that triggered the advice.
+    pub packages: Vec<String>,
+}
+
+/// What installing some packages would pull in besides them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstallPreview {
+    /// Dependencies (or runtimes) that would be installed too.
+    pub additional: Vec<String>,
+    /// Total download size in bytes, when the manager reports one.
+    pub download_size: Option<u64>,
+}
+
+/// One entry of DNF's transaction history (`dnf history list`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnfTransaction {
+    pub id: u32,
+    /// Command line the transaction was started with.
+    pub command: String,
+    /// When it ran, as printed by dnf.
+    pub date: String,
+    /// Action summary, e.g. "Install" or "E, I, U"; empty when dnf5 omits it.
+    pub actions: String,
+    /// Number of packages the transaction touched.
+    pub altered: usize,
+}
+
+/// Packages of an update request, split by a fresh update check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingUpdates {
+    /// Still outdated; these need the update command.
+    pub pending: Vec<String>,
+    /// No longer reported as outdated, e.g. updated by a concurrent refresh.
+    pub up_to_date: Vec<String>,
+}
+
+impl PendingUpdates {
+    /// Split `(name, still_pending)` pairs of a per-package check.
+    pub(crate) fn from_checks(checks: Vec<(String, bool)>) -> Self {
+        let (pending, up_to_date): (Vec<_>, Vec<_>) = checks
+            .into_iter()
+            .partition(|(_, still_pending)| *still_pending);
+
+        Self {
+            pending: pending.into_iter().map(|(name, _)| name).collect(),
+            up_to_date: up_to_date.into_iter().map(|(name, _)| name).collect(),
+        }
+    }
+
+    fn partition(package_names: &[String], updates: &[PackageUpdate]) -> Self {
+        let (pending, up_to_date) = package_names
+            .iter()
+            .cloned()
+            .partition(|name| updates.iter().any(|update| &update.name == name));
+
+        Self {
+            pending,
+            up_to_date,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,11 +186,59 @@ pub struct InstallProgress {
     pub command_message: Option<String>,
 }
 
+/// First wait of `PackageManagerType::wait_until_settled`, doubled per poll.
+const SETTLE_POLL_INITIAL_DELAY: Duration = Duration::from_millis(500);
+/// Longest `PackageManagerType::wait_until_settled` holds back the reload.
+const SETTLE_POLL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long an `is_available` answer is reused before `which` runs again.
+const AVAILABILITY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// `is_available` answers per manager, with when they were probed.
+static AVAILABILITY_CACHE: LazyLock<Mutex<HashMap<PackageManagerType, (Instant, bool)>>> =
+    LazyLock::new(Default::default);
+
+/// Forget cached `is_available` answers so the next detection probes `PATH` again.
+pub fn clear_availability_cache() {
+    AVAILABILITY_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+/// Fresh cached availability of `manager`, or the result of `probe`, which
+/// is then cached.
+async fn cached_availability<F>(manager: PackageManagerType, probe: impl FnOnce() -> F) -> bool
+where
+    F: Future<Output = bool>,
+{
+    let cached = AVAILABILITY_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&manager)
+        .filter(|(probed_at, _)| probed_at.elapsed() < AVAILABILITY_TTL)
+        .map(|(_, available)| *available);
+    if let Some(available) = cached {
+        return available;
+    }
+
+    let available = probe().await;
+    AVAILABILITY_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(manager, (Instant::now(), available));
+    available
+}
+
+/// Kind of change `PackageManagerType::dry_run_commands` plans.
 #[derive(Debug, Clone, Copy)]
-enum PackageAction {
+pub enum PackageAction {
     Uninstall,
     Update,
     Install,
+    /// Update every pending package, through the manager's own "upgrade
+    /// everything" command where it has one
+    UpdateAll,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -68,7 +250,8 @@ enum PackageManagerKind {
 #[derive(Debug, Clone, Copy)]
 struct PackageManagerMetadata {
     name: &'static str,
-    description: &'static str,
+    /// `(English, Chinese)` description.
+    description: (&'static str, &'static str),
     command: &'static str,
     kind: PackageManagerKind,
 }
@@ -82,7 +265,11 @@ macro_rules! define_package_managers {
             $( $app_variant:ident : $app_manager:ident => ($app_name:expr, $app_description:expr, $app_command:expr), )*
         }
     ) => {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+        /// Ordered by declaration: system managers first, then app managers,
+        /// so UI lists built from hash sets can be sorted into a stable order.
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Hash,
+        )]
         pub enum PackageManagerType {
             $($system_variant,)*
             $($app_variant,)*
@@ -101,6 +288,15 @@ macro_rules! define_package_managers {
             $(PackageManagerType::$app_variant,)*
         ];
 
+        /// Map a manager type to its implementation.
+        /// New managers only need an entry in `define_package_managers!`.
+        pub fn manager_for(manager_type: PackageManagerType) -> Box<dyn PackageManager> {
+            match manager_type {
+                $(PackageManagerType::$system_variant => Box::new($system_manager),)*
+                $(PackageManagerType::$app_variant => Box::new($app_manager),)*
+            }
+        }
+
         impl PackageManagerType {
             fn metadata(self) -> PackageManagerMetadata {
                 match self {
@@ -123,25 +319,76 @@ macro_rules! define_package_managers {
                 }
             }
 
+            /// Product name, the same in every language.
             pub fn name(&self) -> &'static str {
                 self.metadata().name
             }
 
-            pub fn description(&self) -> &'static str {
-                self.metadata().description
+            /// Executable looked up on `PATH` when no custom path is set.
+            pub fn command(&self) -> &'static str {
+                self.metadata().command
+            }
+
+            pub fn description(&self, lang: Lang) -> &'static str {
+                let (english, chinese) = self.metadata().description;
+                match lang {
+                    Lang::English => english,
+                    Lang::Chinese => chinese,
+                }
             }
 
             pub fn is_system_manager(&self) -> bool {
                 self.metadata().kind == PackageManagerKind::System
             }
 
+            /// Whether installs/updates of different packages may run in
+            /// parallel (`Config::install_concurrency`). Transactional managers
+            /// such as DNF or Flatpak always run one operation at a time.
+            pub fn supports_concurrent_installs(&self) -> bool {
+                matches!(self, Self::Cargo | Self::Go)
+            }
+
+            /// Whether the manager's command is on `PATH`, or for Homebrew in
+            /// one of its default prefixes. Answers are cached for
+            /// `AVAILABILITY_TTL`; `clear_availability_cache` forces a new probe.
             pub async fn is_available(&self) -> bool {
-                tokio::process::Command::new("which")
-                    .arg(self.metadata().command)
-                    .output()
-                    .await
-                    .map(|output| output.status.success())
-                    .unwrap_or(false)
+                let command = self.metadata().command;
+                let manager = *self;
+                cached_availability(*self, || async move {
+                    // brew 常装在不在 PATH 上的默认前缀里
+                    if manager == Self::Homebrew {
+                        return HomebrewManager::resolve_binary(&Config::default())
+                            .is_some_and(|brew| brew.is_file());
+                    }
+                    tokio::process::Command::new("which")
+                        .arg(command)
+                        .output()
+                        .await
+                        .map(|output| output.status.success())
+                        .unwrap_or(false)
+                })
+                .await
+            }
+
+            /// Whether the binary `config` points at exists: the custom path
+            /// when one is set, otherwise the command on `PATH` with
+            /// `Config::extra_path` prepended. Homebrew also looks in its
+            /// default prefixes, as `HomebrewManager::resolve_binary` does.
+            pub async fn is_available_with_config(&self, config: &Config) -> bool {
+                if *self == Self::Homebrew {
+                    return HomebrewManager::resolve_binary(config)
+                        .is_some_and(|brew| brew.is_file());
+                }
+                match config.get_package_path(*self) {
+                    Some(path) => tokio::fs::metadata(&path)
+                        .await
+                        .is_ok_and(|metadata| metadata.is_file()),
+                    None => new_command(config, "which")
+                        .arg(self.metadata().command)
+                        .output()
+                        .await
+                        .is_ok_and(|output| output.status.success()),
+                }
             }
 
             pub async fn get_current_version(
@@ -149,35 +396,62 @@ macro_rules! define_package_managers {
                 config: &Config,
                 package_name: &str,
             ) -> CoreResult<String> {
-                match self {
-                    $(Self::$system_variant => $system_manager::get_current_version(config, package_name).await,)*
-                    $(Self::$app_variant => $app_manager::get_current_version(config, package_name).await,)*
-                }
+                manager_for(*self).get_current_version(config, package_name).await
+            }
+
+            /// Whether `package_name` is installed through this manager.
+            pub async fn is_installed(&self, config: &Config, package_name: &str) -> bool {
+                self.get_current_version(config, package_name).await.is_ok()
             }
 
             pub async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
-                match self {
-                    $(Self::$system_variant => $system_manager::list_installed(config).await,)*
-                    $(Self::$app_variant => $app_manager::list_installed(config).await,)*
-                }
+                manager_for(*self).list_installed(config).await
             }
 
             pub async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
-                match self {
-                    $(Self::$system_variant => $system_manager::count_installed(config).await,)*
-                    $(Self::$app_variant => $app_manager::count_installed(config).await,)*
-                }
+                manager_for(*self).count_installed(config).await
             }
 
             pub async fn search_package(
                 &self,
                 config: &Config,
                 package_name: &str,
-            ) -> CoreResult<Vec<PackageInfo>> {
-                match self {
-                    $(Self::$system_variant => $system_manager::search_package(config, package_name).await,)*
-                    $(Self::$app_variant => $app_manager::search_package(config, package_name).await,)*
-                }
+            ) -> CoreResult<SearchResults> {
+                manager_for(*self).search_package(config, package_name).await
+            }
+
+            pub async fn list_problems(&self, config: &Config) -> CoreResult<Vec<Problem>> {
+                manager_for(*self).list_problems(config).await
+            }
+
+            pub async fn installed_size(
+                &self,
+                config: &Config,
+                package_name: &str,
+            ) -> CoreResult<Option<u64>> {
+                manager_for(*self).installed_size(config, package_name).await
+            }
+
+            pub async fn installed_homepage(
+                &self,
+                config: &Config,
+                package_name: &str,
+            ) -> CoreResult<Option<String>> {
+                manager_for(*self).installed_homepage(config, package_name).await
+            }
+
+            pub async fn get_package_details(
+                &self,
+                config: &Config,
+                package_name: &str,
+            ) -> CoreResult<Option<String>> {
+                manager_for(*self).get_package_details(config, package_name).await
+            }
+
+            /// `package_name` trimmed and normalized for this manager, or a
+            /// `CoreError::ParseError` when the manager could not accept it.
+            pub fn normalize_package_name(&self, package_name: &str) -> CoreResult<String> {
+                manager_for(*self).normalize_name(package_name)
             }
 
             pub async fn uninstall_packages_with_progress(
@@ -210,6 +484,27 @@ macro_rules! define_package_managers {
                 .await
             }
 
+            /// Update `package_names`, the manager's pending updates. When
+            /// they are all of the updates the manager reports,
+            /// `PackageManager::update_all` runs, which for DNF, Flatpak and
+            /// Homebrew is their bulk upgrade command; otherwise, e.g. with a
+            /// skipped version left out, or in a dry run, the packages are
+            /// named one by one.
+            pub async fn update_all_with_progress(
+                &self,
+                config: &Config,
+                package_names: &[String],
+                mut on_progress: impl FnMut(InstallProgress),
+            ) -> CoreResult<()> {
+                self.run_packages_with_progress(
+                    PackageAction::UpdateAll,
+                    config,
+                    package_names,
+                    &mut on_progress,
+                )
+                .await
+            }
+
             pub async fn install_packages_with_progress(
                 &self,
                 config: &Config,
@@ -225,6 +520,28 @@ macro_rules! define_package_managers {
                 .await
             }
 
+            /// Commands `action` would run for `package_names`, one shell
+            /// line each, without changing anything (see `Config::dry_run`).
+            pub async fn dry_run_commands(
+                &self,
+                action: PackageAction,
+                config: &Config,
+                package_names: &[String],
+            ) -> CoreResult<Vec<String>> {
+                // One package at a time keeps the commands in order
+                let config = Config {
+                    dry_run: true,
+                    install_concurrency: 1,
+                    ..config.clone()
+                };
+                let mut commands = Vec::new();
+                self.run_packages_once(action, &config, package_names, &mut |progress| {
+                    commands.extend(progress.command_message);
+                })
+                .await?;
+                Ok(commands)
+            }
+
             async fn run_packages_with_progress(
                 &self,
                 action: PackageAction,
@@ -232,91 +549,187 @@ macro_rules! define_package_managers {
                 package_names: &[String],
                 on_progress: &mut impl FnMut(InstallProgress),
             ) -> CoreResult<()> {
+                self.run_packages_once(action, config, package_names, on_progress)
+                    .await?;
+                if config.poll_after_operation.contains(self) && !config.dry_run {
+                    self.wait_until_settled(action, config, package_names).await;
+                }
+                Ok(())
+            }
+
+            async fn run_packages_once(
+                &self,
+                action: PackageAction,
+                config: &Config,
+                package_names: &[String],
+                on_progress: &mut impl FnMut(InstallProgress),
+            ) -> CoreResult<()> {
+                self.check_writable(config)?;
+
+                // 空列表或空白名称直接跳过：不带包名时 flatpak update、brew upgrade 等会更新全部；
+                // 其余名称先校验，任何一个无效都不执行命令
+                let package_names = package_names
+                    .iter()
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| self.normalize_package_name(name))
+                    .collect::<CoreResult<Vec<String>>>()?;
+                let package_names = package_names.as_slice();
                 let total = package_names.len();
                 if total == 0 {
                     return Ok(());
                 }
 
+                // 预览不查询更新列表，按包名列出命令
+                if matches!(action, PackageAction::UpdateAll)
+                    && !config.dry_run
+                    && self.covers_all_updates(config, package_names).await
+                {
+                    return self.run_update_all(config, package_names, on_progress).await;
+                }
+
+                self.run_normalized_packages(action, config, package_names, on_progress)
+                    .await
+            }
+
+            /// Run `action` for `package_names`, already checked and
+            /// normalized: one command for a system manager, otherwise one
+            /// per package.
+            pub(crate) async fn run_normalized_packages(
+                &self,
+                action: PackageAction,
+                config: &Config,
+                package_names: &[String],
+                on_progress: &mut impl FnMut(InstallProgress),
+            ) -> CoreResult<()> {
+                let manager_type = *self;
+                let manager = manager_for(manager_type);
+                let total = package_names.len();
+
                 if self.is_system_manager() {
-                    let mut report = |event: CommandProgressEvent| {
-                        let progress = event.progress.clamp(0.0, 1.0);
-                        let completed = if progress >= 1.0 {
-                            total
-                        } else {
-                            ((progress * total as f32).floor() as usize).min(total)
+                    return forward_progress(on_progress, |tx| async move {
+                        let mut report = |event: CommandProgressEvent| {
+                            let progress = event.progress.clamp(0.0, 1.0);
+                            let completed = if progress >= 1.0 {
+                                total
+                            } else {
+                                ((progress * total as f32).floor() as usize).min(total)
+                            };
+
+                            let _ = tx.send(InstallProgress {
+                                manager: manager_type,
+                                current_package: String::new(),
+                                completed,
+                                total,
+                                command_message: event.command_message,
+                            });
                         };
 
-                        on_progress(InstallProgress {
-                            manager: *self,
-                            current_package: String::new(),
-                            completed,
-                            total,
-                            command_message: event.command_message,
-                        });
-                    };
-
-                    Self::run_system_batch_action_with_progress(
-                        *self,
-                        action,
-                        config,
-                        package_names,
-                        &mut report,
-                    )
-                    .await?;
-                    return Ok(());
+                        manager
+                            .run_batch_action(action, config, package_names, &mut report)
+                            .await
+                    })
+                    .await;
+                }
+
+                let concurrency = config.install_concurrency;
+                if concurrency > 1
+                    && self.supports_concurrent_installs()
+                    && !matches!(action, PackageAction::Uninstall)
+                {
+                    return self
+                        .run_concurrent_packages_with_progress(
+                            action,
+                            config,
+                            package_names,
+                            concurrency,
+                            on_progress,
+                        )
+                        .await;
                 }
 
-                for (index, package_name) in package_names.iter().enumerate() {
-                    let package_name = package_name.clone();
-                    let mut report = |event: CommandProgressEvent| {
-                        let completed = if event.progress.clamp(0.0, 1.0) >= 1.0 {
-                            index + 1
-                        } else {
-                            index
+                forward_progress(on_progress, |tx| async move {
+                    for (index, package_name) in package_names.iter().enumerate() {
+                        let mut report = |event: CommandProgressEvent| {
+                            let completed = if event.progress.clamp(0.0, 1.0) >= 1.0 {
+                                index + 1
+                            } else {
+                                index
+                            };
+
+                            let _ = tx.send(InstallProgress {
+                                manager: manager_type,
+                                current_package: package_name.clone(),
+                                completed,
+                                total,
+                                command_message: event.command_message,
+                            });
                         };
 
-                        on_progress(InstallProgress {
-                            manager: *self,
-                            current_package: package_name.clone(),
-                            completed,
-                            total,
-                            command_message: event.command_message,
-                        });
-                    };
-
-                    self.run_single_package_action_with_progress(
-                        action,
-                        config,
-                        &package_name,
-                        &mut report,
-                    )
-                    .await?;
-                }
+                        manager
+                            .run_package_action(action, config, package_name, &mut report)
+                            .await?;
+                    }
 
-                Ok(())
+                    Ok(())
+                })
+                .await
             }
 
-            async fn run_single_package_action_with_progress(
+            async fn run_concurrent_packages_with_progress(
                 &self,
                 action: PackageAction,
                 config: &Config,
-                package_name: &str,
-                report: &mut impl FnMut(CommandProgressEvent),
+                package_names: &[String],
+                concurrency: usize,
+                on_progress: &mut impl FnMut(InstallProgress),
             ) -> CoreResult<()> {
-                match action {
-                    PackageAction::Uninstall => match self {
-                        $(Self::$app_variant => $app_manager::uninstall_package_with_progress(config, package_name, report).await,)*
-                        _ => Err(CoreError::UnknownError("single-package action is only supported for app package managers".to_owned())),
-                    },
-                    PackageAction::Update => match self {
-                        $(Self::$app_variant => $app_manager::update_package_with_progress(config, package_name, report).await,)*
-                        _ => Err(CoreError::UnknownError("single-package action is only supported for app package managers".to_owned())),
-                    },
-                    PackageAction::Install => match self {
-                        $(Self::$app_variant => $app_manager::install_package_with_progress(config, package_name, report).await,)*
-                        _ => Err(CoreError::UnknownError("single-package action is only supported for app package managers".to_owned())),
-                    },
-                }
+                let total = package_names.len();
+                let manager = &manager_for(*self);
+                // `None` marks a finished package.
+                let (tx, mut rx) =
+                    tokio::sync::mpsc::unbounded_channel::<(String, Option<CommandProgressEvent>)>();
+
+                let work = async move {
+                    let tx = tx;
+                    run_with_concurrency_limit(package_names, concurrency, |package_name| {
+                        let tx = tx.clone();
+                        async move {
+                            let mut report = |event: CommandProgressEvent| {
+                                let _ = tx.send((package_name.clone(), Some(event)));
+                            };
+                            let result = manager
+                                .run_package_action(action, config, package_name, &mut report)
+                                .await;
+                            let _ = tx.send((package_name.clone(), None));
+                            result
+                        }
+                    })
+                    .await
+                };
+
+                let report = async {
+                    let mut completed = 0;
+                    while let Some((package_name, event)) = rx.recv().await {
+                        let command_message = match event {
+                            Some(event) => event.command_message,
+                            None => {
+                                completed += 1;
+                                None
+                            }
+                        };
+
+                        on_progress(InstallProgress {
+                            manager: *self,
+                            current_package: package_name,
+                            completed,
+                            total,
+                            command_message,
+                        });
+                    }
+                };
+
+                let (results, ()) = futures::future::join(work, report).await;
+                results.into_iter().collect()
             }
         }
     };
@@ -324,18 +737,22 @@ macro_rules! define_package_managers {
 
 define_package_managers! {
     system {
-        Apt: AptManager => ("APT", "Debian/Ubuntu 系统包管理器", "apt"),
-        Dnf: DnfManager => ("DNF", "Fedora/RHEL 系统包管理器", "dnf"),
-        Pacman: PacmanManager => ("Pacman", "Arch Linux 系统包管理器", "pacman"),
-        Zypper: ZypperManager => ("Zypper", "openSUSE/SUSE 系统包管理器", "zypper"),
+        Apt: AptManager => ("APT", ("Debian/Ubuntu system package manager", "Debian/Ubuntu 系统包管理器"), "apt"),
+        Dnf: DnfManager => ("DNF", ("Fedora/RHEL system package manager", "Fedora/RHEL 系统包管理器"), "dnf"),
+        Pacman: PacmanManager => ("Pacman", ("Arch Linux system package manager", "Arch Linux 系统包管理器"), "pacman"),
+        Zypper: ZypperManager => ("Zypper", ("openSUSE/SUSE system package manager", "openSUSE/SUSE 系统包管理器"), "zypper"),
     }
     app {
-        Flatpak: FlatpakManager => ("Flatpak", "跨平台应用沙箱管理器", "flatpak"),
-        Homebrew: HomebrewManager => ("Homebrew", "macOS/Linux 包管理器", "brew"),
-        Cargo: CargoManager => ("Cargo", "Rust 编程语言的包管理器", "cargo"),
-        Go: GoManager => ("Go", "Go 编程语言的包管理器", "go"),
-        Npm: NpmManager => ("NPM", "Node.js 默认包管理器", "npm"),
-        Pnpm: PnpmManager => ("pnpm", "Node.js 高性能包管理器", "pnpm"),
+        Flatpak: FlatpakManager => ("Flatpak", ("Cross-platform sandboxed app manager", "跨平台应用沙箱管理器"), "flatpak"),
+        Snap: SnapManager => ("Snap", ("Canonical universal Linux package manager", "Canonical 通用 Linux 包管理器"), "snap"),
+        Homebrew: HomebrewManager => ("Homebrew", ("macOS/Linux package manager", "macOS/Linux 包管理器"), "brew"),
+        Cargo: CargoManager => ("Cargo", ("Rust package manager", "Rust 编程语言的包管理器"), "cargo"),
+        Go: GoManager => ("Go", ("Go package manager", "Go 编程语言的包管理器"), "go"),
+        Npm: NpmManager => ("NPM", ("Default Node.js package manager", "Node.js 默认包管理器"), "npm"),
+        Pnpm: PnpmManager => ("pnpm", ("Fast Node.js package manager", "Node.js 高性能包管理器"), "pnpm"),
+        Chocolatey: ChocolateyManager => ("Chocolatey", ("Windows package manager", "Windows 包管理器"), "choco"),
+        Pip: PipManager => ("pip", ("Python user site-packages manager", "Python 用户目录包管理器"), "pip"),
+        Pipx: PipxManager => ("pipx", ("Isolated Python application manager", "Python 独立应用管理器"), "pipx"),
     }
 }
 
@@ -345,121 +762,568 @@ impl PackageManagerType {
         config: &Config,
         refresh: bool,
     ) -> CoreResult<Vec<PackageUpdate>> {
+        let updates = self.list_unfiltered_updates(config, refresh).await?;
+        Ok(drop_skipped_versions(config, updates))
+    }
+
+    /// Every update the manager reports, skipped versions included.
+    async fn list_unfiltered_updates(
+        &self,
+        config: &Config,
+        refresh: bool,
+    ) -> CoreResult<Vec<PackageUpdate>> {
+        let updates = manager_for(*self)
+            .list_updates_with_refresh(config, refresh)
+            .await?;
+        Ok(dedup_updates(updates))
+    }
+
+    /// Whether `package_names` include every update the manager reports, so
+    /// updating everything installs nothing the user left out. `false` when
+    /// the updates cannot be listed.
+    async fn covers_all_updates(&self, config: &Config, package_names: &[String]) -> bool {
+        match self.list_unfiltered_updates(config, false).await {
+            Ok(updates) => names_cover_updates(package_names, &updates),
+            Err(e) => {
+                log::debug!("{}: could not list updates: {}", self.name(), e);
+                false
+            }
+        }
+    }
+
+    /// Versions that can be installed, newest first. Managers without a
+    /// version listing return an empty list. The name is normalized first,
+    /// like every other name-taking entry point.
+    pub async fn list_available_versions(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Vec<String>> {
+        let package_name = self.normalize_package_name(package_name)?;
+        manager_for(*self)
+            .list_available_versions(config, &package_name)
+            .await
+    }
+
+    /// Install prefix of the manager's binary, for diagnostics. Only
+    /// Homebrew reports one, as its prefix differs between Apple Silicon,
+    /// Intel and Linux.
+    pub fn install_prefix(&self, config: &Config) -> Option<std::path::PathBuf> {
         match self {
-            Self::Apt => AptManager::list_updates_with_refresh(config, refresh).await,
-            Self::Dnf => DnfManager::list_updates_with_refresh(config, refresh).await,
-            Self::Pacman => PacmanManager::list_updates_with_refresh(config, refresh).await,
-            Self::Zypper => ZypperManager::list_updates_with_refresh(config, refresh).await,
-            Self::Flatpak => FlatpakManager::list_updates(config).await,
-            Self::Homebrew => HomebrewManager::list_updates(config).await,
-            Self::Cargo => CargoManager::list_updates(config).await,
-            Self::Go => GoManager::list_updates(config).await,
-            Self::Npm => NpmManager::list_updates(config).await,
-            Self::Pnpm => PnpmManager::list_updates(config).await,
+            Self::Homebrew => HomebrewManager::resolve_prefix(config),
+            _ => None,
         }
     }
 
-    async fn run_system_batch_action_with_progress(
-        manager: PackageManagerType,
+    /// Re-check which of `package_names` still have an update, so packages
+    /// already updated elsewhere don't get reinstalled. System managers
+    /// skip current packages on their own and are not re-checked.
+    pub async fn pending_updates(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<PendingUpdates> {
+        if self.is_system_manager() {
+            return Ok(PendingUpdates {
+                pending: package_names.to_vec(),
+                up_to_date: Vec::new(),
+            });
+        }
+
+        manager_for(*self)
+            .pending_updates(config, package_names)
+            .await
+    }
+
+    /// Poll with backoff until `package_names` show the outcome of `action`,
+    /// for managers whose CLI returns before the operation has landed
+    /// (see `Config::poll_after_operation`). Gives up quietly on timeout.
+    async fn wait_until_settled(
+        &self,
         action: PackageAction,
         config: &Config,
         package_names: &[String],
-        report: &mut impl FnMut(CommandProgressEvent),
-    ) -> CoreResult<()> {
-        match manager {
-            Self::Apt => match action {
-                PackageAction::Uninstall => {
-                    AptManager::uninstall_packages_with_progress(config, package_names, report)
-                        .await
-                }
-                PackageAction::Update => {
-                    AptManager::update_packages_with_progress(config, package_names, report).await
-                }
-                PackageAction::Install => {
-                    AptManager::install_packages_with_progress(config, package_names, report).await
-                }
-            },
-            Self::Dnf => match action {
-                PackageAction::Uninstall => {
-                    DnfManager::uninstall_packages_with_progress(config, package_names, report)
-                        .await
-                }
-                PackageAction::Update => {
-                    DnfManager::update_packages_with_progress(config, package_names, report).await
-                }
-                PackageAction::Install => {
-                    DnfManager::install_packages_with_progress(config, package_names, report).await
-                }
-            },
-            Self::Pacman => match action {
-                PackageAction::Uninstall => {
-                    PacmanManager::uninstall_packages_with_progress(config, package_names, report)
-                        .await
-                }
-                PackageAction::Update => {
-                    PacmanManager::update_packages_with_progress(config, package_names, report)
-                        .await
-                }
-                PackageAction::Install => {
-                    PacmanManager::install_packages_with_progress(config, package_names, report)
-                        .await
-                }
-            },
-            Self::Zypper => match action {
-                PackageAction::Uninstall => {
-                    ZypperManager::uninstall_packages_with_progress(config, package_names, report)
-                        .await
-                }
-                PackageAction::Update => {
-                    ZypperManager::update_packages_with_progress(config, package_names, report)
-                        .await
-                }
-                PackageAction::Install => {
-                    ZypperManager::install_packages_with_progress(config, package_names, report)
-                        .await
+    ) {
+        let package_names: HashSet<&str> = package_names
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .collect();
+        if package_names.is_empty() {
+            return;
+        }
+
+        let manager = manager_for(*self);
+        let manager = manager.as_ref();
+        let package_names = &package_names;
+        let settled = poll_with_backoff(
+            || async move {
+                match action {
+                    PackageAction::Install | PackageAction::Uninstall => {
+                        let Ok(installed) = manager.list_installed(config).await else {
+                            return false;
+                        };
+                        let present = installed
+                            .iter()
+                            .filter(|package| package_names.contains(package.name.as_str()))
+                            .count();
+                        match action {
+                            PackageAction::Install => present == package_names.len(),
+                            _ => present == 0,
+                        }
+                    }
+                    PackageAction::Update | PackageAction::UpdateAll => {
+                        manager.list_updates(config).await.is_ok_and(|updates| {
+                            !updates
+                                .iter()
+                                .any(|update| package_names.contains(update.name.as_str()))
+                        })
+                    }
                 }
             },
-            _ => Err(CoreError::UnknownError(
-                "batch action is only supported for system package managers".to_owned(),
-            )),
+            SETTLE_POLL_INITIAL_DELAY,
+            SETTLE_POLL_TIMEOUT,
+        )
+        .await;
+
+        if !settled {
+            log::debug!(
+                "{}: state did not settle within {:?} after {:?}",
+                self.name(),
+                SETTLE_POLL_TIMEOUT,
+                action
+            );
         }
     }
+
+    /// Age of the cached repo metadata `list_updates` reads from.
+    /// `None` for managers without such a cache.
+    pub async fn metadata_age(&self, config: &Config) -> CoreResult<Option<Duration>> {
+        manager_for(*self).metadata_age(config).await
+    }
+
+    /// Download fresh repo metadata; a no-op for managers without a cache.
+    pub async fn refresh_metadata(&self, config: &Config) -> CoreResult<()> {
+        manager_for(*self).refresh_metadata(config).await
+    }
+
+    /// Check whether a reboot is recommended after updates.
+    /// Managers without such a check report no advice.
+    pub async fn needs_restarting(&self, config: &Config) -> CoreResult<RestartAdvice> {
+        manager_for(*self).needs_restarting(config).await
+    }
+
+    /// Resolve an install of `package_names` without running it. DNF,
+    /// Homebrew and Flatpak report the extra packages; other managers return
+    /// an empty preview.
+    pub async fn preview_install(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<InstallPreview> {
+        let package_names = package_names
+            .iter()
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| self.normalize_package_name(name))
+            .collect::<CoreResult<Vec<String>>>()?;
+        if package_names.is_empty() {
+            return Ok(InstallPreview::default());
+        }
+
+        manager_for(*self)
+            .preview_install(config, &package_names)
+            .await
+    }
+
+    /// Recent transactions, newest first. Only DNF keeps a history the app
+    /// can roll back to; other managers report none.
+    pub async fn list_transactions(&self, config: &Config) -> CoreResult<Vec<DnfTransaction>> {
+        manager_for(*self).list_transactions(config).await
+    }
+
+    /// Homebrew's Brewfile of everything installed, which keeps taps and
+    /// casks apart from formulae; other managers have no such format.
+    pub async fn export_brewfile(&self, config: &Config) -> CoreResult<String> {
+        manager_for(*self).export_brewfile(config).await
+    }
+
+    /// Install what the Brewfile at `path` lists and is missing, through
+    /// `brew bundle`.
+    pub async fn import_brewfile(
+        &self,
+        config: &Config,
+        path: &std::path::Path,
+        mut on_progress: impl FnMut(InstallProgress) + Send,
+    ) -> CoreResult<()> {
+        self.check_writable(config)?;
+
+        let mut report = |event: CommandProgressEvent| {
+            on_progress(InstallProgress {
+                manager: *self,
+                current_package: String::new(),
+                completed: usize::from(event.progress >= 1.0),
+                total: 1,
+                command_message: event.command_message,
+            });
+        };
+        manager_for(*self)
+            .import_brewfile(config, path, &mut report)
+            .await
+    }
+
+    /// Undo every transaction after `id`, returning the system to the state
+    /// right after it ran.
+    pub async fn rollback_transaction(
+        &self,
+        config: &Config,
+        id: u32,
+        mut on_progress: impl FnMut(InstallProgress) + Send,
+    ) -> CoreResult<()> {
+        self.check_writable(config)?;
+        let mut report = |event: CommandProgressEvent| {
+            on_progress(InstallProgress {
+                manager: *self,
+                current_package: String::new(),
+                completed: usize::from(event.progress >= 1.0),
+                total: 1,
+                command_message: event.command_message,
+            });
+        };
+        manager_for(*self)
+            .rollback_transaction(config, id, &mut report)
+            .await
+    }
+
+    /// `PackageManager::update_all`, whose progress callback has to be
+    /// `Send`; progress is passed back over a channel as in
+    /// `run_concurrent_packages_with_progress`.
+    async fn run_update_all(
+        &self,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut impl FnMut(InstallProgress),
+    ) -> CoreResult<()> {
+        let manager = manager_for(*self);
+
+        forward_progress(on_progress, |tx| async move {
+            let mut report = |progress: InstallProgress| {
+                let _ = tx.send(progress);
+            };
+            manager.update_all(config, package_names, &mut report).await
+        })
+        .await
+    }
+
+    /// Refuse changes to a manager `Config::system_manager_readonly` protects.
+    fn check_writable(&self, config: &Config) -> CoreResult<()> {
+        if config.is_read_only(*self) {
+            return Err(CoreError::PermissionDenied(format!(
+                "{} is read-only; turn off \"Read-only system manager\" in Settings to change system packages",
+                self.name()
+            )));
+        }
+        Ok(())
+    }
 }
 
 type CoreResult<T> = Result<T, CoreError>;
 
+/// Run `work` with the sending half of a channel and pass whatever it sends
+/// to `on_progress`. `PackageManager` callbacks have to be `Send`, which
+/// `on_progress` need not be.
+async fn forward_progress<F>(
+    on_progress: &mut impl FnMut(InstallProgress),
+    work: impl FnOnce(tokio::sync::mpsc::UnboundedSender<InstallProgress>) -> F,
+) -> CoreResult<()>
+where
+    F: Future<Output = CoreResult<()>>,
+{
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let work = work(tx);
+
+    let report = async {
+        while let Some(progress) = rx.recv().await {
+            on_progress(progress);
+        }
+    };
+
+    let (result, ()) = futures::future::join(work, report).await;
+    result
+}
+
+/// Whether every update in `updates` is one of `package_names`.
+fn names_cover_updates(package_names: &[String], updates: &[PackageUpdate]) -> bool {
+    let package_names: HashSet<&str> = package_names.iter().map(String::as_str).collect();
+    updates
+        .iter()
+        .all(|update| package_names.contains(update.name.as_str()))
+}
+
+/// Progress of one command that covers all `total` packages of `manager`,
+/// for `PackageManager::update_all` overrides.
+pub(crate) fn bulk_progress(
+    manager: PackageManagerType,
+    total: usize,
+    on_progress: &mut (dyn FnMut(InstallProgress) + Send),
+) -> impl FnMut(CommandProgressEvent) + Send + '_ {
+    move |event| {
+        on_progress(InstallProgress {
+            manager,
+            current_package: String::new(),
+            completed: if event.progress >= 1.0 { total } else { 0 },
+            total,
+            command_message: event.command_message,
+        })
+    }
+}
+
 #[async_trait]
 pub trait PackageManager: Send + Sync {
-    async fn list_updates(_config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+    fn manager_type(&self) -> PackageManagerType;
+
+    async fn list_updates(&self, _config: &Config) -> CoreResult<Vec<PackageUpdate>> {
         Err(CoreError::UnknownError(
             "list_updates not implemented".into(),
         ))
     }
 
-    async fn get_current_version(config: &Config, package_name: &str) -> CoreResult<String>;
+    /// Which of `package_names` still have an update
+    /// Default implementation checks them against one `list_updates` pass
+    async fn pending_updates(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<PendingUpdates> {
+        let updates = self.list_updates(config).await?;
+        Ok(PendingUpdates::partition(package_names, &updates))
+    }
+
+    /// `package_name` trimmed and checked against the manager's naming
+    /// rules, in the spelling its CLI expects
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String>;
+
+    /// Pending updates, refreshing the manager's metadata first when
+    /// `refresh` is set
+    /// Default implementation lists updates without a refresh
+    async fn list_updates_with_refresh(
+        &self,
+        config: &Config,
+        _refresh: bool,
+    ) -> CoreResult<Vec<PackageUpdate>> {
+        self.list_updates(config).await
+    }
 
-    async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>>;
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String>;
+
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>>;
+
+    /// Names of packages the user asked for, without the dependencies
+    /// pulled in for them; what a manifest exports
+    /// Default implementation lists every installed package
+    async fn list_user_installed(&self, config: &Config) -> CoreResult<Vec<String>> {
+        Ok(self
+            .list_installed(config)
+            .await?
+            .into_iter()
+            .map(|package| package.name)
+            .collect())
+    }
 
     /// Get Installed package count
     /// Default implementation counts the length of the list_installed result
-    async fn count_installed(config: &Config) -> CoreResult<usize> {
-        Ok(Self::list_installed(config).await?.len())
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
+        Ok(self.list_installed(config).await?.len())
     }
 
-    async fn search_package(_config: &Config, _package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    /// Search packages by name
+    /// Implementations should truncate results to `Config::search_result_limit`
+    async fn search_package(
+        &self,
+        _config: &Config,
+        _package_name: &str,
+    ) -> CoreResult<SearchResults> {
         Err(CoreError::UnknownError(
             "search_package not implemented".into(),
         ))
     }
+
+    /// List broken or partially installed packages
+    /// Default implementation reports no problems
+    async fn list_problems(&self, _config: &Config) -> CoreResult<Vec<Problem>> {
+        Ok(Vec::new())
+    }
+
+    /// Disk space of one installed package in bytes, for managers whose
+    /// `list_installed` leaves `PackageInfo::size` empty
+    /// Default implementation reports the size as unknown
+    async fn installed_size(
+        &self,
+        _config: &Config,
+        _package_name: &str,
+    ) -> CoreResult<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Homepage of one installed package, for managers whose
+    /// `list_installed` leaves `PackageInfo::homepage` empty
+    /// Default implementation reports no homepage
+    async fn installed_homepage(
+        &self,
+        _config: &Config,
+        _package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Description of one package, fetched on demand since `PackageUpdate`
+    /// carries none
+    /// Default implementation reports no description
+    async fn get_package_details(
+        &self,
+        _config: &Config,
+        _package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Versions that can be installed, newest first
+    /// Default implementation lists none
+    async fn list_available_versions(
+        &self,
+        _config: &Config,
+        _package_name: &str,
+    ) -> CoreResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Age of the cached repo metadata `list_updates` reads from
+    /// Default implementation reports no cache
+    async fn metadata_age(&self, _config: &Config) -> CoreResult<Option<Duration>> {
+        Ok(None)
+    }
+
+    /// Download fresh repo metadata
+    /// Default implementation does nothing
+    async fn refresh_metadata(&self, _config: &Config) -> CoreResult<()> {
+        Ok(())
+    }
+
+    /// Whether a reboot is recommended after updates
+    /// Default implementation gives no advice
+    async fn needs_restarting(&self, _config: &Config) -> CoreResult<RestartAdvice> {
+        Ok(RestartAdvice::default())
+    }
+
+    /// Extra packages an install of `package_names` would pull in, resolved
+    /// without running it; names are already normalized
+    /// Default implementation returns an empty preview
+    async fn preview_install(
+        &self,
+        _config: &Config,
+        _package_names: &[String],
+    ) -> CoreResult<InstallPreview> {
+        Ok(InstallPreview::default())
+    }
+
+    /// Recent transactions, newest first
+    /// Default implementation reports no history
+    async fn list_transactions(&self, _config: &Config) -> CoreResult<Vec<DnfTransaction>> {
+        Ok(Vec::new())
+    }
+
+    /// Undo every transaction after `id`
+    async fn rollback_transaction(
+        &self,
+        _config: &Config,
+        _id: u32,
+        _on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        Err(CoreError::UnknownError(
+            "rollback_transaction not implemented".into(),
+        ))
+    }
+
+    /// Brewfile of everything installed
+    async fn export_brewfile(&self, _config: &Config) -> CoreResult<String> {
+        Err(CoreError::UnknownError(
+            "export_brewfile not implemented".into(),
+        ))
+    }
+
+    /// Update `package_names`, which are every pending update of the
+    /// manager, already normalized.
+    /// Default implementation updates them by name.
+    async fn update_all(
+        &self,
+        config: &Config,
+        package_names: &[String],
+        mut on_progress: &mut (dyn FnMut(InstallProgress) + Send),
+    ) -> CoreResult<()> {
+        self.manager_type()
+            .run_normalized_packages(
+                PackageAction::Update,
+                config,
+                package_names,
+                &mut on_progress,
+            )
+            .await
+    }
+
+    /// Run `action` for one package, which is how app managers work
+    /// through a batch
+    /// Default implementation reports the action as unsupported
+    async fn run_package_action(
+        &self,
+        _action: PackageAction,
+        _config: &Config,
+        _package_name: &str,
+        _on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        Err(CoreError::UnknownError(
+            "single-package action is only supported for app package managers".into(),
+        ))
+    }
+
+    /// Run `action` for all of `package_names` in one command, which is how
+    /// system managers work through a batch
+    /// Default implementation reports the action as unsupported
+    async fn run_batch_action(
+        &self,
+        _action: PackageAction,
+        _config: &Config,
+        _package_names: &[String],
+        _on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        Err(CoreError::UnknownError(
+            "batch action is only supported for system package managers".into(),
+        ))
+    }
+
+    /// Install what the Brewfile at `path` lists and is missing
+    async fn import_brewfile(
+        &self,
+        _config: &Config,
+        _path: &std::path::Path,
+        _on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        Err(CoreError::UnknownError(
+            "import_brewfile not implemented".into(),
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::{
+        collections::HashSet,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
 
     use super::{
         ALL_APP_PACKAGE_MANAGERS, ALL_PACKAGE_MANAGERS, ALL_SYSTEM_PACKAGE_MANAGERS,
-        PackageManagerType,
+        AVAILABILITY_CACHE, Config, CoreError, PackageAction, PackageManagerConfig,
+        PackageManagerType, PackageUpdate, PendingUpdates, cached_availability, manager_for,
+        names_cover_updates,
     };
 
     #[test]
@@ -477,6 +1341,15 @@ mod tests {
         assert_eq!(app_unique.len(), ALL_APP_PACKAGE_MANAGERS.len());
     }
 
+    #[test]
+    fn managers_order_system_before_app() {
+        assert!(ALL_PACKAGE_MANAGERS.is_sorted());
+
+        let last_system = ALL_SYSTEM_PACKAGE_MANAGERS.iter().max().unwrap();
+        let first_app = ALL_APP_PACKAGE_MANAGERS.iter().min().unwrap();
+        assert!(last_system < first_app);
+    }
+
     #[test]
     fn system_and_app_managers_cover_all_managers() {
         let mut union: HashSet<PackageManagerType> =
@@ -486,4 +1359,192 @@ mod tests {
         let all: HashSet<PackageManagerType> = ALL_PACKAGE_MANAGERS.iter().copied().collect();
         assert_eq!(union, all);
     }
+
+    #[test]
+    fn pending_updates_skip_packages_that_are_already_current() {
+        let requested = vec!["bat".to_owned(), "ripgrep".to_owned()];
+        // ripgrep 已被并发刷新更新，不再出现在 list_updates 中
+        let updates = vec![PackageUpdate {
+            name: "bat".to_owned(),
+            current_version: "0.23.0".to_owned(),
+            new_version: "0.24.0".to_owned(),
+            security: false,
+            download_size: None,
+        }];
+
+        assert_eq!(
+            PendingUpdates::partition(&requested, &updates),
+            PendingUpdates {
+                pending: vec!["bat".to_owned()],
+                up_to_date: vec!["ripgrep".to_owned()],
+            }
+        );
+        assert!(
+            PendingUpdates::partition(&requested, &[])
+                .pending
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn availability_is_probed_once_within_ttl() {
+        let probes = &AtomicUsize::new(0);
+        let probe = || async move {
+            probes.fetch_add(1, Ordering::SeqCst);
+            true
+        };
+
+        // Chocolatey keeps this test away from real `is_available` callers.
+        AVAILABILITY_CACHE
+            .lock()
+            .unwrap()
+            .remove(&PackageManagerType::Chocolatey);
+        assert!(cached_availability(PackageManagerType::Chocolatey, probe).await);
+        assert!(cached_availability(PackageManagerType::Chocolatey, probe).await);
+        assert_eq!(probes.load(Ordering::SeqCst), 1);
+
+        AVAILABILITY_CACHE
+            .lock()
+            .unwrap()
+            .remove(&PackageManagerType::Chocolatey);
+        assert!(cached_availability(PackageManagerType::Chocolatey, probe).await);
+        assert_eq!(probes.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn read_only_system_manager_rejects_changes() {
+        let config = Config {
+            system_manager_readonly: true,
+            ..Config::default()
+        };
+        let names = ["htop".to_owned()];
+
+        for manager in ALL_SYSTEM_PACKAGE_MANAGERS {
+            let fail = |_| panic!("{:?} ran a command while read-only", manager);
+            let results = [
+                manager
+                    .install_packages_with_progress(&config, &names, fail)
+                    .await,
+                manager
+                    .update_packages_with_progress(&config, &names, fail)
+                    .await,
+                manager
+                    .uninstall_packages_with_progress(&config, &names, fail)
+                    .await,
+                manager.rollback_transaction(&config, 1, fail).await,
+            ];
+            for result in results {
+                assert!(matches!(result, Err(CoreError::PermissionDenied(_))));
+            }
+        }
+        assert!(!config.is_read_only(PackageManagerType::Cargo));
+    }
+
+    #[tokio::test]
+    async fn empty_or_blank_package_lists_run_nothing() {
+        let config = Config::default();
+        let blank = [String::new(), "  ".to_owned()];
+
+        for manager in ALL_PACKAGE_MANAGERS {
+            for names in [&[][..], &blank[..]] {
+                let fail = |_| panic!("{:?} ran a command for {:?}", manager, names);
+                assert!(
+                    manager
+                        .update_packages_with_progress(&config, names, fail)
+                        .await
+                        .is_ok()
+                );
+                assert!(
+                    manager
+                        .update_all_with_progress(&config, names, fail)
+                        .await
+                        .is_ok()
+                );
+                assert!(
+                    manager
+                        .install_packages_with_progress(&config, names, fail)
+                        .await
+                        .is_ok()
+                );
+                assert!(
+                    manager
+                        .uninstall_packages_with_progress(&config, names, fail)
+                        .await
+                        .is_ok()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn update_all_runs_one_bulk_command_where_available() {
+        let config = Config {
+            dry_run: true,
+            ..Config::default()
+        };
+        let names = [
+            "org.gnome.Calculator".to_owned(),
+            "org.gimp.GIMP".to_owned(),
+        ];
+
+        let mut commands = Vec::new();
+        manager_for(PackageManagerType::Flatpak)
+            .update_all(&config, &names, &mut |progress| {
+                assert_eq!(progress.total, 2);
+                commands.extend(progress.command_message);
+            })
+            .await
+            .unwrap();
+        assert_eq!(commands, ["flatpak update -y"]);
+
+        let cargo = PackageManagerType::Cargo
+            .dry_run_commands(PackageAction::UpdateAll, &config, &["ripgrep".to_owned()])
+            .await
+            .unwrap();
+        assert_eq!(cargo.len(), 1);
+        assert!(cargo[0].contains("ripgrep"));
+    }
+
+    #[tokio::test]
+    async fn update_all_names_packages_when_updates_cannot_be_listed() {
+        let config = Config {
+            app_managers: vec![PackageManagerConfig {
+                manager_type: PackageManagerType::Flatpak,
+                custom_path: Some("/nonexistent/flatpak".to_owned()),
+                custom_args: Vec::new(),
+            }],
+            ..Config::default()
+        };
+        let names = [
+            "org.gnome.Calculator".to_owned(),
+            "org.gimp.GIMP".to_owned(),
+        ];
+
+        let commands = PackageManagerType::Flatpak
+            .dry_run_commands(PackageAction::UpdateAll, &config, &names)
+            .await
+            .unwrap();
+
+        assert_eq!(commands.len(), 2);
+        assert!(commands[0].ends_with("update -y org.gnome.Calculator"));
+    }
+
+    #[test]
+    fn bulk_update_needs_every_reported_update() {
+        let update = |name: &str| PackageUpdate {
+            name: name.to_owned(),
+            current_version: "1.0".to_owned(),
+            new_version: "2.0".to_owned(),
+            security: false,
+            download_size: None,
+        };
+        let updates = [update("bat"), update("ripgrep")];
+
+        assert!(names_cover_updates(
+            &["ripgrep".to_owned(), "bat".to_owned()],
+            &updates
+        ));
+        // ripgrep 2.0 is a skipped version, so it was left out
+        assert!(!names_cover_updates(&["bat".to_owned()], &updates));
+    }
 }
@@ -0,0 +1,332 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Config, CoreResult, InstallProgress, Inventory, PackageManagerType, error::CoreError,
+    manager_for, pm::go::GoManager,
+};
+
+/// Packages to reinstall on another machine, grouped by manager. Only names
+/// are kept: a fresh install takes whatever version is current there.
+/// System managers list only packages the user asked for, so dependencies
+/// are resolved again on the other machine. Go packages are listed by the
+/// package path `go install` takes, not by the binary name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub manager: PackageManagerType,
+    pub packages: Vec<String>,
+}
+
+/// What `Config::import_manifest` did, per manager.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ManifestImport {
+    /// Packages that were missing and got installed.
+    pub installed: BTreeMap<PackageManagerType, Vec<String>>,
+    /// Packages left alone because they were already installed.
+    pub already_installed: usize,
+    /// Managers in the manifest that are not configured here.
+    pub skipped_managers: Vec<PackageManagerType>,
+    /// Managers whose listing or install failed, with the error.
+    pub failed: BTreeMap<PackageManagerType, String>,
+}
+
+impl Manifest {
+    /// Package names of `inventory`, managers without packages left out.
+    pub fn from_inventory(inventory: &Inventory) -> Self {
+        let entries = inventory
+            .managers
+            .iter()
+            .filter(|(_, packages)| !packages.is_empty())
+            .map(|(manager, packages)| ManifestEntry {
+                manager: *manager,
+                packages: packages.keys().cloned().collect(),
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn package_count(&self) -> usize {
+        self.entries.iter().map(|entry| entry.packages.len()).sum()
+    }
+
+    pub async fn load(path: impl AsRef<Path>) -> CoreResult<Self> {
+        let json = tokio::fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub async fn save(&self, path: impl AsRef<Path>) -> CoreResult<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+}
+
+impl ManifestImport {
+    pub fn installed_count(&self) -> usize {
+        self.installed.values().map(Vec::len).sum()
+    }
+}
+
+impl Config {
+    /// Installed packages of every configured manager. Managers that fail
+    /// to list are left out, as in `Inventory::collect`.
+    pub async fn export_manifest(&self) -> CoreResult<Manifest> {
+        let mut manifest = Manifest::from_inventory(&Inventory::collect(self).await);
+
+        let mut failed = Vec::new();
+        for entry in &mut manifest.entries {
+            if !entry.manager.is_system_manager() {
+                continue;
+            }
+            match manager_for(entry.manager).list_user_installed(self).await {
+                Ok(mut packages) => {
+                    packages.sort();
+                    packages.dedup();
+                    entry.packages = packages;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Leaving {} out of the manifest: {}",
+                        entry.manager.name(),
+                        e
+                    );
+                    failed.push(entry.manager);
+                }
+            }
+        }
+        manifest
+            .entries
+            .retain(|entry| !failed.contains(&entry.manager) && !entry.packages.is_empty());
+
+        if let Some(entry) = manifest
+            .entries
+            .iter_mut()
+            .find(|entry| entry.manager == PackageManagerType::Go)
+        {
+            let paths = GoManager::package_paths(self).await.unwrap_or_default();
+            entry.packages = go_package_paths(std::mem::take(&mut entry.packages), &paths);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Install the packages of `manifest` that are missing here, one batch
+    /// per manager. Managers that are not configured are skipped, and one
+    /// manager failing does not stop the others; only cancelling does.
+    pub async fn import_manifest(
+        &self,
+        manifest: &Manifest,
+        mut on_progress: impl FnMut(InstallProgress),
+    ) -> CoreResult<ManifestImport> {
+        let configured: HashSet<PackageManagerType> = self
+            .system_manager
+            .iter()
+            .chain(self.app_managers.iter())
+            .map(|manager| manager.manager_type)
+            .collect();
+        let mut import = ManifestImport::default();
+
+        for entry in &manifest.entries {
+            let manager = entry.manager;
+            if !configured.contains(&manager) {
+                import.skipped_managers.push(manager);
+                continue;
+            }
+
+            let installed = match installed_names(self, manager).await {
+                Ok(installed) => installed,
+                Err(e) => {
+                    import.failed.insert(manager, e.to_string());
+                    continue;
+                }
+            };
+            let (missing, already_installed) = missing_packages(&entry.packages, &installed);
+            import.already_installed += already_installed;
+            if missing.is_empty() {
+                continue;
+            }
+
+            match manager
+                .install_packages_with_progress(self, &missing, &mut on_progress)
+                .await
+            {
+                Ok(()) => {
+                    import.installed.insert(manager, missing);
+                }
+                Err(CoreError::Cancelled) => return Err(CoreError::Cancelled),
+                Err(e) => {
+                    import.failed.insert(manager, e.to_string());
+                }
+            }
+        }
+
+        Ok(import)
+    }
+}
+
+/// Names `manager` reports as installed. Go binaries also count under their
+/// package path, which is how a manifest lists them.
+async fn installed_names(
+    config: &Config,
+    manager: PackageManagerType,
+) -> CoreResult<HashSet<String>> {
+    let mut installed: HashSet<String> = manager
+        .list_installed(config)
+        .await?
+        .into_iter()
+        .map(|package| package.name)
+        .collect();
+    if manager == PackageManagerType::Go {
+        installed.extend(GoManager::package_paths(config).await?.into_values());
+    }
+    Ok(installed)
+}
+
+/// `binaries` with each name replaced by its package path from `paths`;
+/// binaries without module info keep their name.
+fn go_package_paths(binaries: Vec<String>, paths: &HashMap<String, String>) -> Vec<String> {
+    binaries
+        .into_iter()
+        .map(|name| paths.get(&name).cloned().unwrap_or(name))
+        .collect()
+}
+
+/// Distinct non-empty `packages` not in `installed`, in manifest order,
+/// and how many were already installed.
+fn missing_packages(packages: &[String], installed: &HashSet<String>) -> (Vec<String>, usize) {
+    let mut seen = HashSet::new();
+    let mut missing = Vec::new();
+    let mut already_installed = 0;
+
+    for package in packages.iter().map(|package| package.trim()) {
+        if package.is_empty() || !seen.insert(package) {
+            continue;
+        }
+        if installed.contains(package) {
+            already_installed += 1;
+        } else {
+            missing.push(package.to_owned());
+        }
+    }
+
+    (missing, already_installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_keeps_names_of_non_empty_managers() {
+        let mut inventory = Inventory::default();
+        inventory.managers.insert(
+            PackageManagerType::Cargo,
+            BTreeMap::from([
+                ("ripgrep".to_owned(), "14.1.0".to_owned()),
+                ("bat".to_owned(), "0.24.0".to_owned()),
+            ]),
+        );
+        inventory
+            .managers
+            .insert(PackageManagerType::Npm, BTreeMap::new());
+
+        let manifest = Manifest::from_inventory(&inventory);
+
+        assert_eq!(
+            manifest.entries,
+            [ManifestEntry {
+                manager: PackageManagerType::Cargo,
+                packages: vec!["bat".to_owned(), "ripgrep".to_owned()],
+            }]
+        );
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.starts_with('['));
+        assert_eq!(serde_json::from_str::<Manifest>(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn missing_packages_skips_installed_blank_and_repeated_names() {
+        let packages = ["bat", " eza ", "ripgrep", "", "eza"].map(str::to_owned);
+        let installed = HashSet::from(["ripgrep".to_owned()]);
+
+        assert_eq!(
+            missing_packages(&packages, &installed),
+            (vec!["bat".to_owned(), "eza".to_owned()], 1)
+        );
+    }
+
+    #[test]
+    fn go_binaries_are_exported_by_package_path() {
+        let paths = HashMap::from([("gopls".to_owned(), "golang.org/x/tools/gopls".to_owned())]);
+
+        assert_eq!(
+            go_package_paths(vec!["gopls".to_owned(), "stripped".to_owned()], &paths),
+            ["golang.org/x/tools/gopls", "stripped"]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_installs_go_entries_by_package_path() {
+        let bin_dir =
+            std::env::temp_dir().join(format!("updater-manifest-go-{}", std::process::id()));
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        let config = Config {
+            dry_run: true,
+            go_bin_dir: Some(bin_dir.to_string_lossy().into_owned()),
+            app_managers: vec![crate::PackageManagerConfig {
+                manager_type: PackageManagerType::Go,
+                custom_path: None,
+                custom_args: Vec::new(),
+            }],
+            ..Config::default()
+        };
+        let manifest = Manifest {
+            entries: vec![ManifestEntry {
+                manager: PackageManagerType::Go,
+                packages: vec!["golang.org/x/tools/gopls".to_owned()],
+            }],
+        };
+        let mut commands = Vec::new();
+
+        let import = config
+            .import_manifest(&manifest, |progress| {
+                commands.extend(progress.command_message)
+            })
+            .await;
+        std::fs::remove_dir_all(&bin_dir).unwrap();
+
+        let import = import.unwrap();
+        assert_eq!(import.installed_count(), 1, "{:?}", import.failed);
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].ends_with("go install golang.org/x/tools/gopls@latest"));
+    }
+
+    #[tokio::test]
+    async fn import_skips_managers_that_are_not_configured() {
+        let manifest = Manifest {
+            entries: vec![ManifestEntry {
+                manager: PackageManagerType::Dnf,
+                packages: vec!["vim-enhanced".to_owned()],
+            }],
+        };
+
+        let import = Config::default()
+            .import_manifest(&manifest, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(import.skipped_managers, [PackageManagerType::Dnf]);
+        assert_eq!(import.installed_count(), 0);
+    }
+}
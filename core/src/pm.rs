@@ -1,5 +1,6 @@
 pub(crate) mod apt;
 pub(crate) mod cargo;
+pub(crate) mod chocolatey;
 pub(crate) mod common;
 pub(crate) mod dnf;
 pub(crate) mod flatpak;
@@ -7,5 +8,8 @@ pub(crate) mod go;
 pub(crate) mod homebrew;
 pub(crate) mod npm;
 pub(crate) mod pacman;
+pub(crate) mod pip;
+pub(crate) mod pipx;
 pub(crate) mod progress;
+pub(crate) mod snap;
 pub(crate) mod zypper;
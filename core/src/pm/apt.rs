@@ -1,32 +1,59 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
-use tokio::process::Command;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
     error::CoreError,
     pm::{
-        common::manager_command_path,
-        progress::{CommandProgressEvent, run_command_with_progress},
+        common::{
+            CommandExt, checked_package_name, limit_search_results, manager_command_path,
+            name_lines, new_command, privileged_args, privileged_removal_args,
+        },
+        progress::{CommandProgressEvent, run_privileged_with_progress},
     },
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct AptManager;
 
+const REMOVE_ARGS: &[&str] = &["remove", "-y"];
+// -y 不会回答 dpkg 的配置文件冲突提示，需显式指定保留旧配置
+const UPGRADE_ARGS: &[&str] = &[
+    "install",
+    "-y",
+    "--only-upgrade",
+    "-o",
+    "Dpkg::Options::=--force-confdef",
+    "-o",
+    "Dpkg::Options::=--force-confold",
+];
+const INSTALL_ARGS: &[&str] = &[
+    "install",
+    "-y",
+    "-o",
+    "Dpkg::Options::=--force-confdef",
+    "-o",
+    "Dpkg::Options::=--force-confold",
+];
+
 fn command_path(config: &Config) -> String {
     manager_command_path(config, PackageManagerType::Apt)
 }
 
 #[async_trait]
 impl PackageManager for AptManager {
-    async fn get_current_version(_config: &Config, package_name: &str) -> CoreResult<String> {
-        let output = Command::new("dpkg-query")
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Apt
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        let output = new_command(config, "dpkg-query")
             .arg("-W")
             .arg("-f=${Version}")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -47,11 +74,11 @@ impl PackageManager for AptManager {
         Ok(version)
     }
 
-    async fn list_installed(_config: &Config) -> CoreResult<Vec<PackageInfo>> {
-        let output = Command::new("dpkg-query")
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
+        let output = new_command(config, "dpkg-query")
             .arg("-W")
-            .arg("-f=${binary:Package}\t${Version}\t${binary:Summary}\n")
-            .output()
+            .arg("-f=${binary:Package}\t${Version}\t${Installed-Size}\t${binary:Summary}\n")
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -59,44 +86,33 @@ impl PackageManager for AptManager {
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let packages = stdout
-            .lines()
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() < 2 {
-                    return None;
-                }
+        Ok(stdout.lines().filter_map(parse_installed_line).collect())
+    }
 
-                let description = parts
-                    .get(2)
-                    .map(|desc| desc.trim())
-                    .filter(|desc| !desc.is_empty())
-                    .map(ToOwned::to_owned);
+    async fn list_user_installed(&self, config: &Config) -> CoreResult<Vec<String>> {
+        let output = new_command(config, "apt-mark")
+            .arg("showmanual")
+            .try_output()
+            .await?;
 
-                Some(PackageInfo {
-                    name: parts[0].trim().to_owned(),
-                    version: parts[1].trim().to_owned(),
-                    source: PackageManagerType::Apt,
-                    description,
-                    size: None,
-                    install_date: None,
-                    homepage: None,
-                })
-            })
-            .collect();
+        if !output.status.success() {
+            return Err(CoreError::UnknownError(
+                "apt-mark showmanual failed".to_string(),
+            ));
+        }
 
-        Ok(packages)
+        Ok(name_lines(&String::from_utf8(output.stdout)?))
     }
 
-    async fn count_installed(_config: &Config) -> CoreResult<usize> {
-        let output = Command::new("dpkg-query")
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
+        let output = new_command(config, "dpkg-query")
             .arg("-W")
             .arg("-f=${binary:Package}\n")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Self::list_installed(_config).await?.len());
+            return Ok(self.list_installed(config).await?.len());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
@@ -106,19 +122,23 @@ impl PackageManager for AptManager {
             .count())
     }
 
-    async fn search_package(_config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
-        let output = Command::new("apt-cache")
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
+        let output = new_command(config, "apt-cache")
             .arg("search")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let installed_versions = Self::installed_version_map().await?;
+        let installed_versions = Self::installed_version_map(config).await?;
 
         let packages = stdout
             .lines()
@@ -140,16 +160,81 @@ impl PackageManager for AptManager {
                     size: None,
                     install_date: None,
                     homepage: None,
+                    popularity: None,
+                    has_update_info: true,
+                    eol: None,
                 })
             })
             .collect();
 
-        Ok(packages)
+        Ok(limit_search_results(config, packages))
     }
-}
 
-impl AptManager {
-    pub async fn list_updates_with_refresh(
+    async fn installed_size(&self, config: &Config, package_name: &str) -> CoreResult<Option<u64>> {
+        let output = new_command(config, "dpkg-query")
+            .arg("-W")
+            .arg("-f=${Installed-Size}")
+            .arg(package_name)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(parse_installed_size_kib(&String::from_utf8(output.stdout)?))
+    }
+
+    async fn installed_homepage(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        let output = new_command(config, "dpkg-query")
+            .arg("-W")
+            .arg("-f=${Homepage}")
+            .arg(package_name)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let homepage = String::from_utf8(output.stdout)?.trim().to_owned();
+        Ok((!homepage.is_empty()).then_some(homepage))
+    }
+
+    async fn get_package_details(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        // 只取候选版本，即待更新版本的描述
+        let output = new_command(config, "apt-cache")
+            .env("LC_ALL", "C")
+            .args(["show", "--no-all-versions", package_name])
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(parse_show_description(&String::from_utf8(output.stdout)?))
+    }
+
+    /// Debian package names are lowercase `[a-z0-9+.-]`, optionally with an
+    /// `:arch` qualifier.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Apt, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-' | ':')
+        })
+        .map(str::to_ascii_lowercase)
+    }
+
+    async fn list_updates_with_refresh(
+        &self,
         config: &Config,
         refresh: bool,
     ) -> CoreResult<Vec<PackageUpdate>> {
@@ -157,13 +242,13 @@ impl AptManager {
 
         if refresh {
             let args = vec![path.clone(), "update".to_owned()];
-            run_command_with_progress("pkexec", &args, |_| {}).await?;
+            run_privileged_with_progress(config, &args, |_| {}).await?;
         }
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("list")
             .arg("--upgradable")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -183,7 +268,8 @@ impl AptManager {
             };
 
             if current_version == "unknown" {
-                current_version = Self::get_current_version(config, &name)
+                current_version = Self
+                    .get_current_version(config, &name)
                     .await
                     .unwrap_or_else(|_| "unknown".to_owned());
             }
@@ -192,12 +278,36 @@ impl AptManager {
                 name,
                 current_version,
                 new_version,
+                security: false,
+                download_size: None,
             });
         }
 
         Ok(updates)
     }
 
+    async fn run_batch_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_packages_with_progress(config, package_names, on_progress).await
+            }
+        }
+    }
+}
+
+impl AptManager {
     pub async fn uninstall_packages_with_progress(
         config: &Config,
         package_names: &[String],
@@ -207,11 +317,10 @@ impl AptManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-        let mut args = vec![path, "remove".to_owned(), "-y".to_owned()];
-        args.extend(package_names.iter().cloned());
+        let args =
+            privileged_removal_args(config, PackageManagerType::Apt, REMOVE_ARGS, package_names);
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn update_packages_with_progress(
@@ -223,16 +332,9 @@ impl AptManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-        let mut args = vec![
-            path,
-            "install".to_owned(),
-            "-y".to_owned(),
-            "--only-upgrade".to_owned(),
-        ];
-        args.extend(package_names.iter().cloned());
-
-        run_command_with_progress("pkexec", &args, on_progress).await
+        let args = privileged_args(config, PackageManagerType::Apt, UPGRADE_ARGS, package_names);
+
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn install_packages_with_progress(
@@ -244,18 +346,16 @@ impl AptManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-        let mut args = vec![path, "install".to_owned(), "-y".to_owned()];
-        args.extend(package_names.iter().cloned());
+        let args = privileged_args(config, PackageManagerType::Apt, INSTALL_ARGS, package_names);
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
-    async fn installed_version_map() -> CoreResult<HashMap<String, String>> {
-        let output = Command::new("dpkg-query")
+    async fn installed_version_map(config: &Config) -> CoreResult<HashMap<String, String>> {
+        let output = new_command(config, "dpkg-query")
             .arg("-W")
             .arg("-f=${binary:Package}\t${Version}\n")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -313,9 +413,64 @@ fn parse_upgradable_from(line: &str) -> Option<String> {
     Some(value.to_owned())
 }
 
+/// `package\tversion\tinstalled-size\tsummary` row of `dpkg-query -W`.
+fn parse_installed_line(line: &str) -> Option<PackageInfo> {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let description = parts
+        .get(3)
+        .map(|desc| desc.trim())
+        .filter(|desc| !desc.is_empty())
+        .map(ToOwned::to_owned);
+
+    Some(PackageInfo {
+        name: parts[0].trim().to_owned(),
+        version: parts[1].trim().to_owned(),
+        source: PackageManagerType::Apt,
+        description,
+        size: parts.get(2).and_then(|size| parse_installed_size_kib(size)),
+        install_date: None,
+        homepage: None,
+        popularity: None,
+        has_update_info: true,
+        eol: None,
+    })
+}
+
+/// `Installed-Size` of `dpkg-query` is in KiB.
+fn parse_installed_size_kib(output: &str) -> Option<u64> {
+    output.trim().parse::<u64>().ok().map(|kib| kib * 1024)
+}
+
+/// `Description` field of `apt-cache show`: the synopsis, then the
+/// indented long description where a lone ` .` marks a blank line.
+fn parse_show_description(output: &str) -> Option<String> {
+    let mut lines = output.lines();
+    let synopsis = lines.find_map(|line| {
+        line.strip_prefix("Description:")
+            .or_else(|| line.strip_prefix("Description-en:"))
+    })?;
+
+    let mut description = synopsis.trim().to_owned();
+    for line in lines.take_while(|line| line.starts_with(' ')) {
+        description.push('\n');
+        let line = line.trim();
+        if line != "." {
+            description.push_str(line);
+        }
+    }
+
+    let description = description.trim();
+    (!description.is_empty()).then(|| description.to_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
 
     #[test]
     fn parse_upgradable_line_extracts_name_and_versions() {
@@ -341,4 +496,111 @@ mod tests {
     fn parse_upgradable_line_skips_listing_header() {
         assert!(parse_upgradable_line("Listing...").is_none());
     }
+
+    #[test]
+    fn parse_upgradable_line_reads_fixture() {
+        let updates: Vec<_> = fixture("apt/list_upgradable.txt")
+            .lines()
+            .filter_map(parse_upgradable_line)
+            .collect();
+
+        assert_eq!(updates.len(), 3);
+        assert_eq!(
+            updates[1],
+            (
+                "libssl3".to_owned(),
+                "3.0.2-0ubuntu1.14".to_owned(),
+                "3.0.2-0ubuntu1.15".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn custom_args_follow_the_subcommand() {
+        let config = config_with_custom_args(PackageManagerType::Apt, &["--no-install-recommends"]);
+        let packages = ["curl".to_owned()];
+
+        assert_eq!(
+            privileged_args(&config, PackageManagerType::Apt, UPGRADE_ARGS, &packages),
+            [
+                "apt",
+                "install",
+                "-y",
+                "--only-upgrade",
+                "-o",
+                "Dpkg::Options::=--force-confdef",
+                "-o",
+                "Dpkg::Options::=--force-confold",
+                "--no-install-recommends",
+                "curl"
+            ]
+        );
+        assert_eq!(
+            privileged_removal_args(&config, PackageManagerType::Apt, REMOVE_ARGS, &packages),
+            ["apt", "remove", "-y", "curl"]
+        );
+    }
+
+    #[test]
+    fn installed_size_is_converted_from_kib() {
+        assert_eq!(parse_installed_size_kib("2048\n"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_installed_size_kib(""), None);
+    }
+
+    #[test]
+    fn installed_line_reads_size_and_summary() {
+        let package = parse_installed_line(
+            "curl\t8.5.0-2ubuntu10\t521\tcommand line tool for transferring data with URL syntax",
+        )
+        .unwrap();
+        assert_eq!(package.name, "curl");
+        assert_eq!(package.version, "8.5.0-2ubuntu10");
+        assert_eq!(package.size, Some(521 * 1024));
+        assert_eq!(
+            package.description.as_deref(),
+            Some("command line tool for transferring data with URL syntax")
+        );
+
+        // Virtual or half-removed packages have no size or summary.
+        let package = parse_installed_line("libfoo1:amd64\t1.0-1\t\t").unwrap();
+        assert_eq!(package.size, None);
+        assert_eq!(package.description, None);
+        assert!(parse_installed_line("garbage").is_none());
+    }
+
+    #[test]
+    fn show_description_keeps_synopsis_and_paragraphs() {
+        let output = "\
+Package: htop
+Version: 3.3.0-4
+Description-en: interactive processes viewer
+ Htop is an ncursed-based process viewer similar to top.
+ .
+ It supports mouse input.
+Description-md5: 2ba7c8d5
+";
+
+        assert_eq!(
+            parse_show_description(output).as_deref(),
+            Some(
+                "interactive processes viewer\n\
+                 Htop is an ncursed-based process viewer similar to top.\n\
+                 \n\
+                 It supports mouse input."
+            )
+        );
+        assert_eq!(parse_show_description("Package: htop\n"), None);
+    }
+
+    #[test]
+    fn normalize_name_lowercases_and_rejects_invalid_names() {
+        assert_eq!(AptManager.normalize_name(" LibSSL3 ").unwrap(), "libssl3");
+        assert_eq!(
+            AptManager.normalize_name("libc6:amd64").unwrap(),
+            "libc6:amd64"
+        );
+        assert!(AptManager.normalize_name("").is_err());
+        assert!(AptManager.normalize_name("-y").is_err());
+        assert!(AptManager.normalize_name("vim; rm -rf /").is_err());
+    }
 }
@@ -1,13 +1,25 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use futures::{StreamExt, stream};
 use regex::Regex;
-use tokio::process::Command;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, PendingUpdates, Problem, SearchResults,
+    cache::{CRATE_CACHE, CrateInfo, unix_now},
+    compare_versions,
+    http::{CRATES_IO_CACHE, get_with_retry, shared_client},
     pm::{
-        common::manager_command_path,
+        common::{
+            CommandExt, allow_prerelease_updates, checked_package_name, flag_args,
+            invalid_package_name, limit_search_results, manager_command_path, new_command,
+            read_bin_dir, sort_versions_newest_first,
+        },
         progress::{CommandProgressEvent, run_command_with_progress},
     },
 };
@@ -15,7 +27,29 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct CargoManager;
 
-#[derive(Debug)]
+/// Maximum concurrent crates.io requests when enriching installed crates
+/// or checking them for updates.
+const CRATE_INFO_CONCURRENCY: usize = 8;
+
+/// Binaries rustup places in `~/.cargo/bin` that `cargo install` never tracks.
+const RUSTUP_PROXIES: &[&str] = &[
+    "cargo",
+    "cargo-clippy",
+    "cargo-fmt",
+    "cargo-miri",
+    "clippy-driver",
+    "rls",
+    "rust-analyzer",
+    "rust-gdb",
+    "rust-gdbgui",
+    "rust-lldb",
+    "rustc",
+    "rustdoc",
+    "rustfmt",
+    "rustup",
+];
+
+#[derive(Debug, Clone)]
 struct InstalledCrate {
     name: String,
     version: String,
@@ -28,48 +62,91 @@ fn command_path(config: &Config) -> String {
 
 #[async_trait]
 impl PackageManager for CargoManager {
-    async fn list_updates(config: &Config) -> CoreResult<Vec<PackageUpdate>> {
-        let path = command_path(config);
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Cargo
+    }
 
-        let install_output = Command::new(&path)
-            .arg("install")
-            .arg("--list")
-            .output()
-            .await?;
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+        let installed = Self::list_installed_crates(config).await?;
 
-        if !install_output.status.success() {
-            return Err(crate::error::CoreError::UnknownError(
-                "cargo install --list failed".into(),
-            ));
-        }
+        // A failed lookup only drops that crate from the result, so one
+        // retry is enough
+        let config = &Config {
+            http_retries: config.http_retries.min(1),
+            ..config.clone()
+        };
+        let client = shared_client(config)?;
+        let mut updates: Vec<PackageUpdate> = stream::iter(installed)
+            .map(|inst| {
+                let client = client.clone();
+                async move {
+                    Self::find_update(config, &client, &inst)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::debug!("Cargo: no latest version for {}: {}", inst.name, e);
+                            None
+                        })
+                }
+            })
+            .buffer_unordered(CRATE_INFO_CONCURRENCY)
+            .filter_map(std::future::ready)
+            .collect()
+            .await;
 
-        let stdout = String::from_utf8(install_output.stdout)?;
+        updates.sort_by(|a, b| a.name.cmp(&b.name));
 
-        let installed = Self::parse_cargo_install_list(&stdout);
+        Ok(updates)
+    }
 
-        let mut updates: Vec<PackageUpdate> = Vec::new();
-        for inst in installed {
-            if let Ok(latest_version) = Self::get_latest_version(&inst.name).await
-                && latest_version != inst.version
-            {
-                updates.push(PackageUpdate {
-                    name: inst.name,
-                    current_version: inst.version,
-                    new_version: latest_version,
-                });
-            }
-        }
+    /// Look up only the requested crates. A failed lookup keeps the crate
+    /// pending, so a network error never skips an update.
+    async fn pending_updates(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<PendingUpdates> {
+        let installed = Self::list_installed_crates(config).await?;
+        let client = shared_client(config)?;
+
+        let lookups: Vec<(String, Option<InstalledCrate>)> = package_names
+            .iter()
+            .map(|name| {
+                let inst = installed.iter().find(|inst| &inst.name == name).cloned();
+                (name.clone(), inst)
+            })
+            .collect();
+
+        let checks = stream::iter(lookups)
+            .map(|(name, inst)| {
+                let client = client.clone();
+                async move {
+                    let still_pending = match inst {
+                        Some(inst) => match Self::find_update(config, &client, &inst).await {
+                            Ok(update) => update.is_some(),
+                            Err(e) => {
+                                log::debug!("Cargo: no latest version for {}: {}", name, e);
+                                true
+                            }
+                        },
+                        None => true,
+                    };
+                    (name, still_pending)
+                }
+            })
+            .buffered(CRATE_INFO_CONCURRENCY)
+            .collect()
+            .await;
 
-        Ok(updates)
+        Ok(PendingUpdates::from_checks(checks))
     }
 
-    async fn get_current_version(config: &Config, package_name: &str) -> CoreResult<String> {
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
         let path = command_path(config);
 
-        let install_output = Command::new(&path)
+        let install_output = new_command(config, &path)
             .arg("install")
             .arg("--list")
-            .output()
+            .try_output()
             .await?;
 
         if !install_output.status.success() {
@@ -93,13 +170,13 @@ impl PackageManager for CargoManager {
         )))
     }
 
-    async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>> {
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
         let path = command_path(config);
 
-        let install_output = Command::new(&path)
+        let install_output = new_command(config, &path)
             .arg("install")
             .arg("--list")
-            .output()
+            .try_output()
             .await?;
 
         if !install_output.status.success() {
@@ -111,35 +188,61 @@ impl PackageManager for CargoManager {
         let stdout = String::from_utf8(install_output.stdout)?;
         let installed = Self::parse_cargo_install_list(&stdout);
 
-        // Batch fetch crate info from crates.io
-        let mut packages = Vec::new();
-        for crate_info in installed {
-            let (description, homepage) = match Self::get_crate_info(&crate_info.name).await {
-                Ok((desc, home)) => (desc, home),
-                Err(_) => (None, None),
-            };
-
-            packages.push(PackageInfo {
-                name: crate_info.name,
-                version: crate_info.version,
-                source: PackageManagerType::Cargo,
-                description,
-                size: None,
-                install_date: None,
-                homepage,
-            });
-        }
+        // Name/version come from the local list; crates.io only enriches
+        // description/homepage, so failures there never fail the listing
+        // and are not retried, keeping an offline listing fast.
+        let config = &Config {
+            http_retries: 0,
+            ..config.clone()
+        };
+        let client = shared_client(config)?;
+        let mut packages: Vec<PackageInfo> = stream::iter(installed)
+            .map(|crate_info| {
+                let client = client.clone();
+                async move {
+                    let (description, homepage) =
+                        match Self::get_crate_info(config, &client, &crate_info.name).await {
+                            Ok((desc, home)) => (desc, home),
+                            Err(e) => {
+                                log::debug!(
+                                    "Cargo: skipping crates.io info for {}: {}",
+                                    crate_info.name,
+                                    e
+                                );
+                                (None, None)
+                            }
+                        };
+
+                    PackageInfo {
+                        name: crate_info.name,
+                        version: crate_info.version,
+                        source: PackageManagerType::Cargo,
+                        description,
+                        size: None,
+                        install_date: None,
+                        homepage,
+                        popularity: None,
+                        has_update_info: true,
+                        eol: None,
+                    }
+                }
+            })
+            .buffer_unordered(CRATE_INFO_CONCURRENCY)
+            .collect()
+            .await;
+
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
 
         Ok(packages)
     }
 
-    async fn count_installed(config: &Config) -> CoreResult<usize> {
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
         let path = command_path(config);
 
-        let install_output = Command::new(&path)
+        let install_output = new_command(config, &path)
             .arg("install")
             .arg("--list")
-            .output()
+            .try_output()
             .await?;
 
         if !install_output.status.success() {
@@ -152,31 +255,66 @@ impl PackageManager for CargoManager {
         Ok(Self::parse_cargo_install_list(&stdout).len())
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn list_problems(&self, config: &Config) -> CoreResult<Vec<Problem>> {
+        let path = command_path(config);
+
+        let install_output = new_command(config, &path)
+            .arg("install")
+            .arg("--list")
+            .try_output()
+            .await?;
+
+        if !install_output.status.success() {
+            return Err(crate::error::CoreError::UnknownError(
+                "cargo install --list failed".into(),
+            ));
+        }
+
+        let stdout = String::from_utf8(install_output.stdout)?;
+        let bin_dir = Self::cargo_bin_dir();
+
+        let bin_files: Vec<String> = read_bin_dir(&bin_dir)
+            .await?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let recorded_bins = Self::recorded_bins(&Self::cargo_home()).await;
+
+        Ok(Self::find_install_problems(
+            &stdout,
+            &bin_files,
+            &recorded_bins,
+        ))
+    }
+
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         // 使用 crates.io API 搜索
         let encoded_name = package_name.replace(' ', "%20");
         let url = format!(
-            "https://crates.io/api/v1/crates?page=1&per_page=10&q={}",
+            "https://crates.io/api/v1/crates?page=1&per_page={}&q={}",
+            Self::search_page_size(config),
             encoded_name
         );
 
         log::debug!("Cargo search: querying URL: {}", url);
 
         // crates.io API 要求提供 User-Agent 头
-        let client = reqwest::Client::builder()
-            .user_agent("updater/0.1.0 (https://github.com/Yiki21/updater)")
-            .build()?;
+        let client = shared_client(config)?;
 
-        let resp = client.get(&url).send().await?;
+        let resp = get_with_retry(config, &client, &url).await?;
 
         log::debug!("Cargo search: got response status: {}", resp.status());
 
+        // A failed search must not look like a search without matches
         if !resp.status().is_success() {
-            log::warn!(
-                "Cargo search: HTTP request failed with status {}",
+            return Err(crate::error::CoreError::RequestError(format!(
+                "crates.io search returned {}",
                 resp.status()
-            );
-            return Ok(Vec::new());
+            )));
         }
 
         let search_result: serde_json::Value = resp.json().await?;
@@ -206,6 +344,9 @@ impl PackageManager for CargoManager {
                         size: None,
                         install_date: None,
                         homepage,
+                        popularity: crate_info["downloads"].as_u64(),
+                        has_update_info: true,
+                        eol: None,
                     });
                 }
             }
@@ -214,15 +355,143 @@ impl PackageManager for CargoManager {
         }
 
         log::debug!("Cargo search: returning {} packages", packages.len());
-        Ok(packages)
+        let mut results = limit_search_results(config, packages);
+        // crates.io reports the full match count, not just the current page
+        if let Some(total) = search_result["meta"]["total"].as_u64() {
+            results.total = results.total.max(total as usize);
+        }
+        Ok(results)
+    }
+
+    /// Published, non-yanked versions from crates.io, newest first.
+    async fn list_available_versions(
+        &self,
+        config: &Config,
+        crate_name: &str,
+    ) -> CoreResult<Vec<String>> {
+        let url = format!("https://crates.io/api/v1/crates/{}/versions", crate_name);
+        let resp = get_with_retry(config, &shared_client(config)?, &url).await?;
+
+        if !resp.status().is_success() {
+            log::debug!(
+                "crates.io versions lookup for {} returned {}",
+                crate_name,
+                resp.status()
+            );
+            return Ok(Vec::new());
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        Ok(sort_versions_newest_first(Self::parse_crate_versions(
+            &json,
+        )))
+    }
+
+    /// crates.io names: ASCII letters, digits, `-` and `_`, starting with a
+    /// letter, at most 64 characters. Case is kept, as `cargo uninstall`
+    /// needs the installed spelling.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        let name = checked_package_name(PackageManagerType::Cargo, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '-' | '_')
+        })?;
+
+        let reason = if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            Some("crate names must start with a letter")
+        } else if name.len() > 64 {
+            Some("crate names are at most 64 characters")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => Err(invalid_package_name(
+                PackageManagerType::Cargo,
+                package_name,
+                reason,
+            )),
+            None => Ok(name.to_owned()),
+        }
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
     }
 }
 
 impl CargoManager {
+    /// Crates recorded by `cargo install --list`.
+    async fn list_installed_crates(config: &Config) -> CoreResult<Vec<InstalledCrate>> {
+        let path = command_path(config);
+
+        let install_output = new_command(config, &path)
+            .arg("install")
+            .arg("--list")
+            .try_output()
+            .await?;
+
+        if !install_output.status.success() {
+            return Err(crate::error::CoreError::UnknownError(
+                "cargo install --list failed".into(),
+            ));
+        }
+
+        let stdout = String::from_utf8(install_output.stdout)?;
+
+        Ok(Self::parse_cargo_install_list(&stdout))
+    }
+
+    /// Update of `inst` from crates.io, or `None` when it is current.
+    async fn find_update(
+        config: &Config,
+        client: &reqwest::Client,
+        inst: &InstalledCrate,
+    ) -> CoreResult<Option<PackageUpdate>> {
+        let include_prereleases = allow_prerelease_updates(config, &inst.version);
+        let latest_version =
+            Self::get_latest_version(config, client, &inst.name, include_prereleases).await?;
+
+        // A git or local install may be ahead of crates.io
+        Ok(compare_versions(&inst.version, &latest_version)
+            .is_lt()
+            .then(|| PackageUpdate {
+                name: inst.name.clone(),
+                current_version: inst.version.clone(),
+                new_version: latest_version,
+                security: false,
+                download_size: None,
+            }))
+    }
+
+    /// crates.io caps `per_page` at 100.
+    const MAX_SEARCH_PAGE_SIZE: usize = 100;
+
+    fn search_page_size(config: &Config) -> usize {
+        match config.search_result_limit {
+            0 => Self::MAX_SEARCH_PAGE_SIZE,
+            limit => limit.min(Self::MAX_SEARCH_PAGE_SIZE),
+        }
+    }
+
     async fn get_installed_versions(config: &Config) -> HashMap<String, String> {
         let path = command_path(config);
 
-        let output = match Command::new(&path)
+        let output = match new_command(config, &path)
             .arg("install")
             .arg("--list")
             .output()
@@ -256,7 +525,7 @@ impl CargoManager {
 
         let args = vec!["uninstall".to_string(), package_name.to_owned()];
 
-        run_command_with_progress(&path, &args, on_progress).await
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     pub async fn update_package_with_progress(
@@ -264,84 +533,198 @@ impl CargoManager {
         package_name: &str,
         on_progress: impl FnMut(CommandProgressEvent),
     ) -> CoreResult<()> {
-        let path = command_path(config);
-
-        let args = vec![
-            "install".to_string(),
-            "--force".to_string(),
-            package_name.to_owned(),
-        ];
-
-        run_command_with_progress(&path, &args, on_progress).await
+        Self::install_with_fallback(config, package_name, true, on_progress).await
     }
 
     pub async fn install_package_with_progress(
         config: &Config,
         package_name: &str,
         on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        Self::install_with_fallback(config, package_name, false, on_progress).await
+    }
+
+    /// Install via `cargo binstall` when preferred and available, falling
+    /// back to compiling with `cargo install` if binstall fails.
+    async fn install_with_fallback(
+        config: &Config,
+        package_name: &str,
+        force: bool,
+        mut on_progress: impl FnMut(CommandProgressEvent),
     ) -> CoreResult<()> {
         let path = command_path(config);
+        let binstall_available = config.prefer_binstall && Self::binstall_available(config).await;
+
+        if Self::use_binstall(config, binstall_available) {
+            let args = Self::install_args(package_name, force, true, &[]);
+            match run_command_with_progress(config, &path, &args, &mut on_progress).await {
+                Ok(()) => return Ok(()),
+                Err(e) => log::warn!(
+                    "cargo binstall {} failed, falling back to cargo install: {}",
+                    package_name,
+                    e
+                ),
+            }
+        }
 
-        let args = vec!["install".to_string(), package_name.to_owned()];
+        let flags = [
+            config.cargo_install_flags.as_slice(),
+            config.custom_args(PackageManagerType::Cargo),
+        ]
+        .concat();
+        let args = Self::install_args(package_name, force, false, &flags);
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
 
-        run_command_with_progress(&path, &args, on_progress).await
+    /// Whether the `cargo binstall` subcommand can be run.
+    async fn binstall_available(config: &Config) -> bool {
+        new_command(config, command_path(config))
+            .args(["binstall", "-V"])
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success())
     }
 
-    /// get crate info from crates.io API
-    async fn get_crate_info(crate_name: &str) -> CoreResult<(Option<String>, Option<String>)> {
-        let client = reqwest::Client::builder()
-            .user_agent("updater/0.1.0 (https://github.com/Yiki21/updater)")
-            .build()?;
-
-        let resp = client
-            .get(format!("https://crates.io/api/v1/crates/{}", crate_name))
-            .send()
-            .await?;
+    fn use_binstall(config: &Config, binstall_available: bool) -> bool {
+        config.prefer_binstall && binstall_available
+    }
 
-        if !resp.status().is_success() {
-            return Ok((None, None));
+    /// `flags` are `Config::cargo_install_flags` and the custom arguments;
+    /// binstall takes none of them since it rejects some (e.g. `--frozen`).
+    fn install_args(
+        package_name: &str,
+        force: bool,
+        binstall: bool,
+        flags: &[String],
+    ) -> Vec<String> {
+        let mut args = if binstall {
+            // -y 跳过 binstall 的交互确认
+            vec!["binstall".to_string(), "-y".to_string()]
+        } else {
+            vec!["install".to_string()]
+        };
+        if force {
+            args.push("--force".to_string());
         }
+        args.extend(flag_args(flags));
+        args.push(package_name.to_owned());
+        args
+    }
 
-        let crate_info: serde_json::Value = resp.json().await?;
+    /// crates.io `/crates/{name}` response, cached for the session.
+    /// `None` when crates.io doesn't know the crate; other failures, after
+    /// retries, are errors.
+    async fn fetch_crate_json(
+        config: &Config,
+        client: &reqwest::Client,
+        crate_name: &str,
+    ) -> CoreResult<Option<serde_json::Value>> {
+        CRATES_IO_CACHE
+            .get_or_fetch(crate_name, || async {
+                let url = format!("https://crates.io/api/v1/crates/{}", crate_name);
+                let resp = get_with_retry(config, client, &url).await?;
+
+                if resp.status() == reqwest::StatusCode::NOT_FOUND {
+                    log::debug!("crates.io has no crate {}", crate_name);
+                    return Ok(None);
+                }
 
-        let description = crate_info["crate"]["description"]
-            .as_str()
-            .map(|s| s.to_string());
+                Ok(Some(resp.error_for_status()?.json().await?))
+            })
+            .await
+    }
 
-        let homepage = crate_info["crate"]["homepage"]
-            .as_str()
-            .or_else(|| crate_info["crate"]["repository"].as_str())
-            .map(|s| s.to_string());
+    fn parse_crate_versions(json: &serde_json::Value) -> Vec<String> {
+        json["versions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|version| !version["yanked"].as_bool().unwrap_or(false))
+            .filter_map(|version| version["num"].as_str().map(str::to_owned))
+            .collect()
+    }
+
+    /// Info on `crate_name`, from `CRATE_CACHE` while younger than
+    /// `Config::crate_cache_ttl_hours`, otherwise from crates.io.
+    /// `None` when crates.io doesn't know the crate.
+    async fn crate_info(
+        config: &Config,
+        client: &reqwest::Client,
+        crate_name: &str,
+    ) -> CoreResult<Option<CrateInfo>> {
+        if let Some(ttl) = Self::crate_cache_ttl(config)
+            && let Some(info) = CRATE_CACHE.get(crate_name, ttl).await
+        {
+            return Ok(Some(info));
+        }
 
-        Ok((description, homepage))
+        Self::fetch_crate_info(config, client, crate_name).await
     }
 
-    /// Get latest version of a crate from crates.io
-    async fn get_latest_version(package_name: &str) -> CoreResult<String> {
-        let client = reqwest::Client::builder()
-            .user_agent("updater/0.1.0 (https://github.com/Yiki21/updater)")
-            .build()?;
-
-        let resp = client
-            .get(format!("https://crates.io/api/v1/crates/{}", package_name))
-            .send()
-            .await?;
+    /// Info on `crate_name` straight from crates.io, bypassing `CRATE_CACHE`
+    /// but refreshing it. Version checks use this so a cached
+    /// `max_version` never hides a new release.
+    async fn fetch_crate_info(
+        config: &Config,
+        client: &reqwest::Client,
+        crate_name: &str,
+    ) -> CoreResult<Option<CrateInfo>> {
+        let Some(json) = Self::fetch_crate_json(config, client, crate_name).await? else {
+            return Ok(None);
+        };
+        let info = Self::parse_crate_info(&json);
+        if Self::crate_cache_ttl(config).is_some() {
+            CRATE_CACHE.insert(crate_name, info.clone()).await;
+        }
+        Ok(Some(info))
+    }
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read body".to_string());
-            return Err(crate::error::CoreError::UnknownError(format!(
-                "Failed to fetch crate info for {} (status: {}): {}",
-                package_name, status, body
-            )));
+    /// `Config::crate_cache_ttl_hours`, `None` when the cache is disabled.
+    fn crate_cache_ttl(config: &Config) -> Option<Duration> {
+        let ttl = Duration::from_secs(config.crate_cache_ttl_hours.saturating_mul(60 * 60));
+        (!ttl.is_zero()).then_some(ttl)
+    }
+
+    /// The parts of a crates.io `/crates/{name}` response worth caching.
+    fn parse_crate_info(json: &serde_json::Value) -> CrateInfo {
+        let field = |key: &str| json["crate"][key].as_str().map(str::to_owned);
+
+        CrateInfo {
+            description: field("description"),
+            homepage: field("homepage").or_else(|| field("repository")),
+            max_version: field("max_version"),
+            max_stable_version: field("max_stable_version"),
+            fetched_at: unix_now(),
         }
+    }
+
+    /// get crate info from crates.io API
+    async fn get_crate_info(
+        config: &Config,
+        client: &reqwest::Client,
+        crate_name: &str,
+    ) -> CoreResult<(Option<String>, Option<String>)> {
+        Ok(Self::crate_info(config, client, crate_name)
+            .await?
+            .map(|info| (info.description, info.homepage))
+            .unwrap_or_default())
+    }
 
-        let crate_info: serde_json::Value = resp.json().await?;
-        if let Some(version) = crate_info["crate"]["max_version"].as_str() {
-            Ok(version.to_owned())
+    /// Get latest version of a crate from crates.io
+    async fn get_latest_version(
+        config: &Config,
+        client: &reqwest::Client,
+        package_name: &str,
+        include_prereleases: bool,
+    ) -> CoreResult<String> {
+        let Some(crate_info) = Self::fetch_crate_info(config, client, package_name).await? else {
+            return Err(crate::error::CoreError::UnknownError(format!(
+                "Failed to fetch crate info for {}",
+                package_name
+            )));
+        };
+        if let Some(version) = Self::select_latest_version(&crate_info, include_prereleases) {
+            Ok(version)
         } else {
             Err(crate::error::CoreError::UnknownError(format!(
                 "Version info not found for crate {}",
@@ -350,8 +733,142 @@ impl CargoManager {
         }
     }
 
+    /// `max_version` may be a pre-release; `max_stable_version` never is
+    fn select_latest_version(crate_info: &CrateInfo, include_prereleases: bool) -> Option<String> {
+        if include_prereleases {
+            crate_info.max_version.clone()
+        } else {
+            crate_info.max_stable_version.clone()
+        }
+    }
+
+    /// `$CARGO_HOME`, defaulting to `~/.cargo`
+    fn cargo_home() -> PathBuf {
+        if let Ok(cargo_home) = std::env::var("CARGO_HOME") {
+            return PathBuf::from(cargo_home);
+        }
+
+        directories_next::UserDirs::new()
+            .map(|dirs| dirs.home_dir().join(".cargo"))
+            .unwrap_or_else(|| PathBuf::from(".cargo"))
+    }
+
+    /// `$CARGO_HOME/bin`, defaulting to `~/.cargo/bin`
+    fn cargo_bin_dir() -> PathBuf {
+        Self::cargo_home().join("bin")
+    }
+
+    /// Binaries cargo's install metadata in `cargo_home` records, from
+    /// `.crates2.json` and `.crates.toml`. Unreadable files record nothing.
+    async fn recorded_bins(cargo_home: &Path) -> HashSet<String> {
+        let mut bins = HashSet::new();
+
+        if let Ok(json) = tokio::fs::read_to_string(cargo_home.join(".crates2.json")).await {
+            bins.extend(Self::parse_crates2_bins(&json));
+        }
+        if let Ok(toml) = tokio::fs::read_to_string(cargo_home.join(".crates.toml")).await {
+            bins.extend(Self::parse_crates_toml_bins(&toml));
+        }
+
+        bins
+    }
+
+    /// `bins` of every entry in `.crates2.json`'s `installs` map.
+    fn parse_crates2_bins(json: &str) -> Vec<String> {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(json) else {
+            return Vec::new();
+        };
+
+        json["installs"]
+            .as_object()
+            .into_iter()
+            .flat_map(|installs| installs.values())
+            .flat_map(|install| install["bins"].as_array().into_iter().flatten())
+            .filter_map(|bin| bin.as_str().map(str::to_owned))
+            .collect()
+    }
+
+    /// Binary names in `.crates.toml`, whose `[v1]` table maps each install
+    /// to an array of them: `"rg-pkg 1.0.0 (registry+...)" = ["rg"]`.
+    fn parse_crates_toml_bins(toml: &str) -> Vec<String> {
+        let quoted = Regex::new(r#""([^"]+)""#).unwrap();
+
+        toml.lines()
+            .filter_map(|line| line.split_once("= [").map(|(_, bins)| bins))
+            .flat_map(|bins| {
+                quoted
+                    .captures_iter(bins)
+                    .map(|caps| caps[1].to_owned())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Compare `cargo install --list` with the files in the bin directory:
+    /// listed binaries that are gone point at a half-finished install,
+    /// unlisted binaries cargo's metadata still records were left behind by
+    /// an interrupted one. Binaries cargo never recorded are someone else's.
+    fn find_install_problems(
+        install_list: &str,
+        bin_files: &[String],
+        recorded_bins: &HashSet<String>,
+    ) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        let bin_files: HashSet<&str> = bin_files
+            .iter()
+            .map(|file| {
+                file.strip_suffix(std::env::consts::EXE_SUFFIX)
+                    .unwrap_or(file)
+            })
+            .collect();
+
+        for crate_info in Self::parse_cargo_install_list(install_list) {
+            for bin in &crate_info.bins {
+                if !bin_files.contains(bin.as_str()) {
+                    problems.push(Problem {
+                        manager: PackageManagerType::Cargo,
+                        package: crate_info.name.clone(),
+                        description: format!("Binary `{}` is listed but missing", bin),
+                        suggested_fix: Some(format!("cargo install --force {}", crate_info.name)),
+                    });
+                }
+            }
+        }
+
+        // Include bins of local-path installs, which the crate parser skips.
+        let listed_bins: HashSet<&str> = install_list
+            .lines()
+            .filter(|line| line.starts_with(char::is_whitespace))
+            .map(str::trim)
+            .collect();
+
+        let mut untracked: Vec<&str> = bin_files
+            .into_iter()
+            .filter(|bin| {
+                recorded_bins.contains(*bin)
+                    && !listed_bins.contains(bin)
+                    && !RUSTUP_PROXIES.contains(bin)
+            })
+            .collect();
+        untracked.sort_unstable();
+
+        problems.extend(untracked.into_iter().map(|bin| Problem {
+            manager: PackageManagerType::Cargo,
+            package: bin.to_owned(),
+            description: "Binary is not tracked by cargo install".to_owned(),
+            suggested_fix: Some(format!(
+                "Reinstall the crate providing `{}` with cargo install --force, or delete the file",
+                bin
+            )),
+        }));
+
+        problems
+    }
+
     fn parse_cargo_install_list(input: &str) -> Vec<InstalledCrate> {
-        let crate_line = Regex::new(r"^(\S+)\s+v([\d\.]+):$").unwrap();
+        let crate_line = Regex::new(r"^(\S+)\s+v(\S+):$").unwrap();
+        // 从本地路径或 git 安装的 crate 无法通过 crates.io 更新，跳过
+        let other_source_line = Regex::new(r"^\S+\s+v\S+\s+\(.+\):$").unwrap();
         let bin_line = Regex::new(r"^\s+(\S+)").unwrap();
 
         let mut result = Vec::new();
@@ -367,6 +884,10 @@ impl CargoManager {
                     version: caps[2].to_string(),
                     bins: Vec::new(),
                 });
+            } else if other_source_line.is_match(line) {
+                if let Some(c) = current_crate.take() {
+                    result.push(c);
+                }
             } else if let Some(caps) = bin_line.captures(line)
                 && let Some(ref mut c) = current_crate
             {
@@ -385,6 +906,7 @@ impl CargoManager {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::pm::common::fixture;
 
     #[test]
     fn test_parse_cargo_install_list() {
@@ -429,6 +951,24 @@ starship v1.24.2:
         assert_eq!(crates[6].bins, vec!["sea", "sea-orm-cli"]);
     }
 
+    #[test]
+    fn test_parse_cargo_install_list_fixture() {
+        let crates = CargoManager::parse_cargo_install_list(&fixture("cargo/install_list.txt"));
+
+        let names: Vec<_> = crates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["bat", "cargo-binstall", "ripgrep", "taplo-cli", "zellij"]
+        );
+        // 路径安装的 crate 的二进制不能归到上一个 crate
+        assert_eq!(crates[1].bins, vec!["cargo-binstall"]);
+        assert_eq!(crates[2].version, "14.1.0");
+        assert_eq!(crates[2].bins, vec!["rg"]);
+        // 预发布版本同样是一个 crate，二进制不能归到上一个 crate
+        assert_eq!(crates[3].version, "0.10.0-beta.1");
+        assert_eq!(crates[3].bins, vec!["taplo"]);
+    }
+
     #[test]
     fn test_parse_empty_list() {
         let input = "";
@@ -493,12 +1033,145 @@ ripgrep v14.1.0:
         assert_eq!(crates[1].name, "ripgrep");
     }
 
+    #[test]
+    fn test_select_latest_version_respects_prerelease_flag() {
+        // stable 1.4.0 installed, 2.0.0-beta.1 published
+        let crate_info = CargoManager::parse_crate_info(&serde_json::json!({
+            "crate": {
+                "max_version": "2.0.0-beta.1",
+                "max_stable_version": "1.5.0",
+            }
+        }));
+
+        assert_eq!(
+            CargoManager::select_latest_version(&crate_info, false),
+            Some("1.5.0".to_owned())
+        );
+        assert_eq!(
+            CargoManager::select_latest_version(&crate_info, true),
+            Some("2.0.0-beta.1".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_crate_versions_skips_yanked() {
+        let json = serde_json::json!({
+            "versions": [
+                { "num": "14.1.1", "yanked": false },
+                { "num": "14.1.0", "yanked": true },
+                { "num": "13.0.0", "yanked": false },
+            ]
+        });
+
+        assert_eq!(
+            CargoManager::parse_crate_versions(&json),
+            ["14.1.1", "13.0.0"]
+        );
+        assert!(CargoManager::parse_crate_versions(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_install_command_selection() {
+        let config = Config::default();
+        assert!(CargoManager::use_binstall(&config, true));
+        assert!(!CargoManager::use_binstall(&config, false));
+
+        let opted_out = Config {
+            prefer_binstall: false,
+            ..Config::default()
+        };
+        assert!(!CargoManager::use_binstall(&opted_out, true));
+
+        assert_eq!(
+            CargoManager::install_args("ripgrep", true, true, &[]),
+            ["binstall", "-y", "--force", "ripgrep"]
+        );
+        assert_eq!(
+            CargoManager::install_args("ripgrep", false, false, &[]),
+            ["install", "ripgrep"]
+        );
+    }
+
+    #[test]
+    fn test_install_args_append_configured_flags_in_order() {
+        let flags = [
+            "--locked".to_owned(),
+            "  ".to_owned(),
+            "bat".to_owned(),
+            " --features pcre2 ".to_owned(),
+        ];
+
+        assert_eq!(
+            CargoManager::install_args("ripgrep", true, false, &flags),
+            [
+                "install",
+                "--force",
+                "--locked",
+                "--features",
+                "pcre2",
+                "ripgrep"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_install_problems() {
+        let input = r#"ripgrep v14.1.0:
+    rg
+local-tool v1.0.0 (/home/user/local-tool):
+    local-tool
+eza v0.20.0:
+    eza
+"#;
+        let bin_files: Vec<String> = ["rg", "local-tool", "cargo", "rustup", "leftover", "stray"]
+            .iter()
+            .map(|file| file.to_string())
+            .collect();
+        let recorded_bins = ["rg", "local-tool", "eza", "leftover"]
+            .map(str::to_owned)
+            .into();
+
+        let problems = CargoManager::find_install_problems(input, &bin_files, &recorded_bins);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].package, "eza");
+        assert_eq!(
+            problems[0].suggested_fix.as_deref(),
+            Some("cargo install --force eza")
+        );
+        assert_eq!(problems[1].package, "leftover");
+    }
+
+    #[test]
+    fn test_parse_recorded_bins() {
+        let crates2 = r#"{"installs":{
+            "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)":
+                {"version_req":null,"bins":["rg"],"features":[],"all_features":false,
+                 "no_default_features":false,"profile":"release","target":"x86_64-unknown-linux-gnu",
+                 "rustc":"rustc 1.80.0"},
+            "eza 0.20.0 (registry+https://github.com/rust-lang/crates.io-index)":
+                {"bins":["eza"]}
+        }}"#;
+        let mut bins = CargoManager::parse_crates2_bins(crates2);
+        bins.sort();
+        assert_eq!(bins, ["eza", "rg"]);
+
+        let crates_toml = r#"[v1]
+"cargo-edit 0.12.2 (registry+https://github.com/rust-lang/crates.io-index)" = ["cargo-add", "cargo-rm"]
+"ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)" = ["rg"]
+"#;
+        assert_eq!(
+            CargoManager::parse_crates_toml_bins(crates_toml),
+            ["cargo-add", "cargo-rm", "rg"]
+        );
+        assert!(CargoManager::parse_crates2_bins("not json").is_empty());
+    }
+
     #[tokio::test]
     async fn test_search_package_yazi() {
         let _ = env_logger::builder().is_test(true).try_init();
         let config = crate::Config::default();
-        match CargoManager::search_package(&config, "yazi").await {
-            Ok(packages) => {
+        match CargoManager.search_package(&config, "yazi").await {
+            Ok(SearchResults { packages, .. }) => {
                 println!("Found {} packages for 'yazi':", packages.len());
                 assert!(!packages.is_empty(), "Should find at least one package");
                 for (i, pkg) in packages.iter().take(5).enumerate() {
@@ -523,8 +1196,8 @@ ripgrep v14.1.0:
     async fn test_search_package_eza() {
         let _ = env_logger::builder().is_test(true).try_init();
         let config = crate::Config::default();
-        match CargoManager::search_package(&config, "eza").await {
-            Ok(packages) => {
+        match CargoManager.search_package(&config, "eza").await {
+            Ok(SearchResults { packages, .. }) => {
                 println!("Found {} packages for 'eza':", packages.len());
                 assert!(!packages.is_empty(), "Should find at least one package");
                 for (i, pkg) in packages.iter().take(5).enumerate() {
@@ -551,8 +1224,8 @@ ripgrep v14.1.0:
     async fn test_search_package_returns_install_state() {
         let _ = env_logger::builder().is_test(true).try_init();
         let config = crate::Config::default();
-        match CargoManager::search_package(&config, "serde").await {
-            Ok(packages) => {
+        match CargoManager.search_package(&config, "serde").await {
+            Ok(SearchResults { packages, .. }) => {
                 println!("Found {} packages for 'serde':", packages.len());
                 assert!(!packages.is_empty(), "Should find serde packages");
 
@@ -575,8 +1248,8 @@ ripgrep v14.1.0:
     async fn test_search_empty_query() {
         let config = crate::Config::default();
         // 空查询应该返回一些结果（crates.io 会返回流行的包）
-        match CargoManager::search_package(&config, "").await {
-            Ok(packages) => {
+        match CargoManager.search_package(&config, "").await {
+            Ok(SearchResults { packages, .. }) => {
                 println!("Empty query returned {} packages", packages.len());
                 // crates.io API 对空查询会返回结果
                 // 不强制要求有结果，但如果有结果应该是有效的
@@ -594,10 +1267,11 @@ ripgrep v14.1.0:
     async fn test_search_nonexistent_package() {
         let config = crate::Config::default();
         // 搜索一个不存在的包名
-        match CargoManager::search_package(&config, "this-package-definitely-does-not-exist-12345")
+        match CargoManager
+            .search_package(&config, "this-package-definitely-does-not-exist-12345")
             .await
         {
-            Ok(packages) => {
+            Ok(SearchResults { packages, .. }) => {
                 println!(
                     "Search for nonexistent package returned {} results",
                     packages.len()
@@ -732,4 +1406,22 @@ ripgrep v14.1.0:
     //         assert_eq!(installed, "1.17.4");
     //         assert_eq!(latest, "1.17.5");
     //     }
+
+    #[test]
+    fn normalize_name_accepts_crate_names_only() {
+        assert_eq!(CargoManager.normalize_name(" ripgrep ").unwrap(), "ripgrep");
+        assert_eq!(
+            CargoManager.normalize_name("cargo-binstall").unwrap(),
+            "cargo-binstall"
+        );
+        assert_eq!(
+            CargoManager.normalize_name("Inflector").unwrap(),
+            "Inflector"
+        );
+        assert!(CargoManager.normalize_name("").is_err());
+        assert!(CargoManager.normalize_name("--git").is_err());
+        assert!(CargoManager.normalize_name("1password").is_err());
+        assert!(CargoManager.normalize_name("serde@1.0").is_err());
+        assert!(CargoManager.normalize_name(&"a".repeat(65)).is_err());
+    }
 }
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
+    error::CoreError,
+    pm::{
+        common::{
+            CommandExt, checked_package_name, command_args, decode_command_output,
+            limit_search_results, manager_command_path, new_command, removal_args,
+        },
+        progress::{CommandProgressEvent, run_command_with_progress},
+    },
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChocolateyManager;
+
+const UNINSTALL_ARGS: &[&str] = &["uninstall", "-y"];
+const UPGRADE_ARGS: &[&str] = &["upgrade", "-y"];
+const INSTALL_ARGS: &[&str] = &["install", "-y"];
+
+fn command_path(config: &Config) -> String {
+    manager_command_path(config, PackageManagerType::Chocolatey)
+}
+
+#[async_trait]
+impl PackageManager for ChocolateyManager {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Chocolatey
+    }
+
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+        let path = command_path(config);
+
+        // --limit-output 格式：name|current|available|pinned
+        let output = new_command(config, &path)
+            .arg("outdated")
+            .arg("--limit-output")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "choco outdated failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = decode_command_output(&output.stdout)?;
+        Ok(stdout.lines().filter_map(parse_outdated_line).collect())
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        Self::installed_version_map(config)
+            .await?
+            .remove(&package_name.to_lowercase())
+            .ok_or_else(|| CoreError::ParseError(format!("Package {} not found", package_name)))
+    }
+
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
+        let stdout = Self::list_local_output(config).await?;
+
+        Ok(stdout
+            .lines()
+            .filter_map(parse_name_version_line)
+            .map(|(name, version)| PackageInfo {
+                name,
+                version,
+                source: PackageManagerType::Chocolatey,
+                description: None,
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            })
+            .collect())
+    }
+
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
+        let stdout = Self::list_local_output(config).await?;
+        Ok(stdout.lines().filter_map(parse_name_version_line).count())
+    }
+
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .arg("search")
+            .arg(package_name)
+            .arg("--limit-output")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(SearchResults::default());
+        }
+
+        let stdout = decode_command_output(&output.stdout)?;
+        let installed_versions = Self::installed_version_map(config).await?;
+
+        let packages = stdout
+            .lines()
+            .filter_map(parse_name_version_line)
+            .map(|(name, _available_version)| PackageInfo {
+                version: installed_versions
+                    .get(&name.to_lowercase())
+                    .cloned()
+                    .unwrap_or_else(|| "Not Installed".to_owned()),
+                name,
+                source: PackageManagerType::Chocolatey,
+                description: None,
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            })
+            .collect();
+
+        Ok(limit_search_results(config, packages))
+    }
+
+    /// Package IDs are `[A-Za-z0-9._-]`; choco matches them case-insensitively.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Chocolatey, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+        })
+        .map(str::to_owned)
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
+    }
+}
+
+impl ChocolateyManager {
+    pub async fn uninstall_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let args = removal_args(UNINSTALL_ARGS, &[package_name]);
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    pub async fn update_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let args = command_args(
+            config,
+            PackageManagerType::Chocolatey,
+            UPGRADE_ARGS,
+            &[package_name],
+        );
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    pub async fn install_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let args = command_args(
+            config,
+            PackageManagerType::Chocolatey,
+            INSTALL_ARGS,
+            &[package_name],
+        );
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    async fn list_local_output(config: &Config) -> CoreResult<String> {
+        let path = command_path(config);
+
+        // --limit-output 格式：name|version
+        let output = new_command(config, &path)
+            .arg("list")
+            .arg("--local-only")
+            .arg("--limit-output")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "choco list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        decode_command_output(&output.stdout)
+    }
+
+    /// Installed versions keyed by lowercase package id (Chocolatey ids are case-insensitive).
+    async fn installed_version_map(config: &Config) -> CoreResult<HashMap<String, String>> {
+        let stdout = Self::list_local_output(config).await?;
+
+        Ok(stdout
+            .lines()
+            .filter_map(parse_name_version_line)
+            .map(|(name, version)| (name.to_lowercase(), version))
+            .collect())
+    }
+}
+
+fn parse_name_version_line(line: &str) -> Option<(String, String)> {
+    let mut parts = line.trim().split('|');
+    let name = parts.next()?.trim();
+    let version = parts.next()?.trim();
+
+    if name.is_empty() || version.is_empty() {
+        return None;
+    }
+
+    Some((name.to_owned(), version.to_owned()))
+}
+
+fn parse_outdated_line(line: &str) -> Option<PackageUpdate> {
+    let parts: Vec<&str> = line.trim().split('|').map(str::trim).collect();
+    if parts.len() < 3 || parts[..3].iter().any(|part| part.is_empty()) {
+        return None;
+    }
+
+    // 被 pin 的包不会被 choco upgrade 升级
+    if parts
+        .get(3)
+        .is_some_and(|pinned| pinned.eq_ignore_ascii_case("true"))
+    {
+        return None;
+    }
+
+    Some(PackageUpdate {
+        name: parts[0].to_owned(),
+        current_version: parts[1].to_owned(),
+        new_version: parts[2].to_owned(),
+        security: false,
+        download_size: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
+
+    #[test]
+    fn parse_name_version_line_reads_limit_output_format() {
+        assert_eq!(
+            parse_name_version_line("git|2.44.0\r"),
+            Some(("git".to_owned(), "2.44.0".to_owned()))
+        );
+        assert_eq!(parse_name_version_line("Chocolatey v2.2.2"), None);
+        assert_eq!(parse_name_version_line("broken|"), None);
+    }
+
+    #[test]
+    fn parse_outdated_line_skips_pinned_packages() {
+        let parsed =
+            parse_outdated_line("nodejs|20.11.0|20.11.1|false").expect("line should parse");
+
+        assert_eq!(parsed.name, "nodejs");
+        assert_eq!(parsed.current_version, "20.11.0");
+        assert_eq!(parsed.new_version, "20.11.1");
+
+        assert!(parse_outdated_line("python|3.12.1|3.12.2|true").is_none());
+        assert!(parse_outdated_line("git|2.44.0").is_none());
+    }
+
+    #[test]
+    fn parse_outdated_line_reads_bom_prefixed_crlf_output() {
+        let stdout = decode_command_output(
+            b"\xEF\xBB\xBFnodejs|20.11.0|20.11.1|false\r\ngit|2.44.0|2.45.0|false\r\n",
+        )
+        .unwrap();
+
+        let updates: Vec<PackageUpdate> = stdout.lines().filter_map(parse_outdated_line).collect();
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "nodejs");
+        assert_eq!(updates[1].new_version, "2.45.0");
+    }
+
+    #[test]
+    fn parse_outdated_line_reads_fixture() {
+        let updates: Vec<_> = fixture("chocolatey/outdated.txt")
+            .lines()
+            .filter_map(parse_outdated_line)
+            .collect();
+
+        let names: Vec<_> = updates.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["7zip", "git"]);
+        assert_eq!(updates[1].current_version, "2.44.0");
+        assert_eq!(updates[1].new_version, "2.46.0");
+    }
+
+    #[test]
+    fn custom_args_go_before_the_package_name() {
+        let config =
+            config_with_custom_args(PackageManagerType::Chocolatey, &["--ignore-checksums"]);
+
+        assert_eq!(
+            command_args(
+                &config,
+                PackageManagerType::Chocolatey,
+                UPGRADE_ARGS,
+                &["git"]
+            ),
+            ["upgrade", "-y", "--ignore-checksums", "git"]
+        );
+    }
+
+    #[test]
+    fn normalize_name_rejects_invalid_ids() {
+        assert_eq!(
+            ChocolateyManager.normalize_name(" GoogleChrome ").unwrap(),
+            "GoogleChrome"
+        );
+        assert_eq!(
+            ChocolateyManager
+                .normalize_name("dotnet-8.0-runtime")
+                .unwrap(),
+            "dotnet-8.0-runtime"
+        );
+        assert!(ChocolateyManager.normalize_name("").is_err());
+        assert!(ChocolateyManager.normalize_name("-y").is_err());
+        assert!(ChocolateyManager.normalize_name("git & calc").is_err());
+    }
+}
@@ -1,4 +1,19 @@
-use crate::{Config, PackageManagerType};
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    process::{Output, Stdio},
+    time::{Duration, Instant},
+};
+
+use tokio::{process::Command, sync::Semaphore};
+
+use crate::{
+    Config, CoreResult, PackageInfo, PackageManagerType, PackageUpdate, PrivilegeEscalation,
+    SearchResults, error::CoreError, version::compare_versions,
+};
 
 pub(crate) fn manager_default_command(manager_type: PackageManagerType) -> &'static str {
     match manager_type {
@@ -7,11 +22,15 @@ pub(crate) fn manager_default_command(manager_type: PackageManagerType) -> &'sta
         PackageManagerType::Pacman => "pacman",
         PackageManagerType::Zypper => "zypper",
         PackageManagerType::Flatpak => "flatpak",
+        PackageManagerType::Snap => "snap",
         PackageManagerType::Homebrew => "brew",
         PackageManagerType::Cargo => "cargo",
         PackageManagerType::Go => "go",
         PackageManagerType::Npm => "npm",
         PackageManagerType::Pnpm => "pnpm",
+        PackageManagerType::Chocolatey => "choco",
+        PackageManagerType::Pip => "pip",
+        PackageManagerType::Pipx => "pipx",
     }
 }
 
@@ -20,3 +39,843 @@ pub(crate) fn manager_command_path(config: &Config, manager_type: PackageManager
         .get_package_path(manager_type)
         .unwrap_or_else(|| manager_default_command(manager_type).to_owned())
 }
+
+/// Command-line flags from user-entered `flags`, each entry split on whitespace.
+/// Entries not starting with `-` are dropped, the manager would take them for
+/// package names.
+pub(crate) fn flag_args(flags: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    for flag in flags {
+        let flag = flag.trim();
+        if !flag.starts_with('-') {
+            if !flag.is_empty() {
+                log::warn!("Ignoring argument {:?}, flags must start with '-'", flag);
+            }
+            continue;
+        }
+        args.extend(flag.split_whitespace().map(str::to_owned));
+    }
+    args
+}
+
+/// `leading` (subcommand and fixed flags), then the manager's `custom_args`,
+/// then `packages`, so custom flags never end up after package names.
+/// For installs and upgrades; removals use `removal_args`.
+pub(crate) fn command_args<S: AsRef<str>>(
+    config: &Config,
+    manager_type: PackageManagerType,
+    leading: &[&str],
+    packages: &[S],
+) -> Vec<String> {
+    leading
+        .iter()
+        .map(|arg| (*arg).to_owned())
+        .chain(flag_args(config.custom_args(manager_type)))
+        .chain(packages.iter().map(|package| package.as_ref().to_owned()))
+        .collect()
+}
+
+/// `leading`, then `packages`, without the manager's `custom_args`: they
+/// are meant for installs and upgrades, and removals reject many of them
+/// (`pip uninstall` has no `--index-url`, `pacman -R` no `--overwrite`).
+pub(crate) fn removal_args<S: AsRef<str>>(leading: &[&str], packages: &[S]) -> Vec<String> {
+    leading
+        .iter()
+        .map(|arg| (*arg).to_owned())
+        .chain(packages.iter().map(|package| package.as_ref().to_owned()))
+        .collect()
+}
+
+/// Program and leading flags that run a command as root, following
+/// `Config::privilege_escalation`.
+pub(crate) fn privilege_prefix(config: &Config) -> (&'static str, &'static [&'static str]) {
+    match config.privilege_escalation {
+        PrivilegeEscalation::Pkexec => ("pkexec", &[]),
+        PrivilegeEscalation::SudoAskpass => ("sudo", &["-A"]),
+    }
+}
+
+/// `new_command` running `program` as root, see `privilege_prefix`.
+pub(crate) fn privileged_command(config: &Config, program: impl AsRef<OsStr>) -> Command {
+    let (escalation, flags) = privilege_prefix(config);
+    let mut command = new_command(config, escalation);
+    command.args(flags).arg(program);
+    command
+}
+
+/// `package_name` trimmed, or a `ParseError` when `manager_type` could not
+/// take it as a package: empty, option-like (leading `-`), or containing a
+/// character outside `allowed`. Managers add their own rules on top.
+pub(crate) fn checked_package_name(
+    manager_type: PackageManagerType,
+    package_name: &str,
+    allowed: impl Fn(char) -> bool,
+) -> CoreResult<&str> {
+    let name = package_name.trim();
+    let reason = if name.is_empty() {
+        Some("name is empty".to_owned())
+    } else if name.starts_with('-') {
+        Some("name must not start with '-'".to_owned())
+    } else {
+        name.chars()
+            .find(|c| !allowed(*c))
+            .map(|c| format!("invalid character {:?}", c))
+    };
+
+    match reason {
+        Some(reason) => Err(invalid_package_name(manager_type, package_name, &reason)),
+        None => Ok(name),
+    }
+}
+
+/// `ParseError` for a package name `manager_type` rejected.
+pub(crate) fn invalid_package_name(
+    manager_type: PackageManagerType,
+    package_name: &str,
+    reason: &str,
+) -> CoreError {
+    CoreError::ParseError(format!(
+        "Invalid {} package name {:?}: {}",
+        manager_type.name(),
+        package_name,
+        reason
+    ))
+}
+
+/// Actionable explanation when a privileged command failed to get root
+/// rather than failing itself, judged from its output.
+pub(crate) fn privilege_failure_hint(output: &str) -> Option<&'static str> {
+    let output = output.to_ascii_lowercase();
+
+    if output.contains("no authentication agent found") {
+        Some(
+            "no authentication agent found; install and start a polkit agent \
+             (e.g. polkit-gnome or lxqt-policykit) or switch to sudo with an askpass helper in Settings",
+        )
+    } else if output.contains("no askpass program specified") {
+        Some(
+            "sudo needs an askpass helper; set one in Settings or in SUDO_ASKPASS \
+             (e.g. ssh-askpass or ksshaskpass)",
+        )
+    } else if output.contains("not in the sudoers file") {
+        Some("your user may not use sudo; ask an administrator or switch to pkexec in Settings")
+    } else if output.contains("request dismissed")
+        || output.contains("not authorized")
+        || output.contains("incorrect password attempt")
+    {
+        Some("authentication was cancelled or the password was wrong")
+    } else {
+        None
+    }
+}
+
+/// Arguments for `<manager> <leading> <custom_args> <packages>`, to be run
+/// as root through `privilege_prefix`.
+pub(crate) fn privileged_args<S: AsRef<str>>(
+    config: &Config,
+    manager_type: PackageManagerType,
+    leading: &[&str],
+    packages: &[S],
+) -> Vec<String> {
+    let mut args = vec![manager_command_path(config, manager_type)];
+    args.extend(command_args(config, manager_type, leading, packages));
+    args
+}
+
+/// `<manager> <leading> <packages>`, a removal run as root through
+/// `privilege_prefix`; see `removal_args`.
+pub(crate) fn privileged_removal_args<S: AsRef<str>>(
+    config: &Config,
+    manager_type: PackageManagerType,
+    leading: &[&str],
+    packages: &[S],
+) -> Vec<String> {
+    let mut args = vec![manager_command_path(config, manager_type)];
+    args.extend(removal_args(leading, packages));
+    args
+}
+
+/// `Command` for `program` with `Config::extra_path` prepended to `PATH`,
+/// so tools outside a desktop session's minimal `PATH` are still found.
+/// Stdin is closed and git never asks for credentials, so a command that
+/// wants input fails instead of hanging.
+pub(crate) fn new_command(config: &Config, program: impl AsRef<OsStr>) -> Command {
+    let mut command = Command::new(program);
+    command.stdin(Stdio::null()).env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(path) = config.command_path_env() {
+        command.env("PATH", path);
+    }
+    if let Some(askpass) = config.sudo_askpass.as_deref().map(str::trim)
+        && !askpass.is_empty()
+    {
+        command.env("SUDO_ASKPASS", askpass);
+    }
+    command
+}
+
+/// `CoreError` for a failure to start `command`: `ExecutableNotFound` with
+/// the program name when it does not exist, otherwise a `CommandError`.
+pub(crate) fn spawn_error(command: &Command, error: io::Error) -> CoreError {
+    if error.kind() == io::ErrorKind::NotFound {
+        CoreError::ExecutableNotFound(
+            command
+                .as_std()
+                .get_program()
+                .to_string_lossy()
+                .into_owned(),
+        )
+    } else {
+        error.into()
+    }
+}
+
+pub(crate) trait CommandExt {
+    /// `output()`, reporting a missing program as `CoreError::ExecutableNotFound`.
+    async fn try_output(&mut self) -> CoreResult<Output>;
+}
+
+impl CommandExt for Command {
+    async fn try_output(&mut self) -> CoreResult<Output> {
+        match self.output().await {
+            Ok(output) => Ok(output),
+            Err(e) => Err(spawn_error(self, e)),
+        }
+    }
+}
+
+/// Truncate search matches to the configured limit, keeping the original total.
+pub(crate) fn limit_search_results(
+    config: &Config,
+    mut packages: Vec<PackageInfo>,
+) -> SearchResults {
+    let total = packages.len();
+    if config.search_result_limit > 0 {
+        packages.truncate(config.search_result_limit);
+    }
+
+    SearchResults { packages, total }
+}
+
+/// Number of matches worth enriching (e.g. per-package version lookups).
+pub(crate) fn search_limit(config: &Config, total: usize) -> usize {
+    match config.search_result_limit {
+        0 => total,
+        limit => limit.min(total),
+    }
+}
+
+/// Decode stdout of Windows tools before line-based parsing.
+///
+/// Handles UTF-16LE/BE (by BOM, or LE detected from NUL high bytes), a UTF-8
+/// BOM and CRLF or bare CR line endings. Plain UTF-8 passes through unchanged.
+pub(crate) fn decode_command_output(bytes: &[u8]) -> CoreResult<String> {
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units)
+            .map_err(|e| CoreError::Utf8Error(format!("invalid UTF-16 output: {}", e)))
+    };
+
+    let text = if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        utf16(rest, u16::from_le_bytes)?
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        utf16(rest, u16::from_be_bytes)?
+    } else if looks_like_utf16le(bytes) {
+        utf16(bytes, u16::from_le_bytes)?
+    } else {
+        String::from_utf8(
+            bytes
+                .strip_prefix(b"\xEF\xBB\xBF")
+                .unwrap_or(bytes)
+                .to_vec(),
+        )?
+    };
+
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(&text);
+    Ok(text.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+/// BOM-less UTF-16LE: ASCII-heavy text has a NUL in every odd byte.
+fn looks_like_utf16le(bytes: &[u8]) -> bool {
+    bytes.len() >= 2
+        && bytes.len().is_multiple_of(2)
+        && bytes.iter().skip(1).step_by(2).all(|byte| *byte == 0)
+}
+
+/// Lowercased terms of a search query.
+///
+/// CLI managers treat several words differently (`brew search a b` runs one
+/// search per word, `dnf search a b` matches all of them), so multi-word
+/// queries are handled the same everywhere: the manager is searched with the
+/// longest term and results are kept only if they contain every term.
+pub(crate) fn search_terms(query: &str) -> Vec<String> {
+    query.split_whitespace().map(str::to_lowercase).collect()
+}
+
+/// The most selective term to hand to the manager's own search.
+pub(crate) fn primary_search_term(terms: &[String]) -> Option<&str> {
+    terms
+        .iter()
+        .max_by_key(|term| term.chars().count())
+        .map(String::as_str)
+}
+
+/// Whether every term occurs case-insensitively in at least one of the fields.
+pub(crate) fn matches_all_terms(terms: &[String], fields: &[&str]) -> bool {
+    let fields: Vec<String> = fields.iter().map(|field| field.to_lowercase()).collect();
+    terms
+        .iter()
+        .all(|term| fields.iter().any(|field| field.contains(term.as_str())))
+}
+
+/// Regular files in a manager's binary directory as `(file name, path)`.
+/// A missing or unreadable directory is an error rather than an empty list,
+/// since it usually means a misconfigured path.
+pub(crate) async fn read_bin_dir(dir: &Path) -> CoreResult<Vec<(String, PathBuf)>> {
+    let mut entries = tokio::fs::read_dir(dir).await.map_err(|e| {
+        CoreError::BinDirError(format!(
+            "{} ({}), check the directory in Settings",
+            dir.display(),
+            e
+        ))
+    })?;
+
+    let mut files = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_file())
+            && let Some(name) = entry.file_name().to_str()
+        {
+            files.push((name.to_owned(), entry.path()));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Whether a version carries a semver pre-release component,
+/// e.g. `1.2.0-beta.1` or `v0.3.0-rc1` (build metadata after `+` is ignored).
+pub(crate) fn is_prerelease(version: &str) -> bool {
+    let version = version.split('+').next().unwrap_or(version);
+    version.contains('-')
+}
+
+/// Whether updates for a package should consider pre-release versions.
+/// A package that is already on a pre-release keeps following them, so we
+/// never suggest "updating" back to an older stable release.
+pub(crate) fn allow_prerelease_updates(config: &Config, current_version: &str) -> bool {
+    config.include_prereleases || is_prerelease(current_version)
+}
+
+/// Drop updates to a version the user skipped; a newer version shows again.
+pub(crate) fn drop_skipped_versions(
+    config: &Config,
+    mut updates: Vec<PackageUpdate>,
+) -> Vec<PackageUpdate> {
+    if !config.skipped_versions.is_empty() {
+        updates.retain(|update| {
+            config.skipped_versions.get(&update.name) != Some(&update.new_version)
+        });
+    }
+    updates
+}
+
+/// Keep one update per package name, the one with the highest new version,
+/// at the position the name first appeared. Flatpak apps on several branches
+/// and versioned Homebrew formulae can otherwise be listed twice.
+pub(crate) fn dedup_updates(updates: Vec<PackageUpdate>) -> Vec<PackageUpdate> {
+    let mut deduped: Vec<PackageUpdate> = Vec::with_capacity(updates.len());
+    let mut index_by_name: HashMap<String, usize> = HashMap::new();
+
+    for update in updates {
+        match index_by_name.get(&update.name) {
+            Some(&index) => {
+                if compare_versions(&deduped[index].new_version, &update.new_version).is_lt() {
+                    deduped[index] = update;
+                }
+            }
+            None => {
+                index_by_name.insert(update.name.clone(), deduped.len());
+                deduped.push(update);
+            }
+        }
+    }
+
+    deduped
+}
+
+/// Non-empty trimmed lines of a command that prints one name per line.
+pub(crate) fn name_lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Deduplicate `versions` and sort them newest first.
+pub(crate) fn sort_versions_newest_first(mut versions: Vec<String>) -> Vec<String> {
+    versions.sort_by(|a, b| compare_versions(b, a));
+    versions.dedup();
+    versions
+}
+
+/// Bytes of a human-readable size such as `2.3 M`, `181 k`, `458.0 KiB` or
+/// `1.2 GB`. Units are binary multiples, as dnf and flatpak print them.
+pub(crate) fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim().trim_end_matches('.');
+    let unit_start = size.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let (value, unit) = size.split_at(unit_start);
+
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit.trim().to_uppercase().as_str() {
+        "B" | "BYTES" => 1.0,
+        "K" | "KB" | "KIB" => 1024.0,
+        "M" | "MB" | "MIB" => 1024.0 * 1024.0,
+        "G" | "GB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    Some((value * multiplier) as u64)
+}
+
+/// Run `task` for every item with at most `limit` tasks in flight, returning
+/// results in input order.
+pub(crate) async fn run_with_concurrency_limit<T, R, Fut>(
+    items: impl IntoIterator<Item = T>,
+    limit: usize,
+    task: impl Fn(T) -> Fut,
+) -> Vec<R>
+where
+    Fut: Future<Output = R>,
+{
+    let semaphore = Semaphore::new(limit.max(1));
+    let semaphore = &semaphore;
+
+    let tasks = items.into_iter().map(|item| {
+        let task = task(item);
+        async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .expect("semaphore is never closed");
+            task.await
+        }
+    });
+
+    futures::future::join_all(tasks).await
+}
+
+/// Re-run `check` with exponential backoff, starting at `initial_delay`,
+/// until it returns `true` or `timeout` elapses. Returns whether the
+/// expected state was observed.
+pub(crate) async fn poll_with_backoff<Fut>(
+    mut check: impl FnMut() -> Fut,
+    initial_delay: Duration,
+    timeout: Duration,
+) -> bool
+where
+    Fut: Future<Output = bool>,
+{
+    let started = Instant::now();
+    let mut delay = initial_delay;
+
+    loop {
+        if check().await {
+            return true;
+        }
+
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            return false;
+        }
+        tokio::time::sleep(delay.min(remaining)).await;
+        delay = delay.saturating_mul(2);
+    }
+}
+
+/// Captured command output under `core/tests/fixtures/`, for parser tests.
+#[cfg(test)]
+pub(crate) fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read fixture {}: {}", path.display(), e))
+}
+
+/// Config whose `manager_type` entry carries `custom_args`, for placement tests.
+#[cfg(test)]
+pub(crate) fn config_with_custom_args(
+    manager_type: PackageManagerType,
+    custom_args: &[&str],
+) -> Config {
+    Config {
+        app_managers: vec![crate::PackageManagerConfig {
+            manager_type,
+            custom_path: None,
+            custom_args: custom_args.iter().map(|arg| (*arg).to_owned()).collect(),
+        }],
+        ..Config::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn sort_versions_newest_first_dedups() {
+        let versions = ["1.2.0", "1.10.0", "1.2.0", "2.0.0-beta.1", "2.0.0"]
+            .map(str::to_owned)
+            .to_vec();
+
+        assert_eq!(
+            sort_versions_newest_first(versions),
+            ["2.0.0", "2.0.0-beta.1", "1.10.0", "1.2.0"]
+        );
+    }
+
+    #[test]
+    fn parse_size_reads_dnf_and_flatpak_units() {
+        assert_eq!(parse_size("181 k"), Some(181 * 1024));
+        assert_eq!(parse_size("2 M"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_size("458.0 KiB"), Some(458 * 1024));
+        assert_eq!(parse_size("1 MiB."), Some(1024 * 1024));
+        assert_eq!(parse_size("18.5 kB"), Some(18944));
+        assert_eq!(parse_size("0 B"), Some(0));
+        assert_eq!(parse_size("12"), None);
+        assert_eq!(parse_size("many MB"), None);
+    }
+
+    #[test]
+    fn privilege_prefix_follows_config() {
+        assert_eq!(privilege_prefix(&Config::default()), ("pkexec", &[][..]));
+
+        let config = Config {
+            privilege_escalation: PrivilegeEscalation::SudoAskpass,
+            ..Config::default()
+        };
+        assert_eq!(privilege_prefix(&config), ("sudo", &["-A"][..]));
+    }
+
+    #[test]
+    fn privilege_failure_hint_classifies_auth_failures() {
+        assert!(
+            privilege_failure_hint(
+                "Error executing command as another user: No authentication agent found."
+            )
+            .is_some_and(|hint| hint.contains("polkit agent"))
+        );
+        assert!(
+            privilege_failure_hint("sudo: no askpass program specified, try setting SUDO_ASKPASS")
+                .is_some_and(|hint| hint.contains("askpass"))
+        );
+        assert!(
+            privilege_failure_hint("Error executing command as another user: Request dismissed")
+                .is_some()
+        );
+        assert_eq!(
+            privilege_failure_hint("Error: Unable to find a match: foo"),
+            None
+        );
+    }
+
+    fn package(name: &str) -> PackageInfo {
+        PackageInfo {
+            name: name.to_owned(),
+            version: "Not Installed".to_owned(),
+            source: PackageManagerType::Dnf,
+            description: None,
+            size: None,
+            install_date: None,
+            homepage: None,
+            popularity: None,
+            has_update_info: true,
+            eol: None,
+        }
+    }
+
+    #[test]
+    fn limit_search_results_keeps_total() {
+        let config = Config {
+            search_result_limit: 2,
+            ..Config::default()
+        };
+        let results = limit_search_results(&config, vec![package("a"), package("b"), package("c")]);
+
+        assert_eq!(results.packages.len(), 2);
+        assert_eq!(results.total, 3);
+        assert_eq!(search_limit(&config, 1), 1);
+    }
+
+    #[test]
+    fn zero_search_limit_disables_truncation() {
+        let config = Config {
+            search_result_limit: 0,
+            ..Config::default()
+        };
+        let results = limit_search_results(&config, vec![package("a"), package("b")]);
+
+        assert_eq!(results.packages.len(), 2);
+        assert_eq!(search_limit(&config, 7), 7);
+    }
+
+    #[test]
+    fn is_prerelease_detects_prerelease_component() {
+        assert!(is_prerelease("2.0.0-beta.1"));
+        assert!(is_prerelease("v0.3.0-rc1"));
+        assert!(!is_prerelease("1.4.2"));
+        assert!(!is_prerelease("v1.4.2+incompatible"));
+    }
+
+    #[test]
+    fn prerelease_installs_keep_following_prereleases() {
+        let config = Config::default();
+        assert!(!allow_prerelease_updates(&config, "1.0.0"));
+        assert!(allow_prerelease_updates(&config, "2.0.0-beta.1"));
+
+        let config = Config {
+            include_prereleases: true,
+            ..Config::default()
+        };
+        assert!(allow_prerelease_updates(&config, "1.0.0"));
+    }
+
+    #[test]
+    fn new_command_prepends_extra_path() {
+        let config = Config {
+            extra_path: vec!["/opt/custom/bin".to_owned(), " ".to_owned()],
+            ..Config::default()
+        };
+
+        let command = new_command(&config, "brew");
+        let path = command
+            .as_std()
+            .get_envs()
+            .find(|(key, _)| *key == "PATH")
+            .and_then(|(_, value)| value)
+            .expect("PATH should be set");
+
+        let dirs: Vec<PathBuf> = std::env::split_paths(path).collect();
+        assert_eq!(dirs[0], PathBuf::from("/opt/custom/bin"));
+        assert!(!dirs.contains(&PathBuf::new()));
+
+        let no_extra = Config {
+            extra_path: Vec::new(),
+            ..Config::default()
+        };
+        assert!(
+            !new_command(&no_extra, "brew")
+                .as_std()
+                .get_envs()
+                .any(|(key, _)| key == "PATH")
+        );
+    }
+
+    #[test]
+    fn skipped_version_hides_until_newer_version_appears() {
+        let update = |new_version: &str| PackageUpdate {
+            name: "ripgrep".to_owned(),
+            current_version: "14.0.0".to_owned(),
+            new_version: new_version.to_owned(),
+            security: false,
+            download_size: None,
+        };
+        let mut config = Config::default();
+        config
+            .skipped_versions
+            .insert("ripgrep".to_owned(), "14.1.0".to_owned());
+
+        assert!(drop_skipped_versions(&config, vec![update("14.1.0")]).is_empty());
+
+        let updates = drop_skipped_versions(&config, vec![update("14.2.0")]);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].new_version, "14.2.0");
+    }
+
+    #[test]
+    fn decode_command_output_strips_bom_and_crlf() {
+        let utf8 = b"\xEF\xBB\xBF7zip|23.1.0\r\ngit|2.44.0\r\n";
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(
+                "7zip|23.1.0\r\ngit|2.44.0\r\n"
+                    .encode_utf16()
+                    .flat_map(u16::to_le_bytes),
+            )
+            .collect();
+        let bomless_utf16: Vec<u8> = "git|2.44.0\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        for bytes in [&utf8[..], &utf16] {
+            let text = decode_command_output(bytes).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines, vec!["7zip|23.1.0", "git|2.44.0"]);
+            assert!(!text.contains('\r'));
+        }
+        assert_eq!(
+            decode_command_output(&bomless_utf16).unwrap(),
+            "git|2.44.0\n"
+        );
+        assert_eq!(decode_command_output(b"plain\n").unwrap(), "plain\n");
+    }
+
+    #[test]
+    fn multi_word_queries_require_every_term() {
+        let terms = search_terms("  Python   LSP ");
+
+        assert_eq!(terms, vec!["python", "lsp"]);
+        assert_eq!(primary_search_term(&terms), Some("python"));
+        assert!(matches_all_terms(&terms, &["python-lsp-server", ""]));
+        assert!(matches_all_terms(
+            &terms,
+            &["pylsp", "LSP server for Python"]
+        ));
+        assert!(!matches_all_terms(
+            &terms,
+            &["python3", "Python interpreter"]
+        ));
+        assert_eq!(primary_search_term(&search_terms(" ")), None);
+    }
+
+    #[tokio::test]
+    async fn read_bin_dir_distinguishes_missing_from_empty() {
+        let dir = std::env::temp_dir().join(format!("updater-bin-dir-{}", std::process::id()));
+        let missing = dir.join("missing");
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join("tool"), b"").await.unwrap();
+
+        let files = read_bin_dir(&dir).await.unwrap();
+        let missing_result = read_bin_dir(&missing).await;
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(files, vec![("tool".to_owned(), dir.join("tool"))]);
+        assert!(matches!(missing_result, Err(CoreError::BinDirError(_))));
+    }
+
+    #[tokio::test]
+    async fn run_with_concurrency_limit_bounds_running_tasks() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        let results = run_with_concurrency_limit(0..10, 3, |index| {
+            let running = &running;
+            let max_running = &max_running;
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                index
+            }
+        })
+        .await;
+
+        assert_eq!(results, (0..10).collect::<Vec<_>>());
+        assert_eq!(max_running.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn poll_with_backoff_stops_once_state_flips() {
+        let polls = AtomicUsize::new(0);
+
+        let settled = poll_with_backoff(
+            || {
+                let polls = &polls;
+                async move { polls.fetch_add(1, Ordering::SeqCst) >= 2 }
+            },
+            Duration::from_millis(1),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(settled);
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn missing_program_is_reported_by_name() {
+        let config = Config::default();
+
+        let error = new_command(&config, "updater-no-such-manager")
+            .try_output()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CoreError::ExecutableNotFound(ref program) if program == "updater-no-such-manager"
+        ));
+
+        let progress = crate::pm::progress::run_command_with_progress(
+            &config,
+            "updater-no-such-manager",
+            &[],
+            |_| {},
+        )
+        .await;
+        assert!(matches!(progress, Err(CoreError::ExecutableNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn poll_with_backoff_gives_up_after_timeout() {
+        let settled = poll_with_backoff(
+            || async { false },
+            Duration::from_millis(1),
+            Duration::from_millis(20),
+        )
+        .await;
+
+        assert!(!settled);
+    }
+
+    #[test]
+    fn flag_args_splits_entries_and_drops_non_flags() {
+        let flags = vec![
+            " --locked ".to_owned(),
+            "-j 4".to_owned(),
+            "ripgrep".to_owned(),
+            "  ".to_owned(),
+        ];
+
+        assert_eq!(flag_args(&flags), ["--locked", "-j", "4"]);
+    }
+
+    #[test]
+    fn command_args_puts_custom_args_between_subcommand_and_packages() {
+        let config = config_with_custom_args(PackageManagerType::Dnf, &["--refresh"]);
+
+        assert_eq!(
+            command_args(
+                &config,
+                PackageManagerType::Dnf,
+                &["install", "-y"],
+                &["vim", "git"]
+            ),
+            ["install", "-y", "--refresh", "vim", "git"]
+        );
+        // 其他管理器的自定义参数不会混入
+        assert_eq!(
+            command_args(&config, PackageManagerType::Flatpak, &["install"], &["vim"]),
+            ["install", "vim"]
+        );
+        assert_eq!(
+            privileged_args(
+                &config,
+                PackageManagerType::Dnf,
+                &["remove", "-y"],
+                &["vim"]
+            ),
+            ["dnf", "remove", "-y", "--refresh", "vim"]
+        );
+    }
+}
@@ -1,52 +1,80 @@
 use async_trait::async_trait;
+use directories_next::BaseDirs;
 use log::debug;
-use std::{collections::HashSet, process::ExitStatus};
-use tokio::process::Command;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Output},
+    time::{Duration, SystemTime},
+};
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, DnfTransaction, InstallPreview, InstallProgress, PackageAction,
+    PackageInfo, PackageManager, PackageManagerType, PackageUpdate, Problem, RestartAdvice,
+    SearchResults, bulk_progress,
     error::CoreError,
     pm::{
-        common::manager_command_path,
-        progress::{CommandProgressEvent, run_command_with_progress},
+        common::{
+            CommandExt, checked_package_name, manager_command_path, matches_all_terms, name_lines,
+            new_command, parse_size, primary_search_term, privilege_failure_hint, privilege_prefix,
+            privileged_args, privileged_command, privileged_removal_args, search_limit,
+            search_terms, sort_versions_newest_first,
+        },
+        progress::{CommandProgressEvent, run_privileged_with_progress},
     },
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct DnfManager;
 
+const REMOVE_ARGS: &[&str] = &["remove", "-y"];
+const UPGRADE_ARGS: &[&str] = &["upgrade", "-y", "--skip-unavailable"];
+const INSTALL_ARGS: &[&str] = &["install", "-y"];
+const ROLLBACK_ARGS: &[&str] = &["history", "rollback", "-y"];
+
 fn command_path(config: &Config) -> String {
     manager_command_path(config, PackageManagerType::Dnf)
 }
 
 #[async_trait]
 impl PackageManager for DnfManager {
-    async fn get_current_version(_config: &Config, package_name: &str) -> CoreResult<String> {
-        let output = Command::new("rpm")
-            .arg("-q")
-            .arg("--queryformat")
-            .arg("%{VERSION}-%{RELEASE}")
-            .arg(package_name)
-            .output()
-            .await?;
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Dnf
+    }
 
-        if output.status.success() {
-            Ok(String::from_utf8(output.stdout)?.trim().to_string())
-        } else {
-            Err(CoreError::ParseError(format!(
-                "Package {} not found",
-                package_name
-            )))
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        for query in rpm_query_candidates(package_name) {
+            let output = new_command(config, "rpm")
+                .arg("-q")
+                .arg("--queryformat")
+                .arg("%{VERSION}-%{RELEASE}\n")
+                .arg(&query)
+                .try_output()
+                .await?;
+
+            // 多架构同时安装时每个架构输出一行，取第一行
+            let stdout = String::from_utf8(output.stdout)?;
+            if output.status.success()
+                && let Some(version) = stdout.lines().map(str::trim).find(|l| !l.is_empty())
+            {
+                return Ok(version.to_owned());
+            }
+            debug!("rpm -q {} failed, trying next form", query);
         }
+
+        Err(CoreError::ParseError(format!(
+            "Package {} not found",
+            package_name
+        )))
     }
 
-    async fn list_installed(_config: &Config) -> CoreResult<Vec<PackageInfo>> {
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
         // use rpm -qa to list installed packages
-        let output = Command::new("rpm")
+        let output = new_command(config, "rpm")
             .arg("-qa")
             .arg("--queryformat")
             .arg("%{NAME}\t%{VERSION}-%{RELEASE}\t%{SUMMARY}\t%{SIZE}\t%{INSTALLTIME}\t%{URL}\n")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -89,6 +117,9 @@ impl PackageManager for DnfManager {
                         size,
                         install_date,
                         homepage,
+                        popularity: None,
+                        has_update_info: true,
+                        eol: None,
                     })
                 } else {
                     None
@@ -96,18 +127,44 @@ impl PackageManager for DnfManager {
             })
             .collect();
 
-        Ok(packages)
+        Ok(filter_noise_packages(config, packages))
     }
 
-    async fn count_installed(_config: &Config) -> CoreResult<usize> {
-        let output = Command::new("sh")
+    async fn list_user_installed(&self, config: &Config) -> CoreResult<Vec<String>> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .arg("repoquery")
+            .arg("--userinstalled")
+            .arg("--qf")
+            .arg("%{name}\n")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "dnf repoquery --userinstalled failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(name_lines(&String::from_utf8(output.stdout)?))
+    }
+
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
+        // 过滤后的数量只能通过完整列表得到
+        if config.dnf_hide_noise {
+            return Ok(self.list_installed(config).await?.len());
+        }
+
+        let output = new_command(config, "sh")
             .arg("-c")
             .arg("rpm -qa | wc -l")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Self::list_installed(_config).await?.len());
+            return Ok(self.list_installed(config).await?.len());
         }
 
         let count_str = String::from_utf8(output.stdout)?.trim().to_string();
@@ -117,89 +174,346 @@ impl PackageManager for DnfManager {
             .map_err(|e| CoreError::ParseError(format!("Failed to parse count: {}", e)))
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         let path = command_path(config);
+        let terms = search_terms(package_name);
+        let Some(primary_term) = primary_search_term(&terms) else {
+            return Ok(SearchResults::default());
+        };
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("search")
             .arg("--quiet")
-            .arg(package_name)
-            .output()
+            .arg(primary_term)
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let mut packages = Vec::new();
-        let mut seen_packages = HashSet::new();
 
         debug!("Starting dnf search_package");
         debug!("dnf search output size: {} bytes", stdout.len());
 
-        // dnf search 输出格式：
-        // Matched fields: name, summary
-        // package-name.arch<TAB>Summary description
-        for line in stdout.lines() {
-            let line = line.trim();
+        let mut names = parse_search_output(&stdout, &terms);
 
-            // 跳过头部行和空行
-            if line.is_empty() || line.starts_with("Matched fields:") {
-                continue;
-            }
+        // Only query installed versions for the results we keep
+        let total = names.len();
+        names.truncate(search_limit(config, total));
 
-            // DNF 使用 tab 分隔包名和描述
-            if let Some((name_part, _summary)) = line.split_once('\t') {
-                let name_part = name_part.trim();
+        let mut packages = Vec::with_capacity(names.len());
+        for name in names {
+            let version = self
+                .get_current_version(config, &name)
+                .await
+                .unwrap_or_else(|_| "Not Installed".to_string());
+
+            packages.push(PackageInfo {
+                name,
+                version,
+                source: PackageManagerType::Dnf,
+                description: None,
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            });
+        }
 
-                // 移除架构后缀 (如 .x86_64, .noarch)
-                let name = name_part
-                    .rsplit_once('.')
-                    .map(|(n, _)| n)
-                    .unwrap_or(name_part)
-                    .to_string();
+        Ok(SearchResults { packages, total })
+    }
 
-                if !seen_packages.insert(name.clone()) {
-                    continue;
-                }
+    async fn list_problems(&self, config: &Config) -> CoreResult<Vec<Problem>> {
+        let path = command_path(config);
 
-                let version = Self::get_current_version(config, &name)
-                    .await
-                    .unwrap_or_else(|_| "Not Installed".to_string());
-
-                packages.push(PackageInfo {
-                    name,
-                    version,
-                    source: PackageManagerType::Dnf,
-                    description: None,
-                    size: None,
-                    install_date: None,
-                    homepage: None,
-                });
+        // dnf check exits non-zero when it finds problems, so only a missing
+        // report counts as a failure.
+        let output = new_command(config, &path).arg("check").try_output().await?;
+        let stdout = String::from_utf8(output.stdout)?;
+        let problems = parse_check_output(&stdout);
+
+        if problems.is_empty() && !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "dnf check failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(problems)
+    }
+
+    async fn installed_size(&self, config: &Config, package_name: &str) -> CoreResult<Option<u64>> {
+        let output = new_command(config, "rpm")
+            .args(["-q", "--queryformat", "%{SIZE}\n", package_name])
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(sum_sizes(&String::from_utf8(output.stdout)?))
+    }
+
+    async fn get_package_details(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        let output = new_command(config, "rpm")
+            .args([
+                "-q",
+                "--queryformat",
+                "%{SUMMARY}\n\n%{DESCRIPTION}",
+                package_name,
+            ])
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let details = String::from_utf8(output.stdout)?.trim().to_owned();
+        Ok((!details.is_empty()).then_some(details))
+    }
+
+    /// Installed and available `version-release`s from
+    /// `dnf --showduplicates list`, newest first.
+    async fn list_available_versions(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Vec<String>> {
+        let output = new_command(config, command_path(config))
+            .arg("--showduplicates")
+            .arg("--quiet")
+            .arg("list")
+            .arg(package_name)
+            .try_output()
+            .await?;
+
+        // 找不到包时 dnf 返回非零，按没有可用版本处理
+        if !output.status.success() {
+            debug!(
+                "dnf list --showduplicates {} failed: {}",
+                package_name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(Vec::new());
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(sort_versions_newest_first(parse_showduplicates_output(
+            &stdout,
+            package_name,
+        )))
+    }
+
+    /// `dnf upgrade -y` without package names, upgrading everything in one
+    /// transaction.
+    async fn update_all(
+        &self,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(InstallProgress) + Send),
+    ) -> CoreResult<()> {
+        let args = privileged_args::<&str>(config, PackageManagerType::Dnf, UPGRADE_ARGS, &[]);
+        let report = bulk_progress(PackageManagerType::Dnf, package_names.len(), on_progress);
+
+        run_privileged_with_progress(config, &args, report).await
+    }
+
+    /// Run `dnf needs-restarting -r`, falling back to the standalone
+    /// `needs-restarting` from yum-utils when the dnf plugin is missing.
+    async fn needs_restarting(&self, config: &Config) -> CoreResult<RestartAdvice> {
+        let path = command_path(config);
+
+        // dnf4 without the plugin also exits 1, the "reboot required" code,
+        // so only output that names a verdict is trusted. The verdict is
+        // matched in English, hence the forced C locale on both commands.
+        let plugin = new_command(config, &path)
+            .env("LC_ALL", "C")
+            .arg("needs-restarting")
+            .arg("-r")
+            .output()
+            .await;
+        let output = match plugin {
+            Ok(output) if parse_reboot_required(&combined_output(&output)).is_some() => output,
+            _ => {
+                new_command(config, "needs-restarting")
+                    .env("LC_ALL", "C")
+                    .arg("-r")
+                    .try_output()
+                    .await?
             }
+        };
+
+        let text = combined_output(&output);
+        let Some(reboot_recommended) = parse_reboot_required(&text) else {
+            return Err(CoreError::CommandError(format!(
+                "needs-restarting failed with status {:?}: {}",
+                output.status.code(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        };
+
+        Ok(RestartAdvice {
+            reboot_recommended,
+            packages: parse_needs_restarting_packages(&text),
+        })
+    }
+
+    /// Time since repo metadata was last downloaded, from the newest
+    /// `repodata/repomd.xml` in the system and per-user dnf caches.
+    async fn metadata_age(&self, _config: &Config) -> CoreResult<Option<Duration>> {
+        let newest = tokio::task::spawn_blocking(|| newest_repomd_mtime(&metadata_cache_dirs()))
+            .await
+            .map_err(|e| CoreError::UnknownError(e.to_string()))?
+            .ok_or_else(|| CoreError::UnknownError("no dnf metadata cache found".to_owned()))?;
+
+        Ok(Some(
+            SystemTime::now()
+                .duration_since(newest)
+                .unwrap_or(Duration::ZERO),
+        ))
+    }
+
+    /// Download fresh repo metadata with `dnf makecache`. Runs unprivileged
+    /// first (dnf then fills the per-user cache) and only falls back to
+    /// running it as root when that fails.
+    async fn refresh_metadata(&self, config: &Config) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .arg("makecache")
+            .try_output()
+            .await?;
+        if output.status.success() {
+            return Ok(());
         }
+        debug!(
+            "Unprivileged dnf makecache failed, retrying as root: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
 
-        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        let output = privileged_command(config, &path)
+            .arg("makecache")
+            .try_output()
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            return Err(match privilege_failure_hint(stderr) {
+                Some(hint) => CoreError::PrivilegeError(format!("{}\n{}", hint, stderr)),
+                None => CoreError::CommandError(format!("dnf makecache failed: {}", stderr)),
+            });
+        }
 
-        Ok(packages)
+        Ok(())
     }
-}
 
-impl DnfManager {
-    pub async fn list_updates_with_refresh(
+    /// Resolve an install with `dnf install --assumeno`, which prints the
+    /// transaction and then aborts. dnf4 wants root even for that, so there
+    /// the preview fails unless the app itself runs as root.
+    async fn preview_install(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<InstallPreview> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .env("LC_ALL", "C")
+            .arg("install")
+            .arg("--assumeno")
+            .args(package_names)
+            .try_output()
+            .await?;
+
+        // `--assumeno` always exits non-zero; no transaction table is the failure.
+        parse_install_preview(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+            CoreError::CommandError(format!(
+                "dnf could not resolve the install: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        })
+    }
+
+    /// Recent transactions from `dnf history list`, newest first.
+    async fn list_transactions(&self, config: &Config) -> CoreResult<Vec<DnfTransaction>> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .env("LC_ALL", "C")
+            .arg("history")
+            .arg("list")
+            .try_output()
+            .await?;
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "dnf history list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(parse_history_list(&stdout))
+    }
+
+    /// Run `dnf history rollback <id>` as root, undoing every transaction
+    /// after `id`.
+    async fn rollback_transaction(
+        &self,
+        config: &Config,
+        id: u32,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        let id = id.to_string();
+        let args = privileged_args(config, PackageManagerType::Dnf, ROLLBACK_ARGS, &[id]);
+
+        run_privileged_with_progress(config, &args, on_progress).await
+    }
+
+    /// RPM names are case-sensitive, so only the character set is checked.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Dnf, package_name, is_rpm_name_char)
+            .map(str::to_owned)
+    }
+
+    async fn list_updates_with_refresh(
+        &self,
         config: &Config,
         refresh: bool,
     ) -> CoreResult<Vec<PackageUpdate>> {
         debug!("Starting dnf list_updates (refresh={})", refresh);
         let path = command_path(config);
 
-        let (program, args) = build_check_upgrade_command(&path, refresh);
-        let output = Command::new(&program).args(&args).output().await?;
+        let (program, args) = build_check_upgrade_command(config, &path, refresh);
+        // Section headers and metadata errors are matched in English
+        let output = new_command(config, &program)
+            .env("LC_ALL", "C")
+            .args(&args)
+            .try_output()
+            .await?;
 
         if !is_check_upgrade_status_ok(&output.status) {
             let stderr = String::from_utf8_lossy(&output.stderr);
             let stderr = stderr.trim();
+            if refresh && let Some(hint) = privilege_failure_hint(stderr) {
+                return Err(CoreError::PrivilegeError(format!("{}\n{}", hint, stderr)));
+            }
+            if is_metadata_failure(stderr) {
+                return Err(CoreError::MetadataError(stderr.to_string()));
+            }
             let detail = if stderr.is_empty() {
                 "no stderr output".to_string()
             } else {
@@ -217,27 +531,17 @@ impl DnfManager {
         debug!("dnf output size: {} bytes", stdout.len());
 
         let mut updates: Vec<PackageUpdate> = Vec::new();
-        let mut seen_packages: HashSet<String> = HashSet::new();
-
-        for raw_line in stdout.lines() {
-            let Some((name, new_version)) = parse_check_upgrade_entry(raw_line) else {
-                continue;
-            };
-
-            // Skip if we've already processed this package (handle duplicates)
-            if seen_packages.contains(name) {
-                debug!("Skipping duplicate package: {}", name);
-                continue;
-            }
-            seen_packages.insert(name.to_string());
+        let security_packages = Self::security_update_packages(config, &path).await;
 
+        for (name, new_version) in parse_check_upgrade_entries(&stdout) {
             // Get current version, but don't fail entire function if one package fails
-            let current_version = Self::get_current_version(config, name)
-                .await
-                .unwrap_or_else(|e| {
-                    debug!("Failed to get current version for {}: {}", name, e);
-                    "unknown".to_string()
-                });
+            let current_version =
+                Self.get_current_version(config, name)
+                    .await
+                    .unwrap_or_else(|e| {
+                        debug!("Failed to get current version for {}: {}", name, e);
+                        "unknown".to_string()
+                    });
 
             debug!(
                 "Found update: {}: {} -> {}",
@@ -248,12 +552,63 @@ impl DnfManager {
                 name: name.to_owned(),
                 current_version,
                 new_version: new_version.to_owned(),
+                security: security_packages.contains(name),
+                download_size: None,
             });
         }
 
         debug!("Total updates found: {}", updates.len());
         Ok(updates)
     }
+
+    async fn run_batch_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_packages_with_progress(config, package_names, on_progress).await
+            }
+        }
+    }
+}
+
+impl DnfManager {
+    /// Packages with pending security advisories. Failures only lose the
+    /// security flag, so they are logged instead of failing the update list.
+    async fn security_update_packages(config: &Config, path: &str) -> HashSet<String> {
+        let output = match new_command(config, path)
+            .args(["updateinfo", "list", "--security", "--quiet"])
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                debug!(
+                    "dnf updateinfo failed with status {:?}: {}",
+                    output.status.code(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                return HashSet::new();
+            }
+            Err(e) => {
+                debug!("Failed to run dnf updateinfo: {}", e);
+                return HashSet::new();
+            }
+        };
+
+        parse_security_updateinfo(&String::from_utf8_lossy(&output.stdout))
+    }
+
     pub async fn uninstall_packages_with_progress(
         config: &Config,
         package_names: &[String],
@@ -263,12 +618,10 @@ impl DnfManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-
-        let mut args = vec![path, "remove".to_string(), "-y".to_string()];
-        args.extend(package_names.iter().cloned());
+        let args =
+            privileged_removal_args(config, PackageManagerType::Dnf, REMOVE_ARGS, package_names);
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn update_packages_with_progress(
@@ -280,17 +633,9 @@ impl DnfManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-
-        let mut args = vec![
-            path,
-            "upgrade".to_string(),
-            "-y".to_string(),
-            "--skip-unavailable".to_string(),
-        ];
-        args.extend(package_names.iter().cloned());
+        let args = privileged_args(config, PackageManagerType::Dnf, UPGRADE_ARGS, package_names);
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn install_packages_with_progress(
@@ -302,25 +647,68 @@ impl DnfManager {
             return Ok(());
         }
 
-        let path = command_path(config);
+        let args = privileged_args(config, PackageManagerType::Dnf, INSTALL_ARGS, package_names);
 
-        let mut args = vec![path, "install".to_string(), "-y".to_string()];
-        args.extend(package_names.iter().cloned());
+        run_privileged_with_progress(config, &args, on_progress).await
+    }
+}
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+/// dnf4 and dnf5 cache directories, system-wide and per user.
+fn metadata_cache_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/var/cache/dnf"),
+        PathBuf::from("/var/cache/libdnf5"),
+    ];
+    if let Some(base_dirs) = BaseDirs::new() {
+        dirs.push(base_dirs.cache_dir().join("libdnf5"));
     }
+    dirs
+}
+
+/// Newest modification time of `<cache>/<repo>/repodata/repomd.xml`.
+fn newest_repomd_mtime(cache_dirs: &[PathBuf]) -> Option<SystemTime> {
+    cache_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| repomd_mtime(&entry.ok()?.path()))
+        .max()
+}
+
+fn repomd_mtime(repo_dir: &Path) -> Option<SystemTime> {
+    std::fs::metadata(repo_dir.join("repodata").join("repomd.xml"))
+        .ok()?
+        .modified()
+        .ok()
+}
+
+/// Total of the `%{SIZE}` lines `rpm -q` prints, one per installed
+/// architecture of a multilib package.
+fn sum_sizes(output: &str) -> Option<u64> {
+    let sizes = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    (!sizes.is_empty()).then(|| sizes.iter().sum())
 }
 
-fn build_check_upgrade_command(path: &str, refresh: bool) -> (String, Vec<String>) {
+fn build_check_upgrade_command(
+    config: &Config,
+    path: &str,
+    refresh: bool,
+) -> (String, Vec<String>) {
     if refresh {
-        return (
-            "pkexec".to_string(),
-            vec![
-                path.to_string(),
-                "check-upgrade".to_string(),
-                "--refresh".to_string(),
-            ],
-        );
+        let (program, flags) = privilege_prefix(config);
+        let args = flags
+            .iter()
+            .copied()
+            .chain([path, "check-upgrade", "--refresh"])
+            .map(str::to_owned)
+            .collect();
+        return (program.to_string(), args);
     }
 
     (path.to_string(), vec!["check-upgrade".to_string()])
@@ -331,48 +719,418 @@ fn is_check_upgrade_status_ok(status: &ExitStatus) -> bool {
     status.success() || status.code() == Some(100)
 }
 
+/// Whether dnf failed on repo metadata (download, cache sync or cache lock)
+/// rather than on the query itself, judged from its stderr.
+fn is_metadata_failure(stderr: &str) -> bool {
+    const METADATA_MARKERS: &[&str] = &[
+        "failed to download metadata",
+        "cannot download repomd.xml",
+        "failed to synchronize cache",
+        "failed to expire repository cache",
+        "cache is locked",
+        "cannot lock",
+    ];
+
+    let stderr = stderr.to_ascii_lowercase();
+    METADATA_MARKERS
+        .iter()
+        .any(|marker| stderr.contains(marker))
+}
+
+fn parse_check_output(output: &str) -> Vec<Problem> {
+    // dnf4: "foo-1.0-1.fc40.x86_64 has missing requires of libbar.so.1()(64bit)"
+    // dnf5: "foo-1.0-1.fc43.x86_64" followed by indented details, e.g.
+    //        ` missing require "libbar.so.1()(64bit)"`
+    let mut problems = Vec::new();
+    let mut current_package: Option<&str> = None;
+
+    for raw_line in output.lines() {
+        if raw_line.trim().is_empty() {
+            continue;
+        }
+
+        let is_detail = raw_line
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_whitespace());
+
+        let (package, description) = if is_detail {
+            let Some(package) = current_package else {
+                continue;
+            };
+            (package, raw_line.trim())
+        } else {
+            let line = raw_line.trim();
+            match line.split_once(' ') {
+                Some((package, description)) => (package, description.trim()),
+                None => {
+                    current_package = Some(line);
+                    continue;
+                }
+            }
+        };
+
+        let suggested_fix = if description.contains("duplicate") {
+            "sudo dnf remove --duplicates".to_owned()
+        } else if description.contains("missing require") {
+            // distro-sync takes a package name; a full NEVRA pins the broken build
+            let name = package_name_from_nevra(package).unwrap_or(package);
+            format!("sudo dnf distro-sync {}", name)
+        } else {
+            format!("sudo dnf reinstall {}", package)
+        };
+
+        problems.push(Problem {
+            manager: PackageManagerType::Dnf,
+            package: package.to_owned(),
+            description: description.to_owned(),
+            suggested_fix: Some(suggested_fix),
+        });
+    }
+
+    problems
+}
+
+/// Drop `-debuginfo`/`-devel` style packages when `Config::dnf_hide_noise` is set.
+fn filter_noise_packages(config: &Config, packages: Vec<PackageInfo>) -> Vec<PackageInfo> {
+    if !config.dnf_hide_noise {
+        return packages;
+    }
+
+    packages
+        .into_iter()
+        .filter(|package| !is_noise_package(config, &package.name))
+        .collect()
+}
+
+fn is_noise_package(config: &Config, name: &str) -> bool {
+    config
+        .dnf_noise_suffixes
+        .iter()
+        .any(|suffix| !suffix.is_empty() && name.ends_with(suffix.as_str()))
+        || config
+            .dnf_noise_prefixes
+            .iter()
+            .any(|prefix| !prefix.is_empty() && name.starts_with(prefix.as_str()))
+}
+
+fn combined_output(output: &Output) -> String {
+    format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+/// The verdict of `needs-restarting -r`, or `None` when the output has
+/// none, e.g. dnf4's "No such command" when the plugin is not installed.
+fn parse_reboot_required(output: &str) -> Option<bool> {
+    if output.contains("Reboot is required") {
+        Some(true)
+    } else if output.contains("No core libraries")
+        || output.contains("Reboot should not be necessary")
+    {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn parse_needs_restarting_packages(output: &str) -> Vec<String> {
+    // Core libraries or services have been updated since boot-up:
+    //   * glibc
+    //   * kernel
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("* "))
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Characters of an RPM package name, optionally with `.arch` or `epoch:`.
+pub(crate) fn is_rpm_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '+' | ':')
+}
+
+/// Whether `token` looks like an RPM architecture (`x86_64`, `noarch`, `i686`, ...).
+fn is_rpm_arch(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_alphabetic())
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `token` looks like an RPM `[epoch:]version-release`.
+fn is_rpm_version(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit())
+        && !token.ends_with([':', '.'])
+        && token.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+' | '~' | '^' | ':')
+        })
+}
+
+/// Parse `check-upgrade` output into unique `(name, new_version)` pairs.
+fn parse_check_upgrade_entries(output: &str) -> Vec<(&str, &str)> {
+    let mut seen_packages = HashSet::new();
+
+    output
+        .lines()
+        .filter_map(parse_check_upgrade_entry)
+        .filter(|(name, _)| {
+            let is_new = seen_packages.insert(*name);
+            if !is_new {
+                debug!("Skipping duplicate package: {}", name);
+            }
+            is_new
+        })
+        .collect()
+}
+
+/// One `name.arch  version  repo` row of `check-upgrade`. Anything else, such
+/// as headers, metadata and repo-loading lines of any dnf release, is skipped
+/// by shape rather than by matching known header texts.
 fn parse_check_upgrade_entry(raw_line: &str) -> Option<(&str, &str)> {
     // Obsoleted package rows are indented and should not be treated as direct upgrades.
     if raw_line
         .chars()
         .next()
-        .is_some_and(|first| first.is_whitespace())
-    {
-        return None;
-    }
-
-    let line = raw_line.trim();
-    if line.is_empty()
-        || line.starts_with("Updating and loading repositories:")
-        || line.starts_with("Repositories loaded.")
-        || line.starts_with("Available upgrades")
-        || line.starts_with("Obsoleting packages")
+        .is_none_or(|first| first.is_whitespace())
     {
         return None;
     }
 
-    // Expected format: name.arch version repository
-    let mut parts = line.split_whitespace();
+    let mut parts = raw_line.split_whitespace();
     let package_with_arch = parts.next()?;
     let new_version = parts.next()?;
     let _repo = parts.next()?;
 
     let (name, arch) = package_with_arch.rsplit_once('.')?;
-    if name.is_empty() || arch.is_empty() {
+    if name.is_empty() || !is_rpm_arch(arch) || !is_rpm_version(new_version) {
         return None;
     }
 
     Some((name, new_version))
 }
 
+/// Versions of `package_name` from `dnf --showduplicates list` rows
+/// (`name.arch  version-release  repo`), skipping section headers.
+fn parse_showduplicates_output(stdout: &str, package_name: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let (name, version, _repo) = (columns.next()?, columns.next()?, columns.next()?);
+            let (name, _arch) = name.rsplit_once('.')?;
+            (name == package_name).then(|| version.to_owned())
+        })
+        .collect()
+}
+
+/// Package names from `dnf updateinfo list --security`.
+///
+/// dnf4: `FEDORA-2024-1a2b3c Important/Sec. openssl-libs-1:3.2.1-2.fc40.x86_64`
+/// dnf5: `FEDORA-2024-1a2b3c security Important openssl-libs-1:3.2.1-2.fc40.x86_64 2024-05-01 00:00:00`
+fn parse_security_updateinfo(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            // 第一列是公告编号，之后找第一个形如 name-version-release.arch 的字段
+            line.split_whitespace()
+                .skip(1)
+                .find_map(package_name_from_nevra)
+        })
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Architectures rpm appends to package names.
+const RPM_ARCHES: &[&str] = &[
+    "x86_64", "noarch", "i686", "aarch64", "ppc64le", "s390x", "armv7hl", "riscv64", "src",
+];
+
+/// Forms of `package_name` to try with `rpm -q`, most likely first.
+///
+/// rpm accepts `name[-version-release][.arch]` but not an epoch, so a
+/// `-E:` before the version is dropped. The arch suffix is stripped first
+/// and the arch-qualified form kept as a fallback.
+fn rpm_query_candidates(package_name: &str) -> Vec<String> {
+    let name = package_name.trim();
+    let name = match name.split_once(':') {
+        Some((before, after)) => match before.rsplit_once('-') {
+            Some((prefix, epoch))
+                if !epoch.is_empty() && epoch.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                format!("{}-{}", prefix, after)
+            }
+            _ => name.to_owned(),
+        },
+        None => name.to_owned(),
+    };
+
+    let mut candidates = Vec::new();
+    if let Some((bare, arch)) = name.rsplit_once('.')
+        && RPM_ARCHES.contains(&arch)
+    {
+        candidates.push(bare.to_owned());
+    }
+    candidates.push(name);
+    candidates
+}
+
+/// `name` from `name-[epoch:]version-release.arch`.
+fn package_name_from_nevra(nevra: &str) -> Option<&str> {
+    let (nevr, arch) = nevra.rsplit_once('.')?;
+    let (rest, release) = nevr.rsplit_once('-')?;
+    let (name, version) = rest.rsplit_once('-')?;
+
+    let is_valid = !name.is_empty()
+        && !arch.is_empty()
+        && !release.is_empty()
+        && version.starts_with(|c: char| c.is_ascii_digit());
+    is_valid.then_some(name)
+}
+
+/// What `dnf install --assumeno` would add: the packages under the
+/// `Installing dependencies:` / `Installing weak dependencies:` headings of
+/// its transaction table, and the download size (dnf4 `Total download
+/// size:`, dnf5 `Need to download`). `None` when dnf printed no transaction.
+fn parse_install_preview(stdout: &str) -> Option<InstallPreview> {
+    let mut preview = InstallPreview::default();
+    let mut resolved = false;
+    let mut in_dependencies = false;
+
+    for line in stdout.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(' ') {
+            in_dependencies = trimmed.starts_with("Installing") && trimmed.contains("dependencies");
+            resolved |= trimmed.starts_with("Transaction Summary") || trimmed == "Nothing to do.";
+            if let Some(size) = trimmed.strip_prefix("Total download size:") {
+                preview.download_size = parse_size(size);
+            } else if let Some((_, size)) = trimmed.split_once("Need to download ") {
+                preview.download_size = parse_size(size);
+            }
+            continue;
+        }
+
+        // Rows are indented by one space; dnf4 continues a row whose name
+        // was too long on a further-indented line.
+        if in_dependencies
+            && !line.starts_with("  ")
+            && let Some(name) = trimmed.split_whitespace().next()
+        {
+            preview.additional.push(name.to_owned());
+        }
+    }
+
+    resolved.then_some(preview)
+}
+
+/// Parse `dnf history list`. dnf4 prints `|`-separated columns; dnf5 pads
+/// with spaces and leaves the action column blank, so the command line is
+/// whatever sits between the ID and the date.
+fn parse_history_list(stdout: &str) -> Vec<DnfTransaction> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            if line.contains('|') {
+                parse_history_row_dnf4(line)
+            } else {
+                parse_history_row_dnf5(line)
+            }
+        })
+        .collect()
+}
+
+fn parse_history_row_dnf4(line: &str) -> Option<DnfTransaction> {
+    let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+    let [id, command, date, actions, altered, ..] = fields.as_slice() else {
+        return None;
+    };
+
+    Some(DnfTransaction {
+        id: id.parse().ok()?,
+        command: command.to_string(),
+        date: date.to_string(),
+        actions: actions.to_string(),
+        // dnf4 appends flags such as "EE" or ">" after the count.
+        altered: altered.split_whitespace().next()?.parse().ok()?,
+    })
+}
+
+fn parse_history_row_dnf5(line: &str) -> Option<DnfTransaction> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let id = tokens.first()?.parse().ok()?;
+    let date_at = tokens.iter().position(|token| is_history_date(token))?;
+    let time = tokens.get(date_at + 1)?;
+    let (altered, actions) = tokens.get(date_at + 2..)?.split_last()?;
+
+    Some(DnfTransaction {
+        id,
+        command: tokens[1..date_at].join(" "),
+        date: format!("{} {}", tokens[date_at], time),
+        actions: actions.join(" "),
+        altered: altered.parse().ok()?,
+    })
+}
+
+/// `YYYY-MM-DD`.
+fn is_history_date(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, b)| i == 4 || i == 7 || b.is_ascii_digit())
+}
+
+/// Package names from `dnf search` output that match every query term
+/// in their name or summary.
+fn parse_search_output(stdout: &str, terms: &[String]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut seen_packages = HashSet::new();
+
+    // dnf5: `package-name.arch<TAB>Summary`，dnf4: `package-name.arch : Summary`
+    for line in stdout.lines() {
+        let line = line.trim();
+
+        // 跳过头部行和空行
+        if line.is_empty() || line.starts_with("Matched fields:") || line.starts_with('=') {
+            continue;
+        }
+
+        let Some((name_part, summary)) = line.split_once('\t').or_else(|| line.split_once(" : "))
+        else {
+            continue;
+        };
+        let name_part = name_part.trim();
+
+        // 移除架构后缀 (如 .x86_64, .noarch)
+        let name = name_part
+            .rsplit_once('.')
+            .map(|(n, _)| n)
+            .unwrap_or(name_part)
+            .to_string();
+
+        if matches_all_terms(terms, &[&name, summary]) && seen_packages.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+
+    names.sort();
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::PrivilegeEscalation;
+    use crate::pm::common::{config_with_custom_args, fixture};
 
     #[tokio::test]
     async fn test_dnf_list_updates() {
         let config = crate::Config::default();
-        match DnfManager::list_updates_with_refresh(&config, false).await {
+        match DnfManager.list_updates_with_refresh(&config, false).await {
             Ok(updates) => {
                 println!("Found {} updates:", updates.len());
                 for update in updates.iter().take(5) {
@@ -386,11 +1144,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_metadata_failure() {
+        assert!(is_metadata_failure(
+            "Errors during downloading metadata for repository 'updates':\n\
+             Error: Failed to download metadata for repo 'updates': Cannot download repomd.xml"
+        ));
+        assert!(is_metadata_failure(
+            "Error: Failed to synchronize cache for repo 'fedora'"
+        ));
+        assert!(is_metadata_failure(
+            ">>> Librepo error: Cannot download repomd.xml: Cannot download repodata/repomd.xml"
+        ));
+        assert!(!is_metadata_failure("Error: Unable to find a match: foo"));
+        assert!(!is_metadata_failure(""));
+    }
+
     #[tokio::test]
     async fn test_dnf_get_current_version() {
         let config = crate::Config::default();
         let package_name = "bash"; // Common package
-        match DnfManager::get_current_version(&config, package_name).await {
+        match DnfManager.get_current_version(&config, package_name).await {
             Ok(version) => println!("Current version of {}: {}", package_name, version),
             Err(e) => eprintln!("Error: {}", e),
         }
@@ -409,6 +1183,21 @@ mod tests {
         assert!(parse_check_upgrade_entry("Repositories loaded.").is_none());
         assert!(parse_check_upgrade_entry("Available upgrades").is_none());
         assert!(parse_check_upgrade_entry("Obsoleting packages").is_none());
+        assert!(
+            parse_check_upgrade_entry(
+                "Last metadata expiration check: 0:41:05 ago on Tue 14 May 2024."
+            )
+            .is_none()
+        );
+        assert!(
+            parse_check_upgrade_entry("Fedora 43 - x86_64 - Updates 100% | 12.1 KiB/s").is_none()
+        );
+        assert!(
+            parse_check_upgrade_entry(
+                "Security: kernel-core-6.8.10 is an installed security update"
+            )
+            .is_none()
+        );
     }
 
     #[test]
@@ -417,17 +1206,468 @@ mod tests {
         assert!(parse_check_upgrade_entry(line).is_none());
     }
 
+    #[test]
+    fn test_parse_check_output_handles_dnf4_and_dnf5() {
+        let dnf4 = "foo-1.0-1.fc40.x86_64 has missing requires of libbar.so.1()(64bit)\nbaz-2.0-1.fc40.x86_64 is a duplicate with baz-1.9-1.fc40.x86_64\n";
+        let problems = parse_check_output(dnf4);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].package, "foo-1.0-1.fc40.x86_64");
+        assert_eq!(
+            problems[0].suggested_fix.as_deref(),
+            Some("sudo dnf distro-sync foo")
+        );
+        assert_eq!(
+            problems[1].suggested_fix.as_deref(),
+            Some("sudo dnf remove --duplicates")
+        );
+
+        let dnf5 = "foo-1.0-1.fc43.x86_64\n missing require \"libbar.so.1()(64bit)\"\n";
+        let problems = parse_check_output(dnf5);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].package, "foo-1.0-1.fc43.x86_64");
+        assert_eq!(
+            problems[0].description,
+            "missing require \"libbar.so.1()(64bit)\""
+        );
+    }
+
+    #[test]
+    fn test_filter_noise_packages() {
+        let package = |name: &str| PackageInfo {
+            name: name.to_owned(),
+            version: "1.0-1.fc40".to_owned(),
+            source: PackageManagerType::Dnf,
+            description: None,
+            size: None,
+            install_date: None,
+            homepage: None,
+            popularity: None,
+            has_update_info: true,
+            eol: None,
+        };
+        let packages = vec![
+            package("glibc"),
+            package("glibc-debuginfo"),
+            package("glibc-debugsource"),
+            package("openssl-devel"),
+            package("devel-tools"),
+            package("firefox"),
+        ];
+
+        let names = |packages: Vec<PackageInfo>| -> Vec<String> {
+            packages.into_iter().map(|package| package.name).collect()
+        };
+
+        let config = Config::default();
+        assert_eq!(
+            names(filter_noise_packages(&config, packages.clone())),
+            vec!["glibc", "devel-tools", "firefox"]
+        );
+
+        let config = Config {
+            dnf_noise_prefixes: vec!["devel-".to_owned()],
+            ..Config::default()
+        };
+        assert_eq!(
+            names(filter_noise_packages(&config, packages.clone())),
+            vec!["glibc", "firefox"]
+        );
+
+        let config = Config {
+            dnf_hide_noise: false,
+            ..Config::default()
+        };
+        assert_eq!(filter_noise_packages(&config, packages).len(), 6);
+    }
+
+    #[test]
+    fn test_parse_search_output_requires_all_terms() {
+        let dnf5 = "Matched fields: name, summary\n python3-lsp-server.noarch\tPython Language Server for the Language Server Protocol\n python3-lsp-jsonrpc.noarch\tJSON RPC 2.0 server library\n python3.x86_64\tPython 3.12 interpreter\nMatched fields: summary\n pylsp-mypy.noarch\tMypy linter for the Python LSP server\n";
+        let dnf4 = "======== Name Matched: python ========\npython3-lsp-server.noarch : Python Language Server for the Language Server Protocol\npython3.x86_64 : Python 3.12 interpreter\n";
+        let terms = search_terms("python lsp");
+
+        assert_eq!(
+            parse_search_output(dnf5, &terms),
+            vec!["pylsp-mypy", "python3-lsp-jsonrpc", "python3-lsp-server"]
+        );
+        assert_eq!(
+            parse_search_output(dnf4, &terms),
+            vec!["python3-lsp-server"]
+        );
+    }
+
+    #[test]
+    fn test_parse_security_updateinfo_dnf4_and_dnf5() {
+        let dnf4 = "FEDORA-2024-1a2b3c4d5e Important/Sec. openssl-libs-1:3.2.1-2.fc40.x86_64\nFEDORA-2024-1a2b3c4d5e Important/Sec. openssl-1:3.2.1-2.fc40.x86_64\nFEDORA-2024-9f8e7d6c5b Moderate/Sec.  curl-8.6.0-7.fc40.x86_64\n";
+        let dnf5 = "Name                    Type     Severity  Package                                  Issued\nFEDORA-2024-9f8e7d6c5b  security Moderate  curl-8.6.0-7.fc40.x86_64                 2024-05-01 00:00:00\nFEDORA-2024-2b3c4d5e6f  security None      python3-pip-wheel-23.3.2-2.fc40.noarch   2024-05-02 00:00:00\n";
+
+        let dnf4_packages = parse_security_updateinfo(dnf4);
+        let dnf5_packages = parse_security_updateinfo(dnf5);
+
+        assert_eq!(dnf4_packages.len(), 3);
+        assert!(dnf4_packages.contains("openssl-libs"));
+        assert!(dnf4_packages.contains("curl"));
+        assert_eq!(dnf5_packages.len(), 2);
+        assert!(dnf5_packages.contains("python3-pip-wheel"));
+        assert!(parse_security_updateinfo("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_showduplicates_output() {
+        let stdout = "\
+Installed packages
+bash.x86_64                  5.2.26-3.fc40                  @fedora
+Available packages
+bash.i686                    5.2.26-3.fc40                  fedora
+bash.x86_64                  5.2.26-3.fc40                  fedora
+bash.x86_64                  5.2.32-1.fc40                  updates
+bash-completion.noarch       1:2.11-12.fc40                 fedora
+";
+
+        assert_eq!(
+            sort_versions_newest_first(parse_showduplicates_output(stdout, "bash")),
+            ["5.2.32-1.fc40", "5.2.26-3.fc40"]
+        );
+    }
+
+    #[test]
+    fn test_parse_check_upgrade_entries_dnf4_capture() {
+        let output =
+            "Last metadata expiration check: 0:41:05 ago on Tue 14 May 2024 09:12:44 AM CEST.
+
+firefox.x86_64                       126.0-1.fc40                  updates
+kernel.x86_64                        6.8.9-300.fc40                updates
+Obsoleting Packages
+grub2-tools.x86_64                   1:2.06-121.fc40               updates
+    grub2-tools.x86_64               1:2.06-118.fc40               @updates
+";
+
+        assert_eq!(
+            parse_check_upgrade_entries(output),
+            vec![
+                ("firefox", "126.0-1.fc40"),
+                ("kernel", "6.8.9-300.fc40"),
+                ("grub2-tools", "1:2.06-121.fc40"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_check_upgrade_entries_dnf5_capture() {
+        let output = "Updating and loading repositories:
+ Fedora 43 - x86_64 - Updates           100% |  12.1 KiB/s |   8.9 KiB |  00m01s
+Repositories loaded.
+Available upgrades
+akonadi-calendar.x86_64 25.12.3-1.fc43 updates
+kernel.x86_64           6.18.3-200.fc43 updates
+Obsoleting packages
+kernel-headers.x86_64   6.18.3-200.fc43 updates
+    kernel-headers.x86_64 6.17.1-300.fc43 @updates
+";
+
+        assert_eq!(
+            parse_check_upgrade_entries(output),
+            vec![
+                ("akonadi-calendar", "25.12.3-1.fc43"),
+                ("kernel", "6.18.3-200.fc43"),
+                ("kernel-headers", "6.18.3-200.fc43"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_check_upgrade_entries_skips_unknown_non_package_lines() {
+        let output = "Updating Subscription Management repositories.
+Fedora 40 - x86_64 - Updates                     42 kB/s |  23 kB     00:00
+Last metadata expiration check: 0:00:01 ago on Wed 15 May 2024 10:02:11 AM CEST.
+Security: kernel-core-6.8.10-300.fc40.x86_64 is an installed security update
+
+NetworkManager.x86_64                 1:1.46.0-2.fc40               updates
+python3-dnf.noarch                    4.21.1-1.fc40                 updates
+Obsoleting Packages
+dnf-utils.noarch                      4.7.0-1.fc40                  updates
+    yum-utils.noarch                  4.6.0-1.fc39                  @fedora
+Obsoleted by: dnf-utils.noarch 4.7.0-1.fc40 updates
+Total download size: 12 M
+";
+
+        assert_eq!(
+            parse_check_upgrade_entries(output),
+            vec![
+                ("NetworkManager", "1:1.46.0-2.fc40"),
+                ("python3-dnf", "4.21.1-1.fc40"),
+                ("dnf-utils", "4.7.0-1.fc40"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_needs_restarting_packages() {
+        let output = "Core libraries or services have been updated since boot-up:\n  * glibc\n  * kernel\n\nReboot is required to fully utilize these updates.\nMore information: https://access.redhat.com/solutions/27943\n";
+
+        assert_eq!(
+            parse_needs_restarting_packages(output),
+            vec!["glibc".to_owned(), "kernel".to_owned()]
+        );
+        assert!(parse_needs_restarting_packages("No core libraries or services have been updated since boot-up.\nReboot should not be necessary.\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn rollback_runs_history_rollback_through_the_progress_runner() {
+        let config = Config {
+            dry_run: true,
+            ..Config::default()
+        };
+        let mut commands = Vec::new();
+
+        DnfManager
+            .rollback_transaction(&config, 42, &mut |event| {
+                commands.extend(event.command_message)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].ends_with("dnf history rollback -y 42"));
+    }
+
+    #[test]
+    fn parse_reboot_required_needs_a_verdict_in_the_output() {
+        assert_eq!(
+            parse_reboot_required(
+                "  * kernel\n\nReboot is required to fully utilize these updates.\n"
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            parse_reboot_required(
+                "No core libraries or services have been updated since boot-up.\nReboot should not be necessary.\n"
+            ),
+            Some(false)
+        );
+        assert_eq!(
+            parse_reboot_required(&fixture("dnf/needs_restarting_no_plugin.txt")),
+            None
+        );
+    }
+
+    #[test]
+    fn sum_sizes_adds_every_installed_architecture() {
+        assert_eq!(sum_sizes("1024\n"), Some(1024));
+        assert_eq!(sum_sizes("1024\n2048\n"), Some(3072));
+        assert_eq!(sum_sizes(""), None);
+        assert_eq!(sum_sizes("(none)\n"), None);
+    }
+
     #[test]
     fn test_build_check_upgrade_command_without_refresh() {
-        let (program, args) = build_check_upgrade_command("/usr/bin/dnf", false);
+        let (program, args) =
+            build_check_upgrade_command(&Config::default(), "/usr/bin/dnf", false);
         assert_eq!(program, "/usr/bin/dnf");
         assert_eq!(args, vec!["check-upgrade"]);
     }
 
     #[test]
     fn test_build_check_upgrade_command_with_refresh_uses_pkexec() {
-        let (program, args) = build_check_upgrade_command("/usr/bin/dnf", true);
+        let (program, args) = build_check_upgrade_command(&Config::default(), "/usr/bin/dnf", true);
         assert_eq!(program, "pkexec");
         assert_eq!(args, vec!["/usr/bin/dnf", "check-upgrade", "--refresh"]);
     }
+
+    #[test]
+    fn test_build_check_upgrade_command_with_refresh_uses_sudo_askpass() {
+        let config = Config {
+            privilege_escalation: PrivilegeEscalation::SudoAskpass,
+            ..Config::default()
+        };
+        let (program, args) = build_check_upgrade_command(&config, "/usr/bin/dnf", true);
+        assert_eq!(program, "sudo");
+        assert_eq!(
+            args,
+            vec!["-A", "/usr/bin/dnf", "check-upgrade", "--refresh"]
+        );
+    }
+
+    #[test]
+    fn test_parse_check_upgrade_fixtures() {
+        let dnf4 = fixture("dnf/check_upgrade_dnf4.txt");
+        assert_eq!(
+            parse_check_upgrade_entries(&dnf4),
+            [
+                ("firefox", "126.0-1.fc40"),
+                ("kernel", "6.8.10-300.fc40"),
+                ("kernel-core", "6.8.10-300.fc40"),
+                ("grub2-tools-efi", "1:2.06-121.fc40"),
+            ]
+        );
+
+        let dnf5 = fixture("dnf/check_upgrade_dnf5.txt");
+        let entries = parse_check_upgrade_entries(&dnf5);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[3], ("openssl-libs", "1:3.2.1-6.fc40"));
+    }
+
+    #[test]
+    fn test_parse_install_preview_fixtures() {
+        let dnf4 = parse_install_preview(&fixture("dnf/install_preview_dnf4.txt")).unwrap();
+        assert_eq!(
+            dnf4.additional,
+            [
+                "hwloc-libs",
+                "lm_sensors-libs",
+                "python3-some-really-long-package-name"
+            ]
+        );
+        assert_eq!(dnf4.download_size, Some(2411724));
+
+        let dnf5 = parse_install_preview(&fixture("dnf/install_preview_dnf5.txt")).unwrap();
+        assert_eq!(dnf5.additional, ["hwloc-libs", "lm_sensors-libs"]);
+        assert_eq!(dnf5.download_size, Some(3 * 1024 * 1024));
+
+        assert_eq!(
+            parse_install_preview(
+                "Package htop-3.3.0-1.fc39.x86_64 is already installed.\nDependencies resolved.\nNothing to do.\n"
+            ),
+            Some(InstallPreview::default())
+        );
+        assert_eq!(
+            parse_install_preview("Error: Unable to find a match: nope\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_history_list_fixtures() {
+        let dnf4 = parse_history_list(&fixture("dnf/history_list_dnf4.txt"));
+        assert_eq!(dnf4.len(), 3);
+        assert_eq!(
+            dnf4[0],
+            DnfTransaction {
+                id: 42,
+                command: "upgrade -y --skip-unavailable".to_owned(),
+                date: "2025-03-14 09:12".to_owned(),
+                actions: "Upgrade".to_owned(),
+                altered: 27,
+            }
+        );
+        assert_eq!(dnf4[1].actions, "Install");
+        assert_eq!(dnf4[1].altered, 1);
+        assert_eq!(dnf4[2].actions, "E, I, U");
+
+        let dnf5 = parse_history_list(&fixture("dnf/history_list_dnf5.txt"));
+        assert_eq!(dnf5.len(), 3);
+        assert_eq!(
+            dnf5[0],
+            DnfTransaction {
+                id: 17,
+                command: "dnf5 upgrade -y".to_owned(),
+                date: "2025-03-14 09:12:44".to_owned(),
+                actions: String::new(),
+                altered: 31,
+            }
+        );
+        assert_eq!(dnf5[2].command, "dnf5 install htop");
+    }
+
+    #[test]
+    fn test_parse_showduplicates_fixture() {
+        let stdout = fixture("dnf/showduplicates.txt");
+
+        assert_eq!(
+            sort_versions_newest_first(parse_showduplicates_output(&stdout, "ripgrep")),
+            ["14.1.0-1.fc40", "13.0.0-8.fc40"]
+        );
+    }
+
+    #[test]
+    fn test_newest_repomd_mtime() {
+        let dir = std::env::temp_dir().join(format!("updater-dnf-cache-{}", std::process::id()));
+        let repodata = dir.join("fedora-0123456789abcdef").join("repodata");
+        std::fs::create_dir_all(&repodata).unwrap();
+        std::fs::create_dir_all(dir.join("updates-fedcba9876543210")).unwrap();
+        std::fs::write(repodata.join("repomd.xml"), b"<repomd/>").unwrap();
+
+        let newest = newest_repomd_mtime(&[dir.clone(), dir.join("missing")]);
+        let empty = newest_repomd_mtime(&[dir.join("missing")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(newest.is_some_and(|mtime| mtime.elapsed().unwrap() < Duration::from_secs(60)));
+        assert_eq!(empty, None);
+    }
+
+    #[test]
+    fn custom_args_follow_the_subcommand() {
+        let config = config_with_custom_args(
+            PackageManagerType::Dnf,
+            &["--refresh", "--setopt=fastestmirror=1"],
+        );
+        let packages = ["vim".to_owned(), "git".to_owned()];
+
+        assert_eq!(
+            privileged_args(&config, PackageManagerType::Dnf, UPGRADE_ARGS, &packages),
+            [
+                "dnf",
+                "upgrade",
+                "-y",
+                "--skip-unavailable",
+                "--refresh",
+                "--setopt=fastestmirror=1",
+                "vim",
+                "git"
+            ]
+        );
+        assert_eq!(
+            privileged_args(
+                &config,
+                PackageManagerType::Dnf,
+                INSTALL_ARGS,
+                &packages[..1]
+            ),
+            [
+                "dnf",
+                "install",
+                "-y",
+                "--refresh",
+                "--setopt=fastestmirror=1",
+                "vim"
+            ]
+        );
+    }
+
+    #[test]
+    fn rpm_query_candidates_strip_arch_then_fall_back_to_it() {
+        assert_eq!(rpm_query_candidates("glibc.i686"), ["glibc", "glibc.i686"]);
+        assert_eq!(rpm_query_candidates("python3.12"), ["python3.12"]);
+        assert_eq!(rpm_query_candidates(" vim-enhanced "), ["vim-enhanced"]);
+    }
+
+    #[test]
+    fn rpm_query_candidates_drop_the_epoch() {
+        assert_eq!(
+            rpm_query_candidates("openssl-libs-1:3.2.1-2.fc40.x86_64"),
+            [
+                "openssl-libs-3.2.1-2.fc40",
+                "openssl-libs-3.2.1-2.fc40.x86_64"
+            ]
+        );
+        assert_eq!(
+            rpm_query_candidates("bash-0:5.2.26-3.fc40"),
+            ["bash-5.2.26-3.fc40"]
+        );
+    }
+
+    #[test]
+    fn normalize_name_keeps_case_and_rejects_invalid_names() {
+        assert_eq!(
+            DnfManager.normalize_name(" NetworkManager ").unwrap(),
+            "NetworkManager"
+        );
+        assert_eq!(
+            DnfManager.normalize_name("kernel-core.x86_64").unwrap(),
+            "kernel-core.x86_64"
+        );
+        assert!(DnfManager.normalize_name("  ").is_err());
+        assert!(DnfManager.normalize_name("--setopt=x").is_err());
+        assert!(DnfManager.normalize_name("foo bar").is_err());
+    }
 }
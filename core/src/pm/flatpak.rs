@@ -6,31 +6,47 @@ use std::{
 use async_trait::async_trait;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, InstallPreview, InstallProgress, PackageAction, PackageInfo,
+    PackageManager, PackageManagerType, PackageUpdate, Problem, SearchResults, bulk_progress,
     pm::{
-        common::manager_command_path,
+        common::{
+            CommandExt, checked_package_name, command_args, invalid_package_name,
+            manager_command_path, new_command, parse_size, removal_args,
+            run_with_concurrency_limit, search_limit, sort_versions_newest_first,
+        },
         progress::{CommandProgressEvent, run_command_with_progress},
     },
 };
 
+/// Concurrent `flatpak info` calls when loading app summaries.
+const FLATPAK_INFO_CONCURRENCY: usize = 8;
+
 #[derive(Debug, Clone, Copy)]
 pub struct FlatpakManager;
 
+const UNINSTALL_ARGS: &[&str] = &["uninstall", "-y"];
+const UPDATE_ARGS: &[&str] = &["update", "-y"];
+const INSTALL_ARGS: &[&str] = &["install", "-y"];
+
 fn command_path(config: &Config) -> String {
     manager_command_path(config, PackageManagerType::Flatpak)
 }
 
 #[async_trait]
 impl PackageManager for FlatpakManager {
-    async fn list_updates(config: &Config) -> crate::CoreResult<Vec<crate::PackageUpdate>> {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Flatpak
+    }
+
+    async fn list_updates(&self, config: &Config) -> crate::CoreResult<Vec<crate::PackageUpdate>> {
         let installed_info = Self::get_all_installed_info(config).await?;
         Self::list_updates_via_update(config, &installed_info).await
     }
 
-    async fn get_current_version(config: &Config, package_name: &str) -> CoreResult<String> {
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("info")
             .arg("--show-version")
             .arg(package_name)
@@ -50,11 +66,11 @@ impl PackageManager for FlatpakManager {
             String::new()
         };
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("info")
             .arg("--show-branch")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -73,14 +89,14 @@ impl PackageManager for FlatpakManager {
         }
     }
 
-    async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>> {
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("list")
             .arg("--app")
             .arg("--columns=application,name,version,branch,size,origin")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -101,6 +117,9 @@ impl PackageManager for FlatpakManager {
                         size: None,
                         install_date: None,
                         homepage: None,
+                        popularity: None,
+                        has_update_info: true,
+                        eol: None,
                     }
                 })
                 .collect());
@@ -134,7 +153,7 @@ impl PackageManager for FlatpakManager {
                     format!("{} ({})", version_part, branch)
                 };
 
-                let size = parts.get(4).and_then(|s| Self::parse_flatpak_size(s));
+                let size = parts.get(4).and_then(|s| parse_size(s));
 
                 packages.push(PackageInfo {
                     name: app_id,
@@ -144,81 +163,309 @@ impl PackageManager for FlatpakManager {
                     size,
                     install_date: None,
                     homepage: None,
+                    popularity: None,
+                    has_update_info: true,
+                    eol: None,
                 });
             }
         }
 
+        // `name` 列只是应用的显示名，用 flatpak info 的一句话简介替换，
+        // 同时读取 EOL 标记；简介按用户语言读取，EOL 字段按 C locale 解析
+        let infos = run_with_concurrency_limit(
+            packages.iter().map(|package| package.name.clone()),
+            FLATPAK_INFO_CONCURRENCY,
+            |app_id| async move {
+                let summary = Self::get_summary(config, &app_id).await;
+                let eol = Self::get_info(config, &app_id)
+                    .await
+                    .and_then(|info| parse_info_eol(&info));
+                (summary, eol)
+            },
+        )
+        .await;
+        for (package, (summary, eol)) in packages.iter_mut().zip(infos) {
+            if summary.is_some() {
+                package.description = summary;
+            }
+            package.eol = eol;
+        }
+
         Ok(packages)
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("search")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let mut packages = Vec::new();
+        let mut app_ids = parse_search_app_ids(&stdout);
+
+        // Only query installed versions for the results we keep
+        let total = app_ids.len();
+        app_ids.truncate(search_limit(config, total));
+
+        let mut packages = Vec::with_capacity(app_ids.len());
+        for app_id in app_ids {
+            let version = self
+                .get_current_version(config, &app_id)
+                .await
+                .unwrap_or_else(|_| "Not Installed".to_string());
+
+            packages.push(PackageInfo {
+                name: app_id,
+                version,
+                source: PackageManagerType::Flatpak,
+                description: None,
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            });
+        }
 
-        // flatpak search 输出格式：
-        // Name                     Description                         Application ID                   Version    Branch  Remotes
-        for (i, line) in stdout.lines().enumerate() {
-            let line = line.trim();
+        Ok(SearchResults { packages, total })
+    }
 
-            // Skip header
-            if i == 0 || line.is_empty() {
+    async fn list_problems(&self, config: &Config) -> CoreResult<Vec<Problem>> {
+        let path = command_path(config);
+        let output = new_command(config, &path)
+            .arg("repair")
+            .arg("--dry-run")
+            .env("LC_ALL", "C")
+            .env("LANG", "C")
+            .stdin(Stdio::null())
+            .try_output()
+            .await?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let problems = Self::parse_repair_output(&stdout);
+
+        if problems.is_empty() && !output.status.success() {
+            return Err(crate::error::CoreError::CommandError(format!(
+                "flatpak repair --dry-run failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(problems)
+    }
+
+    async fn get_package_details(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        Ok(Self::get_summary(config, package_name).await)
+    }
+
+    /// Branches of `app_id` offered by the configured remotes.
+    async fn list_available_versions(
+        &self,
+        config: &Config,
+        app_id: &str,
+    ) -> CoreResult<Vec<String>> {
+        let output = new_command(config, command_path(config))
+            .arg("remote-ls")
+            .arg("--app")
+            .arg("--all")
+            .arg("--columns=application,branch")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(crate::error::CoreError::CommandError(format!(
+                "flatpak remote-ls failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(sort_versions_newest_first(Self::parse_remote_branches(
+            &stdout, app_id,
+        )))
+    }
+
+    /// Runtimes the apps need that are not installed yet, and the apps'
+    /// download size, from `flatpak remote-info` on the first remote that
+    /// has each app. Runtime sizes are not known up front.
+    async fn preview_install(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<InstallPreview> {
+        let remotes = Self::list_remotes(config).await?;
+        let mut preview = InstallPreview::default();
+
+        for app_id in package_names {
+            let Some(info) = Self::get_remote_info(config, &remotes, app_id).await else {
                 continue;
+            };
+            let (runtime, download_size) = parse_remote_info(&info);
+
+            if let Some(size) = download_size {
+                *preview.download_size.get_or_insert(0) += size;
             }
+            if let Some(runtime) = runtime
+                && !preview.additional.contains(&runtime)
+                && Self::get_info(config, &runtime).await.is_none()
+            {
+                preview.additional.push(runtime);
+            }
+        }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 3 {
-                // Try to find application ID (usually contains a dot)
-                let app_id = parts
-                    .iter()
-                    .find(|p| p.contains('.') && p.len() > 5)
-                    .map(|s| s.to_string());
+        Ok(preview)
+    }
 
-                if let Some(app_id) = app_id {
-                    let version = Self::get_current_version(config, &app_id)
-                        .await
-                        .unwrap_or_else(|_| "Not Installed".to_string());
+    /// `flatpak update -y` without package names, updating everything.
+    async fn update_all(
+        &self,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(InstallProgress) + Send),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+        let args = command_args::<&str>(config, PackageManagerType::Flatpak, UPDATE_ARGS, &[]);
+        let report = bulk_progress(
+            PackageManagerType::Flatpak,
+            package_names.len(),
+            on_progress,
+        );
 
-                    packages.push(PackageInfo {
-                        name: app_id,
-                        version,
-                        source: PackageManagerType::Flatpak,
-                        description: None,
-                        size: None,
-                        install_date: None,
-                        homepage: None,
-                    });
-                }
-            }
+        run_command_with_progress(config, &path, &args, report).await
+    }
+
+    /// Flatpak operates on case-sensitive reverse-DNS app IDs such as
+    /// `org.gimp.GIMP`, not display names.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        let app_id = checked_package_name(PackageManagerType::Flatpak, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+        })?;
+
+        let segments: Vec<&str> = app_id.split('.').collect();
+        if segments.len() < 3 || segments.iter().any(|segment| segment.is_empty()) {
+            return Err(invalid_package_name(
+                PackageManagerType::Flatpak,
+                package_name,
+                "expected an application ID like org.example.App",
+            ));
         }
 
-        Ok(packages)
+        Ok(app_id.to_owned())
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
     }
 }
 
 impl FlatpakManager {
+    /// `application  branch` rows from `flatpak remote-ls` for `app_id`.
+    fn parse_remote_branches(output: &str, app_id: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut columns = line.split_whitespace();
+                let (application, branch) = (columns.next()?, columns.next()?);
+                (application == app_id).then(|| branch.to_owned())
+            })
+            .collect()
+    }
+
+    fn parse_repair_output(output: &str) -> Vec<Problem> {
+        // flatpak repair --dry-run 输出示例：
+        // Dry run: Deleting ref app/org.gimp.GIMP/x86_64/stable due to missing objects
+        // Remote flathub for ref app/org.gimp.GIMP/x86_64/stable is disabled
+        const PROBLEM_MARKERS: &[&str] = &[
+            "Deleting ref",
+            "Object missing",
+            "Object invalid",
+            "Problems loading data",
+            "is missing",
+            "is disabled",
+        ];
+
+        let mut problems = Vec::new();
+        let mut seen = HashSet::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+            let line = line.strip_prefix("Dry run:").unwrap_or(line).trim();
+            if !PROBLEM_MARKERS.iter().any(|marker| line.contains(marker)) {
+                continue;
+            }
+
+            // ref 格式：app/<id>/<arch>/<branch> 或 runtime/<id>/<arch>/<branch>
+            let Some(app_id) = line.split_whitespace().find_map(|token| {
+                let mut parts = token.split('/');
+                matches!(parts.next(), Some("app") | Some("runtime"))
+                    .then(|| parts.next())
+                    .flatten()
+            }) else {
+                continue;
+            };
+
+            if !seen.insert(line.to_owned()) {
+                continue;
+            }
+
+            problems.push(Problem {
+                manager: PackageManagerType::Flatpak,
+                package: app_id.to_owned(),
+                description: line.to_owned(),
+                suggested_fix: Some(if line.contains("is disabled") {
+                    "Enable the remote with flatpak remote-modify --enable".to_owned()
+                } else {
+                    format!("flatpak repair && flatpak install --reinstall {}", app_id)
+                }),
+            });
+        }
+
+        problems
+    }
+
     async fn list_updates_via_update(
         config: &Config,
         installed_info: &HashMap<String, (String, String)>,
     ) -> CoreResult<Vec<PackageUpdate>> {
         let path = command_path(config);
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("update")
             .env("LC_ALL", "C")
             .env("LANG", "C")
             .stdin(Stdio::null())
-            .output()
+            .try_output()
             .await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
@@ -254,20 +501,20 @@ impl FlatpakManager {
         installed_info: &HashMap<String, (String, String)>,
     ) -> Vec<PackageUpdate> {
         let mut updates = Vec::new();
-        let mut seen = HashSet::new();
 
+        // 同一应用的多个分支都会列出，由 `dedup_updates` 保留最高版本
         for line in output.lines() {
             let Some((app_id, branch)) = Self::parse_update_listing_line(line) else {
                 continue;
             };
 
-            if !seen.insert(app_id.to_string()) {
-                continue;
-            }
-
-            if let Some(update) =
+            if let Some(mut update) =
                 Self::build_package_update(app_id, "", branch, None, installed_info)
             {
+                // 下载大小在行尾，形如 `< 378.7 MB` 或 `18.5 kB / 385.5 MB`
+                update.download_size = line
+                    .rsplit_once(['<', '/'])
+                    .and_then(|(_, size)| parse_size(size));
                 updates.push(update);
             }
         }
@@ -275,6 +522,80 @@ impl FlatpakManager {
         updates
     }
 
+    /// Names of the configured remotes.
+    async fn list_remotes(config: &Config) -> CoreResult<Vec<String>> {
+        let output = new_command(config, command_path(config))
+            .arg("remotes")
+            .arg("--columns=name")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(crate::error::CoreError::CommandError(format!(
+                "flatpak remotes failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    /// `flatpak remote-info` output for `app_id` from the first remote that has it.
+    async fn get_remote_info(config: &Config, remotes: &[String], app_id: &str) -> Option<String> {
+        for remote in remotes {
+            let output = new_command(config, command_path(config))
+                .arg("remote-info")
+                .arg(remote)
+                .arg(app_id)
+                .env("LC_ALL", "C")
+                .env("LANG", "C")
+                .output()
+                .await
+                .ok()?;
+
+            if output.status.success() {
+                return Some(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+        }
+
+        log::debug!("flatpak remote-info found no remote with {}", app_id);
+        None
+    }
+
+    /// `flatpak info` output of an installed app, in the C locale so the
+    /// field labels parse.
+    async fn get_info(config: &Config, app_id: &str) -> Option<String> {
+        Self::info_output(config, app_id, true).await
+    }
+
+    /// One-line summary of an installed app, in the user's language.
+    async fn get_summary(config: &Config, app_id: &str) -> Option<String> {
+        Self::info_output(config, app_id, false)
+            .await
+            .and_then(|info| parse_info_summary(&info))
+    }
+
+    async fn info_output(config: &Config, app_id: &str, c_locale: bool) -> Option<String> {
+        let mut command = new_command(config, command_path(config));
+        command.arg("info").arg(app_id);
+        if c_locale {
+            command.env("LC_ALL", "C").env("LANG", "C");
+        }
+        let output = command.output().await.ok()?;
+
+        if !output.status.success() {
+            log::debug!("flatpak info {} failed", app_id);
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
     fn parse_update_listing_line(line: &str) -> Option<(&str, &str)> {
         let parts: Vec<&str> = line.split_whitespace().collect();
         if parts.len() < 5 || !Self::is_numbered_update_row(parts[0]) {
@@ -343,6 +664,8 @@ impl FlatpakManager {
             name: app_id.to_owned(),
             current_version,
             new_version: new_version_str,
+            security: false,
+            download_size: None,
         })
     }
 
@@ -353,13 +676,9 @@ impl FlatpakManager {
     ) -> CoreResult<()> {
         let path = command_path(config);
 
-        let args = vec![
-            "uninstall".to_string(),
-            "-y".to_string(),
-            package_name.to_owned(),
-        ];
+        let args = removal_args(UNINSTALL_ARGS, &[package_name]);
 
-        run_command_with_progress(&path, &args, on_progress).await
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     pub async fn update_package_with_progress(
@@ -369,13 +688,14 @@ impl FlatpakManager {
     ) -> CoreResult<()> {
         let path = command_path(config);
 
-        let args = vec![
-            "update".to_string(),
-            "-y".to_string(),
-            package_name.to_owned(),
-        ];
+        let args = command_args(
+            config,
+            PackageManagerType::Flatpak,
+            UPDATE_ARGS,
+            &[package_name],
+        );
 
-        run_command_with_progress(&path, &args, on_progress).await
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     pub async fn install_package_with_progress(
@@ -385,31 +705,14 @@ impl FlatpakManager {
     ) -> CoreResult<()> {
         let path = command_path(config);
 
-        let args = vec![
-            "install".to_string(),
-            "-y".to_string(),
-            package_name.to_owned(),
-        ];
-
-        run_command_with_progress(&path, &args, on_progress).await
-    }
-    /// Parse Flatpak size string (e.g., "123.4 MB" or "1.2 GB")
-    fn parse_flatpak_size(size_str: &str) -> Option<u64> {
-        let parts: Vec<&str> = size_str.split_whitespace().collect();
-        if parts.len() != 2 {
-            return None;
-        }
-
-        let value: f64 = parts[0].parse().ok()?;
-        let multiplier = match parts[1].to_uppercase().as_str() {
-            "B" | "BYTES" => 1.0,
-            "KB" | "KIB" => 1024.0,
-            "MB" | "MIB" => 1024.0 * 1024.0,
-            "GB" | "GIB" => 1024.0 * 1024.0 * 1024.0,
-            _ => return None,
-        };
+        let args = command_args(
+            config,
+            PackageManagerType::Flatpak,
+            INSTALL_ARGS,
+            &[package_name],
+        );
 
-        Some((value * multiplier) as u64)
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     #[allow(dead_code)]
@@ -437,10 +740,10 @@ impl FlatpakManager {
     ) -> CoreResult<HashMap<String, (String, String)>> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("list")
             .arg("--columns=application,version,branch")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -485,9 +788,109 @@ impl FlatpakManager {
     }
 }
 
+/// Summary from the `Name - Summary` header that `flatpak info` prints
+/// first; apps without appstream data have no header.
+fn parse_info_summary(output: &str) -> Option<String> {
+    let header = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+
+    let (_name, summary) = header.split_once(" - ")?;
+    let summary = summary.trim();
+    (!summary.is_empty()).then(|| summary.to_owned())
+}
+
+/// End-of-life notice from the `End-of-life:` / `End-of-life-rebase:` fields
+/// of `flatpak info`, e.g. `No longer maintained (replaced by org.new.App)`.
+fn parse_info_eol(output: &str) -> Option<String> {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            (key.trim() == name && !value.is_empty()).then_some(value)
+        })
+    };
+
+    match (field("End-of-life"), field("End-of-life-rebase")) {
+        (Some(reason), Some(rebase)) => Some(format!("{} (replaced by {})", reason, rebase)),
+        (Some(reason), None) => Some(reason.to_owned()),
+        (None, Some(rebase)) => Some(format!("replaced by {}", rebase)),
+        (None, None) => None,
+    }
+}
+
+/// `Runtime:` ref and `Download:` size of `flatpak remote-info` output.
+fn parse_remote_info(output: &str) -> (Option<String>, Option<u64>) {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            (key.trim() == name && !value.is_empty()).then_some(value)
+        })
+    };
+
+    (
+        field("Runtime").map(str::to_owned),
+        field("Download").and_then(parse_size),
+    )
+}
+
+/// Application IDs from `flatpak search`, in output order.
+fn parse_search_app_ids(stdout: &str) -> Vec<String> {
+    // flatpak search 输出格式：
+    // Name                     Description                         Application ID                   Version    Branch  Remotes
+    stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 3 {
+                return None;
+            }
+
+            // Try to find application ID (usually contains a dot)
+            parts
+                .into_iter()
+                .find(|p| p.contains('.') && p.len() > 5)
+                .map(str::to_owned)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::{config_with_custom_args, dedup_updates, fixture};
+
+    #[test]
+    fn test_parse_repair_output() {
+        let output = "[1/3] Verifying flathub:app/org.gimp.GIMP/x86_64/stable…
+Dry run: Deleting ref app/org.gimp.GIMP/x86_64/stable due to missing objects
+[2/3] Verifying flathub:runtime/org.gnome.Platform/x86_64/46…
+Remote flathub for ref runtime/org.gnome.Platform/x86_64/46 is disabled
+Checking remotes...
+";
+
+        let problems = FlatpakManager::parse_repair_output(output);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].package, "org.gimp.GIMP");
+        assert_eq!(
+            problems[0].suggested_fix.as_deref(),
+            Some("flatpak repair && flatpak install --reinstall org.gimp.GIMP")
+        );
+        assert_eq!(problems[1].package, "org.gnome.Platform");
+    }
+
+    #[test]
+    fn test_parse_remote_branches() {
+        let output = "org.gimp.GIMP\tstable\norg.gimp.GIMP\tbeta\norg.mozilla.firefox\tstable\n";
+
+        assert_eq!(
+            FlatpakManager::parse_remote_branches(output, "org.gimp.GIMP"),
+            ["stable", "beta"]
+        );
+    }
 
     #[test]
     fn test_extract_version_and_branch() {
@@ -535,6 +938,44 @@ mod tests {
         assert_eq!(parsed, Some(("org.freedesktop.Platform.Locale", "25.08")));
     }
 
+    #[test]
+    fn test_app_on_two_branches_is_listed_once_with_newest_branch() {
+        let installed_info = HashMap::from([(
+            "org.freedesktop.Platform".to_owned(),
+            ("23.08.20".to_owned(), "23.08".to_owned()),
+        )]);
+        let output = "\
+ 1.\t\torg.freedesktop.Platform\t24.08\tu\tflathub\t< 180.2 MB
+ 2.\t\torg.freedesktop.Platform\t23.08\tu\tflathub\t< 1.1 MB
+";
+        let updates = dedup_updates(FlatpakManager::parse_updates_from_update_output(
+            output,
+            &installed_info,
+        ));
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].name, "org.freedesktop.Platform");
+        assert_eq!(updates[0].new_version, "update available (24.08)");
+    }
+
+    #[test]
+    fn test_parse_updates_reads_download_size() {
+        let installed_info = HashMap::from([(
+            "org.freedesktop.Platform.Locale".to_owned(),
+            ("25.08.1".to_owned(), "25.08".to_owned()),
+        )]);
+        let updates = FlatpakManager::parse_updates_from_update_output(
+            " 3.\t\torg.freedesktop.Platform.Locale\t25.08\tu\tflathub\t< 378.7 MB",
+            &installed_info,
+        );
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(
+            updates[0].download_size,
+            Some((378.7 * 1024.0 * 1024.0) as u64)
+        );
+    }
+
     #[tokio::test]
     async fn test_get_all_installed_info() {
         let config = crate::Config::default();
@@ -556,7 +997,7 @@ mod tests {
     #[tokio::test]
     async fn test_flatpak_list_updates() {
         let config = crate::Config::default();
-        match FlatpakManager::list_updates(&config).await {
+        match FlatpakManager.list_updates(&config).await {
             Ok(updates) => {
                 println!("\nFound {} Flatpak updates:", updates.len());
                 for update in updates {
@@ -573,9 +1014,88 @@ mod tests {
     async fn test_get_current_version() {
         let config = crate::Config::default();
 
-        match FlatpakManager::get_current_version(&config, "org.freedesktop.Platform").await {
+        match FlatpakManager
+            .get_current_version(&config, "org.freedesktop.Platform")
+            .await
+        {
             Ok(version) => println!("Version: {}", version),
             Err(e) => eprintln!("Error: {}", e),
         }
     }
+
+    #[test]
+    fn test_parse_search_app_ids_fixture() {
+        assert_eq!(
+            parse_search_app_ids(&fixture("flatpak/search.txt")),
+            [
+                "org.gimp.GIMP",
+                "org.gimp.GIMP.Plugin.GMic",
+                "org.kde.krita"
+            ]
+        );
+        assert!(parse_search_app_ids("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_info_summary_fixture() {
+        assert_eq!(
+            parse_info_summary(&fixture("flatpak/info.txt")).as_deref(),
+            Some("Create images and edit photographs")
+        );
+        assert_eq!(parse_info_summary("          ID: org.gimp.GIMP\n"), None);
+        assert_eq!(parse_info_summary(""), None);
+    }
+
+    #[test]
+    fn test_parse_remote_info_fixture() {
+        let (runtime, download_size) = parse_remote_info(&fixture("flatpak/remote_info.txt"));
+        assert_eq!(runtime.as_deref(), Some("org.gnome.Platform/x86_64/47"));
+        assert_eq!(download_size, Some(2 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_parse_info_eol_fixture() {
+        assert_eq!(
+            parse_info_eol(&fixture("flatpak/info_eol.txt")).as_deref(),
+            Some("This application is no longer maintained (replaced by org.gnome.Decibels)")
+        );
+        assert_eq!(parse_info_eol(&fixture("flatpak/info.txt")), None);
+        assert_eq!(
+            parse_info_eol("End-of-life-rebase: org.new.App\n").as_deref(),
+            Some("replaced by org.new.App")
+        );
+    }
+
+    #[test]
+    fn custom_args_go_before_the_app_id() {
+        let config = config_with_custom_args(PackageManagerType::Flatpak, &["--user"]);
+
+        assert_eq!(
+            command_args(
+                &config,
+                PackageManagerType::Flatpak,
+                INSTALL_ARGS,
+                &["org.gimp.GIMP"]
+            ),
+            ["install", "-y", "--user", "org.gimp.GIMP"]
+        );
+    }
+
+    #[test]
+    fn normalize_name_requires_an_application_id() {
+        assert_eq!(
+            FlatpakManager.normalize_name(" org.gimp.GIMP ").unwrap(),
+            "org.gimp.GIMP"
+        );
+        assert_eq!(
+            FlatpakManager
+                .normalize_name("org.freedesktop.Platform.GL.default")
+                .unwrap(),
+            "org.freedesktop.Platform.GL.default"
+        );
+        assert!(FlatpakManager.normalize_name("GIMP").is_err());
+        assert!(FlatpakManager.normalize_name("org.gimp").is_err());
+        assert!(FlatpakManager.normalize_name("org..GIMP").is_err());
+        assert!(FlatpakManager.normalize_name("--system").is_err());
+    }
 }
@@ -1,13 +1,20 @@
+use std::collections::{HashMap, HashSet};
+
 use async_trait::async_trait;
 use regex::Regex;
-use tokio::{fs, process::Command};
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, PendingUpdates, SearchResults, compare_versions,
     pm::{
-        common::manager_command_path,
+        common::{
+            CommandExt, allow_prerelease_updates, checked_package_name, command_args,
+            is_prerelease, limit_search_results, manager_command_path, new_command, read_bin_dir,
+            sort_versions_newest_first,
+        },
         progress::{CommandProgressEvent, run_command_with_progress},
     },
+    script::shell_quote,
 };
 
 #[derive(Debug, Clone)]
@@ -25,44 +32,60 @@ fn command_path(config: &Config) -> String {
 
 #[async_trait]
 impl PackageManager for GoManager {
-    async fn list_updates(config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Go
+    }
+
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
         let path = command_path(config);
         let binaries = Self::list_installed_binaries(config).await?;
         let mut updates = Vec::new();
 
+        // Binaries without module info or a reachable module are skipped
         for binary in binaries {
-            // Try to get version info from the binary
-            if let Ok(local_info) = Self::get_binary_info(&path, &binary.path).await {
-                // Extract module path, e.g., github.com/user/repo
-                if let Some(module) = Self::extract_module_path(&local_info) {
-                    // Get latest version
-                    if let Ok(latest_version) = Self::get_latest_version(&path, &module).await {
-                        // Extract local version
-                        if let Some(local_version) = Self::extract_version(&local_info)
-                            && local_version != latest_version
-                            && !latest_version.is_empty()
-                        {
-                            updates.push(PackageUpdate {
-                                name: binary.name.clone(),
-                                current_version: local_version,
-                                new_version: latest_version,
-                            });
-                        }
-                    }
-                }
+            if let Ok(Some(update)) = Self::find_update(config, &path, &binary).await {
+                updates.push(update);
             }
         }
 
         Ok(updates)
     }
 
-    async fn get_current_version(config: &Config, package_name: &str) -> CoreResult<String> {
+    /// Look up only the requested binaries. A failed lookup keeps the
+    /// binary pending, so a network error never skips an update.
+    async fn pending_updates(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<PendingUpdates> {
+        let path = command_path(config);
+        let binaries = Self::list_installed_binaries(config).await?;
+        let mut checks = Vec::new();
+
+        for name in package_names {
+            let still_pending = match binaries.iter().find(|binary| &binary.name == name) {
+                Some(binary) => match Self::find_update(config, &path, binary).await {
+                    Ok(update) => update.is_some(),
+                    Err(e) => {
+                        log::debug!("Go: no latest version for {}: {}", name, e);
+                        true
+                    }
+                },
+                None => true,
+            };
+            checks.push((name.clone(), still_pending));
+        }
+
+        Ok(PendingUpdates::from_checks(checks))
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
         let path = command_path(config);
         let binaries = Self::list_installed_binaries(config).await?;
 
         // Try to find matching binary
         for binary in binaries {
-            if let Ok(info) = Self::get_binary_info(&path, &binary.path).await
+            if let Ok(info) = Self::get_binary_info(config, &path, &binary.path).await
                 && let Some(module) = Self::extract_module_path(&info)
                 && (module == package_name || binary.name == package_name)
                 && let Some(version) = Self::extract_version(&info)
@@ -77,44 +100,38 @@ impl PackageManager for GoManager {
         )))
     }
 
-    async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>> {
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
         let path = command_path(config);
         let binaries = Self::list_installed_binaries(config).await?;
 
         let mut packages = Vec::new();
         for binary in binaries {
-            if let Ok(info) = Self::get_binary_info(&path, &binary.path).await
-                && let Some(_module) = Self::extract_module_path(&info)
-            {
-                let version = Self::extract_version(&info).unwrap_or_else(|| "unknown".to_string());
-                packages.push(PackageInfo {
-                    name: binary.name,
-                    version,
-                    source: PackageManagerType::Go,
-                    description: None,
-                    size: None,
-                    install_date: None,
-                    homepage: None,
-                });
+            // `go version -m` 失败说明不是 Go 程序，跳过
+            if let Ok(info) = Self::get_binary_info(config, &path, &binary.path).await {
+                packages.push(Self::installed_package(binary.name, &info));
             }
         }
 
         Ok(packages)
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         let path = command_path(config);
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("list")
             .arg("-m")
             .arg("-versions")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
@@ -133,7 +150,8 @@ impl PackageManager for GoManager {
             }
 
             let module_name = parts[0].to_string();
-            let version = Self::get_current_version(config, &module_name)
+            let version = self
+                .get_current_version(config, &module_name)
                 .await
                 .unwrap_or_else(|_| "Not Installed".to_string());
 
@@ -145,32 +163,111 @@ impl PackageManager for GoManager {
                 size: None,
                 install_date: None,
                 homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
             });
         }
 
-        Ok(packages)
+        Ok(limit_search_results(config, packages))
+    }
+
+    /// Module versions from `go list -m -versions`, newest first.
+    /// Binary names are resolved to their module path through build info.
+    async fn list_available_versions(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Vec<String>> {
+        let install_path = Self::resolve_install_path(config, package_name).await?;
+        let module_path = install_path.split('@').next().unwrap_or(&install_path);
+
+        let output = new_command(config, command_path(config))
+            .arg("list")
+            .arg("-m")
+            .arg("-versions")
+            .arg(module_path)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(crate::error::CoreError::CommandError(format!(
+                "go list -m -versions {} failed: {}",
+                module_path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(sort_versions_newest_first(
+            Self::parse_versions_from_list_output(&stdout),
+        ))
+    }
+
+    /// A binary name or module path, optionally with `@version`. Module
+    /// paths are case-sensitive, so only the character set is checked.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Go, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '/' | '@' | '+')
+        })
+        .map(str::to_owned)
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
     }
 }
 
 impl GoManager {
     pub async fn uninstall_package_with_progress(
-        _config: &Config,
+        config: &Config,
         package_name: &str,
         mut on_progress: impl FnMut(CommandProgressEvent),
     ) -> CoreResult<()> {
+        // 与 list_installed 一样在 Config::go_bin_dirs 中查找二进制
+        let binary_name = package_name.split('/').next_back().unwrap_or(package_name);
+        let binary_path = Self::list_installed_binaries(config)
+            .await?
+            .into_iter()
+            .find(|binary| binary.name == binary_name)
+            .map(|binary| binary.path)
+            .ok_or_else(|| {
+                crate::error::CoreError::ParseError(format!(
+                    "Go binary {} not found in any Go bin directory",
+                    binary_name
+                ))
+            })?;
+
+        if config.dry_run {
+            on_progress(CommandProgressEvent {
+                progress: 1.0,
+                command_message: Some(format!("rm {}", shell_quote(&binary_path))),
+            });
+            return Ok(());
+        }
+
         // Go uninstall is file removal only, no native streamed percentage.
         on_progress(CommandProgressEvent {
             progress: 0.0,
             command_message: Some(format!("Removing Go binary for {}", package_name)),
         });
 
-        let gobin = std::env::var("GOBIN")
-            .or_else(|_| std::env::var("GOPATH").map(|p| format!("{}/bin", p)))
-            .unwrap_or_else(|_| format!("{}/go/bin", std::env::var("HOME").unwrap_or_default()));
-
-        let binary_name = package_name.split('/').next_back().unwrap_or(package_name);
-        let binary_path = format!("{}/{}", gobin, binary_name);
-
         if let Err(e) = tokio::fs::remove_file(&binary_path).await {
             return Err(crate::error::CoreError::UnknownError(format!(
                 "Failed to remove Go binary {}: {}",
@@ -193,8 +290,13 @@ impl GoManager {
         let path = command_path(config);
         let install_path = Self::resolve_install_path(config, package_name).await?;
 
-        let args = vec!["install".to_string(), install_path];
-        run_command_with_progress(&path, &args, on_progress).await
+        let args = command_args(
+            config,
+            PackageManagerType::Go,
+            &["install"],
+            &[install_path],
+        );
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     pub async fn install_package_with_progress(
@@ -205,18 +307,61 @@ impl GoManager {
         let path = command_path(config);
         let install_path = Self::resolve_install_path(config, package_name).await?;
 
-        let args = vec!["install".to_string(), install_path];
-        run_command_with_progress(&path, &args, on_progress).await
+        let args = command_args(
+            config,
+            PackageManagerType::Go,
+            &["install"],
+            &[install_path],
+        );
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    /// Update of `binary` from its module's versions, or `None` when it is
+    /// current.
+    async fn find_update(
+        config: &Config,
+        path: &str,
+        binary: &InstalledBinary,
+    ) -> CoreResult<Option<PackageUpdate>> {
+        let info = Self::get_binary_info(config, path, &binary.path).await?;
+        let (Some(module), Some(local_version)) = (
+            Self::extract_module_path(&info),
+            Self::extract_version(&info),
+        ) else {
+            return Err(crate::error::CoreError::UnknownError(format!(
+                "{} has no module info",
+                binary.name
+            )));
+        };
+
+        let include_prereleases = allow_prerelease_updates(config, &local_version);
+        let latest_version =
+            Self::get_latest_version(config, path, &module, include_prereleases).await?;
+
+        Ok(compare_versions(&local_version, &latest_version)
+            .is_lt()
+            .then(|| PackageUpdate {
+                name: binary.name.clone(),
+                current_version: local_version,
+                new_version: latest_version,
+                security: false,
+                download_size: None,
+            }))
     }
 
     /// Get latest version using go list
-    async fn get_latest_version(path: &str, package_name: &str) -> CoreResult<String> {
-        let output = Command::new(path)
+    async fn get_latest_version(
+        config: &Config,
+        path: &str,
+        package_name: &str,
+        include_prereleases: bool,
+    ) -> CoreResult<String> {
+        let output = new_command(config, path)
             .arg("list")
             .arg("-m")
             .arg("-versions")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -227,7 +372,9 @@ impl GoManager {
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        if let Some(version) = Self::parse_latest_version_from_list_output(&stdout) {
+        if let Some(version) =
+            Self::parse_latest_version_from_list_output(&stdout, include_prereleases)
+        {
             return Ok(version);
         }
 
@@ -237,35 +384,87 @@ impl GoManager {
         )))
     }
 
-    /// List all installed Go binaries
+    /// Installed entry for a Go binary from its `go version -m` output.
+    /// Binaries without module info (GOPATH mode, stripped) are kept but
+    /// marked as unable to check for updates.
+    fn installed_package(name: String, info: &str) -> PackageInfo {
+        let has_update_info = Self::extract_module_path(info).is_some();
+
+        PackageInfo {
+            name,
+            version: Self::extract_version(info).unwrap_or_else(|| "unknown".to_string()),
+            source: PackageManagerType::Go,
+            description: (!has_update_info)
+                .then(|| "no module info (cannot check updates)".to_string()),
+            size: None,
+            install_date: None,
+            homepage: None,
+            popularity: None,
+            has_update_info,
+            eol: None,
+        }
+    }
+
+    /// List installed Go binaries across `Config::go_bin_dirs`. A name found
+    /// in several directories is listed once, from the first directory.
+    /// Unreadable directories are skipped unless none can be read.
     async fn list_installed_binaries(config: &Config) -> CoreResult<Vec<InstalledBinary>> {
-        let bin_dir = config.get_go_bin_dir();
-        let mut bins = Vec::new();
-
-        if let Ok(mut entries) = fs::read_dir(&bin_dir).await {
-            while let Some(entry) = entries.next_entry().await.unwrap_or(None) {
-                if let Ok(file_type) = entry.file_type().await
-                    && file_type.is_file()
-                    && let Some(name) = entry.file_name().to_str()
-                {
-                    let path = entry.path().to_string_lossy().to_string();
-                    bins.push(InstalledBinary {
-                        name: name.to_owned(),
-                        path,
+        let mut binaries: Vec<InstalledBinary> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut first_error = None;
+        let mut any_read = false;
+
+        for bin_dir in config.go_bin_dirs() {
+            let files = match read_bin_dir(&bin_dir).await {
+                Ok(files) => files,
+                Err(e) => {
+                    log::debug!("Go: skipping bin dir {}: {}", bin_dir.display(), e);
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            any_read = true;
+
+            for (name, path) in files {
+                if seen.insert(name.clone()) {
+                    binaries.push(InstalledBinary {
+                        name,
+                        path: path.to_string_lossy().to_string(),
                     });
                 }
             }
         }
-        Ok(bins)
+
+        match first_error {
+            Some(e) if !any_read => Err(e),
+            _ => Ok(binaries),
+        }
+    }
+
+    /// Package path `go install` takes for each installed binary, keyed by
+    /// binary name. Binaries without module info are left out.
+    pub(crate) async fn package_paths(config: &Config) -> CoreResult<HashMap<String, String>> {
+        let path = command_path(config);
+        let mut paths = HashMap::new();
+
+        for binary in Self::list_installed_binaries(config).await? {
+            if let Ok(info) = Self::get_binary_info(config, &path, &binary.path).await
+                && let Some(package_path) = Self::extract_module_path(&info)
+            {
+                paths.insert(binary.name, package_path);
+            }
+        }
+
+        Ok(paths)
     }
 
     /// Get build info of a binary (using go version -m)
-    async fn get_binary_info(path: &str, binary_path: &str) -> CoreResult<String> {
-        let output = Command::new(path)
+    async fn get_binary_info(config: &Config, path: &str, binary_path: &str) -> CoreResult<String> {
+        let output = new_command(config, path)
             .arg("version")
             .arg("-m")
             .arg(binary_path)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -295,7 +494,7 @@ impl GoManager {
                 continue;
             }
 
-            if let Ok(info) = Self::get_binary_info(&path, &binary.path).await
+            if let Ok(info) = Self::get_binary_info(config, &path, &binary.path).await
                 && let Some(module_path) = Self::extract_module_path(&info)
             {
                 return Ok(Self::build_install_path(&module_path));
@@ -339,9 +538,22 @@ impl GoManager {
             .map(|m| m.as_str().to_string())
     }
 
+    /// All versions from `go list -m -versions` output.
+    fn parse_versions_from_list_output(output: &str) -> Vec<String> {
+        output
+            .split_whitespace()
+            .skip(1)
+            .filter(|token| token.starts_with('v'))
+            .map(str::to_owned)
+            .collect()
+    }
+
     /// Parse latest version from `go list -m -versions` output.
     /// Output format: `module/path v0.1.0 v0.2.0 ...`
-    fn parse_latest_version_from_list_output(output: &str) -> Option<String> {
+    fn parse_latest_version_from_list_output(
+        output: &str,
+        include_prereleases: bool,
+    ) -> Option<String> {
         let tokens: Vec<&str> = output.split_whitespace().collect();
         if tokens.len() <= 1 {
             return None;
@@ -351,7 +563,7 @@ impl GoManager {
             .iter()
             .skip(1)
             .rev()
-            .find(|token| token.starts_with('v'))
+            .find(|token| token.starts_with('v') && (include_prereleases || !is_prerelease(token)))
             .map(|token| (*token).to_owned())
     }
 }
@@ -359,6 +571,7 @@ impl GoManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::fixture;
 
     #[test]
     fn test_extract_module_path() {
@@ -407,14 +620,55 @@ mod tests {
     #[test]
     fn test_parse_latest_version_from_list_output() {
         let output = "github.com/user/tool v0.1.0 v0.2.0 v0.2.1\n";
-        let version = GoManager::parse_latest_version_from_list_output(output);
+        let version = GoManager::parse_latest_version_from_list_output(output, false);
         assert_eq!(version, Some("v0.2.1".to_string()));
     }
 
+    #[test]
+    fn test_installed_package_without_module_info_is_listed() {
+        let info = "/home/user/go/bin/oldtool: go1.10\n";
+        let package = GoManager::installed_package("oldtool".to_string(), info);
+
+        assert_eq!(package.name, "oldtool");
+        assert_eq!(package.version, "unknown");
+        assert!(!package.has_update_info);
+        assert_eq!(
+            package.description.as_deref(),
+            Some("no module info (cannot check updates)")
+        );
+
+        let info = "gopls: go1.22.0\n\tpath\tgolang.org/x/tools/gopls\n\tmod\tgolang.org/x/tools/gopls\tv0.15.3\th1:abc=\n";
+        let package = GoManager::installed_package("gopls".to_string(), info);
+        assert!(package.has_update_info);
+        assert_eq!(package.version, "v0.15.3");
+        assert!(package.description.is_none());
+    }
+
+    #[test]
+    fn test_parse_versions_from_list_output() {
+        let output = "golang.org/x/tools v0.1.0 v0.10.0 v0.2.0-pre.1\n";
+        assert_eq!(
+            sort_versions_newest_first(GoManager::parse_versions_from_list_output(output)),
+            ["v0.10.0", "v0.2.0-pre.1", "v0.1.0"]
+        );
+        assert!(GoManager::parse_versions_from_list_output("example.com/mod\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_latest_version_skips_prereleases_by_default() {
+        let output = "github.com/user/tool v1.2.0 v1.3.0 v1.4.0-beta.1\n";
+
+        let stable = GoManager::parse_latest_version_from_list_output(output, false);
+        assert_eq!(stable, Some("v1.3.0".to_string()));
+
+        let latest = GoManager::parse_latest_version_from_list_output(output, true);
+        assert_eq!(latest, Some("v1.4.0-beta.1".to_string()));
+    }
+
     #[test]
     fn test_parse_latest_version_from_list_output_without_versions() {
         let output = "github.com/user/tool\n";
-        let version = GoManager::parse_latest_version_from_list_output(output);
+        let version = GoManager::parse_latest_version_from_list_output(output, false);
         assert_eq!(version, None);
     }
 
@@ -439,4 +693,144 @@ mod tests {
     fn test_looks_like_module_path_for_binary_name() {
         assert!(!GoManager::looks_like_module_path("gup"));
     }
+
+    #[test]
+    fn test_extract_module_info_from_fixture() {
+        let info = fixture("go/version_m.txt");
+
+        assert_eq!(
+            GoManager::extract_module_path(&info).as_deref(),
+            Some("golang.org/x/tools/gopls")
+        );
+        // dep 行的版本不能当作模块版本
+        assert_eq!(
+            GoManager::extract_version(&info).as_deref(),
+            Some("v0.15.3")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_binaries_are_listed_once_across_bin_dirs() {
+        let root = std::env::temp_dir().join(format!("updater-go-bin-{}", std::process::id()));
+        let gobin = root.join("gobin");
+        let gopath_bin = root.join("gopath/bin");
+        tokio::fs::create_dir_all(&gobin).await.unwrap();
+        tokio::fs::create_dir_all(&gopath_bin).await.unwrap();
+        tokio::fs::write(gobin.join("gopls"), b"").await.unwrap();
+        tokio::fs::write(gopath_bin.join("gopls"), b"")
+            .await
+            .unwrap();
+        tokio::fs::write(gopath_bin.join("dlv"), b"").await.unwrap();
+
+        let config = Config {
+            go_bin_dir: Some(gobin.to_string_lossy().into_owned()),
+            go_extra_bin_dirs: vec![
+                gopath_bin.to_string_lossy().into_owned(),
+                root.join("missing").to_string_lossy().into_owned(),
+            ],
+            ..Config::default()
+        };
+        let binaries = GoManager::list_installed_binaries(&config).await;
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+
+        let mut binaries: Vec<(String, String)> = binaries
+            .unwrap()
+            .into_iter()
+            .map(|binary| (binary.name, binary.path))
+            .collect();
+        binaries.sort();
+        assert_eq!(
+            binaries,
+            vec![
+                (
+                    "dlv".to_owned(),
+                    gopath_bin.join("dlv").to_string_lossy().into_owned()
+                ),
+                (
+                    "gopls".to_owned(),
+                    gobin.join("gopls").to_string_lossy().into_owned()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_updates_keep_binaries_whose_lookup_fails() {
+        let gobin = std::env::temp_dir().join(format!("updater-go-pending-{}", std::process::id()));
+        tokio::fs::create_dir_all(&gobin).await.unwrap();
+        // Not a Go binary, so `go version -m` cannot read its module
+        tokio::fs::write(gobin.join("gopls"), b"").await.unwrap();
+
+        let config = Config {
+            go_bin_dir: Some(gobin.to_string_lossy().into_owned()),
+            ..Config::default()
+        };
+        let pending = GoManager
+            .pending_updates(&config, &["gopls".to_owned(), "dlv".to_owned()])
+            .await;
+        tokio::fs::remove_dir_all(&gobin).await.unwrap();
+
+        assert_eq!(
+            pending.unwrap(),
+            PendingUpdates {
+                pending: vec!["gopls".to_owned(), "dlv".to_owned()],
+                up_to_date: Vec::new(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn uninstall_removes_the_binary_from_the_configured_bin_dir() {
+        let gobin = std::env::temp_dir().join(format!("updater-go-remove-{}", std::process::id()));
+        tokio::fs::create_dir_all(&gobin).await.unwrap();
+        tokio::fs::write(gobin.join("gopls"), b"").await.unwrap();
+
+        let mut config = Config {
+            go_bin_dir: Some(gobin.to_string_lossy().into_owned()),
+            dry_run: true,
+            ..Config::default()
+        };
+        let mut commands = Vec::new();
+        GoManager::uninstall_package_with_progress(&config, "golang.org/x/tools/gopls", |event| {
+            commands.extend(event.command_message)
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            commands,
+            [format!(
+                "rm {}",
+                shell_quote(&gobin.join("gopls").to_string_lossy())
+            )]
+        );
+
+        config.dry_run = false;
+        GoManager::uninstall_package_with_progress(&config, "gopls", |_| {})
+            .await
+            .unwrap();
+        let missing = GoManager::uninstall_package_with_progress(&config, "gopls", |_| {}).await;
+        let still_there = tokio::fs::try_exists(gobin.join("gopls")).await.unwrap();
+        tokio::fs::remove_dir_all(&gobin).await.unwrap();
+
+        assert!(!still_there);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn normalize_name_accepts_binaries_and_module_paths() {
+        assert_eq!(GoManager.normalize_name("gopls").unwrap(), "gopls");
+        assert_eq!(
+            GoManager
+                .normalize_name(" github.com/nao1215/gup@v0.27.0 ")
+                .unwrap(),
+            "github.com/nao1215/gup@v0.27.0"
+        );
+        assert!(GoManager.normalize_name("").is_err());
+        assert!(GoManager.normalize_name("-ldflags=-s").is_err());
+        assert!(
+            GoManager
+                .normalize_name("golang.org/x/tools/gopls latest")
+                .is_err()
+        );
+    }
 }
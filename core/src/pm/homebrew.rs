@@ -1,11 +1,22 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 
 use async_trait::async_trait;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, InstallPreview, InstallProgress, PackageAction, PackageInfo,
+    PackageManager, PackageManagerType, PackageUpdate, SearchResults, bulk_progress,
+    error::CoreError,
     pm::{
-        common::manager_command_path,
+        common::{
+            CommandExt, checked_package_name, command_args, manager_command_path,
+            matches_all_terms, new_command, primary_search_term, removal_args, search_limit,
+            search_terms, sort_versions_newest_first,
+        },
         progress::{CommandProgressEvent, run_command_with_progress},
     },
 };
@@ -13,13 +24,57 @@ use crate::{
 #[derive(Debug, Clone, Copy)]
 pub struct HomebrewManager;
 
+const UNINSTALL_ARGS: &[&str] = &["uninstall"];
+const UPGRADE_ARGS: &[&str] = &["upgrade"];
+const INSTALL_ARGS: &[&str] = &["install"];
+
+/// Default install prefixes, checked in order when `brew` is neither
+/// configured nor on `PATH`: Apple Silicon, Intel macOS, then Linux.
+const BREW_PREFIXES: &[&str] = &["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"];
+
 fn command_path(config: &Config) -> String {
-    manager_command_path(config, PackageManagerType::Homebrew)
+    match HomebrewManager::resolve_binary(config) {
+        Some(brew) => brew.to_string_lossy().into_owned(),
+        None => manager_command_path(config, PackageManagerType::Homebrew),
+    }
+}
+
+/// `brew` binary from the custom path, else the first `brew` on
+/// `search_path`, else `<prefix>/bin/brew` of the first of `BREW_PREFIXES`
+/// where `is_file` finds one.
+fn select_brew_binary(
+    custom_path: Option<&str>,
+    search_path: Option<&OsStr>,
+    is_file: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    if let Some(custom_path) = custom_path {
+        return Some(PathBuf::from(custom_path));
+    }
+
+    search_path
+        .into_iter()
+        .flat_map(env::split_paths)
+        .map(|dir| dir.join("brew"))
+        .chain(
+            BREW_PREFIXES
+                .iter()
+                .map(|prefix| Path::new(prefix).join("bin").join("brew")),
+        )
+        .find(|brew| is_file(brew))
+}
+
+/// Install prefix of a `<prefix>/bin/brew` binary.
+fn prefix_of(brew: &Path) -> Option<PathBuf> {
+    brew.parent()?.parent().map(Path::to_path_buf)
 }
 
 #[async_trait]
 impl PackageManager for HomebrewManager {
-    async fn list_updates(config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Homebrew
+    }
+
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
         /*
          * brew outdated --verbose
          * 输出格式：
@@ -30,10 +85,10 @@ impl PackageManager for HomebrewManager {
          */
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("outdated")
             .arg("--verbose")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -43,44 +98,17 @@ impl PackageManager for HomebrewManager {
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let mut updates: Vec<PackageUpdate> = Vec::new();
-
-        for line in stdout.lines() {
-            let line = line.trim();
-
-            if line.is_empty() {
-                continue;
-            }
-
-            // 解析格式：package_name (current_version) < new_version
-            if let Some((name_and_current, new_version)) = line.split_once('<') {
-                let name_and_current = name_and_current.trim();
-                let new_version = new_version.trim();
-
-                // 提取包名和当前版本
-                if let Some((name, current_version)) =
-                    Self::parse_name_and_version(name_and_current)
-                {
-                    updates.push(PackageUpdate {
-                        name: name.to_owned(),
-                        current_version: current_version.to_owned(),
-                        new_version: new_version.to_owned(),
-                    });
-                }
-            }
-        }
-
-        Ok(updates)
+        Ok(parse_outdated_output(&stdout))
     }
 
-    async fn get_current_version(config: &Config, package_name: &str) -> CoreResult<String> {
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("list")
             .arg("--versions")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -112,90 +140,51 @@ impl PackageManager for HomebrewManager {
         }
     }
 
-    async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>> {
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("info")
             .arg("--json=v2")
             .arg("--installed")
-            .output()
+            .try_output()
             .await?;
 
-        if !output.status.success() {
-            // Fallback to basic info
-            let installed_info = Self::get_all_installed_info().await?;
-            return Ok(installed_info
-                .into_iter()
-                .map(|(name, version)| PackageInfo {
-                    name,
-                    version,
-                    source: PackageManagerType::Homebrew,
-                    description: None,
-                    size: None,
-                    install_date: None,
-                    homepage: None,
-                })
-                .collect());
-        }
-
-        let json_str = String::from_utf8(output.stdout)?;
-        let json: serde_json::Value = serde_json::from_str(&json_str)?;
-
-        let mut packages = Vec::new();
-
-        if let Some(formulae) = json["formulae"].as_array() {
-            for formula in formulae {
-                let name = formula["name"].as_str().unwrap_or("").to_string();
-                let version = formula["versions"]["stable"]
-                    .as_str()
-                    .or_else(|| formula["version"].as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                let description = formula["desc"].as_str().map(|s| s.to_string());
-                let homepage = formula["homepage"].as_str().map(|s| s.to_string());
-
-                packages.push(PackageInfo {
-                    name,
-                    version,
-                    source: PackageManagerType::Homebrew,
-                    description,
-                    size: None,
-                    install_date: None,
-                    homepage,
-                });
-            }
-        }
-
-        if let Some(casks) = json["casks"].as_array() {
-            for cask in casks {
-                let name = cask["token"].as_str().unwrap_or("").to_string();
-                let version = cask["version"].as_str().unwrap_or("unknown").to_string();
-                let description = cask["desc"].as_str().map(|s| s.to_string());
-                let homepage = cask["homepage"].as_str().map(|s| s.to_string());
-
-                packages.push(PackageInfo {
-                    name,
-                    version,
-                    source: PackageManagerType::Homebrew,
-                    description,
-                    size: None,
-                    install_date: None,
-                    homepage,
-                });
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Some(packages) = parse_installed_json(&stdout) {
+                return Ok(packages);
             }
+        } else {
+            log::warn!(
+                "brew info --json=v2 --installed failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
         }
 
-        Ok(packages)
+        // Fallback to basic info
+        let installed_info = Self::get_all_installed_info(config).await?;
+        Ok(installed_info
+            .into_iter()
+            .map(|(name, version)| PackageInfo {
+                name,
+                version,
+                source: PackageManagerType::Homebrew,
+                description: None,
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            })
+            .collect())
     }
 
-    async fn count_installed(config: &Config) -> CoreResult<usize> {
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
         let path = command_path(config);
 
-        let output = tokio::process::Command::new(&path)
-            .arg("list")
-            .output()
-            .await?;
+        let output = new_command(config, &path).arg("list").try_output().await?;
 
         if !output.status.success() {
             return Ok(0);
@@ -205,31 +194,38 @@ impl PackageManager for HomebrewManager {
         Ok(stdout.lines().filter(|l| !l.trim().is_empty()).count())
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         let path = command_path(config);
+        let terms = search_terms(package_name);
+        let Some(primary_term) = primary_search_term(&terms) else {
+            return Ok(SearchResults::default());
+        };
 
-        let output = tokio::process::Command::new(&path)
+        let output = new_command(config, &path)
             .arg("search")
-            .arg(package_name)
-            .output()
+            .arg(primary_term)
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let mut packages = Vec::new();
+        let mut names = parse_search_output(&stdout, &terms);
 
-        // brew search 输出格式：每行一个包名
-        for line in stdout.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('=') {
-                continue;
-            }
+        // Only query installed versions for the results we keep
+        let total = names.len();
+        names.truncate(search_limit(config, total));
 
-            let name = line.to_string();
-            let version = Self::get_current_version(config, &name)
+        let mut packages = Vec::with_capacity(names.len());
+        for name in names {
+            let version = self
+                .get_current_version(config, &name)
                 .await
                 .unwrap_or_else(|_| "Not Installed".to_string());
 
@@ -241,10 +237,151 @@ impl PackageManager for HomebrewManager {
                 size: None,
                 install_date: None,
                 homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
             });
         }
 
-        Ok(packages)
+        Ok(SearchResults { packages, total })
+    }
+
+    /// Installable versions from `brew info --json=v2`: the stable version
+    /// of a formula or cask, plus the versions of its `name@version` formulae.
+    async fn list_available_versions(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Vec<String>> {
+        let output = new_command(config, command_path(config))
+            .arg("info")
+            .arg("--json=v2")
+            .arg(package_name)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "brew info {} failed: {}",
+                package_name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(sort_versions_newest_first(parse_info_versions(&json)))
+    }
+
+    /// Dependencies of `package_names` (`brew deps --union`) that are not
+    /// installed yet. Homebrew knows no download size before fetching.
+    async fn preview_install(
+        &self,
+        config: &Config,
+        package_names: &[String],
+    ) -> CoreResult<InstallPreview> {
+        let output = new_command(config, command_path(config))
+            .arg("deps")
+            .arg("--union")
+            .args(package_names)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(crate::error::CoreError::CommandError(format!(
+                "brew deps failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let installed = Self::get_all_installed_info(config).await?;
+        Ok(InstallPreview {
+            additional: parse_missing_deps(&stdout, &installed),
+            download_size: None,
+        })
+    }
+
+    /// Brewfile of everything installed, from `brew bundle dump`: taps,
+    /// formulae, casks and the other entries `brew bundle` tracks.
+    async fn export_brewfile(&self, config: &Config) -> CoreResult<String> {
+        let output = new_command(config, command_path(config))
+            .args(["bundle", "dump", "--file=-"])
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(crate::error::CoreError::CommandError(format!(
+                "brew bundle dump failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// `brew bundle --file=<path>`, installing what the Brewfile lists and
+    /// is missing here.
+    async fn import_brewfile(
+        &self,
+        config: &Config,
+        path: &Path,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        // --no-upgrade：只安装缺少的，不升级已安装的
+        let args = [
+            "bundle".to_owned(),
+            "--no-upgrade".to_owned(),
+            format!("--file={}", path.to_string_lossy()),
+        ];
+
+        run_command_with_progress(config, &command_path(config), &args, on_progress).await
+    }
+
+    /// `brew upgrade` without package names, updating everything.
+    async fn update_all(
+        &self,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(InstallProgress) + Send),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+        let args = command_args::<&str>(config, PackageManagerType::Homebrew, UPGRADE_ARGS, &[]);
+        let report = bulk_progress(
+            PackageManagerType::Homebrew,
+            package_names.len(),
+            on_progress,
+        );
+
+        run_command_with_progress(config, &path, &args, report).await
+    }
+
+    /// Formula and cask names are lowercase `[a-z0-9@._+-]`, optionally
+    /// prefixed with `user/tap/`.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Homebrew, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '+' | '-' | '/')
+        })
+        .map(str::to_ascii_lowercase)
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
     }
 }
 
@@ -256,9 +393,9 @@ impl HomebrewManager {
     ) -> CoreResult<()> {
         let path = command_path(config);
 
-        let args = vec!["uninstall".to_string(), package_name.to_owned()];
+        let args = removal_args(UNINSTALL_ARGS, &[package_name]);
 
-        run_command_with_progress(&path, &args, on_progress).await
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     pub async fn update_package_with_progress(
@@ -268,9 +405,14 @@ impl HomebrewManager {
     ) -> CoreResult<()> {
         let path = command_path(config);
 
-        let args = vec!["upgrade".to_string(), package_name.to_owned()];
+        let args = command_args(
+            config,
+            PackageManagerType::Homebrew,
+            UPGRADE_ARGS,
+            &[package_name],
+        );
 
-        run_command_with_progress(&path, &args, on_progress).await
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
     pub async fn install_package_with_progress(
@@ -280,11 +422,37 @@ impl HomebrewManager {
     ) -> CoreResult<()> {
         let path = command_path(config);
 
-        let args = vec!["install".to_string(), package_name.to_owned()];
+        let args = command_args(
+            config,
+            PackageManagerType::Homebrew,
+            INSTALL_ARGS,
+            &[package_name],
+        );
 
-        run_command_with_progress(&path, &args, on_progress).await
+        run_command_with_progress(config, &path, &args, on_progress).await
     }
 
+    /// `brew` binary to run, see `select_brew_binary`. `None` when no
+    /// binary was found.
+    pub(crate) fn resolve_binary(config: &Config) -> Option<PathBuf> {
+        let search_path = config.command_path_env().or_else(|| env::var_os("PATH"));
+        select_brew_binary(
+            config
+                .get_package_path(PackageManagerType::Homebrew)
+                .as_deref(),
+            search_path.as_deref(),
+            Path::is_file,
+        )
+    }
+
+    /// Install prefix of the resolved `brew`, e.g. `/opt/homebrew` on Apple
+    /// Silicon or `/usr/local` on Intel Macs.
+    pub fn resolve_prefix(config: &Config) -> Option<PathBuf> {
+        prefix_of(&Self::resolve_binary(config)?)
+    }
+
+    /// `name (version)`; with several installed versions, `name (1.0, 1.1)`,
+    /// the last (newest) one.
     fn parse_name_and_version(s: &str) -> Option<(&str, &str)> {
         let open_paren = s.rfind('(')?;
         let close_paren = s.rfind(')')?;
@@ -294,16 +462,17 @@ impl HomebrewManager {
         }
 
         let name = s[..open_paren].trim();
-        let version = s[open_paren + 1..close_paren].trim();
+        let versions = &s[open_paren + 1..close_paren];
+        let version = versions.rsplit(',').next().unwrap_or(versions).trim();
 
         Some((name, version))
     }
 
-    async fn get_all_installed_info() -> CoreResult<HashMap<String, String>> {
-        let output = tokio::process::Command::new("brew")
+    async fn get_all_installed_info(config: &Config) -> CoreResult<HashMap<String, String>> {
+        let output = new_command(config, "brew")
             .arg("list")
             .arg("--versions")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -313,31 +482,244 @@ impl HomebrewManager {
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let mut info_map = HashMap::new();
+        Ok(parse_list_versions(&stdout))
+    }
+}
 
-        for line in stdout.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+/// Updates from `brew outdated --verbose`.
+fn parse_outdated_output(stdout: &str) -> Vec<PackageUpdate> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            // 解析格式：package_name (current_version) < new_version
+            let (name_and_current, new_version) = line.trim().split_once('<')?;
+            let (name, current_version) =
+                HomebrewManager::parse_name_and_version(name_and_current.trim())?;
+
+            Some(PackageUpdate {
+                name: name.to_owned(),
+                current_version: current_version.to_owned(),
+                new_version: new_version.trim().to_owned(),
+                security: false,
+                download_size: None,
+            })
+        })
+        .collect()
+}
 
+/// `name -> version` from `brew list --versions`, keeping the last
+/// (newest installed) version when several are listed.
+fn parse_list_versions(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
             // 格式：package_name version1 version2 ...
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let package_name = parts[0];
-                // Take the last version
-                let version = parts.last().unwrap();
-                info_map.insert(package_name.to_string(), version.to_string());
-            }
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.last()?;
+            Some((name.to_owned(), version.to_owned()))
+        })
+        .collect()
+}
+
+/// Names from `brew deps` output that are not in `installed`.
+fn parse_missing_deps(stdout: &str, installed: &HashMap<String, String>) -> Vec<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && !installed.contains_key(*name))
+        .map(str::to_owned)
+        .collect()
+}
+
+fn parse_info_versions(json: &serde_json::Value) -> Vec<String> {
+    let formulae = json["formulae"].as_array().into_iter().flatten();
+    let casks = json["casks"].as_array().into_iter().flatten();
+
+    formulae
+        .flat_map(|formula| {
+            let stable = formula["versions"]["stable"].as_str().map(str::to_owned);
+            let versioned = formula["versioned_formulae"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|name| name.as_str()?.split_once('@'))
+                .map(|(_, version)| version.to_owned());
+            stable.into_iter().chain(versioned)
+        })
+        .chain(casks.filter_map(|cask| cask["version"].as_str().map(str::to_owned)))
+        .collect()
+}
+
+/// Packages from `brew info --json=v2 --installed`. Text before the JSON
+/// object (e.g. warnings) is skipped; `None` when the JSON doesn't parse,
+/// such as truncated output, so the caller can fall back to `brew list`.
+fn parse_installed_json(stdout: &str) -> Option<Vec<PackageInfo>> {
+    let json_str = stdout.find('{').map_or(stdout, |start| &stdout[start..]);
+
+    let json: serde_json::Value = match serde_json::from_str(json_str) {
+        Ok(json) => json,
+        Err(e) => {
+            let snippet: String = json_str
+                .lines()
+                .nth(e.line().saturating_sub(1))
+                .unwrap_or_default()
+                .chars()
+                .take(120)
+                .collect();
+            log::warn!(
+                "Failed to parse brew info JSON ({} bytes) at line {} column {}: {}; near {:?}",
+                json_str.len(),
+                e.line(),
+                e.column(),
+                e,
+                snippet
+            );
+            return None;
+        }
+    };
+
+    let mut packages = Vec::new();
+
+    if let Some(formulae) = json["formulae"].as_array() {
+        for formula in formulae {
+            let name = formula["name"].as_str().unwrap_or("").to_string();
+            let version = formula["versions"]["stable"]
+                .as_str()
+                .or_else(|| formula["version"].as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let description = formula["desc"].as_str().map(|s| s.to_string());
+            let homepage = formula["homepage"].as_str().map(|s| s.to_string());
+
+            packages.push(PackageInfo {
+                name,
+                version,
+                source: PackageManagerType::Homebrew,
+                description,
+                size: None,
+                install_date: None,
+                homepage,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            });
         }
+    }
+
+    if let Some(casks) = json["casks"].as_array() {
+        for cask in casks {
+            let name = cask["token"].as_str().unwrap_or("").to_string();
+            let version = cask["version"].as_str().unwrap_or("unknown").to_string();
+            let description = cask["desc"].as_str().map(|s| s.to_string());
+            let homepage = cask["homepage"].as_str().map(|s| s.to_string());
 
-        Ok(info_map)
+            packages.push(PackageInfo {
+                name,
+                version,
+                source: PackageManagerType::Homebrew,
+                description,
+                size: None,
+                install_date: None,
+                homepage,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            });
+        }
     }
+
+    Some(packages)
+}
+
+/// Names from `brew search` output (one per line under `==>` headers)
+/// that contain every query term.
+fn parse_search_output(stdout: &str, terms: &[String]) -> Vec<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('='))
+        .filter(|name| matches_all_terms(terms, &[name]))
+        .map(str::to_string)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
+
+    #[tokio::test]
+    async fn import_brewfile_runs_brew_bundle_with_the_file() {
+        let config = Config {
+            dry_run: true,
+            ..Config::default()
+        };
+        let mut commands = Vec::new();
+
+        HomebrewManager
+            .import_brewfile(&config, Path::new("/tmp/Brewfile"), &mut |event| {
+                commands.extend(event.command_message)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].ends_with("brew bundle --no-upgrade --file=/tmp/Brewfile"));
+    }
+
+    #[test]
+    fn test_parse_missing_deps_skips_installed_formulae() {
+        let installed = parse_list_versions("openssl@3 3.3.0\nca-certificates 2024-03-11\n");
+        assert_eq!(
+            parse_missing_deps("ca-certificates\nlibgit2\nopenssl@3\n\n", &installed),
+            ["libgit2"]
+        );
+    }
+
+    #[test]
+    fn test_parse_installed_json_falls_back_on_malformed_output() {
+        let truncated = r#"{"formulae": [{"name": "git", "versions": {"stable": "2.44.0"}"#;
+        assert!(parse_installed_json(truncated).is_none());
+
+        let with_warning = "Warning: some tap is deprecated\n{\"formulae\": [{\"name\": \"git\", \"versions\": {\"stable\": \"2.44.0\"}}], \"casks\": [{\"token\": \"firefox\", \"version\": \"125.0\"}]}";
+        let packages = parse_installed_json(with_warning).unwrap();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "git");
+        assert_eq!(packages[0].version, "2.44.0");
+        assert_eq!(packages[1].name, "firefox");
+    }
+
+    #[test]
+    fn test_parse_search_output_requires_all_terms() {
+        let stdout = "==> Formulae\npython-lsp-server\npython@3.12\nruff-lsp\n\n==> Casks\npython-lsp-tray\n";
+
+        assert_eq!(
+            parse_search_output(stdout, &search_terms("Python LSP")),
+            vec!["python-lsp-server", "python-lsp-tray"]
+        );
+        assert_eq!(
+            parse_search_output(stdout, &search_terms("ruff")),
+            vec!["ruff-lsp"]
+        );
+    }
+
+    #[test]
+    fn test_parse_info_versions() {
+        let json = serde_json::json!({
+            "formulae": [{
+                "name": "python@3.13",
+                "versions": { "stable": "3.13.1", "head": null },
+                "versioned_formulae": ["python@3.12", "python@3.11"]
+            }],
+            "casks": []
+        });
+
+        assert_eq!(
+            sort_versions_newest_first(parse_info_versions(&json)),
+            ["3.13.1", "3.12", "3.11"]
+        );
+    }
 
     #[test]
     fn test_parse_name_and_version() {
@@ -367,7 +749,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_all_installed_info() {
-        match HomebrewManager::get_all_installed_info().await {
+        match HomebrewManager::get_all_installed_info(&crate::Config::default()).await {
             Ok(info) => {
                 println!("Found {} installed packages:", info.len());
                 for (package, version) in info.iter().take(5) {
@@ -381,7 +763,7 @@ mod tests {
     #[tokio::test]
     async fn test_homebrew_list_updates() {
         let config = crate::Config::default();
-        match HomebrewManager::list_updates(&config).await {
+        match HomebrewManager.list_updates(&config).await {
             Ok(updates) => {
                 println!("\nFound {} Homebrew updates:", updates.len());
                 for update in updates {
@@ -397,9 +779,90 @@ mod tests {
     #[tokio::test]
     async fn test_get_current_version() {
         let config = crate::Config::default();
-        match HomebrewManager::get_current_version(&config, "git").await {
+        match HomebrewManager.get_current_version(&config, "git").await {
             Ok(version) => println!("Git version: {}", version),
             Err(e) => eprintln!("Error: {}", e),
         }
     }
+
+    #[test]
+    fn test_parse_outdated_output_fixture() {
+        let updates = parse_outdated_output(&fixture("homebrew/outdated_verbose.txt"));
+
+        // `!=` 行是 cask 的非版本号比较，不计入
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "git");
+        assert_eq!(updates[0].current_version, "2.44.0");
+        assert_eq!(updates[0].new_version, "2.45.1");
+        // 安装了多个版本时取最新的一个
+        assert_eq!(updates[1].name, "node");
+        assert_eq!(updates[1].current_version, "21.7.2");
+        assert_eq!(updates[1].new_version, "22.2.0");
+    }
+
+    #[test]
+    fn test_parse_list_versions_fixture() {
+        let versions = parse_list_versions(&fixture("homebrew/list_versions.txt"));
+
+        assert_eq!(versions.len(), 4);
+        assert_eq!(versions["node"], "22.2.0");
+        assert_eq!(versions["openssl@3"], "3.3.0");
+    }
+
+    #[test]
+    fn custom_args_go_before_the_formula() {
+        let config = config_with_custom_args(PackageManagerType::Homebrew, &["--verbose"]);
+
+        assert_eq!(
+            command_args(
+                &config,
+                PackageManagerType::Homebrew,
+                UPGRADE_ARGS,
+                &["wget"]
+            ),
+            ["upgrade", "--verbose", "wget"]
+        );
+    }
+
+    #[test]
+    fn brew_prefix_prefers_custom_path_then_path_then_known_prefixes() {
+        let apple_silicon = |path: &Path| path == Path::new("/opt/homebrew/bin/brew");
+        let intel = |path: &Path| path == Path::new("/usr/local/bin/brew");
+        let nothing = |_: &Path| false;
+
+        let brew = select_brew_binary(None, None, apple_silicon).unwrap();
+        assert_eq!(prefix_of(&brew).unwrap(), Path::new("/opt/homebrew"));
+
+        let brew = select_brew_binary(None, Some(OsStr::new("/usr/bin")), intel).unwrap();
+        assert_eq!(prefix_of(&brew).unwrap(), Path::new("/usr/local"));
+
+        // PATH wins over the well-known prefixes
+        let custom_bin =
+            |path: &Path| path == Path::new("/opt/brew/bin/brew") || apple_silicon(path);
+        let brew = select_brew_binary(None, Some(OsStr::new("/opt/brew/bin")), custom_bin);
+        assert_eq!(brew.unwrap(), Path::new("/opt/brew/bin/brew"));
+
+        // 自定义路径无需存在检查
+        let brew = select_brew_binary(Some("/srv/brew/bin/brew"), None, nothing).unwrap();
+        assert_eq!(prefix_of(&brew).unwrap(), Path::new("/srv/brew"));
+
+        assert!(select_brew_binary(None, Some(OsStr::new("/usr/bin")), nothing).is_none());
+    }
+
+    #[test]
+    fn normalize_name_lowercases_and_rejects_invalid_names() {
+        assert_eq!(
+            HomebrewManager.normalize_name("Python@3.12").unwrap(),
+            "python@3.12"
+        );
+        assert_eq!(
+            HomebrewManager
+                .normalize_name("homebrew/cask/firefox")
+                .unwrap(),
+            "homebrew/cask/firefox"
+        );
+        assert!(HomebrewManager.normalize_name("").is_err());
+        assert!(HomebrewManager.normalize_name("--cask").is_err());
+        assert!(HomebrewManager.normalize_name("wget && curl").is_err());
+    }
 }
@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
-use tokio::process::Command;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
+    http::{get_with_retry, shared_client},
     pm::{
-        common::manager_command_path,
+        common::{
+            CommandExt, checked_package_name, command_args, invalid_package_name,
+            limit_search_results, manager_command_path, new_command, removal_args,
+        },
         progress::{CommandProgressEvent, run_command_with_progress},
     },
 };
@@ -25,31 +29,68 @@ macro_rules! impl_global_js_manager {
     ($manager:ty, $manager_type:expr) => {
         #[async_trait]
         impl PackageManager for $manager {
-            async fn list_updates(config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+            fn manager_type(&self) -> PackageManagerType {
+                $manager_type
+            }
+
+            fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+                normalize_npm_name($manager_type, package_name)
+            }
+
+            async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
                 list_updates_by_manager(config, $manager_type).await
             }
 
             async fn get_current_version(
+                &self,
                 config: &Config,
                 package_name: &str,
             ) -> CoreResult<String> {
                 get_current_version_by_manager(config, $manager_type, package_name).await
             }
 
-            async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>> {
+            async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
                 list_installed_by_manager(config, $manager_type).await
             }
 
-            async fn count_installed(config: &Config) -> CoreResult<usize> {
+            async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
                 count_installed_by_manager(config, $manager_type).await
             }
 
             async fn search_package(
+                &self,
                 config: &Config,
                 package_name: &str,
-            ) -> CoreResult<Vec<PackageInfo>> {
+            ) -> CoreResult<SearchResults> {
                 search_package_by_manager(config, $manager_type, package_name).await
             }
+
+            async fn run_package_action(
+                &self,
+                action: PackageAction,
+                config: &Config,
+                package_name: &str,
+                on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+            ) -> CoreResult<()> {
+                match action {
+                    PackageAction::Uninstall => {
+                        <$manager>::uninstall_package_with_progress(
+                            config,
+                            package_name,
+                            on_progress,
+                        )
+                        .await
+                    }
+                    PackageAction::Update | PackageAction::UpdateAll => {
+                        <$manager>::update_package_with_progress(config, package_name, on_progress)
+                            .await
+                    }
+                    PackageAction::Install => {
+                        <$manager>::install_package_with_progress(config, package_name, on_progress)
+                            .await
+                    }
+                }
+            }
         }
     };
 }
@@ -201,6 +242,9 @@ fn parse_installed_from_json(
                     size: None,
                     install_date: None,
                     homepage: None,
+                    popularity: None,
+                    has_update_info: true,
+                    eol: None,
                 });
             }
         }
@@ -214,6 +258,9 @@ fn parse_installed_from_json(
                 size: None,
                 install_date: None,
                 homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
             });
         }
     }
@@ -230,12 +277,12 @@ async fn list_installed_by_manager(
 ) -> CoreResult<Vec<PackageInfo>> {
     let path = command_path(config, manager_type);
 
-    let output = Command::new(&path)
+    let output = new_command(config, &path)
         .arg("ls")
         .arg("-g")
         .arg("--depth=0")
         .arg("--json")
-        .output()
+        .try_output()
         .await?;
 
     if !output.status.success() {
@@ -296,6 +343,8 @@ fn parse_updates_from_json(stdout: &str) -> CoreResult<Vec<PackageUpdate>> {
                     name: name.clone(),
                     current_version: current,
                     new_version,
+                    security: false,
+                    download_size: None,
                 });
             }
         }
@@ -323,6 +372,8 @@ fn parse_updates_from_json(stdout: &str) -> CoreResult<Vec<PackageUpdate>> {
                     name: name.to_owned(),
                     current_version: current,
                     new_version,
+                    security: false,
+                    download_size: None,
                 });
             }
         }
@@ -337,7 +388,7 @@ async fn list_updates_by_manager(
 ) -> CoreResult<Vec<PackageUpdate>> {
     let path = command_path(config, manager_type);
 
-    let mut command = Command::new(&path);
+    let mut command = new_command(config, &path);
     match manager_type {
         PackageManagerType::Npm => {
             command.arg("outdated").arg("-g").arg("--json");
@@ -352,7 +403,7 @@ async fn list_updates_by_manager(
         _ => {}
     }
 
-    let output = command.output().await?;
+    let output = command.try_output().await?;
     let stdout = String::from_utf8(output.stdout)?;
 
     if stdout.trim().is_empty() {
@@ -374,27 +425,29 @@ async fn list_updates_by_manager(
     parsed
 }
 
+/// npm registry search endpoint; pnpm installs from the same registry.
+const REGISTRY_SEARCH_URL: &str = "https://registry.npmjs.org/-/v1/search";
+/// Largest page the registry search endpoint returns.
+const MAX_REGISTRY_SEARCH_SIZE: usize = 250;
+
 async fn search_package_by_manager(
     config: &Config,
     manager_type: PackageManagerType,
     package_name: &str,
-) -> CoreResult<Vec<PackageInfo>> {
-    let path = command_path(config, manager_type);
-    let output = Command::new(&path)
-        .arg("search")
-        .arg(package_name)
-        .arg("--json")
-        .output()
-        .await?;
-
-    if !output.status.success() {
-        return Ok(Vec::new());
-    }
-
-    let stdout = String::from_utf8(output.stdout)?;
-    let json: serde_json::Value = match serde_json::from_str(&stdout) {
-        Ok(value) => value,
-        Err(_) => return Ok(Vec::new()),
+) -> CoreResult<SearchResults> {
+    let (found, total) = match search_registry(config, package_name).await {
+        Ok(json) => parse_registry_search(&json),
+        Err(e) => {
+            log::warn!(
+                "npm registry search failed, falling back to {} search: {}",
+                manager_type.name(),
+                e
+            );
+            match search_with_cli(config, manager_type, package_name).await? {
+                Some(json) => (parse_cli_search(&json), None),
+                None => return Ok(SearchResults::default()),
+            }
+        }
     };
 
     let installed_versions: HashMap<String, String> =
@@ -404,47 +457,147 @@ async fn search_package_by_manager(
             .map(|pkg| (pkg.name, pkg.version))
             .collect();
 
-    let mut packages = Vec::new();
+    let packages = found
+        .into_iter()
+        .map(|found| PackageInfo {
+            version: installed_versions
+                .get(&found.name)
+                .cloned()
+                .unwrap_or_else(|| "Not Installed".to_owned()),
+            name: found.name,
+            source: manager_type,
+            description: found.description,
+            size: None,
+            install_date: None,
+            homepage: found.homepage,
+            popularity: found.downloads,
+            has_update_info: true,
+            eol: None,
+        })
+        .collect();
 
-    if let Some(results) = json.as_array() {
-        for item in results {
-            let Some(name) = item.get("name").and_then(|v| v.as_str()) else {
-                continue;
-            };
+    let mut results = limit_search_results(config, packages);
+    // The registry reports the full match count, not just this page
+    if let Some(total) = total {
+        results.total = results.total.max(total);
+    }
+    Ok(results)
+}
 
-            let description = item
-                .get("description")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_owned());
+/// Raw response of the registry search endpoint.
+async fn search_registry(config: &Config, query: &str) -> CoreResult<serde_json::Value> {
+    let size = match config.search_result_limit {
+        0 => MAX_REGISTRY_SEARCH_SIZE,
+        limit => limit.min(MAX_REGISTRY_SEARCH_SIZE),
+    };
+    let url = reqwest::Url::parse_with_params(
+        REGISTRY_SEARCH_URL,
+        &[("text", query), ("size", &size.to_string())],
+    )
+    .map_err(|e| crate::error::CoreError::UnknownError(e.to_string()))?;
+
+    let client = shared_client(config)?;
+    let resp = get_with_retry(config, &client, url)
+        .await?
+        .error_for_status()?;
+    Ok(resp.json().await?)
+}
+
+/// `npm search --json` output, or `None` when the command fails or prints
+/// something other than JSON.
+async fn search_with_cli(
+    config: &Config,
+    manager_type: PackageManagerType,
+    package_name: &str,
+) -> CoreResult<Option<serde_json::Value>> {
+    let path = command_path(config, manager_type);
+    let mut command = new_command(config, &path);
+    command.arg("search").arg(package_name).arg("--json");
+    if config.search_result_limit > 0 {
+        command.arg(format!("--searchlimit={}", config.search_result_limit));
+    }
+    let output = command.try_output().await?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(serde_json::from_str(&stdout).ok())
+}
+
+/// One search hit, from either the registry or `npm search`.
+#[derive(Debug)]
+struct FoundPackage {
+    name: String,
+    description: Option<String>,
+    homepage: Option<String>,
+    downloads: Option<u64>,
+}
 
-            let homepage = item
+impl FoundPackage {
+    /// Fields of a package object, shaped the same in `objects[].package`
+    /// of the registry response and in `npm search --json`.
+    fn from_package(package: &serde_json::Value) -> Option<Self> {
+        let name = package.get("name")?.as_str()?;
+        let description = package
+            .get("description")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned());
+        let link = |key: &str| {
+            package
                 .get("links")
-                .and_then(|links| links.get("homepage"))
+                .and_then(|links| links.get(key))
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_owned())
-                .or_else(|| {
-                    item.get("links")
-                        .and_then(|links| links.get("npm"))
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_owned())
-                });
+        };
 
-            packages.push(PackageInfo {
-                name: name.to_owned(),
-                version: installed_versions
-                    .get(name)
-                    .cloned()
-                    .unwrap_or_else(|| "Not Installed".to_owned()),
-                source: manager_type,
-                description,
-                size: None,
-                install_date: None,
-                homepage,
-            });
-        }
+        Some(Self {
+            name: name.to_owned(),
+            description,
+            homepage: link("homepage").or_else(|| link("npm")),
+            downloads: None,
+        })
     }
+}
 
-    Ok(packages)
+/// Hits and the total match count of a registry search response.
+fn parse_registry_search(json: &serde_json::Value) -> (Vec<FoundPackage>, Option<usize>) {
+    let packages = json
+        .get("objects")
+        .and_then(|v| v.as_array())
+        .map(|objects| {
+            objects
+                .iter()
+                .filter_map(|object| {
+                    let mut found = FoundPackage::from_package(object.get("package")?)?;
+                    found.downloads = object
+                        .get("downloads")
+                        .and_then(|downloads| downloads.get("monthly"))
+                        .and_then(|v| v.as_u64());
+                    Some(found)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let total = json
+        .get("total")
+        .and_then(|v| v.as_u64())
+        .map(|total| total as usize);
+
+    (packages, total)
+}
+
+fn parse_cli_search(json: &serde_json::Value) -> Vec<FoundPackage> {
+    json.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(FoundPackage::from_package)
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 async fn run_global_package_command_with_progress(
@@ -455,69 +608,93 @@ async fn run_global_package_command_with_progress(
     on_progress: impl FnMut(CommandProgressEvent),
 ) -> CoreResult<()> {
     let path = command_path(config, manager_type);
-    let args = global_package_command_args(manager_type, action, package_name)?;
+    let args = global_package_command_args(config, manager_type, action, package_name)?;
 
-    run_command_with_progress(&path, &args, on_progress).await
+    run_command_with_progress(config, &path, &args, on_progress).await
+}
+
+/// An npm package name, optionally scoped (`@scope/name`). Case is kept:
+/// older packages such as `JSONStream` predate the lowercase rule.
+fn normalize_npm_name(manager_type: PackageManagerType, package_name: &str) -> CoreResult<String> {
+    let name = checked_package_name(manager_type, package_name, |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '@' | '/')
+    })?;
+
+    let unscoped = match name.strip_prefix('@') {
+        Some(scoped) => match scoped.split_once('/') {
+            Some((scope, rest)) if !scope.is_empty() => rest,
+            _ => {
+                return Err(invalid_package_name(
+                    manager_type,
+                    package_name,
+                    "scoped names look like @scope/name",
+                ));
+            }
+        },
+        None => name,
+    };
+
+    let reason = if unscoped.is_empty() || unscoped.contains(['@', '/']) {
+        Some("expected name or @scope/name")
+    } else if unscoped.starts_with(['.', '_']) {
+        Some("names must not start with '.' or '_'")
+    } else if name.len() > 214 {
+        Some("names are at most 214 characters")
+    } else {
+        None
+    };
+
+    match reason {
+        Some(reason) => Err(invalid_package_name(manager_type, package_name, reason)),
+        None => Ok(name.to_owned()),
+    }
 }
 
 fn global_package_command_args(
+    config: &Config,
     manager_type: PackageManagerType,
     action: &str,
     package_name: &str,
 ) -> CoreResult<Vec<String>> {
-    match manager_type {
-        PackageManagerType::Npm => match action {
-            "install" => Ok(vec![
-                "install".to_owned(),
-                "-g".to_owned(),
-                package_name.to_owned(),
-            ]),
-            "update" => Ok(vec![
-                "install".to_owned(),
-                "-g".to_owned(),
-                format!("{}@latest", package_name),
-            ]),
-            "uninstall" => Ok(vec![
-                "uninstall".to_owned(),
-                "-g".to_owned(),
-                package_name.to_owned(),
-            ]),
-            _ => Err(crate::error::CoreError::UnknownError(format!(
-                "Unsupported npm action: {}",
+    let latest = format!("{}@latest", package_name);
+    let (subcommand, target) = match (manager_type, action) {
+        (PackageManagerType::Npm, "install") => ("install", package_name),
+        (PackageManagerType::Npm, "update") => ("install", latest.as_str()),
+        (PackageManagerType::Npm, "uninstall") => ("uninstall", package_name),
+        (PackageManagerType::Pnpm, "install") => ("add", package_name),
+        (PackageManagerType::Pnpm, "update") => ("add", latest.as_str()),
+        (PackageManagerType::Pnpm, "uninstall") => ("remove", package_name),
+        (PackageManagerType::Npm | PackageManagerType::Pnpm, _) => {
+            return Err(crate::error::CoreError::UnknownError(format!(
+                "Unsupported {} action: {}",
+                manager_type.name().to_lowercase(),
                 action
-            ))),
-        },
-        PackageManagerType::Pnpm => match action {
-            "install" => Ok(vec![
-                "add".to_owned(),
-                "-g".to_owned(),
-                package_name.to_owned(),
-            ]),
-            "update" => Ok(vec![
-                "add".to_owned(),
-                "-g".to_owned(),
-                format!("{}@latest", package_name),
-            ]),
-            "uninstall" => Ok(vec![
-                "remove".to_owned(),
-                "-g".to_owned(),
-                package_name.to_owned(),
-            ]),
-            _ => Err(crate::error::CoreError::UnknownError(format!(
-                "Unsupported pnpm action: {}",
-                action
-            ))),
-        },
-        _ => Err(crate::error::CoreError::UnknownError(format!(
-            "Unsupported manager for global command: {:?}",
-            manager_type
-        ))),
+            )));
+        }
+        _ => {
+            return Err(crate::error::CoreError::UnknownError(format!(
+                "Unsupported manager for global command: {:?}",
+                manager_type
+            )));
+        }
+    };
+
+    if action == "uninstall" {
+        return Ok(removal_args(&[subcommand, "-g"], &[target]));
     }
+
+    Ok(command_args(
+        config,
+        manager_type,
+        &[subcommand, "-g"],
+        &[target],
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
 
     #[test]
     fn test_parse_installed_from_json_supports_object_and_array() {
@@ -585,12 +762,24 @@ mod tests {
     #[test]
     fn test_global_package_command_args_for_npm_and_pnpm() {
         assert_eq!(
-            global_package_command_args(PackageManagerType::Npm, "install", "eslint").unwrap(),
+            global_package_command_args(
+                &Config::default(),
+                PackageManagerType::Npm,
+                "install",
+                "eslint"
+            )
+            .unwrap(),
             vec!["install".to_owned(), "-g".to_owned(), "eslint".to_owned()]
         );
 
         assert_eq!(
-            global_package_command_args(PackageManagerType::Pnpm, "install", "eslint").unwrap(),
+            global_package_command_args(
+                &Config::default(),
+                PackageManagerType::Pnpm,
+                "install",
+                "eslint"
+            )
+            .unwrap(),
             vec!["add".to_owned(), "-g".to_owned(), "eslint".to_owned()]
         );
     }
@@ -598,8 +787,13 @@ mod tests {
     #[test]
     fn test_global_package_update_args_force_latest() {
         assert_eq!(
-            global_package_command_args(PackageManagerType::Npm, "update", "@google/gemini-cli")
-                .unwrap(),
+            global_package_command_args(
+                &Config::default(),
+                PackageManagerType::Npm,
+                "update",
+                "@google/gemini-cli"
+            )
+            .unwrap(),
             vec![
                 "install".to_owned(),
                 "-g".to_owned(),
@@ -608,8 +802,13 @@ mod tests {
         );
 
         assert_eq!(
-            global_package_command_args(PackageManagerType::Pnpm, "update", "@google/gemini-cli")
-                .unwrap(),
+            global_package_command_args(
+                &Config::default(),
+                PackageManagerType::Pnpm,
+                "update",
+                "@google/gemini-cli"
+            )
+            .unwrap(),
             vec![
                 "add".to_owned(),
                 "-g".to_owned(),
@@ -620,7 +819,84 @@ mod tests {
 
     #[test]
     fn test_global_package_command_args_rejects_unsupported_action() {
-        let result = global_package_command_args(PackageManagerType::Npm, "remove", "eslint");
+        let result = global_package_command_args(
+            &Config::default(),
+            PackageManagerType::Npm,
+            "remove",
+            "eslint",
+        );
         assert!(result.is_err());
     }
+
+    #[test]
+    fn registry_search_reads_packages_and_total() {
+        let json: serde_json::Value =
+            serde_json::from_str(&fixture("npm/registry_search.json")).unwrap();
+        let (packages, total) = parse_registry_search(&json);
+
+        assert_eq!(total, Some(1342));
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "typescript");
+        assert_eq!(
+            packages[0].description.as_deref(),
+            Some("TypeScript is a language for application scale JavaScript development")
+        );
+        assert_eq!(
+            packages[0].homepage.as_deref(),
+            Some("https://www.typescriptlang.org/")
+        );
+        assert_eq!(packages[0].downloads, Some(318004512));
+        assert_eq!(packages[1].name, "@types/node");
+        assert_eq!(
+            packages[1].homepage.as_deref(),
+            Some("https://www.npmjs.com/package/@types/node")
+        );
+        assert_eq!(packages[1].downloads, None);
+    }
+
+    #[test]
+    fn test_parse_updates_from_json_fixture() {
+        let updates = parse_updates_from_json(&fixture("npm/outdated.json")).unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].name, "eslint");
+        assert_eq!(updates[0].current_version, "8.57.0");
+        assert_eq!(updates[0].new_version, "9.3.0");
+    }
+
+    #[test]
+    fn custom_args_go_before_the_package_spec() {
+        let config = config_with_custom_args(PackageManagerType::Pnpm, &["--reporter=silent"]);
+
+        assert_eq!(
+            global_package_command_args(&config, PackageManagerType::Pnpm, "update", "eslint")
+                .unwrap(),
+            ["add", "-g", "--reporter=silent", "eslint@latest"]
+        );
+        // npm 的参数不受 pnpm 配置影响
+        assert_eq!(
+            global_package_command_args(&config, PackageManagerType::Npm, "install", "eslint")
+                .unwrap(),
+            ["install", "-g", "eslint"]
+        );
+    }
+
+    #[test]
+    fn normalize_name_accepts_plain_and_scoped_names() {
+        assert_eq!(NpmManager.normalize_name(" eslint ").unwrap(), "eslint");
+        assert_eq!(
+            PnpmManager.normalize_name("@google/gemini-cli").unwrap(),
+            "@google/gemini-cli"
+        );
+        assert_eq!(
+            NpmManager.normalize_name("JSONStream").unwrap(),
+            "JSONStream"
+        );
+        assert!(NpmManager.normalize_name("").is_err());
+        assert!(NpmManager.normalize_name("--global").is_err());
+        assert!(NpmManager.normalize_name("@google").is_err());
+        assert!(NpmManager.normalize_name("eslint@latest").is_err());
+        assert!(PnpmManager.normalize_name(".hidden").is_err());
+        assert!(NpmManager.normalize_name("a b").is_err());
+    }
 }
@@ -1,33 +1,43 @@
 use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
-use tokio::process::Command;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
     error::CoreError,
     pm::{
-        common::manager_command_path,
-        progress::{CommandProgressEvent, run_command_with_progress},
+        common::{
+            CommandExt, checked_package_name, limit_search_results, manager_command_path,
+            name_lines, new_command, privileged_args, privileged_removal_args,
+        },
+        progress::{CommandProgressEvent, run_privileged_with_progress},
     },
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct PacmanManager;
 
+const REMOVE_ARGS: &[&str] = &["-R", "--noconfirm"];
+const SYNC_ARGS: &[&str] = &["-S", "--needed", "--noconfirm"];
+
 fn command_path(config: &Config) -> String {
     manager_command_path(config, PackageManagerType::Pacman)
 }
 
 #[async_trait]
 impl PackageManager for PacmanManager {
-    async fn get_current_version(config: &Config, package_name: &str) -> CoreResult<String> {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Pacman
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
         let path = command_path(config);
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("-Q")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -47,43 +57,42 @@ impl PackageManager for PacmanManager {
             .ok_or_else(|| CoreError::ParseError("Failed to parse pacman version".to_owned()))
     }
 
-    async fn list_installed(config: &Config) -> CoreResult<Vec<PackageInfo>> {
+    /// Every installed package from `pacman -Qi`, which carries the
+    /// description and installed size that `pacman -Q` lacks.
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
         let path = command_path(config);
 
-        let output = Command::new(&path).arg("-Q").output().await?;
+        let output = new_command(config, &path)
+            .env("LC_ALL", "C")
+            .arg("-Qi")
+            .try_output()
+            .await?;
         if !output.status.success() {
-            return Err(CoreError::UnknownError("pacman -Q failed".to_owned()));
+            return Err(CoreError::UnknownError("pacman -Qi failed".to_owned()));
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let packages = stdout
-            .lines()
-            .filter_map(|line| {
-                let mut parts = line.split_whitespace();
-                let name = parts.next()?;
-                let version = parts.next()?;
-
-                Some(PackageInfo {
-                    name: name.to_owned(),
-                    version: version.to_owned(),
-                    source: PackageManagerType::Pacman,
-                    description: None,
-                    size: None,
-                    install_date: None,
-                    homepage: None,
-                })
-            })
-            .collect();
+        Ok(parse_query_info(&stdout))
+    }
+
+    /// Explicitly installed packages (`pacman -Qqe`).
+    async fn list_user_installed(&self, config: &Config) -> CoreResult<Vec<String>> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path).arg("-Qqe").try_output().await?;
+        if !output.status.success() {
+            return Err(CoreError::UnknownError("pacman -Qqe failed".to_owned()));
+        }
 
-        Ok(packages)
+        Ok(name_lines(&String::from_utf8(output.stdout)?))
     }
 
-    async fn count_installed(config: &Config) -> CoreResult<usize> {
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
         let path = command_path(config);
 
-        let output = Command::new(&path).arg("-Qq").output().await?;
+        let output = new_command(config, &path).arg("-Qq").try_output().await?;
         if !output.status.success() {
-            return Ok(Self::list_installed(config).await?.len());
+            return Ok(self.list_installed(config).await?.len());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
@@ -93,17 +102,21 @@ impl PackageManager for PacmanManager {
             .count())
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         let path = command_path(config);
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("-Ss")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
@@ -122,26 +135,74 @@ impl PackageManager for PacmanManager {
                 size: None,
                 install_date: None,
                 homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
             })
             .collect();
 
-        Ok(packages)
+        Ok(limit_search_results(config, packages))
     }
-}
 
-impl PacmanManager {
-    pub async fn list_updates_with_refresh(
+    async fn installed_size(&self, config: &Config, package_name: &str) -> CoreResult<Option<u64>> {
+        let output = new_command(config, command_path(config))
+            .env("LC_ALL", "C")
+            .args(["-Qi", package_name])
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(parse_installed_size(&String::from_utf8(output.stdout)?))
+    }
+
+    async fn get_package_details(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<Option<String>> {
+        // -Si 读取同步数据库，即待更新版本的描述
+        let output = new_command(config, command_path(config))
+            .env("LC_ALL", "C")
+            .args(["-Si", package_name])
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(parse_description(&String::from_utf8(output.stdout)?))
+    }
+
+    /// Arch package names are lowercase `[a-z0-9@._+-]`, optionally
+    /// prefixed with `repo/`.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Pacman, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '+' | '-' | '/')
+        })
+        .map(str::to_ascii_lowercase)
+    }
+
+    async fn list_updates_with_refresh(
+        &self,
         config: &Config,
         refresh: bool,
     ) -> CoreResult<Vec<PackageUpdate>> {
+        if let Some(updates) = Self::checkupdates(config, refresh).await {
+            return Ok(updates);
+        }
+
         let path = command_path(config);
 
         if refresh {
             let args = vec![path.clone(), "-Sy".to_owned(), "--noconfirm".to_owned()];
-            run_command_with_progress("pkexec", &args, |_| {}).await?;
+            run_privileged_with_progress(config, &args, |_| {}).await?;
         }
 
-        let output = Command::new(&path).arg("-Qu").output().await?;
+        let output = new_command(config, &path).arg("-Qu").try_output().await?;
 
         if !output.status.success() {
             let stdout = String::from_utf8_lossy(&output.stdout);
@@ -165,6 +226,59 @@ impl PacmanManager {
         Ok(updates)
     }
 
+    async fn run_batch_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_packages_with_progress(config, package_names, on_progress).await
+            }
+        }
+    }
+}
+
+impl PacmanManager {
+    /// Updates from `checkupdates` (pacman-contrib), which syncs a temporary
+    /// copy of the databases and so needs no root and never leaves the
+    /// system half-synced. `--nosync` reuses that copy when no refresh is
+    /// asked for. `None` when the script is missing or fails, so the caller
+    /// falls back to `pacman -Qu`.
+    async fn checkupdates(config: &Config, refresh: bool) -> Option<Vec<PackageUpdate>> {
+        let mut command = new_command(config, "checkupdates");
+        if !refresh {
+            command.arg("--nosync");
+        }
+
+        let output = command.output().await.ok()?;
+        match output.status.code() {
+            Some(0) => Some(
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(parse_update_line)
+                    .collect(),
+            ),
+            // Exit code 2 means no updates.
+            Some(2) => Some(Vec::new()),
+            _ => {
+                log::debug!(
+                    "checkupdates failed, falling back to pacman -Qu: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                None
+            }
+        }
+    }
+
     pub async fn uninstall_packages_with_progress(
         config: &Config,
         package_names: &[String],
@@ -174,12 +288,14 @@ impl PacmanManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-
-        let mut args = vec![path, "-R".to_owned(), "--noconfirm".to_owned()];
-        args.extend(package_names.iter().cloned());
+        let args = privileged_removal_args(
+            config,
+            PackageManagerType::Pacman,
+            REMOVE_ARGS,
+            package_names,
+        );
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn update_packages_with_progress(
@@ -191,17 +307,9 @@ impl PacmanManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-
-        let mut args = vec![
-            path,
-            "-S".to_owned(),
-            "--needed".to_owned(),
-            "--noconfirm".to_owned(),
-        ];
-        args.extend(package_names.iter().cloned());
+        let args = privileged_args(config, PackageManagerType::Pacman, SYNC_ARGS, package_names);
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn install_packages_with_progress(
@@ -213,23 +321,15 @@ impl PacmanManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-
-        let mut args = vec![
-            path,
-            "-S".to_owned(),
-            "--needed".to_owned(),
-            "--noconfirm".to_owned(),
-        ];
-        args.extend(package_names.iter().cloned());
+        let args = privileged_args(config, PackageManagerType::Pacman, SYNC_ARGS, package_names);
 
-        run_command_with_progress("pkexec", &args, on_progress).await
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     async fn installed_version_map(config: &Config) -> CoreResult<HashMap<String, String>> {
         let path = command_path(config);
 
-        let output = Command::new(&path).arg("-Q").output().await?;
+        let output = new_command(config, &path).arg("-Q").try_output().await?;
         if !output.status.success() {
             return Ok(HashMap::new());
         }
@@ -263,6 +363,8 @@ fn parse_update_line(line: &str) -> Option<PackageUpdate> {
         name: parts[0].to_owned(),
         current_version: parts[1].to_owned(),
         new_version: parts[3].to_owned(),
+        security: false,
+        download_size: None,
     })
 }
 
@@ -305,9 +407,72 @@ fn parse_search_results(output: &str) -> Vec<(String, String, Option<String>)> {
     packages
 }
 
+/// Packages of `pacman -Qi` output, one blank-line separated block each.
+fn parse_query_info(output: &str) -> Vec<PackageInfo> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let field = |name: &str| {
+                block.lines().find_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    (key.trim() == name).then(|| value.trim())
+                })
+            };
+
+            Some(PackageInfo {
+                name: field("Name")?.to_owned(),
+                version: field("Version")?.to_owned(),
+                source: PackageManagerType::Pacman,
+                description: parse_description(block),
+                size: parse_installed_size(block),
+                install_date: field("Install Date").map(str::to_owned),
+                homepage: field("URL")
+                    .filter(|url| !url.is_empty() && *url != "None")
+                    .map(str::to_owned),
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            })
+        })
+        .collect()
+}
+
+/// `Installed Size` field of `pacman -Qi`, e.g. `1.50 MiB`.
+fn parse_installed_size(output: &str) -> Option<u64> {
+    let value = output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Installed Size").then_some(value.trim())
+    })?;
+
+    let (number, unit) = value.split_once(' ')?;
+    let multiplier = match unit.trim() {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    // 非 C locale 下可能使用逗号作为小数点
+    let number: f64 = number.replace(',', ".").parse().ok()?;
+    Some((number * multiplier) as u64)
+}
+
+/// `Description` field of `pacman -Si` / `pacman -Qi`.
+fn parse_description(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        (key.trim() == "Description" && !value.is_empty() && value != "None")
+            .then(|| value.to_owned())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
 
     #[test]
     fn parse_update_line_accepts_standard_pacman_format() {
@@ -337,4 +502,97 @@ mod tests {
         assert_eq!(parsed[0].2.as_deref(), Some("The GNU Bourne Again shell"));
         assert_eq!(parsed[1].0, "fzf");
     }
+
+    #[test]
+    fn parse_update_line_reads_fixture() {
+        let updates: Vec<_> = fixture("pacman/query_upgrades.txt")
+            .lines()
+            .filter_map(parse_update_line)
+            .collect();
+
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates[1].name, "linux");
+        assert_eq!(updates[1].current_version, "6.8.9.arch1-2");
+        assert_eq!(updates[1].new_version, "6.9.1.arch1-1");
+    }
+
+    #[test]
+    fn custom_args_follow_the_operation_flags() {
+        let config =
+            config_with_custom_args(PackageManagerType::Pacman, &["--overwrite=/usr/lib/*"]);
+        let packages = ["htop".to_owned()];
+
+        assert_eq!(
+            privileged_args(&config, PackageManagerType::Pacman, SYNC_ARGS, &packages),
+            [
+                "pacman",
+                "-S",
+                "--needed",
+                "--noconfirm",
+                "--overwrite=/usr/lib/*",
+                "htop"
+            ]
+        );
+        assert_eq!(
+            privileged_removal_args(&config, PackageManagerType::Pacman, REMOVE_ARGS, &packages),
+            ["pacman", "-R", "--noconfirm", "htop"]
+        );
+    }
+
+    #[test]
+    fn parse_query_info_reads_fixture() {
+        let packages = parse_query_info(&fixture("pacman/query_info.txt"));
+
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "bash");
+        assert_eq!(packages[0].version, "5.2.037-1");
+        assert_eq!(
+            packages[0].description.as_deref(),
+            Some("The GNU Bourne Again shell")
+        );
+        assert_eq!(packages[0].size, Some((9.25 * 1024.0 * 1024.0) as u64));
+        assert_eq!(
+            packages[0].homepage.as_deref(),
+            Some("https://www.gnu.org/software/bash/bash.html")
+        );
+        assert_eq!(packages[1].name, "zlib");
+        assert_eq!(packages[1].size, Some((381.32 * 1024.0) as u64));
+        assert_eq!(packages[1].homepage, None);
+    }
+
+    #[test]
+    fn parse_installed_size_reads_qi_field() {
+        let output = "Name            : htop\nVersion         : 3.3.0-3\nInstalled Size  : 1.50 MiB\nPackager        : Arch\n";
+
+        assert_eq!(parse_installed_size(output), Some(1572864));
+        assert_eq!(
+            parse_installed_size("Installed Size  : 512.00 B\n"),
+            Some(512)
+        );
+        assert_eq!(parse_installed_size("Name : htop\n"), None);
+    }
+
+    #[test]
+    fn parse_description_reads_si_field() {
+        let output = "Repository      : extra\nName            : htop\nVersion         : 3.3.0-4\nDescription     : Interactive process viewer\nArchitecture    : x86_64\n";
+
+        assert_eq!(
+            parse_description(output).as_deref(),
+            Some("Interactive process viewer")
+        );
+        assert_eq!(parse_description("Description     : None\n"), None);
+        assert_eq!(parse_description("Name : htop\n"), None);
+    }
+
+    #[test]
+    fn normalize_name_lowercases_and_rejects_invalid_names() {
+        assert_eq!(PacmanManager.normalize_name("Htop").unwrap(), "htop");
+        assert_eq!(
+            PacmanManager.normalize_name("extra/gtk4").unwrap(),
+            "extra/gtk4"
+        );
+        assert!(PacmanManager.normalize_name("").is_err());
+        assert!(PacmanManager.normalize_name("-Syu").is_err());
+        assert!(PacmanManager.normalize_name("htop$(id)").is_err());
+    }
 }
@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
+    error::CoreError,
+    http::{get_with_retry, shared_client},
+    pm::{
+        common::{
+            CommandExt, checked_package_name, command_args, invalid_package_name,
+            limit_search_results, manager_command_path, new_command, removal_args,
+        },
+        progress::{CommandProgressEvent, run_command_with_progress},
+    },
+};
+
+/// `pip install --user` packages in the user site-packages.
+#[derive(Debug, Clone, Copy)]
+pub struct PipManager;
+
+// --no-input 让需要凭据的私有索引直接失败而不是等待输入
+const UNINSTALL_ARGS: &[&str] = &["uninstall", "-y", "--no-input"];
+const UPGRADE_ARGS: &[&str] = &["install", "--user", "-U", "--no-input"];
+const INSTALL_ARGS: &[&str] = &["install", "--user", "--no-input"];
+
+/// Entry of `pip list --format=json`; `--outdated` adds `latest_version`.
+#[derive(Debug, Deserialize)]
+struct PipListEntry {
+    name: String,
+    version: String,
+    #[serde(default)]
+    latest_version: Option<String>,
+}
+
+fn command_path(config: &Config) -> String {
+    manager_command_path(config, PackageManagerType::Pip)
+}
+
+#[async_trait]
+impl PackageManager for PipManager {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Pip
+    }
+
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+        let stdout = Self::pip_list(config, &["--outdated"]).await?;
+        parse_outdated_json(&stdout)
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        Self::installed_version_map(config)
+            .await?
+            .remove(&normalize_name(package_name))
+            .ok_or_else(|| CoreError::ParseError(format!("Package {} not found", package_name)))
+    }
+
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
+        let stdout = Self::pip_list(config, &[]).await?;
+        parse_installed_json(&stdout)
+    }
+
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
+        // `pip search` 已被 PyPI 禁用，只能按名称精确查询 JSON API
+        let package_name = package_name.trim();
+        if package_name.is_empty() {
+            return Ok(SearchResults::default());
+        }
+
+        let client = shared_client(config)?;
+        let Some(json) = fetch_pypi_json(config, &client, package_name).await? else {
+            return Ok(SearchResults::default());
+        };
+        let installed_versions = Self::installed_version_map(config).await?;
+
+        let packages = parse_pypi_info(&json, PackageManagerType::Pip)
+            .map(|mut package| {
+                package.version = installed_versions
+                    .get(&normalize_name(&package.name))
+                    .cloned()
+                    .unwrap_or_else(|| "Not Installed".to_owned());
+                package
+            })
+            .into_iter()
+            .collect();
+
+        Ok(limit_search_results(config, packages))
+    }
+
+    /// PEP 508 names: letters, digits, `.`, `_` and `-`, starting and ending
+    /// with a letter or digit. pip matches names case-insensitively, so they
+    /// are lowercased.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        normalize_project_name(PackageManagerType::Pip, package_name)
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
+    }
+}
+
+impl PipManager {
+    pub async fn uninstall_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let args = removal_args(UNINSTALL_ARGS, &[package_name]);
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    pub async fn update_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let args = command_args(
+            config,
+            PackageManagerType::Pip,
+            UPGRADE_ARGS,
+            &[package_name],
+        );
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    pub async fn install_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+
+        let args = command_args(
+            config,
+            PackageManagerType::Pip,
+            INSTALL_ARGS,
+            &[package_name],
+        );
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    async fn pip_list(config: &Config, extra_args: &[&str]) -> CoreResult<String> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .arg("list")
+            .arg("--user")
+            .args(extra_args)
+            .arg("--format=json")
+            .arg("--disable-pip-version-check")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "pip list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// Installed versions keyed by normalized project name.
+    async fn installed_version_map(config: &Config) -> CoreResult<HashMap<String, String>> {
+        let stdout = Self::pip_list(config, &[]).await?;
+
+        Ok(parse_installed_json(&stdout)?
+            .into_iter()
+            .map(|package| (normalize_name(&package.name), package.version))
+            .collect())
+    }
+}
+
+/// A PEP 508 project name for `manager_type`, lowercased.
+pub(crate) fn normalize_project_name(
+    manager_type: PackageManagerType,
+    package_name: &str,
+) -> CoreResult<String> {
+    let name = checked_package_name(manager_type, package_name, |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+    })?;
+
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !name.ends_with(|c: char| c.is_ascii_alphanumeric())
+    {
+        return Err(invalid_package_name(
+            manager_type,
+            package_name,
+            "names must start and end with a letter or digit",
+        ));
+    }
+
+    Ok(name.to_ascii_lowercase())
+}
+
+/// PyPI `/pypi/{name}/json` response, or `None` when PyPI doesn't know the
+/// project; other failures, after retries, are errors.
+pub(crate) async fn fetch_pypi_json(
+    config: &Config,
+    client: &reqwest::Client,
+    package_name: &str,
+) -> CoreResult<Option<serde_json::Value>> {
+    let url = format!("https://pypi.org/pypi/{}/json", package_name);
+    let resp = get_with_retry(config, client, &url).await?;
+
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        log::debug!("PyPI has no project {}", package_name);
+        return Ok(None);
+    }
+
+    Ok(Some(resp.error_for_status()?.json().await?))
+}
+
+/// PEP 503 name normalization: case-insensitive, `-`, `_` and `.` are equivalent.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+fn parse_list_json(stdout: &str) -> CoreResult<Vec<PipListEntry>> {
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(serde_json::from_str(stdout)?)
+}
+
+fn parse_installed_json(stdout: &str) -> CoreResult<Vec<PackageInfo>> {
+    let mut packages: Vec<PackageInfo> = parse_list_json(stdout)?
+        .into_iter()
+        .map(|entry| PackageInfo {
+            name: entry.name,
+            version: entry.version,
+            source: PackageManagerType::Pip,
+            description: None,
+            size: None,
+            install_date: None,
+            homepage: None,
+            popularity: None,
+            has_update_info: true,
+            eol: None,
+        })
+        .collect();
+
+    packages.sort_by_key(|package| package.name.to_lowercase());
+    Ok(packages)
+}
+
+fn parse_outdated_json(stdout: &str) -> CoreResult<Vec<PackageUpdate>> {
+    Ok(parse_list_json(stdout)?
+        .into_iter()
+        .filter_map(|entry| {
+            let new_version = entry.latest_version?;
+            (new_version != entry.version).then_some(PackageUpdate {
+                name: entry.name,
+                current_version: entry.version,
+                new_version,
+                security: false,
+                download_size: None,
+            })
+        })
+        .collect())
+}
+
+pub(crate) fn parse_pypi_info(
+    json: &serde_json::Value,
+    source: PackageManagerType,
+) -> Option<PackageInfo> {
+    let info = json.get("info")?;
+    let name = info.get("name")?.as_str()?;
+
+    let non_empty = |key: &str| {
+        info.get(key)
+            .and_then(|value| value.as_str())
+            .filter(|value| !value.trim().is_empty())
+            .map(str::to_owned)
+    };
+
+    Some(PackageInfo {
+        name: name.to_owned(),
+        version: non_empty("version").unwrap_or_default(),
+        source,
+        description: non_empty("summary"),
+        size: None,
+        install_date: None,
+        homepage: non_empty("home_page").or_else(|| non_empty("project_url")),
+        popularity: None,
+        has_update_info: true,
+        eol: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
+
+    #[test]
+    fn parse_installed_json_reads_pip_list_output() {
+        let stdout = r#"[{"name": "requests", "version": "2.31.0"}, {"name": "Jinja2", "version": "3.1.3"}]"#;
+
+        let packages = parse_installed_json(stdout).unwrap();
+
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "Jinja2");
+        assert_eq!(packages[0].version, "3.1.3");
+        assert_eq!(packages[1].name, "requests");
+        assert!(parse_installed_json("\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_outdated_json_reads_latest_version() {
+        let stdout = r#"[
+          {"name": "requests", "version": "2.31.0", "latest_version": "2.32.3", "latest_filetype": "wheel"},
+          {"name": "idna", "version": "3.7", "latest_version": "3.7", "latest_filetype": "wheel"}
+        ]"#;
+
+        let updates = parse_outdated_json(stdout).unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].name, "requests");
+        assert_eq!(updates[0].current_version, "2.31.0");
+        assert_eq!(updates[0].new_version, "2.32.3");
+    }
+
+    #[test]
+    fn normalize_name_follows_pep_503() {
+        assert_eq!(normalize_name("Foo_Bar.baz"), "foo-bar-baz");
+    }
+
+    #[test]
+    fn parse_outdated_json_reads_fixture() {
+        let updates = parse_outdated_json(&fixture("pip/list_outdated.json")).unwrap();
+
+        let names: Vec<_> = updates.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["black", "requests"]);
+        assert_eq!(updates[0].new_version, "24.4.2");
+    }
+
+    #[tokio::test]
+    async fn uninstall_leaves_out_custom_args() {
+        let config = Config {
+            dry_run: true,
+            ..config_with_custom_args(
+                PackageManagerType::Pip,
+                &["--index-url https://example.org/simple"],
+            )
+        };
+        let mut commands = Vec::new();
+
+        PipManager::uninstall_package_with_progress(&config, "requests", |event| {
+            commands.extend(event.command_message)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(commands.len(), 1);
+        assert!(
+            commands[0].ends_with("uninstall -y --no-input requests"),
+            "{}",
+            commands[0]
+        );
+    }
+
+    #[test]
+    fn custom_args_go_before_the_project_name() {
+        let config = config_with_custom_args(
+            PackageManagerType::Pip,
+            &["--index-url https://example.org/simple"],
+        );
+
+        assert_eq!(
+            command_args(
+                &config,
+                PackageManagerType::Pip,
+                UPGRADE_ARGS,
+                &["requests"]
+            ),
+            [
+                "install",
+                "--user",
+                "-U",
+                "--no-input",
+                "--index-url",
+                "https://example.org/simple",
+                "requests"
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_name_lowercases_and_rejects_invalid_names() {
+        assert_eq!(PipManager.normalize_name(" Django ").unwrap(), "django");
+        assert_eq!(
+            PipManager.normalize_name("zope.interface").unwrap(),
+            "zope.interface"
+        );
+        assert!(PipManager.normalize_name("").is_err());
+        assert!(PipManager.normalize_name("--user").is_err());
+        assert!(PipManager.normalize_name("requests==2.0").is_err());
+        assert!(PipManager.normalize_name("pkg_").is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults, compare_versions,
+    error::CoreError,
+    http::shared_client,
+    pm::{
+        common::{
+            CommandExt, command_args, limit_search_results, manager_command_path, new_command,
+            removal_args, run_with_concurrency_limit,
+        },
+        pip::{fetch_pypi_json, normalize_name, normalize_project_name, parse_pypi_info},
+        progress::{CommandProgressEvent, run_command_with_progress},
+    },
+};
+
+/// Python applications installed into their own virtualenvs by pipx.
+#[derive(Debug, Clone, Copy)]
+pub struct PipxManager;
+
+const UNINSTALL_ARGS: &[&str] = &["uninstall"];
+const UPGRADE_ARGS: &[&str] = &["upgrade"];
+const INSTALL_ARGS: &[&str] = &["install"];
+
+/// PyPI lookups in flight at once while checking for updates.
+const PYPI_CONCURRENCY: usize = 8;
+
+/// `pipx list --json`, keyed by venv name.
+#[derive(Debug, Deserialize)]
+struct PipxList {
+    #[serde(default)]
+    venvs: HashMap<String, PipxVenv>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxVenv {
+    metadata: PipxMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxMetadata {
+    main_package: PipxPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipxPackage {
+    package: String,
+    #[serde(default)]
+    package_version: String,
+}
+
+fn command_path(config: &Config) -> String {
+    manager_command_path(config, PackageManagerType::Pipx)
+}
+
+#[async_trait]
+impl PackageManager for PipxManager {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Pipx
+    }
+
+    /// Installed apps whose PyPI release is newer. Apps PyPI doesn't know,
+    /// e.g. installed from a git URL, are skipped.
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+        let installed = self.list_installed(config).await?;
+        let client = shared_client(config)?;
+        let client = &client;
+
+        let latest = run_with_concurrency_limit(
+            installed.iter().map(|package| package.name.clone()),
+            PYPI_CONCURRENCY,
+            |name| async move {
+                match fetch_pypi_json(config, client, &name).await {
+                    Ok(json) => json.and_then(|json| latest_pypi_version(&json)),
+                    Err(e) => {
+                        log::debug!("pipx: PyPI lookup for {} failed: {}", name, e);
+                        None
+                    }
+                }
+            },
+        )
+        .await;
+
+        Ok(installed
+            .into_iter()
+            .zip(latest)
+            .filter_map(|(package, latest)| {
+                let new_version = latest?;
+                compare_versions(&package.version, &new_version)
+                    .is_lt()
+                    .then_some(PackageUpdate {
+                        name: package.name,
+                        current_version: package.version,
+                        new_version,
+                        security: false,
+                        download_size: None,
+                    })
+            })
+            .collect())
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        let wanted = normalize_name(package_name);
+
+        self.list_installed(config)
+            .await?
+            .into_iter()
+            .find(|package| normalize_name(&package.name) == wanted)
+            .map(|package| package.version)
+            .ok_or_else(|| CoreError::ParseError(format!("Package {} not found", package_name)))
+    }
+
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .arg("list")
+            .arg("--json")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "pipx list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        parse_list_json(&stdout)
+    }
+
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
+        // PyPI has no search API, only exact project lookups
+        let package_name = package_name.trim();
+        if package_name.is_empty() {
+            return Ok(SearchResults::default());
+        }
+
+        let client = shared_client(config)?;
+        let Some(json) = fetch_pypi_json(config, &client, package_name).await? else {
+            return Ok(SearchResults::default());
+        };
+        let installed_versions: HashMap<String, String> = self
+            .list_installed(config)
+            .await?
+            .into_iter()
+            .map(|package| (normalize_name(&package.name), package.version))
+            .collect();
+
+        let packages = parse_pypi_info(&json, PackageManagerType::Pipx)
+            .map(|mut package| {
+                package.version = installed_versions
+                    .get(&normalize_name(&package.name))
+                    .cloned()
+                    .unwrap_or_else(|| "Not Installed".to_owned());
+                package
+            })
+            .into_iter()
+            .collect();
+
+        Ok(limit_search_results(config, packages))
+    }
+
+    /// PEP 508 names, lowercased like pip's.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        normalize_project_name(PackageManagerType::Pipx, package_name)
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
+    }
+}
+
+impl PipxManager {
+    pub async fn uninstall_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+        let args = removal_args(UNINSTALL_ARGS, &[package_name]);
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+
+    pub async fn update_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        Self::run(config, UPGRADE_ARGS, package_name, on_progress).await
+    }
+
+    pub async fn install_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        Self::run(config, INSTALL_ARGS, package_name, on_progress).await
+    }
+
+    async fn run(
+        config: &Config,
+        leading: &[&str],
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let path = command_path(config);
+        let args = command_args(config, PackageManagerType::Pipx, leading, &[package_name]);
+
+        run_command_with_progress(config, &path, &args, on_progress).await
+    }
+}
+
+/// Main package of every pipx venv; injected packages are not listed.
+fn parse_list_json(stdout: &str) -> CoreResult<Vec<PackageInfo>> {
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let list: PipxList = serde_json::from_str(stdout)?;
+    let mut packages: Vec<PackageInfo> = list
+        .venvs
+        .into_values()
+        .map(|venv| {
+            let main = venv.metadata.main_package;
+            PackageInfo {
+                name: main.package,
+                version: main.package_version,
+                source: PackageManagerType::Pipx,
+                description: None,
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            }
+        })
+        .collect();
+
+    packages.sort_by_key(|package| package.name.to_lowercase());
+    Ok(packages)
+}
+
+fn latest_pypi_version(json: &serde_json::Value) -> Option<String> {
+    json.get("info")?
+        .get("version")?
+        .as_str()
+        .filter(|version| !version.is_empty())
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm::common::fixture;
+
+    #[test]
+    fn parse_list_json_reads_main_packages() {
+        let packages = parse_list_json(&fixture("pipx/list.json")).unwrap();
+
+        let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["black", "poetry"]);
+        assert_eq!(packages[0].version, "24.4.2");
+        assert_eq!(packages[1].version, "1.8.3");
+        assert!(parse_list_json("\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn latest_pypi_version_reads_info_version() {
+        let json = serde_json::json!({ "info": { "name": "black", "version": "24.8.0" } });
+
+        assert_eq!(latest_pypi_version(&json).as_deref(), Some("24.8.0"));
+        assert_eq!(latest_pypi_version(&serde_json::json!({})), None);
+    }
+}
@@ -1,13 +1,18 @@
-use std::{collections::VecDeque, path::Path, process::Stdio};
+use std::{collections::VecDeque, path::Path, process::Stdio, time::Duration};
 
 use regex::Regex;
 use tokio::{
     io::{AsyncRead, AsyncReadExt},
-    process::Command,
     sync::mpsc,
 };
 
-use crate::{CoreResult, error::CoreError};
+use crate::{
+    Config, CoreResult,
+    cancel::{cancelled, current_token},
+    error::CoreError,
+    pm::common::{new_command, privilege_failure_hint, privilege_prefix, spawn_error},
+    script::shell_quote,
+};
 
 #[derive(Debug, Clone)]
 pub struct CommandProgressEvent {
@@ -84,9 +89,11 @@ fn command_looks_like_dnf(command: &str) -> bool {
 }
 
 fn is_dnf_command(command: &str, args: &[String]) -> bool {
+    // 经 pkexec/sudo -A 运行时，dnf 是第一个非选项参数
     command_looks_like_dnf(command)
         || args
-            .first()
+            .iter()
+            .find(|arg| !arg.starts_with('-'))
             .is_some_and(|first| command_looks_like_dnf(first))
 }
 
@@ -95,16 +102,88 @@ fn is_dnf_transaction_marker(line: &str) -> bool {
     lower.contains("running transaction") || line.contains("运行事务")
 }
 
+/// Next output line, `Ok(None)` once the command closed its output, or
+/// `Err` after `idle_timeout` without any line.
+async fn next_line(
+    rx: &mut mpsc::UnboundedReceiver<String>,
+    idle_timeout: Option<Duration>,
+) -> Result<Option<String>, tokio::time::error::Elapsed> {
+    match idle_timeout {
+        Some(idle_timeout) => tokio::time::timeout(idle_timeout, rx.recv()).await,
+        None => Ok(rx.recv().await),
+    }
+}
+
+/// `run_command_with_progress` for `args` run as root (see `privilege_prefix`).
+/// Failures to get root become `CoreError::PrivilegeError` with a hint on
+/// how to fix the setup.
+pub async fn run_privileged_with_progress(
+    config: &Config,
+    args: &[String],
+    on_progress: impl FnMut(CommandProgressEvent),
+) -> CoreResult<()> {
+    let (program, flags) = privilege_prefix(config);
+    let args: Vec<String> = flags
+        .iter()
+        .map(|flag| (*flag).to_owned())
+        .chain(args.iter().cloned())
+        .collect();
+
+    run_command_with_progress(config, program, &args, on_progress)
+        .await
+        .map_err(|error| {
+            let detail = error.to_string();
+            match privilege_failure_hint(&detail) {
+                Some(hint) => CoreError::PrivilegeError(format!("{}\n{}", hint, detail)),
+                None => error,
+            }
+        })
+}
+
+/// Run `command` with piped stdout/stderr, passing each output line to
+/// `on_progress` as it arrives together with a progress estimate. Every
+/// install, update and uninstall goes through here, so the UI's output
+/// panels show the live log instead of waiting for the command to exit.
+///
+/// With `Config::dry_run`, the quoted command line is reported as the only
+/// message and nothing runs.
+///
+/// Inside `with_cancel_token`, cancelling kills the command and returns
+/// `CoreError::Cancelled`. A command run through sudo or pkexec only loses
+/// its wrapper; the root process may still finish its transaction.
 pub async fn run_command_with_progress(
+    config: &Config,
     command: &str,
     args: &[String],
     mut on_progress: impl FnMut(CommandProgressEvent),
 ) -> CoreResult<()> {
-    let mut child = Command::new(command)
+    let cancel_token = current_token();
+    if cancel_token
+        .as_ref()
+        .is_some_and(|token| token.is_cancelled())
+    {
+        return Err(CoreError::Cancelled);
+    }
+
+    if config.dry_run {
+        let command_line = std::iter::once(command)
+            .chain(args.iter().map(String::as_str))
+            .map(shell_quote)
+            .collect::<Vec<_>>()
+            .join(" ");
+        on_progress(CommandProgressEvent {
+            progress: 1.0,
+            command_message: Some(command_line),
+        });
+        return Ok(());
+    }
+
+    let mut process = new_command(config, command);
+    process
         .args(args)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()?;
+        .stderr(Stdio::piped());
+    let mut child = process.spawn().map_err(|e| spawn_error(&process, e))?;
 
     let stdout = child
         .stdout
@@ -143,7 +222,44 @@ pub async fn run_command_with_progress(
         command_message: None,
     });
 
-    while let Some(line) = rx.recv().await {
+    let idle_timeout = (config.command_idle_timeout_secs > 0)
+        .then(|| Duration::from_secs(config.command_idle_timeout_secs));
+
+    loop {
+        let line = tokio::select! {
+            line = next_line(&mut rx, idle_timeout) => line,
+            () = cancelled(cancel_token.as_ref()) => {
+                let _ = child.kill().await;
+                return Err(CoreError::Cancelled);
+            }
+        };
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => {
+                // 长时间无输出多半是在等待输入（如未处理的确认提示）
+                let stopped = match child.kill().await {
+                    Ok(()) => "was stopped",
+                    Err(e) => {
+                        log::warn!("Failed to stop {} after the idle timeout: {}", command, e);
+                        "could not be stopped"
+                    }
+                };
+                // 只能结束 pkexec/sudo 本身，以 root 运行的命令可能仍在继续
+                let note = if command == privilege_prefix(config).0 {
+                    "; the command running as root may still be running"
+                } else {
+                    ""
+                };
+                return Err(CoreError::CommandError(format!(
+                    "{} {:?} printed nothing for {} s and {}; it may be waiting for input{}",
+                    command, args, config.command_idle_timeout_secs, stopped, note
+                )));
+            }
+        };
+        let Some(line) = line else {
+            break;
+        };
+
         if tail_logs.len() >= 20 {
             tail_logs.pop_front();
         }
@@ -286,4 +402,81 @@ mod tests {
         assert!(is_dnf_transaction_marker("开始运行事务"));
         assert!(!is_dnf_transaction_marker("Downloading packages"));
     }
+
+    #[tokio::test]
+    async fn silent_command_is_stopped_after_idle_timeout() {
+        let config = Config {
+            command_idle_timeout_secs: 1,
+            ..Config::default()
+        };
+
+        let started = std::time::Instant::now();
+        let result = run_command_with_progress(&config, "sleep", &["30".to_owned()], |_| {}).await;
+
+        assert!(matches!(result, Err(CoreError::CommandError(_))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn cancelled_command_is_killed() {
+        let token = crate::CancelToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let result = crate::with_cancel_token(
+            token.clone(),
+            run_command_with_progress(&Config::default(), "sleep", &["30".to_owned()], |_| {}),
+        )
+        .await;
+
+        assert!(matches!(result, Err(CoreError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // Commands started after cancelling never run
+        let result = crate::with_cancel_token(
+            token,
+            run_command_with_progress(&Config::default(), "true", &[], |_| {}),
+        )
+        .await;
+        assert!(matches!(result, Err(CoreError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_the_command_without_running_it() {
+        let config = Config {
+            dry_run: true,
+            ..Config::default()
+        };
+        let mut messages = Vec::new();
+
+        let result = run_command_with_progress(
+            &config,
+            "false",
+            &["upgrade".to_owned(), "two words".to_owned()],
+            |event| messages.extend(event.command_message),
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(messages, ["false upgrade 'two words'"]);
+    }
+
+    #[tokio::test]
+    async fn commands_get_no_stdin() {
+        // cat 读到 EOF 立即退出，而不是一直等待输入
+        let config = Config {
+            command_idle_timeout_secs: 5,
+            ..Config::default()
+        };
+
+        assert!(
+            run_command_with_progress(&config, "cat", &[], |_| {})
+                .await
+                .is_ok()
+        );
+    }
 }
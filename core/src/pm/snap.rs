@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use tokio::process::Command;
+
+use crate::{
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
+    error::CoreError,
+    pm::{
+        common::{
+            CommandExt, checked_package_name, limit_search_results, manager_command_path,
+            new_command, parse_size, privileged_args, privileged_removal_args,
+        },
+        progress::{CommandProgressEvent, run_privileged_with_progress},
+    },
+};
+
+/// Snaps from the Snap Store. snapd only lets root change snaps, so every
+/// change goes through pkexec like the system managers do.
+#[derive(Debug, Clone, Copy)]
+pub struct SnapManager;
+
+const REMOVE_ARGS: &[&str] = &["remove"];
+const REFRESH_ARGS: &[&str] = &["refresh"];
+const INSTALL_ARGS: &[&str] = &["install"];
+const INSTALL_CLASSIC_ARGS: &[&str] = &["install", "--classic"];
+
+fn command_path(config: &Config) -> String {
+    manager_command_path(config, PackageManagerType::Snap)
+}
+
+/// `snap` with its output in the C locale: table headers and notes are
+/// parsed in English.
+fn snap_command(config: &Config) -> Command {
+    let mut command = new_command(config, command_path(config));
+    command.env("LC_ALL", "C");
+    command
+}
+
+#[async_trait]
+impl PackageManager for SnapManager {
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Snap
+    }
+
+    async fn list_updates(&self, config: &Config) -> CoreResult<Vec<PackageUpdate>> {
+        let output = snap_command(config)
+            .arg("refresh")
+            .arg("--list")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "snap refresh --list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let available = parse_refresh_list(&stdout);
+        if available.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let installed: HashMap<String, String> = self
+            .list_installed(config)
+            .await?
+            .into_iter()
+            .map(|package| (package.name, package.version))
+            .collect();
+
+        Ok(available
+            .into_iter()
+            .map(|mut update| {
+                if let Some(version) = installed.get(&update.name) {
+                    update.current_version = version.clone();
+                }
+                update
+            })
+            .collect())
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        let name = self.normalize_name(package_name)?;
+        let output = snap_command(config)
+            .arg("list")
+            .arg(&name)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::ParseError(format!(
+                "Package {} not found",
+                package_name
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        parse_list(&stdout)
+            .into_iter()
+            .find(|package| package.name == name)
+            .map(|package| package.version)
+            .ok_or_else(|| CoreError::ParseError(format!("Package {} not found", package_name)))
+    }
+
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
+        let output = snap_command(config).arg("list").try_output().await?;
+
+        if !output.status.success() {
+            return Err(CoreError::CommandError(format!(
+                "snap list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(parse_list(&stdout))
+    }
+
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
+        let query = package_name.trim();
+        if query.is_empty() {
+            return Ok(SearchResults::default());
+        }
+
+        let output = snap_command(config)
+            .arg("find")
+            .arg(query)
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("No matching snaps") {
+                return Ok(SearchResults::default());
+            }
+            return Err(CoreError::CommandError(format!(
+                "snap find failed: {}",
+                stderr.trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let mut packages = parse_find(&stdout);
+        if packages.is_empty() {
+            return Ok(SearchResults::default());
+        }
+
+        let installed: HashMap<String, String> = self
+            .list_installed(config)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|package| (package.name, package.version))
+            .collect();
+        for package in &mut packages {
+            package.version = installed
+                .get(&package.name)
+                .cloned()
+                .unwrap_or_else(|| "Not Installed".to_owned());
+        }
+
+        Ok(limit_search_results(config, packages))
+    }
+
+    /// Snap names are lowercase letters, digits and hyphens; `_` separates
+    /// the instance key of a parallel install such as `firefox_beta`.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Snap, package_name, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '-' | '_')
+        })
+        .map(str::to_ascii_lowercase)
+    }
+
+    async fn run_package_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_name: &str,
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_package_with_progress(config, package_name, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_package_with_progress(config, package_name, on_progress).await
+            }
+        }
+    }
+}
+
+impl SnapManager {
+    pub async fn uninstall_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let args = privileged_removal_args(
+            config,
+            PackageManagerType::Snap,
+            REMOVE_ARGS,
+            &[package_name],
+        );
+        run_privileged_with_progress(config, &args, on_progress).await
+    }
+
+    pub async fn update_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        Self::run(config, REFRESH_ARGS, package_name, on_progress).await
+    }
+
+    /// Classic snaps such as `code` can reach the whole system, and snapd
+    /// only installs them when `--classic` acknowledges that.
+    pub async fn install_package_with_progress(
+        config: &Config,
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let leading = if Self::is_classic(config, package_name).await {
+            INSTALL_CLASSIC_ARGS
+        } else {
+            INSTALL_ARGS
+        };
+        Self::run(config, leading, package_name, on_progress).await
+    }
+
+    /// Whether the store lists `package_name` with classic confinement.
+    /// A failed lookup counts as strict; snapd then explains the refusal.
+    async fn is_classic(config: &Config, package_name: &str) -> bool {
+        let output = snap_command(config)
+            .arg("find")
+            .arg(package_name)
+            .output()
+            .await;
+
+        match output {
+            Ok(output) if output.status.success() => {
+                is_classic_in_find(&String::from_utf8_lossy(&output.stdout), package_name)
+            }
+            _ => false,
+        }
+    }
+
+    async fn run(
+        config: &Config,
+        leading: &[&str],
+        package_name: &str,
+        on_progress: impl FnMut(CommandProgressEvent),
+    ) -> CoreResult<()> {
+        let args = privileged_args(config, PackageManagerType::Snap, leading, &[package_name]);
+
+        run_privileged_with_progress(config, &args, on_progress).await
+    }
+}
+
+/// Rows of a snap table after its header line. Columns are separated by
+/// runs of spaces and never contain spaces themselves, except the trailing
+/// summary of `snap find`.
+fn table_rows(stdout: &str) -> impl Iterator<Item = Vec<&str>> {
+    stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Name "))
+        .skip(1)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|columns| !columns.is_empty())
+}
+
+/// `snap list`: Name, Version, Rev, Tracking, Publisher, Notes. The Rev
+/// column is the store revision, not something users recognise, so the
+/// Version column is kept.
+fn parse_list(stdout: &str) -> Vec<PackageInfo> {
+    let mut packages: Vec<PackageInfo> = table_rows(stdout)
+        .filter(|columns| columns.len() >= 3)
+        .map(|columns| PackageInfo {
+            name: columns[0].to_owned(),
+            version: columns[1].to_owned(),
+            source: PackageManagerType::Snap,
+            description: None,
+            size: None,
+            install_date: None,
+            homepage: None,
+            popularity: None,
+            has_update_info: true,
+            eol: None,
+        })
+        .collect();
+
+    packages.sort_by_key(|package| package.name.to_lowercase());
+    packages
+}
+
+/// `snap refresh --list`: Name, Version, Rev, Size, Publisher, Notes, with
+/// the version the refresh would install. When everything is current snapd
+/// prints "All snaps up to date." on stderr instead of a table. The current
+/// version is left empty for the caller to fill in from `snap list`.
+fn parse_refresh_list(stdout: &str) -> Vec<PackageUpdate> {
+    table_rows(stdout)
+        .filter(|columns| columns.len() >= 4)
+        .map(|columns| PackageUpdate {
+            name: columns[0].to_owned(),
+            current_version: String::new(),
+            new_version: columns[1].to_owned(),
+            security: false,
+            download_size: parse_size(columns[3]),
+        })
+        .collect()
+}
+
+/// `snap find <query>`: Name, Version, Publisher, Notes, Summary. The
+/// version is the store's; callers replace it with the installed one.
+fn parse_find(stdout: &str) -> Vec<PackageInfo> {
+    table_rows(stdout)
+        .filter(|columns| columns.len() >= 2)
+        .map(|columns| {
+            let summary = columns.get(4..).map(|words| words.join(" "));
+            PackageInfo {
+                name: columns[0].to_owned(),
+                version: columns[1].to_owned(),
+                source: PackageManagerType::Snap,
+                description: summary.filter(|summary| !summary.is_empty()),
+                size: None,
+                install_date: None,
+                homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
+            }
+        })
+        .collect()
+}
+
+/// Whether the `snap find` row of `package_name` has `classic` in its
+/// Notes column.
+fn is_classic_in_find(stdout: &str, package_name: &str) -> bool {
+    table_rows(stdout)
+        .find(|columns| columns[0] == package_name)
+        .and_then(|columns| columns.get(3).copied())
+        .is_some_and(|notes| notes.split(',').any(|note| note == "classic"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pm::common::fixture;
+
+    #[test]
+    fn parse_list_keeps_the_version_not_the_revision() {
+        let packages = parse_list(&fixture("snap/list.txt"));
+
+        let firefox = packages.iter().find(|p| p.name == "firefox").unwrap();
+        assert_eq!(firefox.version, "131.0.2-1");
+        let code = packages.iter().find(|p| p.name == "code").unwrap();
+        assert_eq!(code.version, "e170252f");
+        assert_eq!(packages.len(), 7);
+        assert!(parse_list("").is_empty());
+    }
+
+    #[test]
+    fn parse_refresh_list_reads_new_versions_and_sizes() {
+        let updates = parse_refresh_list(&fixture("snap/refresh_list.txt"));
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].name, "firefox");
+        assert_eq!(updates[0].new_version, "132.0-1");
+        assert_eq!(updates[0].download_size, Some(281 * 1024 * 1024));
+        assert!(parse_refresh_list("All snaps up to date.\n").is_empty());
+    }
+
+    #[test]
+    fn parse_find_reads_names_and_summaries() {
+        let packages = parse_find(&fixture("snap/find.txt"));
+
+        let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["firefox", "firefox-esr", "firefox-nightly"]);
+        assert_eq!(
+            packages[1].description.as_deref(),
+            Some("Mozilla Firefox ESR web browser")
+        );
+    }
+
+    #[test]
+    fn classic_confinement_is_read_from_the_notes_column() {
+        let classic = fixture("snap/find_classic.txt");
+        assert!(is_classic_in_find(&classic, "code"));
+        assert!(is_classic_in_find(&classic, "codium"));
+        assert!(!is_classic_in_find(&classic, "cod"));
+
+        let strict = fixture("snap/find.txt");
+        assert!(!is_classic_in_find(&strict, "firefox"));
+    }
+
+    #[test]
+    fn normalize_name_rejects_options_and_paths() {
+        assert_eq!(SnapManager.normalize_name(" Firefox ").unwrap(), "firefox");
+        assert!(SnapManager.normalize_name("--classic").is_err());
+        assert!(SnapManager.normalize_name("../core").is_err());
+    }
+}
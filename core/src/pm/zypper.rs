@@ -1,33 +1,45 @@
 use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
-use tokio::process::Command;
 
 use crate::{
-    Config, CoreResult, PackageInfo, PackageManager, PackageManagerType, PackageUpdate,
+    Config, CoreResult, PackageAction, PackageInfo, PackageManager, PackageManagerType,
+    PackageUpdate, SearchResults,
     error::CoreError,
     pm::{
-        common::manager_command_path,
-        progress::{CommandProgressEvent, run_command_with_progress},
+        common::{
+            CommandExt, checked_package_name, limit_search_results, manager_command_path,
+            new_command, privileged_args, privileged_removal_args,
+        },
+        dnf::is_rpm_name_char,
+        progress::{CommandProgressEvent, run_privileged_with_progress},
     },
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct ZypperManager;
 
+const REMOVE_ARGS: &[&str] = &["--non-interactive", "remove", "-y"];
+const UPDATE_ARGS: &[&str] = &["--non-interactive", "update", "-y"];
+const INSTALL_ARGS: &[&str] = &["--non-interactive", "install", "-y"];
+
 fn command_path(config: &Config) -> String {
     manager_command_path(config, PackageManagerType::Zypper)
 }
 
 #[async_trait]
 impl PackageManager for ZypperManager {
-    async fn get_current_version(_config: &Config, package_name: &str) -> CoreResult<String> {
-        let output = Command::new("rpm")
+    fn manager_type(&self) -> PackageManagerType {
+        PackageManagerType::Zypper
+    }
+
+    async fn get_current_version(&self, config: &Config, package_name: &str) -> CoreResult<String> {
+        let output = new_command(config, "rpm")
             .arg("-q")
             .arg("--queryformat")
             .arg("%{VERSION}-%{RELEASE}")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if output.status.success() {
@@ -40,12 +52,12 @@ impl PackageManager for ZypperManager {
         }
     }
 
-    async fn list_installed(_config: &Config) -> CoreResult<Vec<PackageInfo>> {
-        let output = Command::new("rpm")
+    async fn list_installed(&self, config: &Config) -> CoreResult<Vec<PackageInfo>> {
+        let output = new_command(config, "rpm")
             .arg("-qa")
             .arg("--queryformat")
             .arg("%{NAME}\t%{VERSION}-%{RELEASE}\t%{SUMMARY}\t%{SIZE}\t%{INSTALLTIME}\t%{URL}\n")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -96,6 +108,9 @@ impl PackageManager for ZypperManager {
                     size,
                     install_date,
                     homepage,
+                    popularity: None,
+                    has_update_info: true,
+                    eol: None,
                 })
             })
             .collect();
@@ -103,10 +118,31 @@ impl PackageManager for ZypperManager {
         Ok(packages)
     }
 
-    async fn count_installed(_config: &Config) -> CoreResult<usize> {
-        let output = Command::new("rpm").arg("-qa").output().await?;
+    async fn list_user_installed(&self, config: &Config) -> CoreResult<Vec<String>> {
+        let path = command_path(config);
+
+        let output = new_command(config, &path)
+            .env("LC_ALL", "C")
+            .arg("--non-interactive")
+            .arg("packages")
+            .arg("--userinstalled")
+            .try_output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(CoreError::UnknownError(
+                "zypper packages --userinstalled failed".to_owned(),
+            ));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(parse_user_installed(&stdout))
+    }
+
+    async fn count_installed(&self, config: &Config) -> CoreResult<usize> {
+        let output = new_command(config, "rpm").arg("-qa").try_output().await?;
         if !output.status.success() {
-            return Ok(Self::list_installed(_config).await?.len());
+            return Ok(self.list_installed(config).await?.len());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
@@ -116,23 +152,27 @@ impl PackageManager for ZypperManager {
             .count())
     }
 
-    async fn search_package(config: &Config, package_name: &str) -> CoreResult<Vec<PackageInfo>> {
+    async fn search_package(
+        &self,
+        config: &Config,
+        package_name: &str,
+    ) -> CoreResult<SearchResults> {
         let path = command_path(config);
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("--non-interactive")
             .arg("search")
             .arg("--details")
             .arg(package_name)
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
-            return Ok(Vec::new());
+            return Ok(SearchResults::default());
         }
 
         let stdout = String::from_utf8(output.stdout)?;
-        let installed_versions = Self::installed_version_map().await?;
+        let installed_versions = Self::installed_version_map(config).await?;
         let search_rows = parse_table_rows(&stdout, &search_headers());
 
         let mut packages = Vec::new();
@@ -160,15 +200,23 @@ impl PackageManager for ZypperManager {
                 size: None,
                 install_date: None,
                 homepage: None,
+                popularity: None,
+                has_update_info: true,
+                eol: None,
             });
         }
 
-        Ok(packages)
+        Ok(limit_search_results(config, packages))
     }
-}
 
-impl ZypperManager {
-    pub async fn list_updates_with_refresh(
+    /// RPM names are case-sensitive, so only the character set is checked.
+    fn normalize_name(&self, package_name: &str) -> CoreResult<String> {
+        checked_package_name(PackageManagerType::Zypper, package_name, is_rpm_name_char)
+            .map(str::to_owned)
+    }
+
+    async fn list_updates_with_refresh(
+        &self,
         config: &Config,
         refresh: bool,
     ) -> CoreResult<Vec<PackageUpdate>> {
@@ -180,13 +228,13 @@ impl ZypperManager {
                 "--non-interactive".to_owned(),
                 "refresh".to_owned(),
             ];
-            run_command_with_progress("pkexec", &args, |_| {}).await?;
+            run_privileged_with_progress(config, &args, |_| {}).await?;
         }
 
-        let output = Command::new(&path)
+        let output = new_command(config, &path)
             .arg("--non-interactive")
             .arg("list-updates")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -226,12 +274,36 @@ impl ZypperManager {
                 name: name.clone(),
                 current_version: current_version.clone(),
                 new_version: new_version.clone(),
+                security: false,
+                download_size: None,
             });
         }
 
         Ok(updates)
     }
 
+    async fn run_batch_action(
+        &self,
+        action: PackageAction,
+        config: &Config,
+        package_names: &[String],
+        on_progress: &mut (dyn FnMut(CommandProgressEvent) + Send),
+    ) -> CoreResult<()> {
+        match action {
+            PackageAction::Uninstall => {
+                Self::uninstall_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Update | PackageAction::UpdateAll => {
+                Self::update_packages_with_progress(config, package_names, on_progress).await
+            }
+            PackageAction::Install => {
+                Self::install_packages_with_progress(config, package_names, on_progress).await
+            }
+        }
+    }
+}
+
+impl ZypperManager {
     pub async fn uninstall_packages_with_progress(
         config: &Config,
         package_names: &[String],
@@ -241,16 +313,14 @@ impl ZypperManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-        let mut args = vec![
-            path,
-            "--non-interactive".to_owned(),
-            "remove".to_owned(),
-            "-y".to_owned(),
-        ];
-        args.extend(package_names.iter().cloned());
-
-        run_command_with_progress("pkexec", &args, on_progress).await
+        let args = privileged_removal_args(
+            config,
+            PackageManagerType::Zypper,
+            REMOVE_ARGS,
+            package_names,
+        );
+
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn update_packages_with_progress(
@@ -262,16 +332,14 @@ impl ZypperManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-        let mut args = vec![
-            path,
-            "--non-interactive".to_owned(),
-            "update".to_owned(),
-            "-y".to_owned(),
-        ];
-        args.extend(package_names.iter().cloned());
-
-        run_command_with_progress("pkexec", &args, on_progress).await
+        let args = privileged_args(
+            config,
+            PackageManagerType::Zypper,
+            UPDATE_ARGS,
+            package_names,
+        );
+
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
     pub async fn install_packages_with_progress(
@@ -283,24 +351,22 @@ impl ZypperManager {
             return Ok(());
         }
 
-        let path = command_path(config);
-        let mut args = vec![
-            path,
-            "--non-interactive".to_owned(),
-            "install".to_owned(),
-            "-y".to_owned(),
-        ];
-        args.extend(package_names.iter().cloned());
-
-        run_command_with_progress("pkexec", &args, on_progress).await
+        let args = privileged_args(
+            config,
+            PackageManagerType::Zypper,
+            INSTALL_ARGS,
+            package_names,
+        );
+
+        run_privileged_with_progress(config, &args, on_progress).await
     }
 
-    async fn installed_version_map() -> CoreResult<HashMap<String, String>> {
-        let output = Command::new("rpm")
+    async fn installed_version_map(config: &Config) -> CoreResult<HashMap<String, String>> {
+        let output = new_command(config, "rpm")
             .arg("-qa")
             .arg("--queryformat")
             .arg("%{NAME}\t%{VERSION}-%{RELEASE}\n")
-            .output()
+            .try_output()
             .await?;
 
         if !output.status.success() {
@@ -407,6 +473,18 @@ fn parse_table_rows(
     rows
 }
 
+/// Distinct names of a `zypper packages --userinstalled` table, which has
+/// one row per repository that carries the package.
+fn parse_user_installed(output: &str) -> Vec<String> {
+    let mut names: Vec<String> = parse_table_rows(output, &search_headers())
+        .into_iter()
+        .filter_map(|mut row| row.remove("name"))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
 fn split_table_row(line: &str) -> Vec<String> {
     line.split('|').map(|part| part.trim().to_owned()).collect()
 }
@@ -427,6 +505,7 @@ fn is_separator_line(line: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pm::common::{config_with_custom_args, fixture};
 
     #[test]
     fn parse_table_rows_extracts_updates_from_list_updates_table() {
@@ -462,9 +541,56 @@ S | Name      | Type    | Version  | Arch   | Repository
         assert_eq!(rows[1].get("version"), Some(&"9.0.0-1".to_owned()));
     }
 
+    #[test]
+    fn parse_user_installed_reads_names_once() {
+        assert_eq!(
+            parse_user_installed(&fixture("zypper/packages_userinstalled.txt")),
+            ["git", "htop", "vlc"]
+        );
+    }
+
     #[test]
     fn normalize_header_removes_spaces_and_hyphens() {
         assert_eq!(normalize_header("Current Version"), "currentversion");
         assert_eq!(normalize_header("Available-Version"), "availableversion");
     }
+
+    #[test]
+    fn parse_table_rows_reads_list_updates_fixture() {
+        let rows = parse_table_rows(&fixture("zypper/list_updates.txt"), &update_headers());
+
+        let names: Vec<_> = rows.iter().map(|row| row["name"].as_str()).collect();
+        assert_eq!(names, ["MozillaFirefox", "bash", "curl"]);
+        assert_eq!(rows[2]["current_version"], "8.7.1-1.1");
+        assert_eq!(rows[2]["available_version"], "8.8.0-1.1");
+    }
+
+    #[test]
+    fn custom_args_follow_the_subcommand() {
+        let config = config_with_custom_args(PackageManagerType::Zypper, &["--no-recommends"]);
+        let packages = ["htop".to_owned()];
+
+        assert_eq!(
+            privileged_args(&config, PackageManagerType::Zypper, INSTALL_ARGS, &packages),
+            [
+                "zypper",
+                "--non-interactive",
+                "install",
+                "-y",
+                "--no-recommends",
+                "htop"
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_name_keeps_case_and_rejects_invalid_names() {
+        assert_eq!(
+            ZypperManager.normalize_name("MozillaFirefox\n").unwrap(),
+            "MozillaFirefox"
+        );
+        assert!(ZypperManager.normalize_name("").is_err());
+        assert!(ZypperManager.normalize_name("-n").is_err());
+        assert!(ZypperManager.normalize_name("foo|bar").is_err());
+    }
 }
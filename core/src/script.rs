@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use crate::PackageManagerType;
+
+/// Shell command a user would type to install `packages` with `manager`,
+/// e.g. `cargo install bat ripgrep`. System managers and snap are prefixed
+/// with `sudo`.
+pub fn install_command(manager: PackageManagerType, packages: &[String]) -> String {
+    let (program, leading): (&str, &[&str]) = match manager {
+        PackageManagerType::Apt => ("sudo apt", &["install", "-y"]),
+        PackageManagerType::Dnf => ("sudo dnf", &["install", "-y"]),
+        PackageManagerType::Pacman => ("sudo pacman", &["-S", "--needed", "--noconfirm"]),
+        PackageManagerType::Zypper => ("sudo zypper", &["--non-interactive", "install"]),
+        PackageManagerType::Flatpak => ("flatpak", &["install", "-y"]),
+        PackageManagerType::Snap => ("sudo snap", &["install"]),
+        PackageManagerType::Homebrew => ("brew", &["install"]),
+        PackageManagerType::Cargo => ("cargo", &["install"]),
+        PackageManagerType::Go => ("go", &["install"]),
+        PackageManagerType::Npm => ("npm", &["install", "-g"]),
+        PackageManagerType::Pnpm => ("pnpm", &["add", "-g"]),
+        PackageManagerType::Chocolatey => ("choco", &["install", "-y"]),
+        PackageManagerType::Pip => ("pip", &["install", "--user"]),
+        PackageManagerType::Pipx => ("pipx", &["install"]),
+    };
+
+    let packages = packages.iter().map(|package| match manager {
+        // go install 需要版本后缀
+        PackageManagerType::Go if !package.contains('@') => format!("{}@latest", package),
+        _ => package.clone(),
+    });
+
+    std::iter::once(program.to_owned())
+        .chain(leading.iter().map(|arg| (*arg).to_owned()))
+        .chain(packages.map(|package| shell_quote(&package)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `sh` script reinstalling `packages`, one `install_command` per manager.
+/// Go binaries listed by name rather than module path are left commented
+/// out, since `go install` cannot resolve them.
+pub fn install_script(packages: &BTreeMap<PackageManagerType, Vec<String>>) -> String {
+    let mut script = String::from("#!/bin/sh\nset -e\n");
+
+    for (manager, names) in packages {
+        if names.is_empty() {
+            continue;
+        }
+
+        let (installable, unresolved): (Vec<String>, Vec<String>) = names
+            .iter()
+            .cloned()
+            .partition(|name| *manager != PackageManagerType::Go || name.contains('/'));
+
+        script.push_str(&format!("\n# {}\n", manager.name()));
+        if !installable.is_empty() {
+            script.push_str(&install_command(*manager, &installable));
+            script.push('\n');
+        }
+        if !unresolved.is_empty() {
+            script.push_str(&format!(
+                "# module path unknown: {}\n",
+                unresolved.join(" ")
+            ));
+        }
+    }
+
+    script
+}
+
+/// Single-quote `arg` unless it only has characters the shell leaves alone.
+pub(crate) fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.+@/:=,".contains(c));
+
+    if is_plain {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| (*name).to_owned()).collect()
+    }
+
+    #[test]
+    fn install_command_uses_each_managers_syntax() {
+        assert_eq!(
+            install_command(PackageManagerType::Cargo, &names(&["bat", "ripgrep"])),
+            "cargo install bat ripgrep"
+        );
+        assert_eq!(
+            install_command(PackageManagerType::Dnf, &names(&["vim-enhanced"])),
+            "sudo dnf install -y vim-enhanced"
+        );
+        assert_eq!(
+            install_command(
+                PackageManagerType::Go,
+                &names(&["golang.org/x/tools/gopls"])
+            ),
+            "go install golang.org/x/tools/gopls@latest"
+        );
+        assert_eq!(
+            install_command(PackageManagerType::Npm, &names(&["@vue/cli", "it's"])),
+            r"npm install -g @vue/cli 'it'\''s'"
+        );
+    }
+
+    #[test]
+    fn install_script_groups_by_manager() {
+        let packages = BTreeMap::from([
+            (PackageManagerType::Homebrew, names(&["jq"])),
+            (PackageManagerType::Cargo, names(&["eza"])),
+            (
+                PackageManagerType::Go,
+                names(&["gopls", "github.com/nao1215/gup"]),
+            ),
+            (PackageManagerType::Pip, Vec::new()),
+        ]);
+
+        assert_eq!(
+            install_script(&packages),
+            "#!/bin/sh\nset -e\n\
+             \n# Homebrew\nbrew install jq\n\
+             \n# Cargo\ncargo install eza\n\
+             \n# Go\ngo install github.com/nao1215/gup@latest\n# module path unknown: gopls\n"
+        );
+    }
+}
@@ -1,33 +1,325 @@
-use directories_next::ProjectDirs;
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use directories_next::{ProjectDirs, UserDirs};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     ALL_APP_PACKAGE_MANAGERS, ALL_SYSTEM_PACKAGE_MANAGERS, CoreResult, PackageManagerType,
-    error::CoreError,
+    PackageUpdate, error::CoreError, i18n::Lang,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+const DEFAULT_SEARCH_RESULT_LIMIT: usize = 50;
+
+/// Held across the reload, change and save of `Config::save_skipped_version`
+/// and `Config::save_ui_preferences`, so concurrent saves don't undo each other.
+static CONFIG_UPDATE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Makes the temporary file names of concurrent `Config::save` calls unique.
+static SAVE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn default_search_result_limit() -> usize {
+    DEFAULT_SEARCH_RESULT_LIMIT
+}
+
+fn default_install_concurrency() -> usize {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_confirm_threshold() -> usize {
+    1
+}
+
+fn default_command_idle_timeout_secs() -> u64 {
+    15 * 60
+}
+
+fn default_crate_cache_ttl_hours() -> u64 {
+    24
+}
+
+fn default_http_timeout_secs() -> u64 {
+    10
+}
+
+fn default_http_retries() -> u32 {
+    3
+}
+
+/// Locations desktop sessions often leave out of `PATH`.
+fn default_extra_path() -> Vec<String> {
+    let dirs: &[&str] = if cfg!(target_os = "macos") {
+        &["/opt/homebrew/bin", "/usr/local/bin", "~/.local/bin"]
+    } else {
+        &["~/.local/bin"]
+    };
+    dirs.iter().map(|dir| (*dir).to_owned()).collect()
+}
+
+fn default_exclude_from_update_all() -> HashSet<PackageManagerType> {
+    ALL_SYSTEM_PACKAGE_MANAGERS.iter().copied().collect()
+}
+
+fn default_poll_after_operation() -> HashSet<PackageManagerType> {
+    HashSet::from([PackageManagerType::Flatpak, PackageManagerType::Snap])
+}
+
+fn default_dnf_noise_suffixes() -> Vec<String> {
+    ["-debuginfo", "-debugsource", "-devel"]
+        .into_iter()
+        .map(str::to_owned)
+        .collect()
+}
+
+/// How system-manager commands get root, stored in `Config::privilege_escalation`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrivilegeEscalation {
+    /// `pkexec`, needs a running polkit authentication agent
+    #[default]
+    Pkexec,
+    /// `sudo -A`, asks for the password through the `SUDO_ASKPASS` helper
+    SudoAskpass,
+}
+
+impl PrivilegeEscalation {
+    pub const ALL: [PrivilegeEscalation; 2] = [
+        PrivilegeEscalation::Pkexec,
+        PrivilegeEscalation::SudoAskpass,
+    ];
+}
+
+/// UI color palette, stored in `Config::color_scheme`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorScheme {
+    #[default]
+    Standard,
+    /// Darker text, stronger borders and badges for readability
+    HighContrast,
+}
+
+impl ColorScheme {
+    pub const ALL: [ColorScheme; 2] = [ColorScheme::Standard, ColorScheme::HighContrast];
+}
+
+/// Spacing of package list rows, stored in `Config::list_density`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListDensity {
+    #[default]
+    Comfortable,
+    /// Tighter rows and smaller text, for long package lists
+    Compact,
+}
+
+impl ListDensity {
+    pub const ALL: [ListDensity; 2] = [ListDensity::Comfortable, ListDensity::Compact];
+}
+
+/// Page state the UI restores at startup, stored in `Config::ui_preferences`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiPreferences {
+    #[serde(default)]
+    pub updates: PagePreferences,
+    #[serde(default)]
+    pub installed: PagePreferences,
+    #[serde(default)]
+    pub finding: PagePreferences,
+    /// Tab shown when the app was last used, e.g. `"updates"`
+    #[serde(default)]
+    pub last_tab: Option<String>,
+}
+
+/// Manager filter and sort order of one page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PagePreferences {
+    #[serde(default)]
+    pub selected_managers: HashSet<PackageManagerType>,
+    /// Stable key of the page's sort option, e.g. `"install_date"`; `None`
+    /// keeps the page default
+    #[serde(default)]
+    pub sort_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub system_manager: Option<PackageManagerConfig>,
     pub app_managers: Vec<PackageManagerConfig>,
     /// 自定义 Go bin 目录，如果为 None 则使用默认规则（GOBIN > GOPATH/bin > ~/go/bin）
     pub go_bin_dir: Option<String>,
+    /// Go bin directories scanned in addition to `go_bin_dir` or the auto-detected ones
+    #[serde(default)]
+    pub go_extra_bin_dirs: Vec<String>,
+    /// Maximum search results kept per manager, 0 disables the limit
+    #[serde(default = "default_search_result_limit")]
+    pub search_result_limit: usize,
+    /// Packages installed/updated in parallel by managers that allow it
+    /// (see `PackageManagerType::supports_concurrent_installs`), 1 keeps it serial
+    #[serde(default = "default_install_concurrency")]
+    pub install_concurrency: usize,
+    /// Offer pre-release versions (e.g. `2.0.0-beta.1`) as Cargo/Go updates
+    #[serde(default)]
+    pub include_prereleases: bool,
+    /// Install/update Cargo crates with `cargo binstall` when it is installed
+    #[serde(default = "default_true")]
+    pub prefer_binstall: bool,
+    /// Extra flags for `cargo install`, e.g. `--locked`, appended in order
+    #[serde(default)]
+    pub cargo_install_flags: Vec<String>,
+    /// Hide DNF packages matching the noise filters below from the installed list
+    #[serde(default = "default_true")]
+    pub dnf_hide_noise: bool,
+    /// Package-name suffixes treated as noise, e.g. `-debuginfo`
+    #[serde(default = "default_dnf_noise_suffixes")]
+    pub dnf_noise_suffixes: Vec<String>,
+    /// Package-name prefixes treated as noise
+    #[serde(default)]
+    pub dnf_noise_prefixes: Vec<String>,
+    /// UI language
+    #[serde(default)]
+    pub language: Lang,
+    /// Package name -> available version the user chose to skip
+    #[serde(default)]
+    pub skipped_versions: HashMap<String, String>,
+    /// Directories prepended to `PATH` of every spawned command, `~/` is the home directory
+    #[serde(default = "default_extra_path")]
+    pub extra_path: Vec<String>,
+    /// UI color palette
+    #[serde(default)]
+    pub color_scheme: ColorScheme,
+    /// Row spacing and text size of the package lists
+    #[serde(default)]
+    pub list_density: ListDensity,
+    /// Ask before removing at least this many packages, 0 never asks
+    #[serde(default = "default_confirm_threshold")]
+    pub confirm_threshold: usize,
+    /// Always ask before removing system-manager packages
+    #[serde(default = "default_true")]
+    pub confirm_system_always: bool,
+    /// Ask before closing the window while an install, update or removal runs
+    #[serde(default = "default_true")]
+    pub confirm_close_during_operation: bool,
+    /// Managers that bulk "update all" runs leave alone; system managers by default
+    #[serde(default = "default_exclude_from_update_all")]
+    pub exclude_from_update_all: HashSet<PackageManagerType>,
+    /// Stop an install, update or removal that prints nothing for this many seconds, 0 never stops
+    #[serde(default = "default_command_idle_timeout_secs")]
+    pub command_idle_timeout_secs: u64,
+    /// Managers whose CLI can return before an operation has fully landed; after
+    /// installs, updates and removals their state is re-checked until it matches
+    #[serde(default = "default_poll_after_operation")]
+    pub poll_after_operation: HashSet<PackageManagerType>,
+    /// Browse the system manager only: its install, update and removal
+    /// commands are refused
+    #[serde(default)]
+    pub system_manager_readonly: bool,
+    /// How install, update and removal commands of system managers get root
+    #[serde(default)]
+    pub privilege_escalation: PrivilegeEscalation,
+    /// Askpass helper passed as `SUDO_ASKPASS` with `PrivilegeEscalation::SudoAskpass`,
+    /// `None` keeps the inherited one
+    #[serde(default)]
+    pub sudo_askpass: Option<String>,
+    /// User-Agent for crates.io/PyPI requests, `None` uses `DEFAULT_USER_AGENT`
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Seconds before a crates.io/PyPI/npm registry request gives up, 0 never does
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64,
+    /// Proxy URL for those requests, `None` follows `HTTP_PROXY`/`HTTPS_PROXY`
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    /// Times those requests are retried after a 5xx answer or a connection
+    /// error, with exponential backoff; 0 never retries
+    #[serde(default = "default_http_retries")]
+    pub http_retries: u32,
+    /// Hours crates.io descriptions and homepages are reused from the on-disk
+    /// cache, 0 disables it. Update checks always ask crates.io
+    #[serde(default = "default_crate_cache_ttl_hours")]
+    pub crate_cache_ttl_hours: u64,
+    /// Report install, update and removal commands instead of running them
+    /// (see `PackageManagerType::dry_run_commands`)
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Selected managers, sort orders and tab, saved as they change
+    #[serde(default)]
+    pub ui_preferences: UiPreferences,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            system_manager: None,
+            app_managers: Vec::new(),
+            go_bin_dir: None,
+            go_extra_bin_dirs: Vec::new(),
+            search_result_limit: DEFAULT_SEARCH_RESULT_LIMIT,
+            install_concurrency: default_install_concurrency(),
+            include_prereleases: false,
+            prefer_binstall: true,
+            cargo_install_flags: Vec::new(),
+            dnf_hide_noise: true,
+            dnf_noise_suffixes: default_dnf_noise_suffixes(),
+            dnf_noise_prefixes: Vec::new(),
+            language: Lang::default(),
+            skipped_versions: HashMap::new(),
+            extra_path: default_extra_path(),
+            color_scheme: ColorScheme::default(),
+            list_density: ListDensity::default(),
+            confirm_threshold: default_confirm_threshold(),
+            confirm_system_always: true,
+            confirm_close_during_operation: true,
+            exclude_from_update_all: default_exclude_from_update_all(),
+            command_idle_timeout_secs: default_command_idle_timeout_secs(),
+            poll_after_operation: default_poll_after_operation(),
+            system_manager_readonly: false,
+            privilege_escalation: PrivilegeEscalation::default(),
+            sudo_askpass: None,
+            user_agent: None,
+            http_timeout_secs: default_http_timeout_secs(),
+            http_proxy: None,
+            http_retries: default_http_retries(),
+            crate_cache_ttl_hours: default_crate_cache_ttl_hours(),
+            dry_run: false,
+            ui_preferences: UiPreferences::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageManagerConfig {
     pub manager_type: PackageManagerType,
     pub custom_path: Option<String>,
+    /// Extra flags passed after the subcommand of install and update commands
+    #[serde(default)]
+    pub custom_args: Vec<String>,
 }
 
 impl Config {
-    /// Load configuration from file, or detect and initialize if not exists
-    pub async fn load() -> CoreResult<Self> {
-        let config_dir = ProjectDirs::from("com", "ayi", "updater").ok_or_else(|| {
+    /// Directory holding the configuration file
+    pub fn config_dir() -> CoreResult<PathBuf> {
+        let project_dirs = ProjectDirs::from("com", "ayi", "updater").ok_or_else(|| {
             CoreError::UnknownError("Could not determine config directory".into())
         })?;
 
-        let path = config_dir.config_dir().join("config.json");
+        Ok(project_dirs.config_dir().to_path_buf())
+    }
+
+    /// Full path of the configuration file
+    pub fn config_path() -> CoreResult<PathBuf> {
+        Ok(Self::config_dir()?.join("config.json"))
+    }
+
+    /// Load configuration from file, or detect and initialize if not exists
+    pub async fn load() -> CoreResult<Self> {
+        let path = Self::config_path()?;
 
         // If config file exists, load it
         if path.exists() {
@@ -52,6 +344,7 @@ impl Config {
             system_manager = Some(PackageManagerConfig {
                 manager_type: system_type,
                 custom_path: None,
+                custom_args: Vec::new(),
             });
         }
 
@@ -61,13 +354,14 @@ impl Config {
             .map(|manager_type| PackageManagerConfig {
                 manager_type,
                 custom_path: None,
+                custom_args: Vec::new(),
             })
             .collect();
 
         Config {
             system_manager,
             app_managers,
-            go_bin_dir: None,
+            ..Config::default()
         }
     }
 
@@ -94,13 +388,27 @@ impl Config {
         managers
     }
 
+    /// Configured managers whose binary is missing, see
+    /// `PackageManagerType::is_available_with_config`.
+    pub async fn unavailable_managers(&self) -> HashSet<PackageManagerType> {
+        let managers = self
+            .system_manager
+            .iter()
+            .chain(self.app_managers.iter())
+            .map(|manager| manager.manager_type);
+
+        futures::future::join_all(managers.map(|manager| async move {
+            (!manager.is_available_with_config(self).await).then_some(manager)
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
     /// Reload configuration from file
     pub async fn reload(&mut self) -> CoreResult<()> {
-        let config_dir = ProjectDirs::from("com", "ayi", "updater").ok_or_else(|| {
-            CoreError::UnknownError("Could not determine config directory".into())
-        })?;
-
-        let path = config_dir.config_dir().join("config.json");
+        let path = Self::config_path()?;
 
         if path.exists() {
             let json = tokio::fs::read_to_string(&path).await?;
@@ -113,20 +421,131 @@ impl Config {
 
     /// Save configuration to file
     pub async fn save(&self) -> CoreResult<()> {
-        let config_dir = ProjectDirs::from("com", "ayi", "updater").ok_or_else(|| {
-            CoreError::UnknownError("Could not determine config directory".into())
-        })?;
-
-        let dir_path = config_dir.config_dir();
-        let file_path = dir_path.join("config.json");
+        let dir_path = Self::config_dir()?;
+        let file_path = Self::config_path()?;
 
         // Create config directory if it doesn't exist
-        tokio::fs::create_dir_all(dir_path).await?;
+        tokio::fs::create_dir_all(&dir_path).await?;
 
         let json = serde_json::to_string_pretty(self)?;
-        tokio::fs::write(&file_path, json).await?;
+        // 先写临时文件再改名，中断的写入不会留下半截配置
+        let tmp_path = file_path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            SAVE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = match tokio::fs::write(&tmp_path, json).await {
+            Ok(()) => tokio::fs::rename(&tmp_path, &file_path).await,
+            Err(e) => Err(e),
+        };
+        if written.is_err() {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
 
-        Ok(())
+        Ok(written?)
+    }
+
+    /// Persist a skipped version without saving other unsaved changes:
+    /// the config on disk is reloaded, updated and written back.
+    pub async fn save_skipped_version(package_name: &str, version: &str) -> CoreResult<()> {
+        let _update = CONFIG_UPDATE_LOCK.lock().await;
+        let mut config = Self::load().await?;
+        config
+            .skipped_versions
+            .insert(package_name.to_owned(), version.to_owned());
+        config.save().await
+    }
+
+    /// Persist `preferences` without saving other unsaved changes, like
+    /// `save_skipped_version`. Saves run one at a time, in call order.
+    pub async fn save_ui_preferences(preferences: UiPreferences) -> CoreResult<()> {
+        let _update = CONFIG_UPDATE_LOCK.lock().await;
+        let mut config = Self::load().await?;
+        config.ui_preferences = preferences;
+        config.save().await
+    }
+
+    /// Configured managers that already have `package_name` installed,
+    /// compared by each manager's normalized name. All managers are checked
+    /// concurrently.
+    pub async fn where_installed(&self, package_name: &str) -> Vec<PackageManagerType> {
+        let checks =
+            futures::future::join_all(self.installed_lookups(package_name).into_iter().map(
+                |(pm_type, name)| async move {
+                    pm_type.is_installed(self, &name).await.then_some(pm_type)
+                },
+            ))
+            .await;
+
+        checks.into_iter().flatten().collect()
+    }
+
+    /// `package_name` as each configured manager spells it; managers that
+    /// cannot accept the name are left out.
+    fn installed_lookups(&self, package_name: &str) -> Vec<(PackageManagerType, String)> {
+        self.system_manager
+            .iter()
+            .chain(self.app_managers.iter())
+            .filter_map(|manager| {
+                let pm_type = manager.manager_type;
+                let name = pm_type.normalize_package_name(package_name).ok()?;
+                Some((pm_type, name))
+            })
+            .collect()
+    }
+
+    /// Packages an "update all" run should update, grouped by manager in
+    /// stable order, leaving out managers in `exclude_from_update_all`.
+    pub fn update_all_groups<'a>(
+        &self,
+        updates: impl IntoIterator<Item = (PackageManagerType, &'a [PackageUpdate])>,
+    ) -> Vec<(PackageManagerType, Vec<String>)> {
+        let mut groups: Vec<_> = updates
+            .into_iter()
+            .filter(|(pm_type, _)| !self.exclude_from_update_all.contains(pm_type))
+            .map(|(pm_type, updates)| {
+                let names = updates.iter().map(|update| update.name.clone()).collect();
+                (pm_type, names)
+            })
+            .filter(|(_, names): &(_, Vec<String>)| !names.is_empty())
+            .collect();
+        groups.sort_by_key(|(pm_type, _)| *pm_type);
+        groups
+    }
+
+    /// Whether `system_manager_readonly` keeps `manager` from changing packages.
+    pub fn is_read_only(&self, manager: PackageManagerType) -> bool {
+        self.system_manager_readonly && manager.is_system_manager()
+    }
+
+    /// Whether removing `package_count` packages should be confirmed first.
+    pub fn needs_confirmation(&self, package_count: usize, involves_system_manager: bool) -> bool {
+        if package_count == 0 {
+            return false;
+        }
+
+        (self.confirm_system_always && involves_system_manager)
+            || (self.confirm_threshold > 0 && package_count >= self.confirm_threshold)
+    }
+
+    /// `PATH` for spawned commands: `extra_path` before the inherited `PATH`.
+    /// `None` when there is nothing to add.
+    pub fn command_path_env(&self) -> Option<OsString> {
+        let home = UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        let extra: Vec<PathBuf> = self
+            .extra_path
+            .iter()
+            .map(|dir| dir.trim())
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| expand_home(dir, home.as_deref()))
+            .collect();
+
+        if extra.is_empty() {
+            return None;
+        }
+
+        let inherited = env::var_os("PATH").unwrap_or_default();
+        env::join_paths(extra.into_iter().chain(env::split_paths(&inherited))).ok()
     }
 
     pub fn get_package_path(&self, manager_type: PackageManagerType) -> Option<String> {
@@ -145,31 +564,317 @@ impl Config {
         None
     }
 
-    pub fn get_go_bin_dir(&self) -> String {
-        use directories_next::UserDirs;
-        use std::env;
+    /// `custom_args` of the configured `manager_type`, empty when not configured.
+    pub fn custom_args(&self, manager_type: PackageManagerType) -> &[String] {
+        self.system_manager
+            .iter()
+            .chain(self.app_managers.iter())
+            .find(|manager| manager.manager_type == manager_type)
+            .map_or(&[], |manager| manager.custom_args.as_slice())
+    }
 
-        if let Some(ref dir) = self.go_bin_dir {
-            return dir.clone();
-        }
+    /// `go_bin_dir` with a leading `~` expanded, `None` when unset or blank.
+    pub fn go_bin_dir_path(&self) -> Option<PathBuf> {
+        let home = UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        self.go_bin_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| expand_home(dir, home.as_deref()))
+    }
 
-        // 按优先级检查环境变量
-        if let Ok(gobin) = env::var("GOBIN") {
-            return gobin;
+    /// Check that a configured `go_bin_dir` names an existing directory.
+    pub fn validate_go_bin_dir(&self) -> CoreResult<()> {
+        match self.go_bin_dir_path() {
+            Some(dir) if !dir.is_dir() => Err(CoreError::BinDirError(format!(
+                "{} is not an existing directory",
+                dir.display()
+            ))),
+            _ => Ok(()),
         }
+    }
+
+    /// Go bin directories to scan, see `resolve_go_bin_dirs`.
+    pub fn go_bin_dirs(&self) -> Vec<PathBuf> {
+        resolve_go_bin_dirs(
+            self.go_bin_dir.as_deref(),
+            &self.go_extra_bin_dirs,
+            env::var_os("GOBIN"),
+            env::var_os("GOPATH"),
+            UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf()),
+        )
+    }
+}
+
+/// `dir` with a leading `~` replaced by `home`, when known.
+fn expand_home(dir: &str, home: Option<&Path>) -> PathBuf {
+    match (dir, home) {
+        ("~", Some(home)) => home.to_path_buf(),
+        (dir, Some(home)) if dir.starts_with("~/") => home.join(&dir[2..]),
+        _ => PathBuf::from(dir),
+    }
+}
 
-        if let Ok(gopath) = env::var("GOPATH") {
-            return format!("{}/bin", gopath);
+/// Go bin directories in priority order without duplicates: `explicit`, or
+/// else `$GOBIN`, each `$GOPATH` entry's `bin` and `~/go/bin`; then `extra`.
+/// A leading `~` in `explicit` and `extra` is expanded.
+fn resolve_go_bin_dirs(
+    explicit: Option<&str>,
+    extra: &[String],
+    gobin: Option<OsString>,
+    gopath: Option<OsString>,
+    home: Option<PathBuf>,
+) -> Vec<PathBuf> {
+    let detected: Vec<PathBuf> = match explicit.map(str::trim).filter(|dir| !dir.is_empty()) {
+        Some(dir) => vec![expand_home(dir, home.as_deref())],
+        None => gobin
+            .filter(|gobin| !gobin.is_empty())
+            .map(PathBuf::from)
+            .into_iter()
+            .chain(
+                gopath
+                    .iter()
+                    .flat_map(env::split_paths)
+                    .filter(|gopath| !gopath.as_os_str().is_empty())
+                    .map(|gopath| gopath.join("bin")),
+            )
+            .chain(home.as_ref().map(|home| home.join("go").join("bin")))
+            .collect(),
+    };
+
+    let extra = extra
+        .iter()
+        .map(|dir| dir.trim())
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| expand_home(dir, home.as_deref()));
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in detected.into_iter().chain(extra) {
+        // `components` 比较时忽略末尾的 `/`
+        if !dirs
+            .iter()
+            .any(|seen| seen.components().eq(dir.components()))
+        {
+            dirs.push(dir);
         }
+    }
+    dirs
+}
 
-        // 默认使用 ~/go/bin
-        UserDirs::new()
-            .and_then(|dirs| {
-                dirs.home_dir()
-                    .join("go/bin")
-                    .to_str()
-                    .map(|s| s.to_owned())
-            })
-            .unwrap_or_else(|| "go/bin".to_owned())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn go_bin_dirs_fall_back_to_gobin_gopath_and_home_without_duplicates() {
+        let dirs = resolve_go_bin_dirs(
+            None,
+            &[
+                "/home/me/go/bin/".to_owned(),
+                "/opt/go-tools/bin".to_owned(),
+            ],
+            Some("/srv/gobin".into()),
+            Some(env::join_paths(["/home/me/go", "/srv/gopath"]).unwrap()),
+            Some(PathBuf::from("/home/me")),
+        );
+        assert_eq!(
+            dirs,
+            [
+                "/srv/gobin",
+                "/home/me/go/bin",
+                "/srv/gopath/bin",
+                "/opt/go-tools/bin",
+            ]
+            .map(PathBuf::from)
+        );
+
+        // 显式目录替代自动探测的目录
+        let dirs = resolve_go_bin_dirs(
+            Some("/custom/bin"),
+            &[],
+            Some("/srv/gobin".into()),
+            None,
+            Some(PathBuf::from("/home/me")),
+        );
+        assert_eq!(dirs, [PathBuf::from("/custom/bin")]);
+
+        let dirs = resolve_go_bin_dirs(
+            Some("~/tools/bin"),
+            &["~".to_owned()],
+            None,
+            None,
+            Some(PathBuf::from("/home/me")),
+        );
+        assert_eq!(
+            dirs,
+            [
+                PathBuf::from("/home/me/tools/bin"),
+                PathBuf::from("/home/me")
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_go_bin_dir_rejects_missing_directories() {
+        let existing = Config {
+            go_bin_dir: Some(env::temp_dir().to_string_lossy().into_owned()),
+            ..Config::default()
+        };
+        assert!(existing.validate_go_bin_dir().is_ok());
+        assert!(Config::default().validate_go_bin_dir().is_ok());
+
+        let missing = Config {
+            go_bin_dir: Some("/nonexistent/updater-go-bin".to_owned()),
+            ..Config::default()
+        };
+        assert!(matches!(
+            missing.validate_go_bin_dir(),
+            Err(CoreError::BinDirError(_))
+        ));
+    }
+
+    #[test]
+    fn default_config_confirms_every_removal() {
+        let config = Config::default();
+
+        assert!(config.needs_confirmation(1, false));
+        assert!(config.needs_confirmation(1, true));
+        assert!(!config.needs_confirmation(0, true));
+    }
+
+    #[test]
+    fn confirmation_follows_threshold_and_system_flag() {
+        let config = Config {
+            confirm_threshold: 5,
+            confirm_system_always: true,
+            ..Config::default()
+        };
+        assert!(!config.needs_confirmation(4, false));
+        assert!(config.needs_confirmation(5, false));
+        assert!(config.needs_confirmation(1, true));
+
+        let never = Config {
+            confirm_threshold: 0,
+            confirm_system_always: false,
+            ..Config::default()
+        };
+        assert!(!never.needs_confirmation(100, true));
+    }
+
+    #[test]
+    fn update_all_skips_excluded_managers() {
+        let update = |name: &str| PackageUpdate {
+            name: name.to_owned(),
+            current_version: "1.0".to_owned(),
+            new_version: "1.1".to_owned(),
+            security: false,
+            download_size: None,
+        };
+        let dnf_updates = vec![update("kernel")];
+        let cargo_updates = vec![update("ripgrep"), update("bat")];
+        let updates = [
+            (PackageManagerType::Cargo, cargo_updates.as_slice()),
+            (PackageManagerType::Dnf, dnf_updates.as_slice()),
+        ];
+
+        let config = Config::default();
+        assert!(
+            config
+                .exclude_from_update_all
+                .contains(&PackageManagerType::Dnf)
+        );
+        assert_eq!(
+            config.update_all_groups(updates),
+            vec![(
+                PackageManagerType::Cargo,
+                vec!["ripgrep".to_owned(), "bat".to_owned()]
+            )]
+        );
+
+        let include_all = Config {
+            exclude_from_update_all: HashSet::new(),
+            ..Config::default()
+        };
+        let groups = include_all.update_all_groups(updates);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, PackageManagerType::Dnf);
+    }
+
+    #[test]
+    fn installed_lookups_keep_case_sensitive_names() {
+        let manager = |manager_type| PackageManagerConfig {
+            manager_type,
+            custom_path: None,
+            custom_args: Vec::new(),
+        };
+        let config = Config {
+            system_manager: Some(manager(PackageManagerType::Dnf)),
+            app_managers: vec![manager(PackageManagerType::Flatpak)],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.installed_lookups(" org.gimp.GIMP "),
+            [
+                (PackageManagerType::Dnf, "org.gimp.GIMP".to_owned()),
+                (PackageManagerType::Flatpak, "org.gimp.GIMP".to_owned()),
+            ]
+        );
+        // Not an application ID, so Flatpak is not asked
+        assert_eq!(
+            config.installed_lookups("NetworkManager"),
+            [(PackageManagerType::Dnf, "NetworkManager".to_owned())]
+        );
+    }
+
+    #[tokio::test]
+    async fn where_installed_only_checks_configured_managers() {
+        assert!(
+            Config::default()
+                .where_installed("ripgrep")
+                .await
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn custom_args_default_to_empty_and_are_looked_up_per_manager() {
+        let manager: PackageManagerConfig =
+            serde_json::from_str(r#"{"manager_type": "Dnf", "custom_path": null}"#).unwrap();
+        assert!(manager.custom_args.is_empty());
+
+        let config = Config {
+            system_manager: Some(PackageManagerConfig {
+                custom_args: vec!["--refresh".to_owned()],
+                ..manager
+            }),
+            ..Config::default()
+        };
+        assert_eq!(config.custom_args(PackageManagerType::Dnf), ["--refresh"]);
+        assert!(config.custom_args(PackageManagerType::Cargo).is_empty());
+    }
+
+    #[test]
+    fn ui_preferences_default_when_missing_and_round_trip() {
+        let config: Config = serde_json::from_str(
+            r#"{"system_manager": null, "app_managers": [], "go_bin_dir": null}"#,
+        )
+        .unwrap();
+        assert_eq!(config.ui_preferences, UiPreferences::default());
+
+        let config = Config {
+            ui_preferences: UiPreferences {
+                updates: PagePreferences {
+                    selected_managers: HashSet::from([PackageManagerType::Flatpak]),
+                    sort_by: Some("new_version".to_owned()),
+                },
+                last_tab: Some("updates".to_owned()),
+                ..UiPreferences::default()
+            },
+            ..Config::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.ui_preferences, config.ui_preferences);
     }
 }
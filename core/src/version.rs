@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+
+use crate::PackageManagerType;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Num(u64),
+    Alpha(&'a str),
+}
+
+/// Compare two version strings from any package manager.
+///
+/// Two SemVer versions (after dropping Go's leading `v`) compare by SemVer
+/// precedence, so any pre-release sorts first (`1.0.0-1 < 1.0.0`).
+/// Otherwise versions are split into numeric and alphabetic runs (like
+/// `rpmvercmp`), so `1.10.0 > 1.2.0`. A leading `v` and `+build` metadata
+/// are ignored. Trailing alphabetic parts mark a pre-release
+/// (`1.0.0-beta < 1.0.0`), trailing numeric parts a later release
+/// (`1.0-2 > 1.0`).
+pub fn compare_versions(current: &str, candidate: &str) -> Ordering {
+    if let (Some(current), Some(candidate)) = (parse_semver(current), parse_semver(candidate)) {
+        return current.cmp_precedence(&candidate);
+    }
+
+    let current = tokenize(current);
+    let candidate = tokenize(candidate);
+
+    for (a, b) in current.iter().zip(candidate.iter()) {
+        let ordering = match (a, b) {
+            (Token::Num(a), Token::Num(b)) => a.cmp(b),
+            (Token::Alpha(a), Token::Alpha(b)) => a.cmp(b),
+            (Token::Num(_), Token::Alpha(_)) => Ordering::Greater,
+            (Token::Alpha(_), Token::Num(_)) => Ordering::Less,
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    let common = current.len().min(candidate.len());
+    match (current.get(common), candidate.get(common)) {
+        (Some(Token::Alpha(_)), None) => Ordering::Less,
+        (Some(Token::Num(_)), None) => Ordering::Greater,
+        (None, Some(Token::Alpha(_))) => Ordering::Greater,
+        (None, Some(Token::Num(_))) => Ordering::Less,
+        _ => Ordering::Equal,
+    }
+}
+
+fn parse_semver(version: &str) -> Option<semver::Version> {
+    let version = version.trim();
+    semver::Version::parse(version.strip_prefix('v').unwrap_or(version)).ok()
+}
+
+/// Shorter `version` for list columns. Drops what each manager adds around
+/// the upstream version: epochs and release suffixes (`2:1.2.3-1.fc40`),
+/// Homebrew revisions (`1.2.3_1`), Go's `v` prefix and Flatpak branches.
+/// Keep the raw string wherever the exact version matters.
+pub fn display_version(version: &str, manager: PackageManagerType) -> String {
+    let version = version.trim();
+
+    let display = match manager {
+        PackageManagerType::Apt
+        | PackageManagerType::Dnf
+        | PackageManagerType::Pacman
+        | PackageManagerType::Zypper => {
+            let version = match version.split_once(':') {
+                Some((epoch, rest)) if epoch.chars().all(|c| c.is_ascii_digit()) => rest,
+                _ => version,
+            };
+            version
+                .rsplit_once('-')
+                .map_or(version, |(upstream, _)| upstream)
+        }
+        PackageManagerType::Flatpak => version
+            .strip_prefix("branch: ")
+            .or_else(|| version.strip_suffix(" (stable)"))
+            .unwrap_or(version),
+        PackageManagerType::Homebrew => match version.rsplit_once('_') {
+            Some((upstream, revision)) if revision.chars().all(|c| c.is_ascii_digit()) => upstream,
+            _ => version,
+        },
+        PackageManagerType::Go => {
+            let version = version.strip_suffix("+incompatible").unwrap_or(version);
+            match version.strip_prefix('v') {
+                Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+                _ => version,
+            }
+        }
+        _ => version,
+    };
+
+    if display.is_empty() { version } else { display }.to_owned()
+}
+
+fn tokenize(version: &str) -> Vec<Token<'_>> {
+    let version = version.trim();
+    let version = version.split('+').next().unwrap_or(version);
+    let version = match version.strip_prefix(['v', 'V']) {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+        _ => version,
+    };
+
+    let mut tokens = Vec::new();
+    let mut rest = version;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric()) {
+        rest = &rest[start..];
+        let is_digit = rest.starts_with(|c: char| c.is_ascii_digit());
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() || c.is_ascii_digit() != is_digit)
+            .unwrap_or(rest.len());
+
+        let run = &rest[..end];
+        tokens.push(if is_digit {
+            // 超长数字段（如日期时间戳）溢出时按最大值处理
+            Token::Num(run.parse().unwrap_or(u64::MAX))
+        } else {
+            Token::Alpha(run)
+        });
+        rest = &rest[end..];
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_numeric_segments() {
+        assert_eq!(compare_versions("1.2.0", "1.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0", "1.99.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2.3", "1.2.3"), Ordering::Equal);
+    }
+
+    #[test]
+    fn prerelease_sorts_before_release() {
+        assert_eq!(compare_versions("1.0.0-beta.1", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-beta.2", "1.0.0-beta.1"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("1.0.0-alpha", "1.0.0-beta"),
+            Ordering::Less
+        );
+        // SemVer: numeric pre-release identifiers still sort before the release
+        assert_eq!(compare_versions("1.0.0-1", "1.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("1.0.0-rc.1", "1.0.0-rc.10"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("v2.0.0-rc.1", "v2.0.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn ignores_v_prefix_and_build_metadata() {
+        assert_eq!(compare_versions("v1.2.3", "1.2.3"), Ordering::Equal);
+        assert_eq!(
+            compare_versions("v1.2.3+incompatible", "v1.2.4"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn install_ahead_of_registry_is_not_older() {
+        // e.g. a git-installed prerelease of the next version
+        assert_eq!(compare_versions("0.25.0-dev", "0.24.1"), Ordering::Greater);
+        assert_eq!(compare_versions("v0.16.0", "v0.15.2"), Ordering::Greater);
+    }
+
+    #[test]
+    fn handles_rpm_style_releases() {
+        assert_eq!(
+            compare_versions("6.8.9-300.fc40", "6.8.10-100.fc40"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("1.0-2", "1.0"), Ordering::Greater);
+    }
+
+    #[test]
+    fn display_version_trims_manager_decorations() {
+        let cases = [
+            (PackageManagerType::Dnf, "1.2.3-1.fc40", "1.2.3"),
+            (PackageManagerType::Dnf, "2:9.1.0-1.fc40", "9.1.0"),
+            (PackageManagerType::Zypper, "6.8.9-1.1", "6.8.9"),
+            (PackageManagerType::Pacman, "1:2.44.0-1", "2.44.0"),
+            (PackageManagerType::Apt, "1:2.39.2-1ubuntu1", "2.39.2"),
+            (PackageManagerType::Flatpak, "1.2.3 (stable)", "1.2.3"),
+            (PackageManagerType::Flatpak, "branch: 24.08", "24.08"),
+            (PackageManagerType::Flatpak, "2.0.0 (beta)", "2.0.0 (beta)"),
+            (PackageManagerType::Homebrew, "1.24.0_1", "1.24.0"),
+            (PackageManagerType::Go, "v0.15.0", "0.15.0"),
+            (PackageManagerType::Go, "v2.0.0+incompatible", "2.0.0"),
+            (PackageManagerType::Cargo, "14.1.0", "14.1.0"),
+            (PackageManagerType::Npm, "9.0.0-rc.1", "9.0.0-rc.1"),
+            (PackageManagerType::Pip, " 2.31.0 ", "2.31.0"),
+            (PackageManagerType::Dnf, "-1", "-1"),
+        ];
+
+        for (manager, raw, expected) in cases {
+            assert_eq!(
+                display_version(raw, manager),
+                expected,
+                "{:?} {}",
+                manager,
+                raw
+            );
+        }
+    }
+}
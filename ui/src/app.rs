@@ -4,66 +4,208 @@ use std::collections::HashSet;
 use std::time::Instant;
 
 use iced::{Length, Subscription, Task};
-use updater_core::{PackageManagerType, PackageUpdate};
+use updater_core::{
+    PackageManagerType, PackageUpdate, UiPreferences,
+    i18n::{Key, tr, tr_args},
+};
 
 use crate::{
     content::{self, Content, FindingInfo, InstalledInfo, UpdatesInfo},
     init_workflows::{InitProgress, ManagerInitTask, run_manager_init_task},
-    sidebar::{self, SideBar},
+    sidebar::{self, BackgroundOperation, SideBar},
     status_panel::{self, StatusPanel},
 };
 
 #[allow(dead_code)]
 pub mod colors {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
     use iced::Color;
+    use updater_core::ColorScheme;
+
+    /// Colors used by the custom widget styles.
+    pub struct Palette {
+        // Primary - Light Green
+        pub primary: Color,
+        pub primary_hover: Color,
+        pub primary_active: Color,
+        pub primary_light: Color,
+        pub primary_muted: Color,
+
+        // Secondary - Cyan Blue
+        pub secondary: Color,
+        pub secondary_hover: Color,
+        pub secondary_active: Color,
+        pub secondary_soft: Color,
+
+        // Surface
+        pub surface: Color,
+        pub surface_hover: Color,
+        pub surface_pressed: Color,
+        pub surface_muted: Color,
+        pub surface_alt: Color,
+
+        // Foreground
+        pub on_primary: Color,
+        pub on_surface: Color,
+        pub on_surface_idle: Color,
+        pub on_surface_muted: Color,
+        pub on_surface_alt: Color,
+
+        // Accent
+        pub accent: Color,
+        pub accent_hover: Color,
+        pub accent_muted: Color,
+
+        // Status
+        pub success: Color,
+        pub warning: Color,
+        pub error: Color,
+
+        // Helpers
+        pub divider: Color,
+        pub divider_light: Color,
+        pub shadow: Color,
+        pub shadow_light: Color,
+        pub shadow_heavy: Color,
+        pub overlay: Color,
+        pub overlay_light: Color,
+        pub focus: Color,
+        pub disabled: Color,
+    }
+
+    pub const STANDARD: Palette = Palette {
+        primary: Color::from_rgb8(211, 249, 216),          // #d3f9d8
+        primary_hover: Color::from_rgb8(196, 240, 204),    // #c4f0cc
+        primary_active: Color::from_rgb8(173, 231, 190),   // #ade7be
+        primary_light: Color::from_rgb8(228, 252, 228),    // #e4fce4
+        primary_muted: Color::from_rgb8(180, 220, 180),    // #b4dcb4
+        secondary: Color::from_rgb8(59, 201, 219),         // #3bc9db
+        secondary_hover: Color::from_rgb8(34, 184, 207),   // #22b8cf
+        secondary_active: Color::from_rgb8(21, 170, 191),  // #15aabf
+        secondary_soft: Color::from_rgb8(150, 219, 230),   // #96dbe6
+        surface: Color::from_rgb8(247, 248, 250),          // #f7f8fa
+        surface_hover: Color::from_rgb8(238, 240, 243),    // #eef0f3
+        surface_pressed: Color::from_rgb8(222, 226, 230),  // #dee2e6
+        surface_muted: Color::from_rgb8(245, 246, 248),    // #f5f6f8
+        surface_alt: Color::from_rgb8(250, 251, 253),      // #fafbfd
+        on_primary: Color::from_rgb8(34, 52, 40),          // #223428
+        on_surface: Color::from_rgb8(52, 58, 64),          // #343a40
+        on_surface_idle: Color::from_rgb8(77, 85, 92),     // #4d555c
+        on_surface_muted: Color::from_rgb8(130, 138, 145), // #828a91
+        on_surface_alt: Color::from_rgb8(95, 102, 110),    // #5f666e
+        accent: Color::from_rgb8(173, 231, 190),           // #ade7be
+        accent_hover: Color::from_rgb8(150, 210, 170),     // #96d2aa
+        accent_muted: Color::from_rgb8(200, 240, 210),     // #c8f0d2
+        success: Color::from_rgb8(76, 175, 80),            // #4caf50
+        warning: Color::from_rgb8(255, 193, 7),            // #ffc107
+        error: Color::from_rgb8(244, 67, 54),              // #f44336
+        divider: Color::from_rgb8(220, 224, 228),          // #dce0e4
+        divider_light: Color::from_rgb8(235, 238, 242),    // #ebeef2
+        shadow: Color::from_rgba(0.0, 0.0, 0.0, 0.08),
+        shadow_light: Color::from_rgba(0.0, 0.0, 0.0, 0.05),
+        shadow_heavy: Color::from_rgba(0.0, 0.0, 0.0, 0.2),
+        overlay: Color::from_rgba(0.0, 0.0, 0.0, 0.3),
+        overlay_light: Color::from_rgba(0.0, 0.0, 0.0, 0.08),
+        focus: Color::from_rgb8(173, 231, 190),    // #ade7be
+        disabled: Color::from_rgb8(200, 205, 210), // #c8cdd2
+    };
+
+    /// WCAG AA palette: text and badges reach at least 4.5:1 on the surfaces,
+    /// borders at least 3:1. Primary/secondary are dark so `on_primary` is white.
+    pub const HIGH_CONTRAST: Palette = Palette {
+        primary: Color::from_rgb8(27, 94, 32),            // #1b5e20
+        primary_hover: Color::from_rgb8(20, 75, 25),      // #144b19
+        primary_active: Color::from_rgb8(14, 60, 18),     // #0e3c12
+        primary_light: Color::from_rgb8(232, 245, 233),   // #e8f5e9
+        primary_muted: Color::from_rgb8(56, 118, 60),     // #38763c
+        secondary: Color::from_rgb8(0, 89, 107),          // #00596b
+        secondary_hover: Color::from_rgb8(0, 70, 85),     // #004655
+        secondary_active: Color::from_rgb8(0, 56, 68),    // #003844
+        secondary_soft: Color::from_rgb8(204, 236, 242),  // #ccecf2
+        surface: Color::from_rgb8(255, 255, 255),         // #ffffff
+        surface_hover: Color::from_rgb8(235, 235, 235),   // #ebebeb
+        surface_pressed: Color::from_rgb8(214, 214, 214), // #d6d6d6
+        surface_muted: Color::from_rgb8(245, 245, 245),   // #f5f5f5
+        surface_alt: Color::from_rgb8(255, 255, 255),     // #ffffff
+        on_primary: Color::from_rgb8(255, 255, 255),      // #ffffff
+        on_surface: Color::from_rgb8(0, 0, 0),            // #000000
+        on_surface_idle: Color::from_rgb8(26, 26, 26),    // #1a1a1a
+        on_surface_muted: Color::from_rgb8(64, 64, 64),   // #404040
+        on_surface_alt: Color::from_rgb8(45, 45, 45),     // #2d2d2d
+        accent: Color::from_rgb8(27, 94, 32),             // #1b5e20
+        accent_hover: Color::from_rgb8(20, 75, 25),       // #144b19
+        accent_muted: Color::from_rgb8(200, 230, 201),    // #c8e6c9
+        success: Color::from_rgb8(27, 110, 32),           // #1b6e20
+        warning: Color::from_rgb8(138, 80, 0),            // #8a5000
+        error: Color::from_rgb8(183, 28, 28),             // #b71c1c
+        divider: Color::from_rgb8(118, 118, 118),         // #767676
+        divider_light: Color::from_rgb8(150, 150, 150),   // #969696
+        shadow: Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+        shadow_light: Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+        shadow_heavy: Color::from_rgba(0.0, 0.0, 0.0, 0.3),
+        overlay: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+        overlay_light: Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+        focus: Color::from_rgb8(0, 89, 107),       // #00596b
+        disabled: Color::from_rgb8(118, 118, 118), // #767676
+    };
+
+    static HIGH_CONTRAST_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+    /// Switch the palette returned by [`palette`]; takes effect on the next redraw.
+    pub fn set_scheme(scheme: ColorScheme) {
+        HIGH_CONTRAST_ACTIVE.store(scheme == ColorScheme::HighContrast, Ordering::Relaxed);
+    }
+
+    /// Palette of the active color scheme.
+    pub fn palette() -> &'static Palette {
+        if HIGH_CONTRAST_ACTIVE.load(Ordering::Relaxed) {
+            &HIGH_CONTRAST
+        } else {
+            &STANDARD
+        }
+    }
+}
+
+pub mod density {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use updater_core::ListDensity;
 
-    // Primary - Light Green
-    pub const PRIMARY: Color = Color::from_rgb8(211, 249, 216); // #d3f9d8
-    pub const PRIMARY_HOVER: Color = Color::from_rgb8(196, 240, 204); // #c4f0cc
-    pub const PRIMARY_ACTIVE: Color = Color::from_rgb8(173, 231, 190); // #ade7be
-    pub const PRIMARY_LIGHT: Color = Color::from_rgb8(228, 252, 228); // #e4fce4
-    pub const PRIMARY_MUTED: Color = Color::from_rgb8(180, 220, 180); // #b4dcb4
-
-    // Secondary - Cyan Blue
-    pub const SECONDARY: Color = Color::from_rgb8(59, 201, 219); // #3bc9db
-    pub const SECONDARY_HOVER: Color = Color::from_rgb8(34, 184, 207); // #22b8cf
-    pub const SECONDARY_ACTIVE: Color = Color::from_rgb8(21, 170, 191); // #15aabf
-    pub const SECONDARY_SOFT: Color = Color::from_rgb8(150, 219, 230); // #96dbe6
-
-    // Surface
-    pub const SURFACE: Color = Color::from_rgb8(247, 248, 250); // #f7f8fa
-    pub const SURFACE_HOVER: Color = Color::from_rgb8(238, 240, 243); // #eef0f3
-    pub const SURFACE_PRESSED: Color = Color::from_rgb8(222, 226, 230); // #dee2e6
-    pub const SURFACE_MUTED: Color = Color::from_rgb8(245, 246, 248); // #f5f6f8
-    pub const SURFACE_ALT: Color = Color::from_rgb8(250, 251, 253); // #fafbfd
-
-    // Foreground
-    pub const ON_PRIMARY: Color = Color::from_rgb8(34, 52, 40); // #223428
-    pub const ON_SURFACE: Color = Color::from_rgb8(52, 58, 64); // #343a40
-    pub const ON_SURFACE_IDLE: Color = Color::from_rgb8(77, 85, 92); // #4d555c
-    pub const ON_SURFACE_MUTED: Color = Color::from_rgb8(130, 138, 145); // #828a91
-    pub const ON_SURFACE_ALT: Color = Color::from_rgb8(95, 102, 110); // #5f666e
-
-    // Accent
-    pub const ACCENT: Color = Color::from_rgb8(173, 231, 190); // #ade7be
-    pub const ACCENT_HOVER: Color = Color::from_rgb8(150, 210, 170); // #96d2aa
-    pub const ACCENT_MUTED: Color = Color::from_rgb8(200, 240, 210); // #c8f0d2
-
-    // Status
-    pub const SUCCESS: Color = Color::from_rgb8(76, 175, 80); // #4caf50
-    pub const WARNING: Color = Color::from_rgb8(255, 193, 7); // #ffc107
-    pub const ERROR: Color = Color::from_rgb8(244, 67, 54); // #f44336
-
-    // Helpers
-    pub const DIVIDER: Color = Color::from_rgb8(220, 224, 228); // #dce0e4
-    pub const DIVIDER_LIGHT: Color = Color::from_rgb8(235, 238, 242); // #ebeef2
-    pub const SHADOW: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.08);
-    pub const SHADOW_LIGHT: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.05);
-    pub const SHADOW_HEAVY: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.2);
-    pub const OVERLAY: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.3);
-    pub const OVERLAY_LIGHT: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.08);
-    pub const FOCUS: Color = Color::from_rgb8(173, 231, 190); // #ade7be
-    pub const DISABLED: Color = Color::from_rgb8(200, 205, 210); // #c8cdd2
+    /// Row padding and text sizes of package list rows.
+    pub struct Metrics {
+        pub row_padding: f32,
+        pub name_size: f32,
+        pub detail_size: f32,
+    }
+
+    const COMFORTABLE: Metrics = Metrics {
+        row_padding: 8.0,
+        name_size: 15.0,
+        detail_size: 12.0,
+    };
+
+    const COMPACT: Metrics = Metrics {
+        row_padding: 2.0,
+        name_size: 13.0,
+        detail_size: 11.0,
+    };
+
+    static COMPACT_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+    /// Switch the metrics returned by [`metrics`]; takes effect on the next redraw.
+    pub fn set_density(density: ListDensity) {
+        COMPACT_ACTIVE.store(density == ListDensity::Compact, Ordering::Relaxed);
+    }
+
+    /// Metrics of the active list density.
+    pub fn metrics() -> &'static Metrics {
+        if COMPACT_ACTIVE.load(Ordering::Relaxed) {
+            &COMPACT
+        } else {
+            &COMFORTABLE
+        }
+    }
 }
 
 /// Root GUI state for the updater application.
@@ -84,6 +226,10 @@ pub struct App {
     pub finding_info: FindingInfo,
     /// Status panel state.
     pub status_panel: StatusPanel,
+    /// Window whose close request waits for the quit confirmation.
+    pub pending_close: Option<iced::window::Id>,
+    /// Whether `pm_config` was loaded; UI preferences are only saved after.
+    pub config_loaded: bool,
 }
 
 /// Top-level application messages.
@@ -95,10 +241,12 @@ pub enum Message {
     Content(content::Message),
     /// Status panel message.
     StatusPanel(status_panel::Message),
-    /// Configuration load result.
-    ConfigLoaded(Result<updater_core::Config, updater_core::error::CoreError>),
+    /// Configuration load result, boxed since `Config` dwarfs the other variants.
+    ConfigLoaded(Box<Result<updater_core::Config, updater_core::error::CoreError>>),
     /// Installed initialization progress message.
     InitInstalledProgress {
+        /// Initialization run that produced it.
+        generation: u64,
         /// Completed manager count.
         completed: usize,
         /// Total manager count.
@@ -110,15 +258,22 @@ pub enum Message {
     },
     /// Installed count payload for one manager.
     InitInstalledCount {
+        /// Initialization run that produced it.
+        generation: u64,
         /// Source manager.
         manager: PackageManagerType,
         /// Installed package count value or failure detail.
         result: Result<usize, String>,
     },
     /// Installed initialization completion message.
-    InitInstalledFinished,
+    InitInstalledFinished {
+        /// Initialization run that finished.
+        generation: u64,
+    },
     /// Updates initialization progress message.
     InitUpdatesProgress {
+        /// Initialization run that produced it.
+        generation: u64,
         /// Completed manager count.
         completed: usize,
         /// Total manager count.
@@ -130,13 +285,24 @@ pub enum Message {
     },
     /// Updates payload for one manager.
     InitUpdatesCount {
+        /// Initialization run that produced it.
+        generation: u64,
         /// Source manager.
         manager: PackageManagerType,
         /// Update entries or failure detail.
         result: Result<Vec<PackageUpdate>, String>,
     },
     /// Updates initialization completion message.
-    InitUpdatesFinished,
+    InitUpdatesFinished {
+        /// Initialization run that finished.
+        generation: u64,
+    },
+    /// The user asked to close the window.
+    CloseRequested(iced::window::Id),
+    /// Quit even though an operation is running.
+    ConfirmQuit,
+    /// Dismiss the quit confirmation and keep running.
+    CancelQuit,
 }
 
 impl App {
@@ -152,18 +318,28 @@ impl App {
             updates_info: UpdatesInfo::default(),
             finding_info: FindingInfo::default(),
             status_panel: StatusPanel::new(now),
+            pending_close: None,
+            config_loaded: false,
         };
 
-        let task = Task::perform(updater_core::Config::load(), Message::ConfigLoaded);
+        let task = Task::perform(updater_core::Config::load(), |result| {
+            Message::ConfigLoaded(Box::new(result))
+        });
 
         (app, task)
     }
 
     /// Builds app subscriptions.
     pub fn subscription(&self) -> Subscription<Message> {
-        self.status_panel
-            .subscription(&self.installed_info, &self.updates_info, &self.finding_info)
-            .map(Message::StatusPanel)
+        Subscription::batch([
+            self.status_panel
+                .subscription(&self.installed_info, &self.updates_info, &self.finding_info)
+                .map(Message::StatusPanel),
+            iced::window::close_requests().map(Message::CloseRequested),
+            iced::keyboard::listen()
+                .filter_map(content::ListKey::from_event)
+                .map(|key| Message::Content(content::Message::ListKey(key))),
+        ])
     }
 
     /// Handles one app message and returns follow-up tasks.
@@ -173,11 +349,18 @@ impl App {
         let mut task = Task::none();
 
         match message {
-            Message::SideBar(sidebar_msg) => {
-                if let sidebar::Action::ChangeContent(content) = self.sidebar.update(sidebar_msg) {
-                    self.content.active_content = content;
+            Message::SideBar(sidebar_msg) => match self.sidebar.update(sidebar_msg) {
+                sidebar::Action::ChangeContent(content) => self.content.active_content = content,
+                sidebar::Action::RefreshAll => {
+                    updater_core::clear_api_cache();
+                    task = Task::batch(vec![
+                        self.reload_installed_counts(),
+                        self.reload_updates_counts(),
+                        Content::availability_task(&self.pm_config).map(Message::Content),
+                    ]);
                 }
-            }
+                sidebar::Action::None => {}
+            },
             Message::Content(content_msg) => {
                 let action = self.content.update(
                     content_msg,
@@ -189,11 +372,19 @@ impl App {
 
                 task = match action {
                     content::Action::Run(content_task) => content_task.map(Message::Content),
-                    content::Action::ReloadInstalledData => {
-                        self.installed_info.is_loading_count = true;
-                        self.installed_info.init_logs.clear();
-                        self.installed_info.init_errors.clear();
-                        self.start_init_installed_counts_task(self.pm_config.clone())
+                    content::Action::ReloadInstalledData => Task::batch(vec![
+                        self.reload_installed_counts(),
+                        Content::availability_task(&self.pm_config).map(Message::Content),
+                    ]),
+                    content::Action::OpenSettings => {
+                        // 经由侧边栏切换，保持选中标签同步
+                        if let sidebar::Action::ChangeContent(content) = self
+                            .sidebar
+                            .update(sidebar::Message::Select(sidebar::Tab::Settings))
+                        {
+                            self.content.active_content = content;
+                        }
+                        Task::none()
                     }
                     content::Action::None => Task::none(),
                 };
@@ -207,19 +398,18 @@ impl App {
                 );
             }
             Message::ConfigLoaded(result) => {
-                task = match result {
+                task = match *result {
                     Ok(config) => {
+                        colors::set_scheme(config.color_scheme);
+                        density::set_density(config.list_density);
+                        self.apply_ui_preferences(&config.ui_preferences);
                         self.pm_config = config;
-                        self.installed_info.is_loading_count = true;
-                        self.updates_info.is_loading_count = true;
-                        self.installed_info.init_logs.clear();
-                        self.updates_info.init_logs.clear();
-                        self.installed_info.init_errors.clear();
-                        self.updates_info.init_errors.clear();
+                        self.config_loaded = true;
 
                         Task::batch(vec![
-                            self.start_init_installed_counts_task(self.pm_config.clone()),
-                            self.start_init_updates_counts_task(self.pm_config.clone()),
+                            self.reload_installed_counts(),
+                            self.reload_updates_counts(),
+                            Content::availability_task(&self.pm_config).map(Message::Content),
                         ])
                     }
                     Err(e) => {
@@ -228,26 +418,60 @@ impl App {
                     }
                 };
             }
+            // 重新初始化后，旧一轮仍在运行的任务结果直接丢弃
+            Message::InitInstalledProgress { generation, .. }
+            | Message::InitInstalledCount { generation, .. }
+            | Message::InitInstalledFinished { generation }
+                if generation != self.installed_info.init_generation => {}
+            Message::InitUpdatesProgress { generation, .. }
+            | Message::InitUpdatesCount { generation, .. }
+            | Message::InitUpdatesFinished { generation }
+                if generation != self.updates_info.init_generation => {}
             Message::InitInstalledProgress {
                 completed,
                 total,
                 manager,
                 command_message,
+                ..
             } => self.apply_init_installed_progress(completed, total, manager, command_message),
-            Message::InitInstalledCount { manager, result } => {
-                self.apply_init_installed_count(manager, result)
+            Message::InitInstalledCount {
+                manager, result, ..
+            } => self.apply_init_installed_count(manager, result),
+            Message::InitInstalledFinished { .. } => {
+                self.finish_init_installed_counts();
+                task = Content::load_selected_installed(&self.installed_info).map(Message::Content);
             }
-            Message::InitInstalledFinished => self.finish_init_installed_counts(),
             Message::InitUpdatesProgress {
                 completed,
                 total,
                 manager,
                 command_message,
+                ..
             } => self.apply_init_updates_progress(completed, total, manager, command_message),
-            Message::InitUpdatesCount { manager, result } => {
-                self.apply_init_updates_count(manager, result)
+            Message::InitUpdatesCount {
+                manager, result, ..
+            } => self.apply_init_updates_count(manager, result),
+            Message::InitUpdatesFinished { .. } => self.finish_init_updates_counts(),
+            Message::CloseRequested(id) => {
+                if self.pm_config.confirm_close_during_operation
+                    && !self.background_operations().is_empty()
+                {
+                    self.pending_close = Some(id);
+                } else {
+                    task = iced::exit();
+                }
             }
-            Message::InitUpdatesFinished => self.finish_init_updates_counts(),
+            Message::ConfirmQuit => task = iced::exit(),
+            Message::CancelQuit => self.pending_close = None,
+        }
+
+        // 操作已结束时收起确认横幅，再次关闭即可直接退出
+        if self.pending_close.is_some() && self.background_operations().is_empty() {
+            self.pending_close = None;
+        }
+
+        if self.config_loaded && !is_status_panel_message {
+            task = Task::batch([task, self.save_ui_preferences_if_changed()]);
         }
 
         if !is_status_panel_message {
@@ -268,25 +492,34 @@ impl App {
             widget::{column, container, row},
         };
 
-        let sidebar = container(self.sidebar.view().map(Message::SideBar))
-            .padding(16)
-            .width(Length::Fixed(220.0))
-            .height(Length::Fill)
-            .style(|_theme: &iced::Theme| container::Style {
-                background: Some(colors::SURFACE_ALT.into()),
-                border: Border {
-                    color: colors::DIVIDER_LIGHT,
-                    width: 0.0,
-                    radius: 0.0.into(),
-                },
-                text_color: None,
-                shadow: Shadow {
-                    color: colors::SHADOW_LIGHT,
-                    offset: Vector::new(2.0, 0.0),
-                    blur_radius: 8.0,
-                },
-                snap: false,
-            });
+        let operations = self.background_operations();
+        let sidebar = container(
+            self.sidebar
+                .view(
+                    self.pm_config.language,
+                    &operations,
+                    self.installed_info.is_loading_count || self.updates_info.is_loading_count,
+                )
+                .map(Message::SideBar),
+        )
+        .padding(16)
+        .width(Length::Fixed(220.0))
+        .height(Length::Fill)
+        .style(|_theme: &iced::Theme| container::Style {
+            background: Some(colors::palette().surface_alt.into()),
+            border: Border {
+                color: colors::palette().divider_light,
+                width: 0.0,
+                radius: 0.0.into(),
+            },
+            text_color: None,
+            shadow: Shadow {
+                color: colors::palette().shadow_light,
+                offset: Vector::new(2.0, 0.0),
+                blur_radius: 8.0,
+            },
+            snap: false,
+        });
 
         let content_area = container(
             self.content
@@ -318,22 +551,144 @@ impl App {
         .width(Length::Fill)
         .height(Length::Fill)
         .style(|_theme: &iced::Theme| container::Style {
-            background: Some(colors::SURFACE_MUTED.into()),
+            background: Some(colors::palette().surface_muted.into()),
             border: Border::default(),
             text_color: None,
             shadow: Shadow::default(),
             snap: false,
         });
 
+        let quit_confirmation = self
+            .pending_close
+            .is_some()
+            .then(|| self.quit_confirmation_view(&operations));
+
         column![
+            quit_confirmation,
             top_layout,
-            self.status_panel.view().map(Message::StatusPanel)
+            self.status_panel
+                .view(self.pm_config.language)
+                .map(Message::StatusPanel)
         ]
         .width(Length::Fill)
         .height(Length::Fill)
         .into()
     }
 
+    /// Banner asking whether to quit while `operations` are still running.
+    fn quit_confirmation_view(
+        &self,
+        operations: &[BackgroundOperation],
+    ) -> iced::Element<'_, Message> {
+        use iced::{
+            Alignment, Border,
+            widget::{button, container, row, text},
+        };
+
+        let lang = self.pm_config.language;
+        let running = operations
+            .iter()
+            .map(|operation| operation.label.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let button_style = |primary: bool| {
+            move |_theme: &iced::Theme, status: button::Status| {
+                let hovered = matches!(status, button::Status::Hovered);
+                let (background, text_color) = match (primary, hovered) {
+                    (true, false) => (colors::palette().error, colors::palette().on_primary),
+                    (true, true) => (colors::palette().warning, colors::palette().on_primary),
+                    (false, false) => (colors::palette().surface, colors::palette().on_surface),
+                    (false, true) => (
+                        colors::palette().surface_hover,
+                        colors::palette().on_surface,
+                    ),
+                };
+
+                button::Style {
+                    background: Some(background.into()),
+                    text_color,
+                    border: Border {
+                        color: colors::palette().divider,
+                        width: if primary { 0.0 } else { 1.0 },
+                        radius: 6.0.into(),
+                    },
+                    ..Default::default()
+                }
+            }
+        };
+
+        container(
+            row![
+                text(tr_args(lang, Key::QuitWhileRunning, &[&running]))
+                    .size(15)
+                    .color(colors::palette().on_surface)
+                    .width(Length::Fill),
+                button(text(tr(lang, Key::KeepRunning)).size(14))
+                    .padding([8, 16])
+                    .on_press(Message::CancelQuit)
+                    .style(button_style(false)),
+                button(text(tr(lang, Key::QuitAnyway)).size(14))
+                    .padding([8, 16])
+                    .on_press(Message::ConfirmQuit)
+                    .style(button_style(true)),
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+        )
+        .padding([10, 16])
+        .width(Length::Fill)
+        .style(|_theme: &iced::Theme| container::Style {
+            background: Some(colors::palette().surface_alt.into()),
+            border: Border {
+                color: colors::palette().warning,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+
+    /// Mutating operations still running. Their results are routed by page,
+    /// not by the active tab, so they land even after navigating away.
+    fn background_operations(&self) -> Vec<BackgroundOperation> {
+        let running = [
+            (
+                sidebar::Tab::Finding,
+                "Installing",
+                self.finding_info.is_installing,
+                &self.finding_info.install_progress,
+            ),
+            (
+                sidebar::Tab::Updates,
+                "Updating",
+                self.updates_info.is_updating,
+                &self.updates_info.update_progress,
+            ),
+            (
+                sidebar::Tab::Installed,
+                "Removing",
+                self.installed_info.is_removing,
+                &self.installed_info.remove_progress,
+            ),
+        ];
+
+        running
+            .into_iter()
+            .filter(|(_, _, active, _)| *active)
+            .map(|(tab, verb, _, progress)| BackgroundOperation {
+                tab,
+                label: match progress {
+                    Some((completed, total, _, package)) => {
+                        format!("{} {}/{} · {}", verb, completed, total, package)
+                    }
+                    None => format!("{}...", verb),
+                },
+            })
+            .collect()
+    }
+
     fn apply_init_installed_count(
         &mut self,
         manager: PackageManagerType,
@@ -459,9 +814,68 @@ impl App {
             .collect()
     }
 
+    /// Restore the selections, sort orders and tab saved last time.
+    fn apply_ui_preferences(&mut self, preferences: &UiPreferences) {
+        Content::apply_ui_preferences(
+            preferences,
+            &mut self.installed_info,
+            &mut self.updates_info,
+            &mut self.finding_info,
+        );
+
+        if let Some(tab) = preferences
+            .last_tab
+            .as_deref()
+            .and_then(sidebar::Tab::from_key)
+            && let sidebar::Action::ChangeContent(content) =
+                self.sidebar.update(sidebar::Message::Select(tab))
+        {
+            self.content.active_content = content;
+        }
+    }
+
+    /// Save selections, sort orders and the active tab when they changed.
+    fn save_ui_preferences_if_changed(&mut self) -> Task<Message> {
+        let preferences = Content::ui_preferences(
+            &self.installed_info,
+            &self.updates_info,
+            &self.finding_info,
+            self.sidebar.active_tab.key(),
+        );
+        if preferences == self.pm_config.ui_preferences {
+            return Task::none();
+        }
+
+        self.pm_config.ui_preferences = preferences.clone();
+        Task::future(async move {
+            if let Err(e) = updater_core::Config::save_ui_preferences(preferences).await {
+                log::warn!("Failed to save UI preferences: {}", e);
+            }
+        })
+        .discard()
+    }
+
+    /// Clear the previous run's logs and errors, then reload installed counts.
+    fn reload_installed_counts(&mut self) -> Task<Message> {
+        self.installed_info.is_loading_count = true;
+        self.installed_info.init_logs.clear();
+        self.installed_info.init_errors.clear();
+        self.start_init_installed_counts_task(self.pm_config.clone())
+    }
+
+    /// Clear the previous run's logs and errors, then reload update counts.
+    fn reload_updates_counts(&mut self) -> Task<Message> {
+        self.updates_info.is_loading_count = true;
+        self.updates_info.init_logs.clear();
+        self.updates_info.init_errors.clear();
+        self.start_init_updates_counts_task(self.pm_config.clone())
+    }
+
     fn start_init_installed_counts_task(&mut self, config: updater_core::Config) -> Task<Message> {
         let managers = Self::configured_managers(&config);
         let manager_set: HashSet<_> = managers.iter().copied().collect();
+        self.installed_info.init_generation += 1;
+        let generation = self.installed_info.init_generation;
         self.installed_info
             .installed_packages
             .retain(|pm_type, _| manager_set.contains(pm_type));
@@ -491,14 +905,19 @@ impl App {
                 work: |pm: PackageManagerType, config| async move {
                     pm.count_installed(&config).await.map_err(|e| e.to_string())
                 },
-                item_message: |manager, result| Message::InitInstalledCount { manager, result },
-                progress_message: |progress: InitProgress| Message::InitInstalledProgress {
+                item_message: move |manager, result| Message::InitInstalledCount {
+                    generation,
+                    manager,
+                    result,
+                },
+                progress_message: move |progress: InitProgress| Message::InitInstalledProgress {
+                    generation,
                     completed: progress.completed,
                     total: progress.total,
                     manager: progress.manager,
                     command_message: progress.command_message,
                 },
-                done_message: || Message::InitInstalledFinished,
+                done_message: move || Message::InitInstalledFinished { generation },
             },
         )
     }
@@ -506,6 +925,8 @@ impl App {
     fn start_init_updates_counts_task(&mut self, config: updater_core::Config) -> Task<Message> {
         let managers = Self::configured_managers(&config);
         let manager_set: HashSet<_> = managers.iter().copied().collect();
+        self.updates_info.init_generation += 1;
+        let generation = self.updates_info.init_generation;
         self.updates_info
             .updates_by_manager
             .retain(|pm_type, _| manager_set.contains(pm_type));
@@ -540,14 +961,19 @@ impl App {
                         .await
                         .map_err(|e| e.to_string())
                 },
-                item_message: |manager, result| Message::InitUpdatesCount { manager, result },
-                progress_message: |progress: InitProgress| Message::InitUpdatesProgress {
+                item_message: move |manager, result| Message::InitUpdatesCount {
+                    generation,
+                    manager,
+                    result,
+                },
+                progress_message: move |progress: InitProgress| Message::InitUpdatesProgress {
+                    generation,
                     completed: progress.completed,
                     total: progress.total,
                     manager: progress.manager,
                     command_message: progress.command_message,
                 },
-                done_message: || Message::InitUpdatesFinished,
+                done_message: move || Message::InitUpdatesFinished { generation },
             },
         )
     }
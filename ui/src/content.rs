@@ -6,10 +6,16 @@ mod shared;
 mod updates;
 mod workflows;
 
+use std::collections::HashSet;
+
+use serde::{Serialize, de::DeserializeOwned};
+use updater_core::{PackageManagerType, PagePreferences, UiPreferences};
+
 use crate::content::{finding::Finding, installed::Installed, setting::Settings, updates::Updates};
 
 pub use finding::FindingInfo;
 pub use installed::InstalledInfo;
+pub use shared::{ListKey, SharedUi};
 pub use updates::UpdatesInfo;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -37,6 +43,8 @@ pub struct Content {
     pub updates: Updates,
     /// Finding page state.
     pub finding: Finding,
+    /// Configured managers whose binary was not found on the last check.
+    pub unavailable_managers: HashSet<PackageManagerType>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +57,10 @@ pub enum Message {
     Updates(updates::Message),
     /// Finding page message.
     Finding(finding::Message),
+    /// Keyboard navigation of the active page's package list.
+    ListKey(ListKey),
+    /// Result of `availability_task`.
+    AvailabilityChecked(HashSet<PackageManagerType>),
 }
 
 pub enum Action {
@@ -58,9 +70,96 @@ pub enum Action {
     Run(iced::Task<Message>),
     /// Installed-data reload request action.
     ReloadInstalledData,
+    /// Settings page switch request action.
+    OpenSettings,
 }
 
 impl Content {
+    /// Restore the manager selections and sort orders of `preferences`.
+    pub fn apply_ui_preferences(
+        preferences: &UiPreferences,
+        installed_info: &mut InstalledInfo,
+        updates_info: &mut UpdatesInfo,
+        finding_info: &mut FindingInfo,
+    ) {
+        installed_info.selected_managers = preferences.installed.selected_managers.clone();
+        updates_info.selected_managers = preferences.updates.selected_managers.clone();
+        finding_info.selected_managers = preferences.finding.selected_managers.clone();
+
+        let installed_sort = preferences.installed.sort_by.as_deref();
+        if let Some(sort_by) = installed_sort.and_then(|key| {
+            sort_option_from_key(key).or_else(|| installed::SortOption::from_name(key))
+        }) {
+            installed_info.sort_by = sort_by;
+        }
+        let updates_sort = preferences.updates.sort_by.as_deref();
+        if let Some(sort_by) = updates_sort.and_then(|key| {
+            sort_option_from_key(key).or_else(|| updates::SortOption::from_name(key))
+        }) {
+            updates_info.sort_by = sort_by;
+        }
+        let finding_sort = preferences.finding.sort_by.as_deref();
+        if let Some(sort_by) = finding_sort.and_then(|key| {
+            sort_option_from_key(key).or_else(|| finding::SortOption::from_name(key))
+        }) {
+            finding_info.sort_by = sort_by;
+        }
+    }
+
+    /// Current manager selections and sort orders, as
+    /// `Config::ui_preferences` stores them.
+    pub fn ui_preferences(
+        installed_info: &InstalledInfo,
+        updates_info: &UpdatesInfo,
+        finding_info: &FindingInfo,
+        last_tab: &str,
+    ) -> UiPreferences {
+        UiPreferences {
+            installed: PagePreferences {
+                selected_managers: installed_info.selected_managers.clone(),
+                sort_by: sort_option_key(installed_info.sort_by),
+            },
+            updates: PagePreferences {
+                selected_managers: updates_info.selected_managers.clone(),
+                sort_by: sort_option_key(updates_info.sort_by),
+            },
+            finding: PagePreferences {
+                selected_managers: finding_info.selected_managers.clone(),
+                sort_by: sort_option_key(finding_info.sort_by),
+            },
+            last_tab: Some(last_tab.to_owned()),
+        }
+    }
+
+    /// Load the lists of Installed managers selected before their counts
+    /// arrived, e.g. restored from `Config::ui_preferences`.
+    pub fn load_selected_installed(installed_info: &InstalledInfo) -> iced::Task<Message> {
+        iced::Task::batch(installed_info.selected_managers.iter().map(|pm_type| {
+            iced::Task::done(Message::Installed(
+                installed::Message::SelectPackageManager(*pm_type, true),
+            ))
+        }))
+    }
+
+    /// Check which configured managers are missing their binary.
+    pub fn availability_task(pm_config: &updater_core::Config) -> iced::Task<Message> {
+        let pm_config = pm_config.clone();
+        iced::Task::perform(
+            async move { pm_config.unavailable_managers().await },
+            Message::AvailabilityChecked,
+        )
+    }
+
+    /// Run `action` and re-check availability alongside it.
+    fn with_availability_check(action: Action, pm_config: &updater_core::Config) -> Action {
+        let check = Self::availability_task(pm_config);
+        match action {
+            Action::None => Action::Run(check),
+            Action::Run(task) => Action::Run(iced::Task::batch([task, check])),
+            action => action,
+        }
+    }
+
     pub fn update(
         &mut self,
         message: Message,
@@ -72,11 +171,25 @@ impl Content {
         let pm_config_ref: &updater_core::Config = pm_config;
 
         match message {
+            Message::AvailabilityChecked(unavailable) => {
+                for pm_type in &unavailable {
+                    installed_info.selected_managers.remove(pm_type);
+                    updates_info.selected_managers.remove(pm_type);
+                    finding_info.selected_managers.remove(pm_type);
+                }
+                self.unavailable_managers = unavailable;
+                Action::None
+            }
             Message::Settings(settings_msg) => {
-                let action = self.settings.update(settings_msg, pm_config);
-                match action {
+                let is_saved = matches!(settings_msg, setting::Message::SaveConfigResult(Ok(())));
+                let action = match self.settings.update(settings_msg, pm_config) {
                     setting::Action::Run(task) => Action::Run(task.map(Message::Settings)),
                     setting::Action::None => Action::None,
+                };
+                if is_saved {
+                    Self::with_availability_check(action, pm_config)
+                } else {
+                    action
                 }
             }
             Message::Installed(installed_msg) => {
@@ -87,17 +200,72 @@ impl Content {
                     installed::Action::Run(task) => Action::Run(task.map(Message::Installed)),
                     installed::Action::None => Action::None,
                     installed::Action::ClearCacheAndReload => Action::ReloadInstalledData,
+                    installed::Action::OpenSettings => Action::OpenSettings,
                 }
             }
             Message::Updates(updates_msg) => {
+                // A manager whose binary vanished since the last check is
+                // marked unavailable, offering the Settings fix
+                let recheck_availability = matches!(
+                    updates_msg,
+                    updates::Message::RefreshAll
+                        | updates::Message::RefreshSelected
+                        | updates::Message::LoadUpdatesResult(
+                            _,
+                            _,
+                            Err(updater_core::error::CoreError::ExecutableNotFound(_))
+                        )
+                );
                 let action = self
                     .updates
                     .update(updates_msg, pm_config_ref, updates_info);
-                match action {
+                let action = match action {
                     updates::Action::Run(task) => Action::Run(task.map(Message::Updates)),
                     updates::Action::None => Action::None,
+                    updates::Action::OpenSettings => Action::OpenSettings,
+                    updates::Action::SkipVersion(package_name, version) => {
+                        pm_config
+                            .skipped_versions
+                            .insert(package_name.clone(), version.clone());
+                        Action::Run(
+                            iced::Task::perform(
+                                async move {
+                                    updater_core::Config::save_skipped_version(
+                                        &package_name,
+                                        &version,
+                                    )
+                                    .await
+                                    .map_err(|e| e.to_string())
+                                },
+                                updates::Message::SkipVersionSaved,
+                            )
+                            .map(Message::Updates),
+                        )
+                    }
+                };
+                if recheck_availability {
+                    Self::with_availability_check(action, pm_config)
+                } else {
+                    action
                 }
             }
+            Message::ListKey(key) => match self.active_content {
+                ActiveContentPage::Installed => self.update(
+                    Message::Installed(installed::Message::ListKey(key)),
+                    pm_config,
+                    installed_info,
+                    updates_info,
+                    finding_info,
+                ),
+                ActiveContentPage::Updates => self.update(
+                    Message::Updates(updates::Message::ListKey(key)),
+                    pm_config,
+                    installed_info,
+                    updates_info,
+                    finding_info,
+                ),
+                ActiveContentPage::Finding | ActiveContentPage::Settings => Action::None,
+            },
             Message::Finding(finding_msg) => {
                 let action = self
                     .finding
@@ -105,13 +273,14 @@ impl Content {
                 match action {
                     finding::Action::Run(task) => Action::Run(task.map(Message::Finding)),
                     finding::Action::None => Action::None,
+                    finding::Action::OpenSettings => Action::OpenSettings,
                 }
             }
         }
     }
 
     pub fn view<'a>(
-        &self,
+        &'a self,
         pm_config: &updater_core::Config,
         installed_info: &'a InstalledInfo,
         updates_info: &'a UpdatesInfo,
@@ -120,17 +289,73 @@ impl Content {
         match self.active_content {
             ActiveContentPage::Finding => self
                 .finding
-                .view(finding_info, pm_config)
+                .view(finding_info, pm_config, &self.unavailable_managers)
                 .map(Message::Finding),
             ActiveContentPage::Updates => self
                 .updates
-                .view(updates_info, pm_config)
+                .view(updates_info, pm_config, &self.unavailable_managers)
                 .map(Message::Updates),
             ActiveContentPage::Installed => self
                 .installed
-                .view(installed_info, pm_config)
+                .view(installed_info, pm_config, &self.unavailable_managers)
                 .map(Message::Installed),
             ActiveContentPage::Settings => self.settings.view(pm_config).map(Message::Settings),
         }
     }
 }
+
+/// Stable key a sort option is saved under in `Config::ui_preferences`: its
+/// snake_case variant name, which unlike the label survives renames and
+/// translation.
+fn sort_option_key(option: impl Serialize) -> Option<String> {
+    match serde_json::to_value(option) {
+        Ok(serde_json::Value::String(key)) => Some(key),
+        _ => None,
+    }
+}
+
+/// Sort option saved under `key` by `sort_option_key`.
+fn sort_option_from_key<T: DeserializeOwned>(key: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(key.to_owned())).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_options_are_saved_by_key_and_old_labels_still_load() {
+        let preferences = Content::ui_preferences(
+            &InstalledInfo {
+                sort_by: installed::SortOption::InstallDate,
+                ..InstalledInfo::default()
+            },
+            &UpdatesInfo::default(),
+            &FindingInfo::default(),
+            "installed",
+        );
+        assert_eq!(
+            preferences.installed.sort_by.as_deref(),
+            Some("install_date")
+        );
+
+        let mut installed_info = InstalledInfo::default();
+        let mut updates_info = UpdatesInfo::default();
+        let mut finding_info = FindingInfo::default();
+        let legacy = UiPreferences {
+            updates: PagePreferences {
+                sort_by: Some("New Version".to_owned()),
+                ..PagePreferences::default()
+            },
+            ..preferences
+        };
+        Content::apply_ui_preferences(
+            &legacy,
+            &mut installed_info,
+            &mut updates_info,
+            &mut finding_info,
+        );
+        assert_eq!(installed_info.sort_by, installed::SortOption::InstallDate);
+        assert_eq!(updates_info.sort_by, updates::SortOption::NewVersion);
+    }
+}
@@ -3,24 +3,43 @@
 use std::collections::{HashMap, HashSet};
 
 use iced::{Border, Task};
-use updater_core::{PackageInfo, PackageManagerType};
+use serde::{Deserialize, Serialize};
+use updater_core::{
+    CancelToken, InstallPreview, PackageInfo, PackageManagerType, SearchResults,
+    i18n::{Key, Lang, tr},
+};
 
 use crate::{
     app,
     content::errors::{ManagerErrors, apply_manager_items_result},
     content::shared::{PackageSelectionKey, SharedUi},
     content::workflows::{
-        BatchProgress, PackageBatchAction, collect_selected_package_groups, push_command_log,
-        run_grouped_package_action,
+        BatchProgress, CommandPreview, OperationError, OperationOutput, PackageBatchAction,
+        PackageOperation, collect_selected_package_groups, preview_package_operation,
+        push_command_log, run_grouped_package_action, save_install_script,
+        selection_install_script,
     },
 };
 
+/// Packages about to be installed that other managers already have,
+/// `(package, target manager, managers it is installed in)`.
+type InstalledElsewhere = Vec<(String, PackageManagerType, Vec<PackageManagerType>)>;
+
+/// Per-manager dry resolves of the packages about to be installed; only
+/// those that pull in more packages are kept.
+type InstallPreviews = Vec<(PackageManagerType, InstallPreview)>;
+
+/// Dependencies listed by name in the install preview before "and N more".
+const PREVIEW_NAME_LIMIT: usize = 8;
+
 #[derive(Debug, Clone, Default)]
 pub struct Finding {
     /// Search query being edited by user.
     search_query: String,
     /// Last executed query used for post-install refresh.
     last_search_query: String,
+    /// Last install-script export status line.
+    script_status: Option<Result<String, String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,13 +51,24 @@ pub enum Message {
     /// Search execution message.
     ExecuteSearch,
     /// Search result message.
-    SearchResult(PackageManagerType, Result<Vec<PackageInfo>, String>),
+    SearchResult(PackageManagerType, Result<SearchResults, String>),
+    /// Re-run the last search for a manager that failed.
+    RetrySearch(PackageManagerType),
     /// Sort-option change message.
     SortOptionChanged(SortOption),
+    /// Result-grouping change message.
+    GroupByChanged(GroupBy),
     /// Package-selection toggle message.
     TogglePackageSelection(PackageManagerType, String, bool),
     /// Install-selected message.
     InstallSelectedPackages,
+    /// Result of checking other managers and resolving dependencies for the
+    /// packages to install.
+    InstallChecked(PackageOperation, InstalledElsewhere, InstallPreviews),
+    /// Install after reviewing other managers' copies and extra dependencies.
+    ConfirmInstall,
+    /// Drop the install waiting for confirmation.
+    CancelInstall,
     /// Install progress message.
     InstallProgress {
         /// Number of finished packages.
@@ -53,13 +83,35 @@ pub enum Message {
         command_message: Option<String>,
     },
     /// Install result message.
-    InstallPackagesResult(Result<(), String>),
+    InstallPackagesResult(Result<(), OperationError>),
+    /// Re-run the last failed install.
+    RetryInstall,
+    /// Stop the running install.
+    CancelOperation,
+    /// Commands listed for the install (see `Config::dry_run`).
+    CommandPreviewResult(Result<CommandPreview, String>),
+    /// Run the previewed commands.
+    ConfirmPreview,
+    /// Drop the previewed install without running it.
+    CancelPreview,
+    /// Expand or collapse the captured command output.
+    ToggleOutput,
+    /// Copy install commands for the selection to the clipboard.
+    CopyInstallScript,
+    /// Save install commands for the selection as a shell script.
+    SaveInstallScript,
+    /// Script save result, `None` when the dialog was cancelled.
+    InstallScriptSaved(Result<Option<String>, String>),
+    /// Open Settings to fix an unavailable manager's path.
+    OpenSettings,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct FindingInfo {
     /// Search results grouped by manager.
     pub search_results: HashMap<PackageManagerType, Vec<PackageInfo>>,
+    /// Total matches reported by each manager before the result limit.
+    pub search_totals: HashMap<PackageManagerType, usize>,
     /// Search errors grouped by manager.
     pub search_errors: ManagerErrors,
     /// Managers selected in the filter panel.
@@ -68,14 +120,58 @@ pub struct FindingInfo {
     pub searching_managers: HashSet<PackageManagerType>,
     /// Current sort option.
     pub sort_by: SortOption,
+    /// Whether results are shown per manager or as one merged list.
+    pub group_by: GroupBy,
     /// Selected package keys for batch operations.
     pub selected_packages: HashSet<PackageSelectionKey>,
     /// Whether install operation is in progress.
     pub is_installing: bool,
+    /// Whether other managers are being checked before installing.
+    pub is_checking_install: bool,
+    /// Install waiting for confirmation because some packages are already
+    /// installed through other managers or pull in dependencies.
+    pub pending_install: Option<(PackageOperation, InstalledElsewhere, InstallPreviews)>,
     /// Install progress `(completed, total, manager, package)`.
     pub install_progress: Option<(usize, usize, PackageManagerType, String)>,
     /// Install command logs.
     pub install_logs: Vec<String>,
+    /// Captured output of the last install run.
+    pub install_output: OperationOutput,
+    /// Cancels the running install; `None` when idle.
+    pub install_cancel: Option<CancelToken>,
+    /// Install whose commands are waiting for confirmation (see `Config::dry_run`).
+    pub command_preview: Option<CommandPreview>,
+    /// Last install error shown in UI.
+    pub last_install_error: Option<OperationError>,
+    /// Last dispatched install, kept for retry after a failure.
+    pub last_install_operation: Option<PackageOperation>,
+}
+
+/// What a finished search produced for one manager.
+pub enum SearchOutcome<'a> {
+    /// The manager has not reported back (or was not searched).
+    Pending,
+    /// The search ran and matched nothing.
+    Empty,
+    /// The search matched at least one package.
+    Found(&'a [PackageInfo]),
+    /// The search itself failed.
+    Failed(&'a str),
+}
+
+impl FindingInfo {
+    /// Outcome of the last search in `pm_type`, keeping a failure apart
+    /// from a search that simply found nothing.
+    pub fn search_outcome(&self, pm_type: PackageManagerType) -> SearchOutcome<'_> {
+        if let Some(error) = self.search_errors.get(&pm_type) {
+            return SearchOutcome::Failed(error);
+        }
+        match self.search_results.get(&pm_type) {
+            Some(packages) if packages.is_empty() => SearchOutcome::Empty,
+            Some(packages) => SearchOutcome::Found(packages),
+            None => SearchOutcome::Pending,
+        }
+    }
 }
 
 pub enum Action {
@@ -83,13 +179,17 @@ pub enum Action {
     None,
     /// Asynchronous task action.
     Run(iced::Task<Message>),
+    /// Settings page switch request action.
+    OpenSettings,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortOption {
     Name,
     #[default]
     Relevance,
+    Downloads,
 }
 
 impl SortOption {
@@ -97,10 +197,42 @@ impl SortOption {
         match self {
             SortOption::Name => "Name",
             SortOption::Relevance => "Relevance",
+            SortOption::Downloads => "Downloads",
+        }
+    }
+
+    /// Option whose `name` is `name`, as saved in `Config::ui_preferences`
+    /// before options were saved by key.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|option| option.name() == name)
+    }
+
+    pub const ALL: [SortOption; 3] = [
+        SortOption::Name,
+        SortOption::Relevance,
+        SortOption::Downloads,
+    ];
+}
+
+/// Layout of the search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// One section per manager.
+    #[default]
+    Manager,
+    /// One list across all managers, each row tagged with its manager.
+    Flat,
+}
+
+impl GroupBy {
+    pub fn name(&self, lang: Lang) -> &'static str {
+        match self {
+            GroupBy::Manager => tr(lang, Key::GroupByManager),
+            GroupBy::Flat => tr(lang, Key::GroupByFlat),
         }
     }
 
-    pub const ALL: [SortOption; 2] = [SortOption::Name, SortOption::Relevance];
+    pub const ALL: [GroupBy; 2] = [GroupBy::Manager, GroupBy::Flat];
 }
 
 impl Finding {
@@ -111,6 +243,7 @@ impl Finding {
         info: &mut FindingInfo,
     ) -> Action {
         match message {
+            Message::OpenSettings => Action::OpenSettings,
             Message::SelectPackageManager(pm_type, selected) => {
                 if selected {
                     info.selected_managers.insert(pm_type);
@@ -121,6 +254,7 @@ impl Finding {
                     info.selected_packages
                         .retain(|(manager, _)| *manager != pm_type);
                     info.search_results.remove(&pm_type);
+                    info.search_totals.remove(&pm_type);
                 }
                 Action::None
             }
@@ -141,6 +275,7 @@ impl Finding {
 
                 // Clear previous results before running a new search.
                 info.search_results.clear();
+                info.search_totals.clear();
                 info.selected_packages.clear();
                 info.searching_managers.clear();
                 info.search_errors.clear();
@@ -155,6 +290,10 @@ impl Finding {
             }
             Message::SearchResult(pm_type, result) => {
                 info.searching_managers.remove(&pm_type);
+                let result = result.map(|SearchResults { packages, total }| {
+                    info.search_totals.insert(pm_type, total);
+                    packages
+                });
                 apply_manager_items_result(
                     &mut info.search_results,
                     &mut info.search_errors,
@@ -163,12 +302,32 @@ impl Finding {
                 );
                 Action::None
             }
+            Message::RetrySearch(pm_type) => {
+                if self.last_search_query.is_empty() || info.searching_managers.contains(&pm_type) {
+                    return Action::None;
+                }
+
+                info.search_errors.remove(&pm_type);
+                info.searching_managers.insert(pm_type);
+                Self::execute_search_action(
+                    pm_config,
+                    &HashSet::from([pm_type]),
+                    &self.last_search_query,
+                )
+            }
             Message::SortOptionChanged(sort_option) => {
                 info.sort_by = sort_option;
                 Action::None
             }
+            Message::GroupByChanged(group_by) => {
+                info.group_by = group_by;
+                Action::None
+            }
             Message::TogglePackageSelection(pm_type, package_name, selected) => {
                 let key = SharedUi::selection_key(pm_type, &package_name);
+                if selected && pm_config.is_read_only(pm_type) {
+                    return Action::None;
+                }
                 if selected {
                     info.selected_packages.insert(key);
                 } else {
@@ -177,24 +336,67 @@ impl Finding {
                 Action::None
             }
             Message::InstallSelectedPackages => {
-                if info.selected_packages.is_empty() {
+                if info.selected_packages.is_empty()
+                    || info.is_installing
+                    || info.is_checking_install
+                {
                     return Action::None;
                 }
-                info.is_installing = true;
-                info.install_logs.clear();
-                let initial_manager = info
-                    .selected_packages
-                    .iter()
-                    .next()
-                    .map(|(pm_type, _)| *pm_type)
-                    .unwrap_or(PackageManagerType::Dnf);
-                info.install_progress = Some((
-                    0,
-                    info.selected_packages.len(),
-                    initial_manager,
-                    String::new(),
-                ));
-                Self::install_packages_action(pm_config, info)
+                let operation = Self::selected_install_operation(info);
+                info.is_checking_install = true;
+                info.pending_install = None;
+                Action::Run(Self::create_install_check_task(pm_config, operation))
+            }
+            Message::InstallChecked(operation, installed_elsewhere, previews) => {
+                info.is_checking_install = false;
+                if installed_elsewhere.is_empty() && previews.is_empty() {
+                    return Self::install_packages_action(pm_config, info, operation);
+                }
+                info.pending_install = Some((operation, installed_elsewhere, previews));
+                Action::None
+            }
+            Message::ConfirmInstall => match info.pending_install.take() {
+                Some((operation, _, _)) if !info.is_installing => {
+                    Self::install_packages_action(pm_config, info, operation)
+                }
+                _ => Action::None,
+            },
+            Message::CancelInstall => {
+                info.pending_install = None;
+                Action::None
+            }
+            Message::CopyInstallScript => {
+                let script = selection_install_script(&info.selected_packages);
+                self.script_status = Some(Ok("Install commands copied".to_owned()));
+                Action::Run(iced::clipboard::write(script))
+            }
+            Message::SaveInstallScript => {
+                let script = selection_install_script(&info.selected_packages);
+                Action::Run(Task::perform(
+                    save_install_script(script),
+                    Message::InstallScriptSaved,
+                ))
+            }
+            Message::InstallScriptSaved(result) => {
+                self.script_status = match result {
+                    Ok(Some(path)) => Some(Ok(format!("Saved to {}", path))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(e)),
+                };
+                Action::None
+            }
+            Message::ToggleOutput => {
+                info.install_output.expanded = !info.install_output.expanded;
+                Action::None
+            }
+            Message::RetryInstall => {
+                if info.is_installing {
+                    return Action::None;
+                }
+                match info.last_install_operation.clone() {
+                    Some(operation) => Self::install_packages_action(pm_config, info, operation),
+                    None => Action::None,
+                }
             }
             Message::InstallProgress {
                 completed,
@@ -205,6 +407,7 @@ impl Finding {
             } => {
                 info.install_progress = Some((completed, total, manager, current_package));
                 if let Some(command_message) = command_message {
+                    info.install_output.push_line(&command_message);
                     push_command_log(
                         &mut info.install_logs,
                         PackageBatchAction::Install,
@@ -217,12 +420,41 @@ impl Finding {
                 }
                 Action::None
             }
+            Message::CommandPreviewResult(result) => {
+                match result {
+                    Ok(preview) => info.command_preview = Some(preview),
+                    Err(e) => info.last_install_error = Some(e.into()),
+                }
+                Action::None
+            }
+            Message::ConfirmPreview => match info.command_preview.take() {
+                Some(preview) if !info.is_installing => {
+                    let pm_config = updater_core::Config {
+                        dry_run: false,
+                        ..pm_config.clone()
+                    };
+                    Self::install_packages_action(&pm_config, info, preview.operation)
+                }
+                _ => Action::None,
+            },
+            Message::CancelPreview => {
+                info.command_preview = None;
+                Action::None
+            }
+            Message::CancelOperation => {
+                if let Some(cancel) = &info.install_cancel {
+                    cancel.cancel();
+                }
+                Action::None
+            }
             Message::InstallPackagesResult(result) => {
                 info.is_installing = false;
                 info.install_progress = None;
+                info.install_cancel = None;
                 match result {
                     Ok(_) => {
                         info.selected_packages.clear();
+                        info.last_install_operation = None;
                         // Re-run search to refresh package status.
                         if !self.last_search_query.is_empty() {
                             // Mark selected managers as searching.
@@ -237,8 +469,14 @@ impl Finding {
                         }
                         Action::None
                     }
+                    Err(OperationError::Cancelled) => {
+                        log::info!("Package install cancelled");
+                        info.last_install_error = Some(OperationError::Cancelled);
+                        Action::None
+                    }
                     Err(e) => {
                         log::error!("Failed to install packages: {}", e);
+                        info.last_install_error = Some(e);
                         Action::None
                     }
                 }
@@ -250,14 +488,16 @@ impl Finding {
         &self,
         info: &'a FindingInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::Element<'a, Message> {
         use iced::widget::{column, container, row};
 
         row![
             container(
                 column![
-                    self.manager_filter_view(info, pm_config),
-                    self.sort_order_view(info),
+                    self.manager_filter_view(info, pm_config, unavailable_managers),
+                    self.sort_order_view(info, pm_config.language),
+                    self.group_by_view(info, pm_config.language),
                 ]
                 .spacing(24)
             )
@@ -265,8 +505,8 @@ impl Finding {
             container(
                 column![
                     self.search_input_view(),
-                    self.batch_actions_view(info),
-                    self.search_results_view(info)
+                    self.batch_actions_view(info, pm_config.language),
+                    self.search_results_view(info, pm_config)
                 ]
                 .spacing(20)
             )
@@ -282,10 +522,11 @@ impl Finding {
         &self,
         info: &'a FindingInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::Element<'a, Message> {
         use iced::widget::column;
 
-        let filters_content = self.active_filter_view(info, pm_config);
+        let filters_content = self.active_filter_view(info, pm_config, unavailable_managers);
 
         column![
             SharedUi::section_title("Search Sources"),
@@ -299,6 +540,7 @@ impl Finding {
         &self,
         info: &'a FindingInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::widget::Column<'a, Message> {
         use iced::widget::{column, text};
 
@@ -308,18 +550,26 @@ impl Finding {
             return column![
                 text("No package managers detected")
                     .size(14)
-                    .color(app::colors::ON_SURFACE_MUTED)
+                    .color(app::colors::palette().on_surface_muted)
             ]
             .spacing(8);
         }
 
         column(all_managers.iter().map(|pm_type| {
             let pm_type = *pm_type;
+            if unavailable_managers.contains(&pm_type) {
+                return SharedUi::unavailable_manager_row(pm_type, Message::OpenSettings);
+            }
+
             let is_selected = info.selected_managers.contains(&pm_type);
             let is_searching = info.searching_managers.contains(&pm_type);
 
             let label = if is_searching {
-                format!("{} (Searching...)", pm_type.name())
+                format!(
+                    "{} ({})",
+                    pm_type.name(),
+                    SharedUi::spinner_text("Searching")
+                )
             } else if info.search_errors.contains_key(&pm_type) {
                 format!("{} (Failed)", pm_type.name())
             } else if let Some(results) = info.search_results.get(&pm_type) {
@@ -345,7 +595,7 @@ impl Finding {
         .spacing(12)
     }
 
-    fn sort_order_view<'a>(&self, info: &'a FindingInfo) -> iced::Element<'a, Message> {
+    fn sort_order_view<'a>(&self, info: &'a FindingInfo, lang: Lang) -> iced::Element<'a, Message> {
         use iced::widget::column;
 
         let sort_options = column(SortOption::ALL.iter().map(|option| {
@@ -365,13 +615,40 @@ impl Finding {
         .spacing(12);
 
         column![
-            SharedUi::section_title("Sort By"),
+            SharedUi::section_title(tr(lang, Key::SortBy)),
             SharedUi::styled_container(sort_options)
         ]
         .spacing(12)
         .into()
     }
 
+    fn group_by_view<'a>(&self, info: &'a FindingInfo, lang: Lang) -> iced::Element<'a, Message> {
+        use iced::widget::column;
+
+        let group_options = column(GroupBy::ALL.iter().map(|option| {
+            let option = *option;
+            iced::widget::radio(
+                option.name(lang),
+                option,
+                Some(info.group_by),
+                Message::GroupByChanged,
+            )
+            .size(15)
+            .spacing(10)
+            .text_size(15)
+            .style(SharedUi::radio_style)
+            .into()
+        }))
+        .spacing(12);
+
+        column![
+            SharedUi::section_title(tr(lang, Key::GroupBy)),
+            SharedUi::styled_container(group_options)
+        ]
+        .spacing(12)
+        .into()
+    }
+
     fn search_input_view(&self) -> iced::Element<'static, Message> {
         SharedUi::search_input_view_with_submit(
             "Search",
@@ -382,7 +659,11 @@ impl Finding {
         )
     }
 
-    fn search_results_view<'a>(&self, info: &'a FindingInfo) -> iced::Element<'a, Message> {
+    fn search_results_view<'a>(
+        &self,
+        info: &'a FindingInfo,
+        pm_config: &updater_core::Config,
+    ) -> iced::Element<'a, Message> {
         use iced::widget::{column, scrollable};
 
         if info.selected_managers.is_empty() {
@@ -394,28 +675,41 @@ impl Finding {
         }
 
         if !info.searching_managers.is_empty() {
-            return SharedUi::centered_message("Searching...");
+            return SharedUi::spinner("Searching");
         }
 
-        let results_sections: Vec<iced::Element<'_, Message>> = info
-            .selected_managers
-            .iter()
-            .filter_map(|pm_type| {
-                if let Some(error) = info.search_errors.get(pm_type) {
-                    Some(self.error_section(*pm_type, error))
-                } else {
-                    info.search_results
-                        .get(pm_type)
-                        .filter(|packages| !packages.is_empty())
-                        .map(|packages| self.package_manager_section(*pm_type, packages, info))
-                }
-            })
-            .collect();
+        let mut results_sections: Vec<iced::Element<'_, Message>> = match info.group_by {
+            GroupBy::Manager => SharedUi::sorted_managers(&info.selected_managers)
+                .into_iter()
+                .filter_map(|pm_type| match info.search_outcome(pm_type) {
+                    SearchOutcome::Failed(error) => Some(self.error_section(pm_type, error)),
+                    SearchOutcome::Found(packages) => {
+                        Some(self.package_manager_section(pm_type, packages, info, pm_config))
+                    }
+                    SearchOutcome::Empty | SearchOutcome::Pending => None,
+                })
+                .collect(),
+            GroupBy::Flat => SharedUi::sorted_managers(&info.selected_managers)
+                .into_iter()
+                .filter_map(|pm_type| {
+                    info.search_errors
+                        .get(&pm_type)
+                        .map(|error| self.error_section(pm_type, error))
+                })
+                .chain(self.flat_section(info, pm_config))
+                .collect(),
+        };
 
         if results_sections.is_empty() {
             return SharedUi::centered_message("No packages found");
         }
 
+        // A partial failure with nothing found elsewhere: say so, rather than
+        // leaving only the errors and implying the other managers weren't searched.
+        if let Some(note) = Self::empty_elsewhere_note(info) {
+            results_sections.push(note);
+        }
+
         scrollable(column(results_sections).spacing(20))
             .width(iced::Length::Fill)
             .height(iced::Length::Fill)
@@ -430,30 +724,60 @@ impl Finding {
         use iced::widget::{column, text};
 
         column![
-            text(pm_type.name()).size(18).color(app::colors::SECONDARY),
-            SharedUi::styled_container(
-                text(format!("Search failed: {}", error))
-                    .size(14)
-                    .color(app::colors::ERROR),
-            )
+            text(pm_type.name())
+                .size(18)
+                .color(app::colors::palette().secondary),
+            SharedUi::styled_container(SharedUi::error_with_retry(
+                format!("Search failed in {}: {}", pm_type.name(), error),
+                Some(Message::RetrySearch(pm_type)),
+            ))
         ]
         .spacing(12)
         .into()
     }
 
+    /// "No packages found in …" for the managers that searched fine but matched
+    /// nothing, shown only when every other manager failed.
+    fn empty_elsewhere_note<'a>(info: &FindingInfo) -> Option<iced::Element<'a, Message>> {
+        use iced::widget::text;
+
+        let mut empty = Vec::new();
+        for pm_type in SharedUi::sorted_managers(&info.selected_managers) {
+            match info.search_outcome(pm_type) {
+                SearchOutcome::Found(_) => return None,
+                SearchOutcome::Empty => empty.push(pm_type.name()),
+                SearchOutcome::Failed(_) | SearchOutcome::Pending => {}
+            }
+        }
+        if empty.is_empty() {
+            return None;
+        }
+
+        Some(
+            text(format!("No packages found in {}", empty.join(", ")))
+                .size(14)
+                .color(app::colors::palette().on_surface_muted)
+                .into(),
+        )
+    }
+
     fn package_manager_section<'a>(
         &self,
         pm_type: PackageManagerType,
         packages: &'a [PackageInfo],
         info: &'a FindingInfo,
+        pm_config: &updater_core::Config,
     ) -> iced::Element<'a, Message> {
         use iced::widget::{column, row, text};
 
+        let read_only = pm_config.is_read_only(pm_type);
         let header = row![
-            text(pm_type.name()).size(18).color(app::colors::SECONDARY),
+            text(pm_type.name())
+                .size(18)
+                .color(app::colors::palette().secondary),
             text(format!("({} results)", packages.len()))
                 .size(16)
-                .color(app::colors::ON_SURFACE_MUTED)
+                .color(app::colors::palette().on_surface_muted)
         ]
         .spacing(10)
         .align_y(iced::Alignment::Center);
@@ -463,15 +787,131 @@ impl Finding {
         let packages_list = column(
             sorted_packages
                 .into_iter()
-                .map(|pkg| self.package_item_view(pm_type, pkg, info)),
+                .map(|pkg| self.package_item_view(pm_type, pkg, info, false, read_only)),
         )
         .spacing(8);
 
+        let total = info
+            .search_totals
+            .get(&pm_type)
+            .copied()
+            .unwrap_or(packages.len());
+
+        let packages_list = if total > packages.len() {
+            packages_list.push(
+                text(format!(
+                    "Showing {} of {} — refine your search",
+                    packages.len(),
+                    total
+                ))
+                .size(13)
+                .color(app::colors::palette().on_surface_muted),
+            )
+        } else {
+            packages_list
+        };
+
         column![header, SharedUi::styled_container(packages_list)]
             .spacing(12)
             .into()
     }
 
+    /// Results of all managers merged into one list, `None` when there are none.
+    fn flat_section<'a>(
+        &self,
+        info: &'a FindingInfo,
+        pm_config: &updater_core::Config,
+    ) -> Option<iced::Element<'a, Message>> {
+        use iced::widget::{column, row, text};
+
+        let packages = self.flat_sorted_packages(info);
+        if packages.is_empty() {
+            return None;
+        }
+
+        let header = row![
+            text("All managers")
+                .size(18)
+                .color(app::colors::palette().secondary),
+            text(format!("({} results)", packages.len()))
+                .size(16)
+                .color(app::colors::palette().on_surface_muted)
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let mut packages_list = column(packages.into_iter().map(|(pm_type, pkg)| {
+            self.package_item_view(pm_type, pkg, info, true, pm_config.is_read_only(pm_type))
+        }))
+        .spacing(8);
+
+        for pm_type in SharedUi::sorted_managers(&info.selected_managers) {
+            let Some(shown) = info.search_results.get(&pm_type).map(Vec::len) else {
+                continue;
+            };
+            let total = info.search_totals.get(&pm_type).copied().unwrap_or(shown);
+            if total > shown {
+                packages_list = packages_list.push(
+                    text(format!(
+                        "Showing {} of {} {} results — refine your search",
+                        shown,
+                        total,
+                        pm_type.name()
+                    ))
+                    .size(13)
+                    .color(app::colors::palette().on_surface_muted),
+                );
+            }
+        }
+
+        Some(
+            column![header, SharedUi::styled_container(packages_list)]
+                .spacing(12)
+                .into(),
+        )
+    }
+
+    /// Results of the selected managers in one list, sorted across managers.
+    /// Relevance interleaves the managers' own rankings: every manager's
+    /// best match comes before any manager's second best.
+    fn flat_sorted_packages<'a>(
+        &self,
+        info: &'a FindingInfo,
+    ) -> Vec<(PackageManagerType, &'a PackageInfo)> {
+        let mut ranked: Vec<_> = SharedUi::sorted_managers(&info.selected_managers)
+            .into_iter()
+            .filter_map(|pm_type| {
+                info.search_results
+                    .get(&pm_type)
+                    .map(|packages| (pm_type, packages))
+            })
+            .flat_map(|(pm_type, packages)| {
+                packages
+                    .iter()
+                    .enumerate()
+                    .map(move |(rank, package)| (rank, pm_type, package))
+            })
+            .collect();
+
+        ranked.sort_by_key(|(rank, pm_type, _)| (*rank, *pm_type));
+        match info.sort_by {
+            SortOption::Name => {
+                ranked.sort_by(|(_, a_type, a), (_, b_type, b)| {
+                    a.name.cmp(&b.name).then(a_type.cmp(b_type))
+                });
+            }
+            SortOption::Relevance => {}
+            SortOption::Downloads => {
+                ranked.sort_by_key(|(_, _, package)| std::cmp::Reverse(package.popularity));
+            }
+        }
+
+        ranked
+            .into_iter()
+            .map(|(_, pm_type, package)| (pm_type, package))
+            .collect()
+    }
+
     fn sort_packages<'a>(
         &self,
         packages: &'a [PackageInfo],
@@ -486,6 +926,10 @@ impl Finding {
             SortOption::Relevance => {
                 // Keep provider order for relevance sorting.
             }
+            SortOption::Downloads => {
+                // Only some registries (crates.io) report downloads; the rest keep provider order.
+                sorted.sort_by_key(|package| std::cmp::Reverse(package.popularity));
+            }
         }
 
         sorted
@@ -496,6 +940,8 @@ impl Finding {
         pm_type: PackageManagerType,
         package: &'a PackageInfo,
         info: &'a FindingInfo,
+        show_manager: bool,
+        read_only: bool,
     ) -> iced::Element<'a, Message> {
         use iced::widget::{checkbox, column, container, row, text};
 
@@ -504,21 +950,44 @@ impl Finding {
             .selected_packages
             .contains(&SharedUi::selection_key(pm_type, &package.name));
         let is_not_installed = package.version.trim() == "Not Installed";
+        let metrics = app::density::metrics();
 
-        let mut name_with_desc =
-            column![text(&package.name).size(15).color(app::colors::ON_SURFACE),]
-                .spacing(4)
-                .width(iced::Length::Fill);
+        let name = text(&package.name)
+            .size(metrics.name_size)
+            .color(app::colors::palette().on_surface);
+        let mut name_with_desc = column![if show_manager {
+            row![
+                name,
+                text(pm_type.name())
+                    .size(metrics.detail_size)
+                    .color(app::colors::palette().secondary),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+            .into()
+        } else {
+            iced::Element::from(name)
+        }]
+        .spacing(4)
+        .width(iced::Length::Fill);
 
         if let Some(description) = &package.description {
             name_with_desc = name_with_desc.push(
                 text(description)
-                    .size(13)
-                    .color(app::colors::ON_SURFACE_MUTED),
+                    .size(metrics.detail_size + 1.0)
+                    .color(app::colors::palette().on_surface_muted),
             );
         };
 
-        let enable_install = !info.is_installing && is_not_installed;
+        if let Some(downloads) = package.popularity {
+            name_with_desc = name_with_desc.push(
+                text(format!("{} downloads", format_downloads(downloads)))
+                    .size(metrics.detail_size)
+                    .color(app::colors::palette().on_surface_muted),
+            );
+        }
+
+        let enable_install = !info.is_installing && is_not_installed && !read_only;
 
         let checkbox = checkbox(is_selected)
             .on_toggle_maybe(if enable_install {
@@ -534,6 +1003,11 @@ impl Finding {
             .size(18)
             .spacing(8)
             .style(SharedUi::checkbox_style(false));
+        let checkbox: iced::Element<'a, Message> = if read_only && is_not_installed {
+            SharedUi::read_only_hint(checkbox)
+        } else {
+            checkbox.into()
+        };
 
         let version_text = package.version.trim();
 
@@ -545,15 +1019,17 @@ impl Finding {
                 container(
                     text("Not Installed")
                         .size(12)
-                        .color(app::colors::ON_SURFACE_MUTED)
+                        .color(app::colors::palette().on_surface_muted)
                 )
                 .padding([4, 8])
                 .style(|_theme: &iced::Theme| {
                     use iced::widget::container::Style;
                     Style {
-                        background: Some(iced::Background::Color(app::colors::SURFACE_MUTED)),
+                        background: Some(iced::Background::Color(
+                            app::colors::palette().surface_muted,
+                        )),
                         border: Border {
-                            color: app::colors::DIVIDER,
+                            color: app::colors::palette().divider,
                             width: 1.0,
                             radius: 4.0.into(),
                         },
@@ -571,15 +1047,17 @@ impl Finding {
                 container(
                     text(version_text)
                         .size(12)
-                        .color(app::colors::ON_SURFACE_MUTED)
+                        .color(app::colors::palette().on_surface_muted)
                 )
                 .padding([4, 8])
                 .style(|_theme: &iced::Theme| {
                     use iced::widget::container::Style;
                     Style {
-                        background: Some(iced::Background::Color(app::colors::SURFACE_MUTED)),
+                        background: Some(iced::Background::Color(
+                            app::colors::palette().surface_muted,
+                        )),
                         border: Border {
-                            color: app::colors::DIVIDER,
+                            color: app::colors::palette().divider,
                             width: 1.0,
                             radius: 4.0.into(),
                         },
@@ -596,15 +1074,19 @@ impl Finding {
         main_row
             .spacing(16)
             .align_y(iced::Alignment::Center)
-            .padding([8, 0])
+            .padding([metrics.row_padding, 0.0])
             .into()
     }
 
-    fn batch_actions_view<'a>(&self, info: &'a FindingInfo) -> iced::Element<'a, Message> {
-        use iced::widget::{button, row, text};
+    fn batch_actions_view<'a>(
+        &self,
+        info: &'a FindingInfo,
+        lang: Lang,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, column, row, text};
 
         let selected_count = info.selected_packages.len();
-        let is_enabled = selected_count > 0 && !info.is_installing;
+        let is_enabled = selected_count > 0 && !info.is_installing && !info.is_checking_install;
 
         let button_text = if info.is_installing {
             if let Some((completed, total, manager, package)) = &info.install_progress {
@@ -622,6 +1104,8 @@ impl Finding {
             } else {
                 "Installing...".to_string()
             }
+        } else if info.is_checking_install {
+            "Checking dependencies...".to_string()
         } else if selected_count > 0 {
             format!("Install {} package(s)", selected_count)
         } else {
@@ -631,17 +1115,19 @@ impl Finding {
         let install_button = button(text(button_text).size(14).color(if is_enabled {
             iced::Color::WHITE
         } else {
-            app::colors::ON_SURFACE_MUTED
+            app::colors::palette().on_surface_muted
         }))
         .padding([8, 16])
         .style(move |_theme, status| {
             use iced::widget::button::Style;
             if !is_enabled {
                 Style {
-                    background: Some(iced::Background::Color(app::colors::SURFACE_MUTED)),
-                    text_color: app::colors::ON_SURFACE_MUTED,
+                    background: Some(iced::Background::Color(
+                        app::colors::palette().surface_muted,
+                    )),
+                    text_color: app::colors::palette().on_surface_muted,
                     border: Border {
-                        color: app::colors::DIVIDER,
+                        color: app::colors::palette().divider,
                         width: 1.0,
                         radius: 6.0.into(),
                     },
@@ -682,14 +1168,223 @@ impl Finding {
             install_button
         };
 
-        row![install_button]
+        let mut actions_row = row![install_button]
             .spacing(12)
-            .align_y(iced::Alignment::Center)
-            .into()
+            .align_y(iced::Alignment::Center);
+        if info.is_installing {
+            actions_row = actions_row.push(SharedUi::cancel_operation_button(
+                lang,
+                info.install_cancel.as_ref(),
+                Message::CancelOperation,
+            ));
+        }
+
+        let mut actions = column![actions_row].spacing(8);
+
+        if selected_count > 0 {
+            actions = actions.push(SharedUi::export_script_view(
+                self.script_status.as_ref(),
+                Message::CopyInstallScript,
+                Message::SaveInstallScript,
+            ));
+        }
+
+        if let Some((_, installed_elsewhere, previews)) = &info.pending_install {
+            actions = actions.push(Self::confirm_install_view(installed_elsewhere, previews));
+        }
+
+        if let Some(preview) = &info.command_preview {
+            actions = actions.push(SharedUi::command_preview_view(
+                preview,
+                Message::ConfirmPreview,
+                Message::CancelPreview,
+            ));
+        }
+
+        if let Some(error) = &info.last_install_error {
+            let retry = (!info.is_installing && info.last_install_operation.is_some())
+                .then_some(Message::RetryInstall);
+            actions = actions.push(SharedUi::operation_error_view(
+                lang,
+                error,
+                "Install failed",
+                retry,
+            ));
+        }
+
+        if !info.install_output.is_empty() {
+            actions = actions.push(SharedUi::operation_output_view(
+                &info.install_output,
+                Message::ToggleOutput,
+            ));
+        }
+
+        actions.into()
+    }
+
+    /// Warning for packages already installed through other managers, and
+    /// the dependencies each manager would pull in.
+    fn confirm_install_view<'a>(
+        installed_elsewhere: &InstalledElsewhere,
+        previews: &InstallPreviews,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, column, row, text};
+
+        let elsewhere_lines = installed_elsewhere
+            .iter()
+            .map(|(package, target, managers)| {
+                let managers = managers
+                    .iter()
+                    .map(|pm_type| pm_type.name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                text(format!(
+                    "{} ({}) is already installed via {}",
+                    package,
+                    target.name(),
+                    managers
+                ))
+                .size(14)
+                .color(app::colors::palette().warning)
+                .into()
+            });
+        let preview_lines = previews.iter().map(|(pm_type, preview)| {
+            text(Self::preview_summary(*pm_type, preview))
+                .size(14)
+                .color(app::colors::palette().on_surface)
+                .into()
+        });
+        let lines: Vec<iced::Element<'a, Message>> = elsewhere_lines.chain(preview_lines).collect();
+
+        let cancel = button(text("Cancel").size(14))
+            .padding([8, 16])
+            .on_press(Message::CancelInstall)
+            .style(|_theme, status| button::Style {
+                background: Some(
+                    if matches!(status, button::Status::Hovered) {
+                        app::colors::palette().surface_hover
+                    } else {
+                        app::colors::palette().surface
+                    }
+                    .into(),
+                ),
+                text_color: app::colors::palette().on_surface,
+                border: Border {
+                    color: app::colors::palette().divider,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            });
+
+        row![
+            column(lines).spacing(4).width(iced::Length::Fill),
+            cancel,
+            SharedUi::refresh_button_with_label(
+                if installed_elsewhere.is_empty() {
+                    "Install"
+                } else {
+                    "Install anyway"
+                },
+                Message::ConfirmInstall,
+            ),
+        ]
+        .spacing(12)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// "DNF will also install 14 dependencies (2.3 MB to download): a, b, …".
+    fn preview_summary(pm_type: PackageManagerType, preview: &InstallPreview) -> String {
+        let mut summary = format!(
+            "{} will also install {} {}",
+            pm_type.name(),
+            preview.additional.len(),
+            if preview.additional.len() == 1 {
+                "dependency"
+            } else {
+                "dependencies"
+            }
+        );
+        if let Some(size) = preview.download_size {
+            summary.push_str(&format!(" ({} to download)", SharedUi::format_size(size)));
+        }
+
+        let names = preview.additional.iter().take(PREVIEW_NAME_LIMIT);
+        summary.push_str(": ");
+        summary.push_str(&names.map(String::as_str).collect::<Vec<_>>().join(", "));
+        if preview.additional.len() > PREVIEW_NAME_LIMIT {
+            summary.push_str(&format!(
+                " and {} more",
+                preview.additional.len() - PREVIEW_NAME_LIMIT
+            ));
+        }
+        summary
     }
 
     // Action creators.
 
+    /// Look up every package of `operation` in the other configured managers,
+    /// and dry-resolve each manager's share to find extra dependencies.
+    fn create_install_check_task(
+        pm_config: &updater_core::Config,
+        operation: PackageOperation,
+    ) -> Task<Message> {
+        let pm_config = pm_config.clone();
+
+        Task::future(async move {
+            let checks = operation
+                .manager_groups
+                .iter()
+                .flat_map(|(pm_type, packages)| packages.iter().map(move |name| (*pm_type, name)))
+                .map(|(pm_type, name)| {
+                    let pm_config = &pm_config;
+                    async move {
+                        let managers: Vec<_> = pm_config
+                            .where_installed(name)
+                            .await
+                            .into_iter()
+                            .filter(|installed_in| *installed_in != pm_type)
+                            .collect();
+                        (name.clone(), pm_type, managers)
+                    }
+                });
+
+            let previews = operation.manager_groups.iter().map(|(pm_type, packages)| {
+                let pm_config = &pm_config;
+                async move {
+                    let preview = pm_type.preview_install(pm_config, packages).await;
+                    (*pm_type, preview)
+                }
+            });
+
+            let (installed_elsewhere, previews) = futures::future::join(
+                futures::future::join_all(checks),
+                futures::future::join_all(previews),
+            )
+            .await;
+            let installed_elsewhere: InstalledElsewhere = installed_elsewhere
+                .into_iter()
+                .filter(|(_, _, managers)| !managers.is_empty())
+                .collect();
+            // The preview only informs the confirmation; a failed resolve
+            // leaves the install itself to report the problem.
+            let previews: InstallPreviews = previews
+                .into_iter()
+                .filter_map(|(pm_type, preview)| match preview {
+                    Ok(preview) => Some((pm_type, preview)),
+                    Err(e) => {
+                        log::debug!("{}: install preview failed: {}", pm_type.name(), e);
+                        None
+                    }
+                })
+                .filter(|(_, preview)| !preview.additional.is_empty())
+                .collect();
+
+            Message::InstallChecked(operation, installed_elsewhere, previews)
+        })
+    }
+
     fn execute_search_action(
         pm_config: &updater_core::Config,
         selected_managers: &HashSet<PackageManagerType>,
@@ -708,7 +1403,7 @@ impl Finding {
                     let result = pm_type
                         .search_package(&pm_config, &query)
                         .await
-                        .map_err(|e| format!("Failed to search in {}: {}", pm_type.name(), e));
+                        .map_err(|e| e.to_string());
                     (pm_type, result)
                 })
                 .then(move |(pm_type, result)| Task::done(Message::SearchResult(pm_type, result)))
@@ -718,19 +1413,53 @@ impl Finding {
         Action::Run(Task::batch(tasks))
     }
 
-    fn install_packages_action(pm_config: &updater_core::Config, info: &FindingInfo) -> Action {
-        let manager_groups = collect_selected_package_groups(
-            info.search_results
-                .iter()
-                .map(|(pm_type, packages)| (*pm_type, packages.as_slice())),
-            &info.selected_packages,
-            |package| package.name.as_str(),
-        );
+    fn selected_install_operation(info: &FindingInfo) -> PackageOperation {
+        PackageOperation {
+            action: PackageBatchAction::Install,
+            manager_groups: collect_selected_package_groups(
+                info.search_results
+                    .iter()
+                    .map(|(pm_type, packages)| (*pm_type, packages.as_slice())),
+                &info.selected_packages,
+                |package| package.name.as_str(),
+            ),
+        }
+    }
+
+    fn install_packages_action(
+        pm_config: &updater_core::Config,
+        info: &mut FindingInfo,
+        operation: PackageOperation,
+    ) -> Action {
+        if pm_config.dry_run {
+            info.command_preview = None;
+            info.last_install_error = None;
+            return Action::Run(preview_package_operation(
+                pm_config,
+                operation,
+                Message::CommandPreviewResult,
+            ));
+        }
+
+        info.is_installing = true;
+        info.last_install_error = None;
+        info.install_logs.clear();
+        info.install_output.clear();
+        info.install_progress = Some((
+            0,
+            operation.package_count(),
+            operation.first_manager().unwrap_or(PackageManagerType::Dnf),
+            String::new(),
+        ));
+        info.last_install_operation = Some(operation.clone());
+        let cancel = CancelToken::new();
+        info.install_cancel = Some(cancel.clone());
 
         Action::Run(run_grouped_package_action(
             pm_config,
-            PackageBatchAction::Install,
-            manager_groups,
+            operation.action,
+            operation.manager_groups,
+            cancel,
             |BatchProgress {
                  completed,
                  total,
@@ -748,3 +1477,29 @@ impl Finding {
         ))
     }
 }
+
+/// Compact download count, e.g. `1.2M`.
+fn format_downloads(downloads: u64) -> String {
+    match downloads {
+        0..1_000 => downloads.to_string(),
+        // 会四舍五入成 1000.0K 的计数改用 M
+        1_000..999_950 => format!("{:.1}K", downloads as f64 / 1_000.0),
+        _ => format!("{:.1}M", downloads as f64 / 1_000_000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_downloads_switches_units_at_thresholds() {
+        assert_eq!(format_downloads(999), "999");
+        assert_eq!(format_downloads(1_000), "1.0K");
+        assert_eq!(format_downloads(12_345), "12.3K");
+        assert_eq!(format_downloads(999_949), "999.9K");
+        assert_eq!(format_downloads(999_950), "1.0M");
+        assert_eq!(format_downloads(1_000_000), "1.0M");
+        assert_eq!(format_downloads(2_500_000), "2.5M");
+    }
+}
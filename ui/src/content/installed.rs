@@ -13,30 +13,57 @@
 // └─────────────────────────────────────┘
 
 use std::collections::{HashMap, HashSet};
+#[cfg(feature = "sqlite-index")]
+use std::sync::{Arc, Mutex};
 
 use iced::{Border, Task};
-use updater_core::{PackageInfo, PackageManagerType};
+use serde::{Deserialize, Serialize};
+use updater_core::{
+    CancelToken, PackageInfo, PackageManagerType,
+    i18n::{Key, Lang, tr, tr_args},
+};
 
 use crate::{
     app,
     content::errors::{ManagerErrors, apply_manager_counted_items_result},
-    content::shared::{PackageSelectionKey, SharedUi},
+    content::shared::{ListKey, PackageSelectionKey, SECTION_PAGE_SIZE, SharedUi},
     content::workflows::{
-        BatchProgress, PackageBatchAction, collect_selected_package_groups, push_command_log,
-        run_grouped_package_action,
+        BatchProgress, CommandPreview, OperationError, OperationOutput, PackageBatchAction,
+        PackageOperation, collect_selected_package_groups, open_with_system,
+        preview_package_operation, push_command_log, run_grouped_package_action,
+        save_install_script, selection_install_script,
     },
 };
 
+/// Widget id of the package list, scrolled by keyboard navigation.
+const PACKAGE_LIST_ID: &str = "installed-packages";
+
 #[derive(Debug, Clone, Default)]
 pub struct Installed {
     /// Search text for filtering installed packages in UI.
     search_query: String,
+    /// Pattern for "Select matching".
+    select_pattern: String,
+    /// Treat `select_pattern` as a regex instead of a substring.
+    select_regex: bool,
+    /// Leave managers with no installed packages out of the filter and the list.
+    hide_empty_managers: bool,
+    /// Last install-script export status line.
+    script_status: Option<Result<String, String>>,
+    /// SQLite index rebuilt from each loaded list; `None` until it opens.
+    #[cfg(feature = "sqlite-index")]
+    index: Option<Arc<Mutex<updater_core::PackageIndex>>>,
+    /// Index hits for `search_query`; `None` falls back to in-memory matching.
+    #[cfg(feature = "sqlite-index")]
+    index_matches: Option<HashSet<PackageSelectionKey>>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     /// Package-manager selection message.
     SelectPackageManager(PackageManagerType, bool),
+    /// Hide-empty-managers toggle message.
+    SetHideEmptyManagers(bool),
     /// Installed-load result message.
     LoadInstalledResult(PackageManagerType, Result<Vec<PackageInfo>, String>),
     /// Installed refresh message.
@@ -49,6 +76,12 @@ pub enum Message {
     TogglePackageSelection(PackageManagerType, String, bool),
     /// Select-all toggle message.
     ToggleSelectAll(bool),
+    /// Select-matching pattern change message.
+    SelectPatternChanged(String),
+    /// Select-matching regex toggle message.
+    SetSelectRegex(bool),
+    /// Select every visible package matching the pattern.
+    SelectMatching,
     /// Remove-selected message.
     RemoveSelectedPackages,
     /// Remove progress message.
@@ -65,7 +98,50 @@ pub enum Message {
         command_message: Option<String>,
     },
     /// Remove result message.
-    RemovePackagesResult(Result<(), String>),
+    RemovePackagesResult(Result<(), OperationError>),
+    /// Re-run the last failed removal.
+    RetryRemove,
+    /// Stop the running removal.
+    CancelOperation,
+    /// Commands listed for the removal (see `Config::dry_run`).
+    CommandPreviewResult(Result<CommandPreview, String>),
+    /// Run the previewed commands.
+    ConfirmPreview,
+    /// Drop the previewed removal without running it.
+    CancelPreview,
+    /// Expand or collapse the captured command output.
+    ToggleOutput,
+    /// Render another page of a manager's rows.
+    ShowMore(PackageManagerType),
+    /// Arrow/space/enter on the package list.
+    ListKey(ListKey),
+    /// Run the removal waiting for confirmation.
+    ConfirmRemove,
+    /// Drop the removal waiting for confirmation.
+    CancelRemove,
+    /// Size looked up for a selected package listed without one.
+    InstalledSizeResult(PackageSelectionKey, Option<u64>),
+    /// Open a package's detail panel, or close it when already open.
+    ShowPackageDetails(PackageManagerType, String),
+    /// Detail panel metadata lookup result message.
+    PackageDetailsResult(PackageSelectionKey, Result<FetchedDetails, String>),
+    /// Close the detail panel.
+    CloseDetails,
+    /// Open a package homepage in the browser.
+    OpenHomepage(String),
+    /// Browser launch result message.
+    HomepageOpened(Result<(), String>),
+    /// Copy install commands for the selection to the clipboard.
+    CopyInstallScript,
+    /// Save install commands for the selection as a shell script.
+    SaveInstallScript,
+    /// Script save result, `None` when the dialog was cancelled.
+    InstallScriptSaved(Result<Option<String>, String>),
+    /// Open Settings to fix an unavailable manager's path.
+    OpenSettings,
+    /// A manager's packages were written to the SQLite index.
+    #[cfg(feature = "sqlite-index")]
+    IndexUpdated,
 }
 
 /// Information about installed packages passed from app state
@@ -89,6 +165,8 @@ pub struct InstalledInfo {
     pub init_progress: Option<(usize, usize)>,
     /// Initialization command logs.
     pub init_logs: Vec<String>,
+    /// Current initialization run; messages from older runs are dropped.
+    pub init_generation: u64,
     /// Current sort option.
     pub sort_by: SortOption,
     /// Selected package keys for batch operations.
@@ -99,6 +177,46 @@ pub struct InstalledInfo {
     pub remove_progress: Option<(usize, usize, PackageManagerType, String)>,
     /// Remove command logs.
     pub remove_logs: Vec<String>,
+    /// Captured output of the last remove run.
+    pub remove_output: OperationOutput,
+    /// Cancels the running removal; `None` when idle.
+    pub remove_cancel: Option<CancelToken>,
+    /// Removal whose commands are waiting for confirmation (see `Config::dry_run`).
+    pub command_preview: Option<CommandPreview>,
+    /// Rows rendered per manager section, grown by "Show more".
+    pub visible_counts: HashMap<PackageManagerType, usize>,
+    /// Keyboard-focused row, an index into the rendered rows of all sections.
+    pub focused_index: Option<usize>,
+    /// Last remove error shown in UI.
+    pub last_remove_error: Option<OperationError>,
+    /// Last dispatched removal, kept for retry after a failure.
+    pub last_remove_operation: Option<PackageOperation>,
+    /// Removal waiting for the user to confirm (see `Config::needs_confirmation`).
+    pub pending_remove: Option<PackageOperation>,
+    /// Sizes looked up on selection for packages listed without one; `None` is unknown.
+    pub queried_sizes: HashMap<PackageSelectionKey, Option<u64>>,
+    /// Package shown in the detail panel.
+    pub selected_package: Option<SelectedPackage>,
+}
+
+/// Package shown in the detail panel of the Installed page.
+#[derive(Debug, Clone)]
+pub struct SelectedPackage {
+    pub key: PackageSelectionKey,
+    /// Metadata looked up when the panel opened; `None` while loading.
+    pub fetched: Option<Result<FetchedDetails, String>>,
+    /// Last failure to open the homepage.
+    pub homepage_error: Option<String>,
+}
+
+/// What the manager reports about an installed package on request, for
+/// fields `list_installed` may leave empty.
+#[derive(Debug, Clone, Default)]
+pub struct FetchedDetails {
+    pub version: String,
+    pub description: Option<String>,
+    pub size: Option<u64>,
+    pub homepage: Option<String>,
 }
 
 pub enum Action {
@@ -108,9 +226,12 @@ pub enum Action {
     Run(iced::Task<Message>),
     /// Cache-clear and reload request action.
     ClearCacheAndReload,
+    /// Settings page switch request action.
+    OpenSettings,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortOption {
     #[default]
     Name,
@@ -127,6 +248,12 @@ impl SortOption {
         }
     }
 
+    /// Option whose `name` is `name`, as saved in `Config::ui_preferences`
+    /// before options were saved by key.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|option| option.name() == name)
+    }
+
     pub const ALL: [SortOption; 3] = [
         SortOption::Name,
         SortOption::Version,
@@ -142,6 +269,10 @@ impl Installed {
         info: &mut InstalledInfo,
     ) -> Action {
         match message {
+            Message::SetHideEmptyManagers(hide) => {
+                self.hide_empty_managers = hide;
+                Action::None
+            }
             Message::SelectPackageManager(pm_type, selected) => {
                 if selected {
                     // Managers still in init phase are not selectable yet.
@@ -176,8 +307,18 @@ impl Installed {
                     pm_type,
                     result,
                 );
+                #[cfg(feature = "sqlite-index")]
+                if let Some(task) = self.index_task(info, pm_type) {
+                    return Action::Run(task);
+                }
+                Action::None
+            }
+            #[cfg(feature = "sqlite-index")]
+            Message::IndexUpdated => {
+                self.refresh_index_matches();
                 Action::None
             }
+            Message::OpenSettings => Action::OpenSettings,
             Message::RefreshInfo => {
                 let pm_types: Vec<PackageManagerType> =
                     info.installed_packages.keys().copied().collect();
@@ -201,6 +342,8 @@ impl Installed {
             }
             Message::SearchQueryChanged(query) => {
                 self.search_query = query;
+                #[cfg(feature = "sqlite-index")]
+                self.refresh_index_matches();
                 Action::None
             }
             Message::SortOptionChanged(sort_option) => {
@@ -209,17 +352,69 @@ impl Installed {
             }
             Message::TogglePackageSelection(pm_type, package_name, selected) => {
                 let key = SharedUi::selection_key(pm_type, &package_name);
+                if selected && pm_config.is_read_only(pm_type) {
+                    return Action::None;
+                }
                 if selected {
-                    info.selected_packages.insert(key);
+                    info.selected_packages.insert(key.clone());
+                    Self::size_lookup_action(pm_config, info, key)
                 } else {
                     info.selected_packages.remove(&key);
+                    Action::None
+                }
+            }
+            Message::InstalledSizeResult(key, size) => {
+                info.queried_sizes.insert(key, size);
+                Action::None
+            }
+            Message::ShowPackageDetails(pm_type, package_name) => {
+                let key = SharedUi::selection_key(pm_type, &package_name);
+                if info
+                    .selected_package
+                    .as_ref()
+                    .is_some_and(|selected| selected.key == key)
+                {
+                    info.selected_package = None;
+                    return Action::None;
+                }
+
+                let listed = Self::find_package(info, &key).cloned();
+                info.selected_package = Some(SelectedPackage {
+                    key: key.clone(),
+                    fetched: None,
+                    homepage_error: None,
+                });
+                Action::Run(Self::create_package_details_task(pm_config, key, listed))
+            }
+            Message::PackageDetailsResult(key, result) => {
+                if let Some(selected) = &mut info.selected_package
+                    && selected.key == key
+                {
+                    selected.fetched = Some(result);
+                }
+                Action::None
+            }
+            Message::CloseDetails => {
+                info.selected_package = None;
+                Action::None
+            }
+            Message::OpenHomepage(url) => Action::Run(Task::perform(
+                async move { open_with_system(url).await },
+                Message::HomepageOpened,
+            )),
+            Message::HomepageOpened(result) => {
+                if let Some(selected) = &mut info.selected_package {
+                    selected.homepage_error = result.err();
                 }
                 Action::None
             }
             Message::ToggleSelectAll(select_all) => {
                 if select_all {
-                    // Select all visible packages from selected managers.
+                    // Select all visible packages from selected, writable managers.
                     for pm_type in &info.selected_managers {
+                        if pm_config.is_read_only(*pm_type) {
+                            continue;
+                        }
                         if let Some((_, packages)) = info.installed_packages.get(pm_type) {
                             for pkg in packages {
                                 info.selected_packages
@@ -233,25 +428,115 @@ impl Installed {
                 }
                 Action::None
             }
+            Message::SelectPatternChanged(pattern) => {
+                self.select_pattern = pattern;
+                Action::None
+            }
+            Message::SetSelectRegex(use_regex) => {
+                self.select_regex = use_regex;
+                Action::None
+            }
+            Message::SelectMatching => {
+                let matches = SharedUi::name_matcher(&self.select_pattern, self.select_regex);
+                for pm_type in &info.selected_managers {
+                    if pm_config.is_read_only(*pm_type) {
+                        continue;
+                    }
+                    if let Some((_, packages)) = info.installed_packages.get(pm_type) {
+                        let keys: Vec<_> = self
+                            .filter_and_sort_packages(*pm_type, packages, info.sort_by)
+                            .into_iter()
+                            .filter(|pkg| matches(&pkg.name))
+                            .map(|pkg| SharedUi::selection_key(*pm_type, &pkg.name))
+                            .collect();
+                        info.selected_packages.extend(keys);
+                    }
+                }
+                Action::None
+            }
             Message::RemoveSelectedPackages => {
                 if info.selected_packages.is_empty() {
                     return Action::None;
                 }
-                info.is_removing = true;
-                info.remove_logs.clear();
-                let initial_manager = info
-                    .selected_packages
-                    .iter()
-                    .next()
-                    .map(|(pm_type, _)| *pm_type)
-                    .unwrap_or(PackageManagerType::Dnf);
-                info.remove_progress = Some((
-                    0,
-                    info.selected_packages.len(),
-                    initial_manager,
-                    String::new(),
-                ));
-                Self::remove_packages_action(pm_config, info)
+                let operation = Self::selected_remove_operation(info);
+                let involves_system = operation.managers().any(|pm| pm.is_system_manager());
+                if pm_config.needs_confirmation(operation.package_count(), involves_system) {
+                    info.pending_remove = Some(operation);
+                    return Action::None;
+                }
+                Self::remove_packages_action(pm_config, info, operation)
+            }
+            Message::ConfirmRemove => match info.pending_remove.take() {
+                Some(operation) if !info.is_removing => {
+                    Self::remove_packages_action(pm_config, info, operation)
+                }
+                _ => Action::None,
+            },
+            Message::CancelRemove => {
+                info.pending_remove = None;
+                Action::None
+            }
+            Message::CopyInstallScript => {
+                let script = selection_install_script(&info.selected_packages);
+                self.script_status = Some(Ok("Install commands copied".to_owned()));
+                Action::Run(iced::clipboard::write(script))
+            }
+            Message::SaveInstallScript => {
+                let script = selection_install_script(&info.selected_packages);
+                Action::Run(Task::perform(
+                    save_install_script(script),
+                    Message::InstallScriptSaved,
+                ))
+            }
+            Message::InstallScriptSaved(result) => {
+                self.script_status = match result {
+                    Ok(Some(path)) => Some(Ok(format!("Saved to {}", path))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(e)),
+                };
+                Action::None
+            }
+            Message::ListKey(key) => {
+                let rows = self.navigable_rows(info);
+                info.focused_index = SharedUi::move_focus(info.focused_index, rows.len(), key);
+
+                match (key, info.focused_index) {
+                    (ListKey::Toggle, Some(index)) => {
+                        let focused = rows[index].clone();
+                        if info.selected_packages.remove(&focused)
+                            || pm_config.is_read_only(focused.0)
+                        {
+                            Action::None
+                        } else {
+                            info.selected_packages.insert(focused.clone());
+                            Self::size_lookup_action(pm_config, info, focused)
+                        }
+                    }
+                    (_, Some(index)) => {
+                        Action::Run(SharedUi::scroll_to_row(PACKAGE_LIST_ID, index, rows.len()))
+                    }
+                    (_, None) => Action::None,
+                }
+            }
+            Message::ShowMore(pm_type) => {
+                *info
+                    .visible_counts
+                    .entry(pm_type)
+                    .or_insert(SECTION_PAGE_SIZE) += SECTION_PAGE_SIZE;
+                Action::None
+            }
+            Message::ToggleOutput => {
+                info.remove_output.expanded = !info.remove_output.expanded;
+                Action::None
+            }
+            Message::RetryRemove => {
+                if info.is_removing {
+                    return Action::None;
+                }
+                match info.last_remove_operation.clone() {
+                    Some(operation) => Self::remove_packages_action(pm_config, info, operation),
+                    None => Action::None,
+                }
             }
             Message::RemoveProgress {
                 completed,
@@ -262,6 +547,7 @@ impl Installed {
             } => {
                 info.remove_progress = Some((completed, total, manager, current_package));
                 if let Some(command_message) = command_message {
+                    info.remove_output.push_line(&command_message);
                     push_command_log(
                         &mut info.remove_logs,
                         PackageBatchAction::Remove,
@@ -274,17 +560,52 @@ impl Installed {
                 }
                 Action::None
             }
+            Message::CommandPreviewResult(result) => {
+                match result {
+                    Ok(preview) => info.command_preview = Some(preview),
+                    Err(e) => info.last_remove_error = Some(e.into()),
+                }
+                Action::None
+            }
+            Message::ConfirmPreview => match info.command_preview.take() {
+                Some(preview) if !info.is_removing => {
+                    let pm_config = updater_core::Config {
+                        dry_run: false,
+                        ..pm_config.clone()
+                    };
+                    Self::remove_packages_action(&pm_config, info, preview.operation)
+                }
+                _ => Action::None,
+            },
+            Message::CancelPreview => {
+                info.command_preview = None;
+                Action::None
+            }
+            Message::CancelOperation => {
+                if let Some(cancel) = &info.remove_cancel {
+                    cancel.cancel();
+                }
+                Action::None
+            }
             Message::RemovePackagesResult(result) => {
                 info.is_removing = false;
                 info.remove_progress = None;
+                info.remove_cancel = None;
                 match result {
                     Ok(_) => {
                         info.selected_packages.clear();
+                        info.last_remove_operation = None;
                         // Reload package data after removal.
                         Action::ClearCacheAndReload
                     }
+                    Err(OperationError::Cancelled) => {
+                        log::info!("Package removal cancelled");
+                        info.last_remove_error = Some(OperationError::Cancelled);
+                        Action::None
+                    }
                     Err(e) => {
                         log::error!("Failed to remove packages: {}", e);
+                        info.last_remove_error = Some(e);
                         Action::None
                     }
                 }
@@ -296,21 +617,34 @@ impl Installed {
         &self,
         info: &'a InstalledInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::Element<'a, Message> {
         use iced::widget::column;
 
         SharedUi::content_page_layout(
             column![
-                self.manager_filter_view(info, pm_config),
-                self.sort_order_view(info),
+                self.manager_filter_view(info, pm_config, unavailable_managers),
+                self.sort_order_view(info, pm_config.language),
                 SharedUi::refresh_button(Message::RefreshInfo)
             ]
             .spacing(24),
             column![
                 self.search_input_view(),
-                self.batch_actions_view(info),
-                self.packages_list_view(info)
+                SharedUi::select_matching_view(
+                    &self.select_pattern,
+                    self.select_regex,
+                    Message::SelectPatternChanged,
+                    Message::SetSelectRegex,
+                    Message::SelectMatching,
+                ),
+                self.batch_actions_view(info, pm_config.language),
             ]
+            .push(
+                info.selected_package
+                    .as_ref()
+                    .map(|selected| Self::package_details_view(info, selected, pm_config.language)),
+            )
+            .push(self.packages_list_view(info, pm_config))
             .spacing(20),
         )
     }
@@ -321,21 +655,22 @@ impl Installed {
         &self,
         info: &'a InstalledInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::Element<'a, Message> {
         let filters_content = if !info.has_loading_count {
             SharedUi::loading_manager_filter_view(
                 pm_config,
                 if info.is_loading_count {
-                    "Loading package information..."
+                    SharedUi::spinner_text(tr(pm_config.language, Key::LoadingPackageInfo))
                 } else {
-                    "Waiting to load package information"
+                    tr(pm_config.language, Key::WaitingPackageInfo).to_owned()
                 },
             )
         } else {
             let managers = SharedUi::configured_managers(pm_config);
             if managers.is_empty() {
                 return SharedUi::filter_section(
-                    "Filter Package Managers",
+                    tr(pm_config.language, Key::FilterManagers),
                     SharedUi::empty_filter_view("No package managers detected"),
                 );
             }
@@ -349,27 +684,42 @@ impl Installed {
                         .map_or(0, |(count, _)| *count);
                     (*pm_type, count)
                 })
+                .filter(|(pm_type, count)| {
+                    unavailable_managers.contains(pm_type)
+                        || !self.is_hidden_manager(info, *pm_type, *count)
+                })
                 .collect();
 
             SharedUi::active_manager_filter_view(
                 entries,
                 &info.selected_managers,
                 &info.loading_installed,
+                unavailable_managers,
                 move |pm_type| {
                     info.is_loading_count && !info.installed_packages.contains_key(&pm_type)
                 },
                 Message::SelectPackageManager,
+                Message::OpenSettings,
             )
+            .push(SharedUi::hide_empty_managers_toggle(
+                self.hide_empty_managers,
+                tr(pm_config.language, Key::HideEmptyManagers),
+                Message::SetHideEmptyManagers,
+            ))
         };
 
         SharedUi::manager_filter_section(
-            "Filter Package Managers",
+            tr(pm_config.language, Key::FilterManagers),
             filters_content,
             &info.init_errors,
         )
     }
 
-    fn sort_order_view<'a>(&self, info: &'a InstalledInfo) -> iced::Element<'a, Message> {
+    fn sort_order_view<'a>(
+        &self,
+        info: &'a InstalledInfo,
+        lang: Lang,
+    ) -> iced::Element<'a, Message> {
         use iced::widget::column;
 
         let sort_options = column(SortOption::ALL.iter().map(|option| {
@@ -389,7 +739,7 @@ impl Installed {
         .spacing(12);
 
         column![
-            SharedUi::section_title("Sort By"),
+            SharedUi::section_title(tr(lang, Key::SortBy)),
             SharedUi::styled_container(sort_options)
         ]
         .spacing(12)
@@ -407,106 +757,248 @@ impl Installed {
         )
     }
 
-    fn packages_list_view<'a>(&self, info: &'a InstalledInfo) -> iced::Element<'a, Message> {
+    fn packages_list_view<'a>(
+        &self,
+        info: &'a InstalledInfo,
+        pm_config: &updater_core::Config,
+    ) -> iced::Element<'a, Message> {
         use iced::widget::{column, scrollable};
 
+        let lang = pm_config.language;
         if !info.has_loading_count {
-            return SharedUi::centered_message(if info.is_loading_count {
-                "Loading package information..."
+            return if info.is_loading_count {
+                SharedUi::spinner(tr(lang, Key::LoadingPackageInfo))
             } else {
-                "Waiting to load package information"
-            });
+                SharedUi::centered_message(tr(lang, Key::WaitingPackageInfo))
+            };
         }
 
-        let filtered_managers: Vec<_> = info
+        let mut filtered_managers: Vec<_> = info
             .installed_packages
             .iter()
             .filter(|(pm_type, _)| info.selected_managers.contains(pm_type))
+            .filter(|(pm_type, (count, _))| !self.is_hidden_manager(info, **pm_type, *count))
             .collect();
+        filtered_managers.sort_by_key(|(pm_type, _)| **pm_type);
 
         if filtered_managers.is_empty() {
-            return SharedUi::centered_message("Please select a package manager to view");
+            return SharedUi::centered_message(tr(lang, Key::SelectManagerToView));
         }
 
-        let search_query = self.search_query.trim().to_lowercase();
         let has_visible_errors = filtered_managers
             .iter()
             .any(|(pm_type, _)| info.load_errors.contains_key(*pm_type));
 
+        let search_query = self.search_query.trim().to_lowercase();
         if !search_query.is_empty() {
-            let has_any_match = filtered_managers.iter().any(|(_, (_, packages))| {
+            let has_any_match = filtered_managers.iter().any(|(pm_type, (_, packages))| {
                 packages
                     .iter()
-                    .any(|pkg| pkg.name.to_lowercase().contains(&search_query))
+                    .any(|pkg| self.matches_search(&search_query, **pm_type, pkg))
             });
 
             if !has_any_match && !has_visible_errors {
-                return SharedUi::centered_message("No packages match your search");
+                return SharedUi::centered_message(tr(lang, Key::NoSearchMatches));
             }
         }
 
+        let focused = info
+            .focused_index
+            .and_then(|index| self.navigable_rows(info).into_iter().nth(index));
         let packages_sections: Vec<iced::Element<'_, Message>> = filtered_managers
             .into_iter()
             .map(|(pm_type, (count, packages))| {
-                self.package_manager_section(*pm_type, *count, packages, info)
+                self.package_manager_section(
+                    *pm_type,
+                    *count,
+                    packages,
+                    info,
+                    focused.as_ref(),
+                    pm_config,
+                )
             })
             .collect();
 
         scrollable(column(packages_sections).spacing(20))
+            .id(iced::widget::Id::new(PACKAGE_LIST_ID))
             .width(iced::Length::Fill)
             .height(iced::Length::Fill)
             .into()
     }
 
+    /// Whether `hide_empty_managers` leaves `pm_type` out. Checked on every
+    /// render, so a manager reappears once its count loads; loading and
+    /// failed managers stay visible.
+    fn is_hidden_manager(
+        &self,
+        info: &InstalledInfo,
+        pm_type: PackageManagerType,
+        count: usize,
+    ) -> bool {
+        self.hide_empty_managers
+            && count == 0
+            && !info.loading_installed.contains(&pm_type)
+            && !info.load_errors.contains_key(&pm_type)
+            && !(info.is_loading_count && !info.installed_packages.contains_key(&pm_type))
+    }
+
     fn package_manager_section<'a>(
         &self,
         pm_type: PackageManagerType,
         count: usize,
         packages: &'a [PackageInfo],
         info: &'a InstalledInfo,
+        focused: Option<&PackageSelectionKey>,
+        pm_config: &updater_core::Config,
     ) -> iced::Element<'a, Message> {
+        let lang = pm_config.language;
+        let read_only = pm_config.is_read_only(pm_type);
         let is_loading = info.loading_installed.contains(&pm_type);
-        let filtered_packages = self.filter_and_sort_packages(packages, info.sort_by);
+        let filtered_packages = self.filter_and_sort_packages(pm_type, packages, info.sort_by);
         let subtitle = if is_loading {
-            "(Loading...)".to_owned()
+            format!("({})", SharedUi::spinner_text(tr(lang, Key::Loading)))
         } else {
-            format!("({} packages)", count)
+            format!("({})", tr_args(lang, Key::PackageCount, &[&count]))
         };
 
         let body = (!filtered_packages.is_empty()).then(|| {
-            iced::widget::column(
-                filtered_packages
-                    .into_iter()
-                    .map(|pkg| self.package_item_view(pm_type, pkg, info)),
+            SharedUi::truncated_rows(
+                filtered_packages,
+                SharedUi::visible_rows(&info.visible_counts, pm_type),
+                |pkg| {
+                    let is_focused = focused == Some(&SharedUi::selection_key(pm_type, &pkg.name));
+                    SharedUi::focusable_row(
+                        self.package_item_view(pm_type, pkg, info, read_only),
+                        is_focused,
+                    )
+                },
+                Message::ShowMore(pm_type),
             )
-            .spacing(8)
-            .into()
         });
 
         SharedUi::manager_section(
             pm_type,
             subtitle,
-            "Failed to load installed packages",
+            tr(lang, Key::LoadInstalledFailed),
             info.load_errors.get(&pm_type).map(String::as_str),
             body,
         )
     }
 
+    /// Keys of the rendered rows in display order, what `focused_index` indexes.
+    fn navigable_rows(&self, info: &InstalledInfo) -> Vec<PackageSelectionKey> {
+        SharedUi::sorted_managers(&info.selected_managers)
+            .into_iter()
+            .filter_map(|pm_type| {
+                info.installed_packages
+                    .get(&pm_type)
+                    .map(|(_, packages)| (pm_type, packages))
+            })
+            .flat_map(|(pm_type, packages)| {
+                self.filter_and_sort_packages(pm_type, packages, info.sort_by)
+                    .into_iter()
+                    .take(SharedUi::visible_rows(&info.visible_counts, pm_type))
+                    .map(move |pkg| SharedUi::selection_key(pm_type, &pkg.name))
+            })
+            .collect()
+    }
+
+    /// Whether `package` matches the lowercased search `query`; empty matches everything.
+    #[cfg_attr(not(feature = "sqlite-index"), allow(unused_variables))]
+    fn matches_search(
+        &self,
+        query: &str,
+        pm_type: PackageManagerType,
+        package: &PackageInfo,
+    ) -> bool {
+        if query.is_empty() {
+            return true;
+        }
+
+        #[cfg(feature = "sqlite-index")]
+        if let Some(matches) = &self.index_matches {
+            return matches.contains(&SharedUi::selection_key(pm_type, &package.name));
+        }
+
+        package.name.to_lowercase().contains(query)
+    }
+
+    /// Open the index on first use and write `pm_type`'s loaded list to it off the UI thread.
+    #[cfg(feature = "sqlite-index")]
+    fn index_task(
+        &mut self,
+        info: &InstalledInfo,
+        pm_type: PackageManagerType,
+    ) -> Option<Task<Message>> {
+        use updater_core::PackageIndex;
+
+        let (_, packages) = info.installed_packages.get(&pm_type)?;
+        if info.load_errors.contains_key(&pm_type) {
+            return None;
+        }
+
+        if self.index.is_none() {
+            match PackageIndex::default_path().and_then(PackageIndex::open) {
+                Ok(index) => self.index = Some(Arc::new(Mutex::new(index))),
+                Err(e) => {
+                    log::warn!("Package index unavailable, searching in memory: {}", e);
+                    return None;
+                }
+            }
+        }
+
+        let index = self.index.clone()?;
+        let packages = packages.clone();
+        Some(Task::perform(
+            async move {
+                let result = tokio::task::spawn_blocking(move || {
+                    index
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .replace_manager(pm_type, &packages)
+                })
+                .await;
+                if let Ok(Err(e)) = result {
+                    log::warn!("Failed to index {} packages: {}", pm_type.name(), e);
+                }
+            },
+            |_| Message::IndexUpdated,
+        ))
+    }
+
+    /// Re-run `search_query` against the index.
+    #[cfg(feature = "sqlite-index")]
+    fn refresh_index_matches(&mut self) {
+        self.index_matches = self.index.as_ref().and_then(|index| {
+            let query = self.search_query.trim();
+            if query.is_empty() {
+                return None;
+            }
+
+            match index
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .search(query)
+            {
+                Ok(matches) => Some(matches.into_iter().collect()),
+                Err(e) => {
+                    log::warn!("Package index search failed: {}", e);
+                    None
+                }
+            }
+        });
+    }
+
     fn filter_and_sort_packages<'a>(
         &self,
+        pm_type: PackageManagerType,
         packages: &'a [PackageInfo],
         sort_by: SortOption,
     ) -> Vec<&'a PackageInfo> {
         let query = self.search_query.trim().to_lowercase();
         let mut filtered: Vec<_> = packages
             .iter()
-            .filter(|pkg| {
-                if query.is_empty() {
-                    true
-                } else {
-                    pkg.name.to_lowercase().contains(&query)
-                }
-            })
+            .filter(|pkg| self.matches_search(&query, pm_type, pkg))
             .collect();
 
         match sort_by {
@@ -534,41 +1026,219 @@ impl Installed {
         pm_type: PackageManagerType,
         package: &'a PackageInfo,
         info: &'a InstalledInfo,
+        read_only: bool,
     ) -> iced::Element<'a, Message> {
-        use iced::widget::{checkbox, row, text};
+        use iced::widget::{Space, button, checkbox, row, text};
 
         let package_name = package.name.clone();
         let is_selected = info
             .selected_packages
             .contains(&SharedUi::selection_key(pm_type, &package.name));
+        let metrics = app::density::metrics();
+
+        let select_checkbox = checkbox(is_selected)
+            .on_toggle_maybe((!read_only).then(|| {
+                let package_name = package_name.clone();
+                move |selected| {
+                    Message::TogglePackageSelection(pm_type, package_name.clone(), selected)
+                }
+            }))
+            .size(18)
+            .spacing(8)
+            .style(SharedUi::checkbox_style(read_only));
 
         row![
-            checkbox(is_selected)
-                .on_toggle({
-                    let package_name = package_name.clone();
-                    move |selected| {
-                        Message::TogglePackageSelection(pm_type, package_name.clone(), selected)
-                    }
-                })
-                .size(18)
-                .spacing(8)
-                .style(SharedUi::checkbox_style(false)),
-            text(&package.name)
-                .size(15)
-                .color(app::colors::ON_SURFACE)
-                .width(iced::Length::Fill),
-            text(&package.version)
-                .size(14)
-                .color(app::colors::ON_SURFACE_MUTED),
+            if read_only {
+                SharedUi::read_only_hint(select_checkbox)
+            } else {
+                select_checkbox.into()
+            },
+            button(
+                text(&package.name)
+                    .size(metrics.name_size)
+                    .color(app::colors::palette().on_surface),
+            )
+            .on_press(Message::ShowPackageDetails(pm_type, package_name))
+            .padding(0)
+            .style(|_theme, _status| button::Style::default()),
+            Space::new().width(iced::Length::Fill),
+            package.eol.as_deref().map(Self::eol_badge),
+            text(if package.has_update_info {
+                ""
+            } else {
+                package
+                    .description
+                    .as_deref()
+                    .unwrap_or("cannot check updates")
+            })
+            .size(metrics.detail_size)
+            .color(app::colors::palette().on_surface_muted),
+            SharedUi::version_text(&package.version, pm_type),
         ]
         .spacing(16)
         .align_y(iced::Alignment::Center)
-        .padding([8, 0])
+        .padding([metrics.row_padding, 0.0])
+        .into()
+    }
+
+    /// Everything known about the selected package: the listing's fields,
+    /// filled in from the lookup made when the panel opened.
+    fn package_details_view<'a>(
+        info: &'a InstalledInfo,
+        selected: &'a SelectedPackage,
+        lang: Lang,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, column, row, text};
+
+        let (pm_type, package_name) = &selected.key;
+        let listed = Self::find_package(info, &selected.key);
+        let fetched = match &selected.fetched {
+            Some(Ok(fetched)) => Some(fetched),
+            _ => None,
+        };
+        let muted = app::colors::palette().on_surface_muted;
+
+        let field = |label: &'static str, value: iced::Element<'a, Message>| {
+            row![text(label).size(14).color(muted).width(110), value]
+                .spacing(12)
+                .align_y(iced::Alignment::Center)
+        };
+        let value = |value: String| -> iced::Element<'a, Message> { text(value).size(14).into() };
+
+        let version = fetched
+            .map(|fetched| fetched.version.clone())
+            .or_else(|| listed.map(|pkg| pkg.version.clone()))
+            .unwrap_or_default();
+        let description = listed
+            .and_then(|pkg| pkg.description.clone())
+            .or_else(|| fetched.and_then(|fetched| fetched.description.clone()))
+            .unwrap_or_else(|| tr(lang, Key::NoDescription).to_owned());
+        let size = listed
+            .and_then(|pkg| pkg.size)
+            .or_else(|| fetched.and_then(|fetched| fetched.size))
+            .map_or_else(|| tr(lang, Key::Unknown).to_owned(), SharedUi::format_size);
+        let install_date = listed
+            .and_then(|pkg| pkg.install_date.clone())
+            .unwrap_or_else(|| tr(lang, Key::Unknown).to_owned());
+        let homepage: iced::Element<'a, Message> = match listed
+            .and_then(|pkg| pkg.homepage.as_deref())
+            .or_else(|| fetched.and_then(|fetched| fetched.homepage.as_deref()))
+        {
+            Some(url) if Self::is_web_url(url) => {
+                button(text(url).size(14).color(app::colors::palette().secondary))
+                    .on_press(Message::OpenHomepage(url.to_owned()))
+                    .padding(0)
+                    .style(|_theme, _status| button::Style::default())
+                    .into()
+            }
+            Some(other) => value(other.to_owned()),
+            None => value(tr(lang, Key::NoHomepage).to_owned()),
+        };
+
+        let header = row![
+            text(format!("{} ({})", package_name, pm_type.name()))
+                .size(16)
+                .color(app::colors::palette().on_surface)
+                .width(iced::Length::Fill),
+            SharedUi::refresh_button_with_label(tr(lang, Key::Close), Message::CloseDetails),
+        ]
+        .spacing(12)
+        .align_y(iced::Alignment::Center);
+
+        let status = match &selected.fetched {
+            None => Some(
+                text(SharedUi::spinner_text(tr(lang, Key::LoadingDetails)))
+                    .size(13)
+                    .color(muted),
+            ),
+            Some(Err(e)) => Some(
+                text(tr_args(lang, Key::LoadDetailsFailed, &[e]))
+                    .size(13)
+                    .color(app::colors::palette().error),
+            ),
+            Some(Ok(_)) => None,
+        };
+        let homepage_error = selected.homepage_error.as_ref().map(|e| {
+            text(tr_args(lang, Key::OpenHomepageFailed, &[e]))
+                .size(13)
+                .color(app::colors::palette().error)
+        });
+
+        SharedUi::styled_container(
+            column![
+                header,
+                field(tr(lang, Key::DetailVersion), value(version)),
+                field(tr(lang, Key::DetailDescription), value(description)),
+                field(tr(lang, Key::DetailSize), value(size)),
+                field(tr(lang, Key::DetailInstalled), value(install_date)),
+                field(tr(lang, Key::DetailHomepage), homepage),
+            ]
+            .push(status)
+            .push(homepage_error)
+            .spacing(8),
+        )
+        .into()
+    }
+
+    /// Whether `homepage` is an http(s) URL. Homepages come from package
+    /// metadata, so anything else (`file://`, local paths) is only shown,
+    /// never handed to the system opener.
+    fn is_web_url(homepage: &str) -> bool {
+        url::Url::parse(homepage).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    }
+
+    fn find_package<'a>(
+        info: &'a InstalledInfo,
+        (pm_type, package_name): &PackageSelectionKey,
+    ) -> Option<&'a PackageInfo> {
+        info.installed_packages
+            .get(pm_type)
+            .and_then(|(_, packages)| packages.iter().find(|pkg| &pkg.name == package_name))
+    }
+
+    /// "EOL" badge of an app its remote marked end-of-life; hovering shows
+    /// the notice and the replacement app, if any.
+    fn eol_badge<'a>(notice: &'a str) -> iced::Element<'a, Message> {
+        use iced::widget::{container, text, tooltip};
+
+        tooltip(
+            container(
+                text("EOL")
+                    .size(12)
+                    .color(app::colors::palette().on_surface),
+            )
+            .padding([2, 8])
+            .style(|_theme: &iced::Theme| container::Style {
+                border: Border {
+                    color: app::colors::palette().warning,
+                    width: 1.5,
+                    radius: 8.0.into(),
+                },
+                ..Default::default()
+            }),
+            container(text(notice).size(13))
+                .padding([4, 8])
+                .style(|_theme: &iced::Theme| container::Style {
+                    background: Some(app::colors::palette().surface.into()),
+                    border: Border {
+                        color: app::colors::palette().divider,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: Some(app::colors::palette().on_surface),
+                    ..Default::default()
+                }),
+            tooltip::Position::Top,
+        )
         .into()
     }
 
-    fn batch_actions_view<'a>(&self, info: &'a InstalledInfo) -> iced::Element<'a, Message> {
-        use iced::widget::{button, checkbox, row, text};
+    fn batch_actions_view<'a>(
+        &self,
+        info: &'a InstalledInfo,
+        lang: Lang,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, checkbox, column, row, text};
 
         let selected_count = info.selected_packages.len();
         let is_enabled = selected_count > 0 && !info.is_removing;
@@ -600,7 +1270,11 @@ impl Installed {
                 "Removing...".to_string()
             }
         } else if selected_count > 0 {
-            format!("Remove {} package(s)", selected_count)
+            format!(
+                "Remove {} package(s){}",
+                selected_count,
+                Self::size_suffix(Self::total_size(info, &info.selected_packages))
+            )
         } else {
             "Remove Selected".to_string()
         };
@@ -616,17 +1290,19 @@ impl Installed {
         let remove_button = button(text(button_text).size(14).color(if is_enabled {
             iced::Color::WHITE
         } else {
-            app::colors::ON_SURFACE_MUTED
+            app::colors::palette().on_surface_muted
         }))
         .padding([8, 16])
         .style(move |_theme, status| {
             use iced::widget::button::Style;
             if !is_enabled {
                 Style {
-                    background: Some(iced::Background::Color(app::colors::SURFACE_MUTED)),
-                    text_color: app::colors::ON_SURFACE_MUTED,
+                    background: Some(iced::Background::Color(
+                        app::colors::palette().surface_muted,
+                    )),
+                    text_color: app::colors::palette().on_surface_muted,
                     border: Border {
-                        color: app::colors::DIVIDER,
+                        color: app::colors::palette().divider,
                         width: 1.0,
                         radius: 6.0.into(),
                     },
@@ -667,10 +1343,124 @@ impl Installed {
             remove_button
         };
 
-        row![select_all_checkbox, remove_button]
+        let mut actions_row = row![select_all_checkbox, remove_button]
             .spacing(12)
-            .align_y(iced::Alignment::Center)
-            .into()
+            .align_y(iced::Alignment::Center);
+        if info.is_removing {
+            actions_row = actions_row.push(SharedUi::cancel_operation_button(
+                lang,
+                info.remove_cancel.as_ref(),
+                Message::CancelOperation,
+            ));
+        }
+
+        let mut actions = column![actions_row].spacing(8);
+
+        if selected_count > 0 {
+            actions = actions.push(SharedUi::export_script_view(
+                self.script_status.as_ref(),
+                Message::CopyInstallScript,
+                Message::SaveInstallScript,
+            ));
+        }
+
+        if let Some(operation) = &info.pending_remove {
+            actions = actions.push(Self::confirm_remove_view(operation, info, lang));
+        }
+
+        if let Some(preview) = &info.command_preview {
+            actions = actions.push(SharedUi::command_preview_view(
+                preview,
+                Message::ConfirmPreview,
+                Message::CancelPreview,
+            ));
+        }
+
+        if let Some(error) = &info.last_remove_error {
+            let retry = (!info.is_removing && info.last_remove_operation.is_some())
+                .then_some(Message::RetryRemove);
+            actions = actions.push(SharedUi::operation_error_view(
+                lang,
+                error,
+                "Remove failed",
+                retry,
+            ));
+        }
+
+        if !info.remove_output.is_empty() {
+            actions = actions.push(SharedUi::operation_output_view(
+                &info.remove_output,
+                Message::ToggleOutput,
+            ));
+        }
+
+        actions.into()
+    }
+
+    /// Inline confirmation for a pending removal.
+    fn confirm_remove_view<'a>(
+        operation: &PackageOperation,
+        info: &InstalledInfo,
+        lang: Lang,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, row, text};
+
+        let managers = operation
+            .managers()
+            .map(|pm_type| pm_type.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let keys: HashSet<PackageSelectionKey> = operation
+            .manager_groups
+            .iter()
+            .flat_map(|(pm_type, packages)| {
+                packages
+                    .iter()
+                    .map(|name| SharedUi::selection_key(*pm_type, name))
+            })
+            .collect();
+        let prompt = text(tr_args(
+            lang,
+            Key::ConfirmRemovePackages,
+            &[
+                &operation.package_count(),
+                &managers,
+                &Self::size_suffix(Self::total_size(info, &keys)),
+            ],
+        ))
+        .size(14)
+        .color(app::colors::palette().error)
+        .width(iced::Length::Fill);
+
+        let cancel = button(text(tr(lang, Key::Cancel)).size(14))
+            .padding([8, 16])
+            .on_press(Message::CancelRemove)
+            .style(|_theme, status| button::Style {
+                background: Some(
+                    if matches!(status, button::Status::Hovered) {
+                        app::colors::palette().surface_hover
+                    } else {
+                        app::colors::palette().surface
+                    }
+                    .into(),
+                ),
+                text_color: app::colors::palette().on_surface,
+                border: Border {
+                    color: app::colors::palette().divider,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            });
+
+        row![
+            prompt,
+            cancel,
+            SharedUi::refresh_button_with_label(tr(lang, Key::Confirm), Message::ConfirmRemove),
+        ]
+        .spacing(12)
+        .align_y(iced::Alignment::Center)
+        .into()
     }
 
     fn create_load_task(
@@ -691,21 +1481,180 @@ impl Installed {
         .then(move |result| Task::done(Message::LoadInstalledResult(pm_type, result)))
     }
 
-    fn remove_packages_action(pm_config: &updater_core::Config, info: &InstalledInfo) -> Action {
-        let manager_groups = collect_selected_package_groups(
-            info.selected_managers.iter().filter_map(|pm_type| {
-                info.installed_packages
-                    .get(pm_type)
-                    .map(|(_, packages)| (*pm_type, packages.as_slice()))
-            }),
-            &info.selected_packages,
-            |package| package.name.as_str(),
-        );
+    /// Current version of a package, plus its description, size and
+    /// homepage when the listing left them empty.
+    fn create_package_details_task(
+        pm_config: &updater_core::Config,
+        key: PackageSelectionKey,
+        listed: Option<PackageInfo>,
+    ) -> Task<Message> {
+        let pm_config = pm_config.clone();
+        let (pm_type, package_name) = key.clone();
+
+        Task::future(async move {
+            let result = async {
+                let version = pm_type
+                    .get_current_version(&pm_config, &package_name)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let mut fetched = FetchedDetails {
+                    version,
+                    ..Default::default()
+                };
+
+                if listed.as_ref().is_none_or(|pkg| pkg.description.is_none()) {
+                    fetched.description = pm_type
+                        .get_package_details(&pm_config, &package_name)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::debug!("Description lookup for {} failed: {}", package_name, e);
+                            None
+                        });
+                }
+                if listed.as_ref().is_none_or(|pkg| pkg.size.is_none()) {
+                    fetched.size = pm_type
+                        .installed_size(&pm_config, &package_name)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::debug!("Size lookup for {} failed: {}", package_name, e);
+                            None
+                        });
+                }
+                if listed.as_ref().is_none_or(|pkg| pkg.homepage.is_none()) {
+                    fetched.homepage = pm_type
+                        .installed_homepage(&pm_config, &package_name)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::debug!("Homepage lookup for {} failed: {}", package_name, e);
+                            None
+                        });
+                }
+                Ok(fetched)
+            }
+            .await;
+
+            Message::PackageDetailsResult(key, result)
+        })
+    }
+
+    /// Look up the size of a newly selected package when its listing had none.
+    fn size_lookup_action(
+        pm_config: &updater_core::Config,
+        info: &mut InstalledInfo,
+        key: PackageSelectionKey,
+    ) -> Action {
+        let (pm_type, package_name) = key.clone();
+        let listed_size = info
+            .installed_packages
+            .get(&pm_type)
+            .and_then(|(_, packages)| packages.iter().find(|pkg| pkg.name == package_name))
+            .and_then(|pkg| pkg.size);
+
+        if listed_size.is_some() || info.queried_sizes.contains_key(&key) {
+            return Action::None;
+        }
+
+        // 先记为未知，避免重复勾选时重复查询
+        info.queried_sizes.insert(key.clone(), None);
+        let pm_config = pm_config.clone();
+
+        Action::Run(Task::perform(
+            async move {
+                pm_type
+                    .installed_size(&pm_config, &package_name)
+                    .await
+                    .unwrap_or_else(|e| {
+                        log::debug!("Size lookup for {} failed: {}", package_name, e);
+                        None
+                    })
+            },
+            move |size| Message::InstalledSizeResult(key, size),
+        ))
+    }
+
+    /// Summed size of the `keys` packages and whether every size is known.
+    fn total_size(info: &InstalledInfo, keys: &HashSet<PackageSelectionKey>) -> (u64, bool) {
+        let mut total = 0;
+        let mut known = 0;
+
+        for (pm_type, (_, packages)) in &info.installed_packages {
+            for pkg in packages {
+                let key = SharedUi::selection_key(*pm_type, &pkg.name);
+                if !keys.contains(&key) {
+                    continue;
+                }
+
+                let size = pkg
+                    .size
+                    .or_else(|| info.queried_sizes.get(&key).copied().flatten());
+                if let Some(size) = size {
+                    total += size;
+                    known += 1;
+                }
+            }
+        }
+
+        (total, known == keys.len())
+    }
+
+    /// ` (128 MB)` for a fully known total, ` (at least 128 MB)` otherwise.
+    fn size_suffix((total, complete): (u64, bool)) -> String {
+        match (total, complete) {
+            (0, false) => String::new(),
+            (total, true) => format!(" ({})", SharedUi::format_size(total)),
+            (total, false) => format!(" (at least {})", SharedUi::format_size(total)),
+        }
+    }
+
+    fn selected_remove_operation(info: &InstalledInfo) -> PackageOperation {
+        PackageOperation {
+            action: PackageBatchAction::Remove,
+            manager_groups: collect_selected_package_groups(
+                info.selected_managers.iter().filter_map(|pm_type| {
+                    info.installed_packages
+                        .get(pm_type)
+                        .map(|(_, packages)| (*pm_type, packages.as_slice()))
+                }),
+                &info.selected_packages,
+                |package| package.name.as_str(),
+            ),
+        }
+    }
+
+    fn remove_packages_action(
+        pm_config: &updater_core::Config,
+        info: &mut InstalledInfo,
+        operation: PackageOperation,
+    ) -> Action {
+        if pm_config.dry_run {
+            info.command_preview = None;
+            info.last_remove_error = None;
+            return Action::Run(preview_package_operation(
+                pm_config,
+                operation,
+                Message::CommandPreviewResult,
+            ));
+        }
+
+        info.is_removing = true;
+        info.last_remove_error = None;
+        info.remove_logs.clear();
+        info.remove_output.clear();
+        info.remove_progress = Some((
+            0,
+            operation.package_count(),
+            operation.first_manager().unwrap_or(PackageManagerType::Dnf),
+            String::new(),
+        ));
+        info.last_remove_operation = Some(operation.clone());
+        let cancel = CancelToken::new();
+        info.remove_cancel = Some(cancel.clone());
 
         Action::Run(run_grouped_package_action(
             pm_config,
-            PackageBatchAction::Remove,
-            manager_groups,
+            operation.action,
+            operation.manager_groups,
+            cancel,
             |BatchProgress {
                  completed,
                  total,
@@ -723,3 +1672,21 @@ impl Installed {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_web_homepages_are_opened() {
+        assert!(Installed::is_web_url(
+            "https://github.com/eza-community/eza"
+        ));
+        assert!(Installed::is_web_url("http://example.org"));
+        assert!(!Installed::is_web_url(
+            "file:///usr/share/applications/x.desktop"
+        ));
+        assert!(!Installed::is_web_url("/usr/bin/true"));
+        assert!(!Installed::is_web_url("javascript:alert(1)"));
+    }
+}
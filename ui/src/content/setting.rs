@@ -1,12 +1,48 @@
 use iced::Task;
 use rfd::FileHandle;
-use updater_core::{ALL_APP_PACKAGE_MANAGERS, Config, PackageManagerConfig, PackageManagerType};
+use updater_core::{
+    ALL_APP_PACKAGE_MANAGERS, ALL_PACKAGE_MANAGERS, ColorScheme, Config, DnfTransaction, Inventory,
+    InventoryDiff, ListDensity, Manifest, ManifestImport, PackageChange, PackageManagerConfig,
+    PackageManagerType, PrivilegeEscalation, Problem,
+    i18n::{Key, Lang, tr, tr_args},
+};
 
 use crate::{
     app::{self},
+    content::{shared::SharedUi, workflows::open_with_system},
     icon::{ADD_ICON, REFRESH_ICON, SAVE_ICON},
 };
 
+/// Most recent DNF transactions listed in the history section.
+const HISTORY_LIMIT: usize = 10;
+
+/// Upper bound offered in the UI for parallel installs.
+const MAX_INSTALL_CONCURRENCY: usize = 8;
+
+/// Hours added or removed per click on the crates.io cache lifetime.
+const CRATE_CACHE_TTL_STEP_HOURS: u64 = 6;
+
+/// `cargo install` flags offered as toggles; others can be added to the config file.
+const COMMON_CARGO_INSTALL_FLAGS: [(&str, &str); 2] = [
+    (
+        "--locked",
+        "Build Cargo crates with their Cargo.lock (--locked)",
+    ),
+    (
+        "--frozen",
+        "Also forbid network access while building (--frozen)",
+    ),
+];
+
+/// Per-manager diagnostics outcome.
+type DiagnosticsResults = Vec<(PackageManagerType, Result<Vec<Problem>, String>)>;
+
+/// Snapshot comparison outcome `(snapshot date, diff)`; `None` when the dialog was cancelled.
+type InventoryCompareResult = Result<Option<(String, InventoryDiff)>, String>;
+
+/// Manifest import outcome; `None` when the dialog was cancelled.
+type ManifestImportResult = Result<Option<ManifestImport>, String>;
+
 #[derive(Debug, Clone, Default)]
 pub struct Settings {
     /// Whether config save is in progress.
@@ -17,8 +53,38 @@ pub struct Settings {
     pub selecting_manager: Option<PackageManagerType>,
     /// Managers detected from PATH scan.
     pub detected_in_path: Vec<PackageManagerType>,
+    /// What the last scan found, shown until the next scan or Add.
+    pub detect_summary: Option<String>,
     /// Last save result shown in UI.
     pub save_status: Option<SaveStatus>,
+    /// Whether diagnostics are running.
+    pub is_diagnosing: bool,
+    /// Last diagnostics result per manager.
+    pub diagnostics: Option<DiagnosticsResults>,
+    /// Whether an inventory snapshot is being exported or compared.
+    pub is_snapshotting: bool,
+    /// Last snapshot export/compare status line.
+    pub inventory_status: Option<Result<String, String>>,
+    /// Changes since the compared snapshot `(snapshot date, diff)`.
+    pub inventory_diff: Option<(String, InventoryDiff)>,
+    /// Whether a package manifest is being exported or imported.
+    pub is_manifest_busy: bool,
+    /// Last manifest export/import status line.
+    pub manifest_status: Option<Result<String, String>>,
+    /// Whether a Brewfile is being exported or imported.
+    pub is_brewfile_busy: bool,
+    /// Last Brewfile export/import status line.
+    pub brewfile_status: Option<Result<String, String>>,
+    /// Why the typed Go-bin directory was rejected on the last save.
+    pub go_bin_dir_error: Option<String>,
+    /// Whether DNF history is being read or rolled back.
+    pub is_loading_history: bool,
+    /// Last `dnf history list` result.
+    pub transactions: Option<Result<Vec<DnfTransaction>, String>>,
+    /// Transaction waiting for the user to confirm a rollback to it.
+    pub pending_rollback: Option<u32>,
+    /// Last rollback status line.
+    pub rollback_status: Option<Result<String, String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -55,6 +121,101 @@ pub enum Message {
     SelectedGoBinDir(FileHandle),
     /// Go-bin directory clear message.
     ClearGoBinDir,
+    /// Go-bin directory typed into the text field.
+    SetGoBinDir(String),
+    /// Additional Go-bin directory dialog message.
+    OpenGoExtraBinDirDialog,
+    /// Additional Go-bin directory selection message.
+    SelectedGoExtraBinDir(FileHandle),
+    /// Additional Go-bin directory removal message, by index.
+    RemoveGoExtraBinDir(usize),
+    /// Diagnostics run message.
+    RunDiagnostics,
+    /// Diagnostics result message.
+    DiagnosticsResult(DiagnosticsResults),
+    /// Install-concurrency change message.
+    SetInstallConcurrency(usize),
+    /// Toggle offering pre-release versions as Cargo/Go updates.
+    SetIncludePrereleases(bool),
+    /// Removal-confirmation threshold change message.
+    SetConfirmThreshold(usize),
+    /// Toggle always confirming system-manager removals.
+    SetConfirmSystemAlways(bool),
+    /// Toggle refusing installs, updates and removals of system packages.
+    SetSystemManagerReadonly(bool),
+    /// Toggle the quit confirmation while an operation runs.
+    SetConfirmCloseDuringOperation(bool),
+    /// Toggle listing an operation's commands for confirmation before running it.
+    SetDryRun(bool),
+    /// Idle-timeout change message, in minutes (0 = never).
+    SetCommandIdleTimeout(u64),
+    /// Toggle leaving a manager out of "update all" runs.
+    SetExcludeFromUpdateAll(PackageManagerType, bool),
+    /// Toggle installing Cargo crates with cargo-binstall.
+    SetPreferBinstall(bool),
+    /// crates.io cache lifetime change message, in hours (0 = off).
+    SetCrateCacheTtl(u64),
+    /// Drop cached crates.io info so the next lookups refetch it.
+    ClearCrateCache,
+    /// Toggle one of `COMMON_CARGO_INSTALL_FLAGS`.
+    SetCargoInstallFlag(&'static str, bool),
+    /// Toggle hiding DNF debuginfo/devel packages from the installed list.
+    SetDnfHideNoise(bool),
+    /// Change how system-manager commands get root.
+    SetPrivilegeEscalation(PrivilegeEscalation),
+    /// Askpass helper passed to `sudo -A` as `SUDO_ASKPASS`.
+    SetSudoAskpass(String),
+    /// UI language change message.
+    SetLanguage(Lang),
+    /// Color scheme change message.
+    SetColorScheme(ColorScheme),
+    /// Package list density change message.
+    SetListDensity(ListDensity),
+    /// Comma-separated directories prepended to `PATH` of spawned commands.
+    SetExtraPath(String),
+    /// Edit the comma-separated custom arguments of one manager.
+    SetCustomArgs(PackageManagerType, String),
+    /// Export current installed packages to a snapshot file.
+    ExportInventory,
+    /// Snapshot export result message, `None` when cancelled.
+    InventoryExported(Result<Option<String>, String>),
+    /// Compare current installed packages with a snapshot file.
+    CompareInventory,
+    /// Snapshot comparison result message.
+    InventoryCompared(InventoryCompareResult),
+    /// Export installed package names to a manifest file.
+    ExportManifest,
+    /// Manifest export result message, `None` when cancelled.
+    ManifestExported(Result<Option<String>, String>),
+    /// Install the missing packages of a manifest file.
+    ImportManifest,
+    /// Manifest import result message.
+    ManifestImported(ManifestImportResult),
+    /// Save `brew bundle dump` output to a Brewfile.
+    ExportBrewfile,
+    /// Brewfile export result message, `None` when cancelled.
+    BrewfileExported(Result<Option<String>, String>),
+    /// Run `brew bundle` on a Brewfile.
+    ImportBrewfile,
+    /// Brewfile import result message, `None` when cancelled.
+    BrewfileImported(Result<Option<String>, String>),
+    /// Reveal the config directory in the system file manager.
+    OpenConfigFolder,
+    /// Config-folder open result message.
+    OpenConfigFolderResult(Result<(), String>),
+    /// Read DNF transaction history.
+    LoadHistory,
+    /// DNF history result message.
+    HistoryResult(Result<Vec<DnfTransaction>, String>),
+    /// Ask to roll back to a transaction; waits for confirmation.
+    RequestRollback(u32),
+    /// Run the rollback waiting for confirmation.
+    ConfirmRollback,
+    /// Drop the rollback waiting for confirmation.
+    CancelRollback,
+    /// Rollback result message, with the target transaction ID and, in a
+    /// dry run, the command that would have run.
+    RollbackResult(u32, Result<Option<String>, String>),
 }
 
 #[derive(Debug)]
@@ -69,7 +230,7 @@ impl Settings {
     fn section_title(text: &'static str) -> iced::widget::Text<'static> {
         iced::widget::text(text)
             .size(18)
-            .color(app::colors::ON_SURFACE)
+            .color(app::colors::palette().on_surface)
     }
 
     fn styled_container<'a>(
@@ -81,9 +242,9 @@ impl Settings {
             .padding(16)
             .width(iced::Length::Fill)
             .style(|_theme: &iced::Theme| container::Style {
-                background: Some(app::colors::SURFACE.into()),
+                background: Some(app::colors::palette().surface.into()),
                 border: Border {
-                    color: app::colors::DIVIDER,
+                    color: app::colors::palette().divider,
                     width: 1.0,
                     radius: 8.0.into(),
                 },
@@ -118,8 +279,8 @@ impl Settings {
 
             if is_disabled {
                 Style {
-                    background: Some(Background::Color(app::colors::SURFACE)),
-                    text_color: app::colors::ON_SURFACE_MUTED,
+                    background: Some(Background::Color(app::colors::palette().surface)),
+                    text_color: app::colors::palette().on_surface_muted,
                     border: Border {
                         radius: 8.0.into(),
                         ..Default::default()
@@ -129,14 +290,14 @@ impl Settings {
                 }
             } else {
                 let (bg_color, shadow_offset) = match status {
-                    Status::Hovered => (app::colors::PRIMARY_HOVER, 3.0),
-                    Status::Pressed => (app::colors::PRIMARY_ACTIVE, 1.0),
-                    _ => (app::colors::PRIMARY, 2.0),
+                    Status::Hovered => (app::colors::palette().primary_hover, 3.0),
+                    Status::Pressed => (app::colors::palette().primary_active, 1.0),
+                    _ => (app::colors::palette().primary, 2.0),
                 };
 
                 Style {
                     background: Some(Background::Color(bg_color)),
-                    text_color: app::colors::ON_PRIMARY,
+                    text_color: app::colors::palette().on_primary,
                     border: Border {
                         radius: 8.0.into(),
                         ..Default::default()
@@ -175,8 +336,8 @@ impl Settings {
 
                 if is_disabled {
                     Style {
-                        background: Some(Background::Color(app::colors::SURFACE)),
-                        text_color: app::colors::ON_SURFACE_MUTED,
+                        background: Some(Background::Color(app::colors::palette().surface)),
+                        text_color: app::colors::palette().on_surface_muted,
                         border: Border {
                             radius: 8.0.into(),
                             ..Default::default()
@@ -186,20 +347,28 @@ impl Settings {
                     }
                 } else {
                     let (bg_color, text_color, shadow_offset) = match status {
-                        Status::Hovered => {
-                            (app::colors::SURFACE_HOVER, app::colors::ON_SURFACE, 2.0)
-                        }
-                        Status::Pressed => {
-                            (app::colors::SURFACE_PRESSED, app::colors::ON_SURFACE, 0.5)
-                        }
-                        _ => (app::colors::SURFACE, app::colors::ON_SURFACE, 1.0),
+                        Status::Hovered => (
+                            app::colors::palette().surface_hover,
+                            app::colors::palette().on_surface,
+                            2.0,
+                        ),
+                        Status::Pressed => (
+                            app::colors::palette().surface_pressed,
+                            app::colors::palette().on_surface,
+                            0.5,
+                        ),
+                        _ => (
+                            app::colors::palette().surface,
+                            app::colors::palette().on_surface,
+                            1.0,
+                        ),
                     };
 
                     Style {
                         background: Some(Background::Color(bg_color)),
                         text_color,
                         border: Border {
-                            color: app::colors::DIVIDER,
+                            color: app::colors::palette().divider,
                             width: 1.0,
                             radius: 8.0.into(),
                         },
@@ -224,16 +393,21 @@ impl Settings {
         match message {
             Message::DetectPackageManagers => {
                 self.is_detecting = true;
+                self.detect_summary = None;
+                // An explicit scan should see binaries installed since the last one.
+                updater_core::clear_availability_cache();
                 let task = Task::future(Config::detect_available_app_managers())
                     .then(|detected_managers| Task::done(Message::FinishDetect(detected_managers)));
                 Action::Run(task)
             }
             Message::FinishDetect(detected_managers) => {
                 self.is_detecting = false;
+                self.detect_summary = Some(Self::detect_summary(&detected_managers));
                 self.detected_in_path = detected_managers;
                 Action::None
             }
             Message::AddDetectedManager(manager_type) => {
+                self.detect_summary = None;
                 let exists = pm_config
                     .app_managers
                     .iter()
@@ -243,6 +417,7 @@ impl Settings {
                     pm_config.app_managers.push(PackageManagerConfig {
                         manager_type,
                         custom_path: None,
+                        custom_args: Vec::new(),
                     });
                 }
                 Action::None
@@ -254,17 +429,20 @@ impl Settings {
 
                 if manager_type == PackageManagerType::Go {
                     pm_config.go_bin_dir = None;
+                    pm_config.go_extra_bin_dirs.clear();
                 }
                 Action::None
             }
             Message::SaveConfig => {
+                if let Err(e) = pm_config.validate_go_bin_dir() {
+                    self.go_bin_dir_error = Some(e.to_string());
+                    self.save_status = Some(SaveStatus::Error(e.to_string()));
+                    return Action::None;
+                }
+                self.go_bin_dir_error = None;
                 self.is_saving = true;
                 self.save_status = None;
-                self.save_config(
-                    pm_config.system_manager.as_ref(),
-                    pm_config.app_managers.as_ref(),
-                    pm_config.go_bin_dir.as_ref(),
-                )
+                self.save_config(pm_config)
             }
             Message::SaveConfigResult(result) => {
                 self.is_saving = false;
@@ -309,6 +487,7 @@ impl Settings {
                         pm_config.app_managers.push(PackageManagerConfig {
                             manager_type,
                             custom_path: Some(path),
+                            custom_args: Vec::new(),
                         });
                     }
                 } else {
@@ -338,25 +517,386 @@ impl Settings {
             Message::SelectedGoBinDir(file_handle) => {
                 let path = file_handle.path().to_string_lossy().to_string();
                 pm_config.go_bin_dir = Some(path);
+                self.go_bin_dir_error = None;
                 Action::None
             }
             Message::ClearGoBinDir => {
                 pm_config.go_bin_dir = None;
+                self.go_bin_dir_error = None;
+                Action::None
+            }
+            Message::SetGoBinDir(input) => {
+                // 原样保存，`~` 在扫描时展开，空白视为自动检测
+                pm_config.go_bin_dir = (!input.trim().is_empty()).then_some(input);
+                self.go_bin_dir_error = None;
+                Action::None
+            }
+            Message::OpenGoExtraBinDirDialog => {
+                let task = Task::future(
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Add Go Binary Directory")
+                        .pick_folder(),
+                )
+                .then(|handle| match handle {
+                    Some(file_handle) => Task::done(Message::SelectedGoExtraBinDir(file_handle)),
+                    None => Task::done(Message::CancelSelection),
+                });
+
+                Action::Run(task)
+            }
+            Message::SelectedGoExtraBinDir(file_handle) => {
+                let path = file_handle.path().to_string_lossy().to_string();
+                if !pm_config.go_extra_bin_dirs.contains(&path) {
+                    pm_config.go_extra_bin_dirs.push(path);
+                }
+                Action::None
+            }
+            Message::RemoveGoExtraBinDir(index) => {
+                if index < pm_config.go_extra_bin_dirs.len() {
+                    pm_config.go_extra_bin_dirs.remove(index);
+                }
+                Action::None
+            }
+            Message::RunDiagnostics => {
+                self.is_diagnosing = true;
+                let config = pm_config.clone();
+                let managers: Vec<PackageManagerType> = config
+                    .system_manager
+                    .iter()
+                    .chain(config.app_managers.iter())
+                    .map(|manager| manager.manager_type)
+                    .collect();
+
+                let task = Task::perform(
+                    async move {
+                        futures::future::join_all(managers.into_iter().map(|pm_type| {
+                            let config = &config;
+                            async move {
+                                let result = pm_type
+                                    .list_problems(config)
+                                    .await
+                                    .map_err(|e| e.to_string());
+                                (pm_type, result)
+                            }
+                        }))
+                        .await
+                    },
+                    Message::DiagnosticsResult,
+                );
+                Action::Run(task)
+            }
+            Message::DiagnosticsResult(results) => {
+                self.is_diagnosing = false;
+                self.diagnostics = Some(results);
+                Action::None
+            }
+            Message::SetInstallConcurrency(concurrency) => {
+                pm_config.install_concurrency = concurrency.clamp(1, MAX_INSTALL_CONCURRENCY);
+                Action::None
+            }
+            Message::SetIncludePrereleases(include) => {
+                pm_config.include_prereleases = include;
+                Action::None
+            }
+            Message::SetConfirmThreshold(threshold) => {
+                pm_config.confirm_threshold = threshold;
+                Action::None
+            }
+            Message::SetConfirmSystemAlways(always) => {
+                pm_config.confirm_system_always = always;
+                Action::None
+            }
+            Message::SetSystemManagerReadonly(readonly) => {
+                pm_config.system_manager_readonly = readonly;
+                Action::None
+            }
+            Message::SetConfirmCloseDuringOperation(confirm) => {
+                pm_config.confirm_close_during_operation = confirm;
+                Action::None
+            }
+            Message::SetDryRun(dry_run) => {
+                pm_config.dry_run = dry_run;
+                Action::None
+            }
+            Message::SetCommandIdleTimeout(minutes) => {
+                pm_config.command_idle_timeout_secs = minutes * 60;
+                Action::None
+            }
+            Message::SetExcludeFromUpdateAll(pm_type, exclude) => {
+                if exclude {
+                    pm_config.exclude_from_update_all.insert(pm_type);
+                } else {
+                    pm_config.exclude_from_update_all.remove(&pm_type);
+                }
+                Action::None
+            }
+            Message::SetPreferBinstall(prefer) => {
+                pm_config.prefer_binstall = prefer;
+                Action::None
+            }
+            Message::SetCrateCacheTtl(hours) => {
+                pm_config.crate_cache_ttl_hours = hours;
+                Action::None
+            }
+            Message::ClearCrateCache => {
+                updater_core::clear_api_cache();
                 Action::None
             }
+            Message::SetCargoInstallFlag(flag, enabled) => {
+                let flags = &mut pm_config.cargo_install_flags;
+                flags.retain(|existing| existing.trim() != flag);
+                if enabled {
+                    flags.push(flag.to_owned());
+                }
+                Action::None
+            }
+            Message::SetDnfHideNoise(hide) => {
+                pm_config.dnf_hide_noise = hide;
+                Action::None
+            }
+            Message::SetPrivilegeEscalation(method) => {
+                pm_config.privilege_escalation = method;
+                Action::None
+            }
+            Message::SetSudoAskpass(input) => {
+                // 原样保存，首尾空白在设置 SUDO_ASKPASS 时忽略
+                pm_config.sudo_askpass = (!input.trim().is_empty()).then_some(input);
+                Action::None
+            }
+            Message::SetLanguage(lang) => {
+                pm_config.language = lang;
+                Action::None
+            }
+            Message::SetColorScheme(scheme) => {
+                pm_config.color_scheme = scheme;
+                app::colors::set_scheme(scheme);
+                Action::None
+            }
+            Message::SetListDensity(density) => {
+                pm_config.list_density = density;
+                app::density::set_density(density);
+                Action::None
+            }
+            Message::SetExtraPath(input) => {
+                // 原样保存，空项和首尾空白在生成 PATH 时忽略
+                pm_config.extra_path = input.split(',').map(str::to_owned).collect();
+                Action::None
+            }
+            Message::SetCustomArgs(manager_type, input) => {
+                if let Some(manager) = pm_config
+                    .system_manager
+                    .iter_mut()
+                    .chain(pm_config.app_managers.iter_mut())
+                    .find(|manager| manager.manager_type == manager_type)
+                {
+                    // 同 extra_path，空项和非 - 开头的项在拼接命令时忽略
+                    manager.custom_args = input.split(',').map(str::to_owned).collect();
+                }
+                Action::None
+            }
+            Message::ExportInventory => {
+                self.is_snapshotting = true;
+                self.inventory_status = None;
+                Action::Run(Task::perform(
+                    export_inventory(pm_config.clone()),
+                    Message::InventoryExported,
+                ))
+            }
+            Message::InventoryExported(result) => {
+                self.is_snapshotting = false;
+                self.inventory_status = match result {
+                    Ok(Some(path)) => Some(Ok(format!("Snapshot saved to {}", path))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(format!("Failed to export snapshot: {}", e))),
+                };
+                Action::None
+            }
+            Message::CompareInventory => {
+                self.is_snapshotting = true;
+                self.inventory_status = None;
+                Action::Run(Task::perform(
+                    compare_inventory(pm_config.clone()),
+                    Message::InventoryCompared,
+                ))
+            }
+            Message::InventoryCompared(result) => {
+                self.is_snapshotting = false;
+                match result {
+                    Ok(Some(diff)) => {
+                        self.inventory_diff = Some(diff);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.inventory_status =
+                            Some(Err(format!("Failed to compare snapshot: {}", e)));
+                    }
+                }
+                Action::None
+            }
+            Message::ExportManifest => {
+                self.is_manifest_busy = true;
+                self.manifest_status = None;
+                Action::Run(Task::perform(
+                    export_manifest(pm_config.clone()),
+                    Message::ManifestExported,
+                ))
+            }
+            Message::ManifestExported(result) => {
+                self.is_manifest_busy = false;
+                self.manifest_status = match result {
+                    Ok(Some(path)) => Some(Ok(format!("Manifest saved to {}", path))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(format!("Failed to export manifest: {}", e))),
+                };
+                Action::None
+            }
+            Message::ImportManifest => {
+                self.is_manifest_busy = true;
+                self.manifest_status = None;
+                Action::Run(Task::perform(
+                    import_manifest(pm_config.clone()),
+                    Message::ManifestImported,
+                ))
+            }
+            Message::ManifestImported(result) => {
+                self.is_manifest_busy = false;
+                self.manifest_status = match result {
+                    Ok(Some(import)) => Some(Self::manifest_import_summary(&import, pm_config)),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(format!("Failed to import manifest: {}", e))),
+                };
+                Action::None
+            }
+            Message::ExportBrewfile => {
+                self.is_brewfile_busy = true;
+                self.brewfile_status = None;
+                Action::Run(Task::perform(
+                    export_brewfile(pm_config.clone()),
+                    Message::BrewfileExported,
+                ))
+            }
+            Message::BrewfileExported(result) => {
+                self.is_brewfile_busy = false;
+                self.brewfile_status = match result {
+                    Ok(Some(path)) => Some(Ok(format!("Brewfile saved to {}", path))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(format!("Failed to export Brewfile: {}", e))),
+                };
+                Action::None
+            }
+            Message::ImportBrewfile => {
+                self.is_brewfile_busy = true;
+                self.brewfile_status = None;
+                Action::Run(Task::perform(
+                    import_brewfile(pm_config.clone()),
+                    Message::BrewfileImported,
+                ))
+            }
+            Message::BrewfileImported(result) => {
+                self.is_brewfile_busy = false;
+                self.brewfile_status = match result {
+                    Ok(Some(path)) if pm_config.dry_run => {
+                        Some(Ok(format!("Dry run: would run brew bundle on {}", path)))
+                    }
+                    Ok(Some(path)) => Some(Ok(format!("Installed the bundle from {}", path))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(format!("Failed to import Brewfile: {}", e))),
+                };
+                Action::None
+            }
+            Message::OpenConfigFolder => Action::Run(Task::perform(
+                open_config_folder(),
+                Message::OpenConfigFolderResult,
+            )),
+            Message::OpenConfigFolderResult(result) => {
+                if let Err(e) = result {
+                    log::error!("Failed to open config folder: {}", e);
+                }
+                Action::None
+            }
+            Message::LoadHistory => {
+                self.is_loading_history = true;
+                Action::Run(Self::load_history_task(pm_config))
+            }
+            Message::HistoryResult(result) => {
+                self.is_loading_history = false;
+                self.transactions = Some(result);
+                Action::None
+            }
+            Message::RequestRollback(id) => {
+                self.pending_rollback = Some(id);
+                Action::None
+            }
+            Message::CancelRollback => {
+                self.pending_rollback = None;
+                Action::None
+            }
+            Message::ConfirmRollback => {
+                let Some(id) = self.pending_rollback.take() else {
+                    return Action::None;
+                };
+                self.is_loading_history = true;
+                self.rollback_status = None;
+                let config = pm_config.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        let dry_run = config.dry_run;
+                        let mut command = None;
+                        let result = PackageManagerType::Dnf
+                            .rollback_transaction(&config, id, |progress| {
+                                if dry_run && progress.command_message.is_some() {
+                                    command = progress.command_message;
+                                }
+                            })
+                            .await;
+                        result.map(|()| command).map_err(|e| e.to_string())
+                    },
+                    move |result| Message::RollbackResult(id, result),
+                ))
+            }
+            Message::RollbackResult(id, result) => {
+                self.rollback_status = Some(match result {
+                    Ok(Some(command)) => Ok(format!("Dry run: would run {}", command)),
+                    Ok(None) => Ok(format!("Rolled back to transaction {}", id)),
+                    Err(e) => Err(format!("Rollback to transaction {} failed: {}", id, e)),
+                });
+                // The rollback itself is a new transaction; show it.
+                Action::Run(Self::load_history_task(pm_config))
+            }
         }
     }
 
+    fn load_history_task(pm_config: &Config) -> Task<Message> {
+        let config = pm_config.clone();
+        Task::perform(
+            async move {
+                PackageManagerType::Dnf
+                    .list_transactions(&config)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::HistoryResult,
+        )
+    }
+
     pub fn view(&self, pm_config: &updater_core::Config) -> iced::Element<'static, Message> {
         use iced::Length;
         use iced::widget::{column, container, scrollable};
 
         let content = column![
-            self.view_header(),
-            self.view_system_manager_section(pm_config.system_manager.as_ref()),
+            self.view_header(pm_config.language),
+            self.view_system_manager_section(pm_config),
             self.view_app_manager_section(pm_config),
             self.view_selection_list(pm_config),
-            self.view_buttons(),
+            self.view_install_section(pm_config),
+            self.view_language_section(pm_config.language),
+            self.view_appearance_section(pm_config),
+            self.view_diagnostics_section(pm_config.language),
+            self.view_inventory_section(pm_config.language),
+            self.view_manifest_section(pm_config.language),
+            self.view_brewfile_section(pm_config),
+            self.view_history_section(pm_config),
+            self.view_buttons(pm_config.language),
             self.view_status(),
         ]
         .spacing(24)
@@ -369,53 +909,112 @@ impl Settings {
             .into()
     }
 
-    fn view_header(&self) -> iced::Element<'static, Message> {
+    fn view_header(&self, lang: Lang) -> iced::Element<'static, Message> {
         use iced::widget::text;
 
-        text("Package Manager Settings").size(24).into()
+        text(tr(lang, Key::SettingsTitle)).size(24).into()
     }
 
     fn view_system_manager_section(
         &self,
-        system_manager: Option<&PackageManagerConfig>,
+        pm_config: &updater_core::Config,
     ) -> iced::Element<'static, Message> {
-        use iced::widget::{column, row, text};
+        use iced::widget::{checkbox, column, row, text};
 
-        let content = if let Some(manager) = system_manager {
+        let lang = pm_config.language;
+        let content = if let Some(manager) = pm_config.system_manager.as_ref() {
             let path_info = manager
                 .custom_path
                 .as_ref()
-                .map(|p| format!("Path: {}", p))
-                .unwrap_or_else(|| "Path: $PATH (System Default)".to_string());
+                .map(|p| tr_args(lang, Key::PathValue, &[p]))
+                .unwrap_or_else(|| tr(lang, Key::PathSystemDefault).to_owned());
 
             column![
                 row![
                     text(manager.manager_type.name()).size(16),
-                    text("✓").size(16).color(app::colors::SUCCESS),
+                    text("✓").size(16).color(app::colors::palette().success),
                 ]
                 .spacing(10),
                 text(path_info)
                     .size(14)
-                    .color(app::colors::ON_SURFACE_MUTED),
+                    .color(app::colors::palette().on_surface_muted),
+                Self::view_custom_args_input(manager, lang),
             ]
             .spacing(8)
+            .push((manager.manager_type == PackageManagerType::Dnf).then(|| {
+                checkbox(pm_config.dnf_hide_noise)
+                    .label(tr(pm_config.language, Key::HideDnfNoise))
+                    .on_toggle(Message::SetDnfHideNoise)
+                    .size(18)
+                    .spacing(8)
+                    .text_size(14)
+                    .style(SharedUi::checkbox_style(false))
+            }))
+            .push(Self::view_privilege_config(pm_config))
         } else {
             column![
-                text("Not detected")
+                text(tr(lang, Key::NotDetected))
                     .size(16)
-                    .color(app::colors::ON_SURFACE_MUTED)
+                    .color(app::colors::palette().on_surface_muted)
             ]
             .spacing(8)
         };
 
         column![
-            Self::section_title("System Package Manager"),
+            Self::section_title(tr(pm_config.language, Key::SystemManager)),
             Self::styled_container(content)
         ]
         .spacing(12)
         .into()
     }
 
+    /// Choice of pkexec or `sudo -A` for commands that need root.
+    fn view_privilege_config(pm_config: &updater_core::Config) -> iced::Element<'static, Message> {
+        use iced::widget::{column, radio, text, text_input};
+
+        let lang = pm_config.language;
+        let options = column(PrivilegeEscalation::ALL.iter().map(|method| {
+            let label = match method {
+                PrivilegeEscalation::Pkexec => tr(lang, Key::PrivilegePkexec),
+                PrivilegeEscalation::SudoAskpass => tr(lang, Key::PrivilegeSudoAskpass),
+            };
+
+            radio(
+                label,
+                *method,
+                Some(pm_config.privilege_escalation),
+                Message::SetPrivilegeEscalation,
+            )
+            .size(15)
+            .spacing(10)
+            .text_size(14)
+            .style(SharedUi::radio_style)
+            .into()
+        }))
+        .spacing(8);
+
+        column![text(tr(lang, Key::RunAsAdminWith)).size(16), options]
+            .spacing(8)
+            .push(
+                (pm_config.privilege_escalation == PrivilegeEscalation::SudoAskpass).then(|| {
+                    column![
+                        text_input(
+                            "/usr/bin/ssh-askpass",
+                            pm_config.sudo_askpass.as_deref().unwrap_or_default(),
+                        )
+                        .on_input(Message::SetSudoAskpass)
+                        .padding(10)
+                        .size(15),
+                        text(tr(lang, Key::SudoAskpassHint))
+                            .size(14)
+                            .color(app::colors::palette().on_surface_muted),
+                    ]
+                    .spacing(8)
+                }),
+            )
+            .into()
+    }
+
     /// App package manager section.
     fn view_app_manager_section(
         &self,
@@ -424,11 +1023,12 @@ impl Settings {
         use iced::Alignment;
         use iced::widget::{column, container, row, text};
 
+        let lang = pm_config.language;
         let managers_list = if pm_config.app_managers.is_empty() {
             column![
-                text("No application package managers in UI management")
+                text(tr(lang, Key::NoAppManagers))
                     .size(16)
-                    .color(app::colors::ON_SURFACE_MUTED)
+                    .color(app::colors::palette().on_surface_muted)
             ]
         } else {
             column(
@@ -437,7 +1037,7 @@ impl Settings {
                     .iter()
                     .map(|manager| {
                         let unload_btn = Self::secondary_button(
-                            "Unload",
+                            tr(lang, Key::Unload),
                             14.0,
                             Some(Message::UnloadManager(manager.manager_type)),
                         );
@@ -457,10 +1057,13 @@ impl Settings {
             .spacing(12)
         };
 
-        column![Self::section_title("App Package Manager"), managers_list]
-            .spacing(12)
-            .width(iced::Length::Fill)
-            .into()
+        column![
+            Self::section_title(tr(pm_config.language, Key::AppManager)),
+            managers_list
+        ]
+        .spacing(12)
+        .width(iced::Length::Fill)
+        .into()
     }
 
     fn view_selection_list(
@@ -476,6 +1079,7 @@ impl Settings {
             .map(|t| PackageManagerConfig {
                 manager_type: *t,
                 custom_path: None,
+                custom_args: Vec::new(),
             })
             .collect();
 
@@ -484,22 +1088,20 @@ impl Settings {
             .filter(|manager| self.detected_in_path.contains(&manager.manager_type))
             .count();
 
+        let lang = pm_config.language;
         let detect_tip = if self.is_detecting {
-            "Scanning $PATH...".to_string()
+            tr(lang, Key::ScanningPath).to_owned()
         } else if detected_count == 0 {
-            "Click \"Scan $PATH\" to discover available managers.".to_string()
+            tr(lang, Key::ScanPathTip).to_owned()
         } else {
-            format!(
-                "Detected {} manager(s) in $PATH. They will only be added after manual Add.",
-                detected_count
-            )
+            tr_args(lang, Key::DetectedManagers, &[&detected_count])
         };
 
         let managers_list: iced::Element<'_, Message> = if selection_list.is_empty() {
             column![
-                text("All available package managers have been added")
+                text(tr(lang, Key::AllManagersAdded))
                     .size(16)
-                    .color(app::colors::ON_SURFACE_MUTED)
+                    .color(app::colors::palette().on_surface_muted)
             ]
             .into()
         } else {
@@ -517,9 +1119,9 @@ impl Settings {
                         };
 
                         let action_label = if detected_in_path {
-                            "Add"
+                            tr(lang, Key::Add)
                         } else {
-                            "Select Path"
+                            tr(lang, Key::SelectPath)
                         };
 
                         let add_btn = Self::icon_button(
@@ -551,10 +1153,10 @@ impl Settings {
         };
 
         column![
-            Self::section_title("Add Other Package Manager"),
+            Self::section_title(tr(pm_config.language, Key::AddOtherManager)),
             text(detect_tip)
                 .size(14)
-                .color(app::colors::ON_SURFACE_MUTED),
+                .color(app::colors::palette().on_surface_muted),
             managers_list
         ]
         .spacing(12)
@@ -574,33 +1176,41 @@ impl Settings {
         let name_row = if is_configured {
             row![
                 text(manager.manager_type.name()).size(16),
-                text("✓").size(16).color(app::colors::SUCCESS)
+                text("✓").size(16).color(app::colors::palette().success)
             ]
             .spacing(10)
         } else {
             row![text(manager.manager_type.name()).size(16)].spacing(10)
         };
 
+        let lang = pm_config.language;
         let info_text = if is_configured {
             manager
                 .custom_path
                 .as_ref()
-                .map(|p| format!("Path: {}", p))
-                .unwrap_or_else(|| "Path: $PATH (System Default)".to_string())
+                .map(|p| tr_args(lang, Key::PathValue, &[p]))
+                .unwrap_or_else(|| tr(lang, Key::PathSystemDefault).to_owned())
         } else if detected_in_path {
-            "Detected in $PATH. Click Add to use system default path.".to_string()
+            tr(lang, Key::DetectedInPath).to_owned()
         } else {
-            manager.manager_type.description().to_string()
+            manager
+                .manager_type
+                .description(pm_config.language)
+                .to_string()
         };
 
         let mut content_items = vec![
             name_row.into(),
             text(info_text)
                 .size(14)
-                .color(app::colors::ON_SURFACE_MUTED)
+                .color(app::colors::palette().on_surface_muted)
                 .into(),
         ];
 
+        if is_configured {
+            content_items.push(Self::view_custom_args_input(manager, lang));
+        }
+
         // Go binary configuration.
         if is_configured && manager.manager_type == PackageManagerType::Go {
             content_items.extend(self.view_go_bin_config(pm_config));
@@ -609,47 +1219,903 @@ impl Settings {
         Self::styled_container(column(content_items).spacing(8)).into()
     }
 
+    /// Custom arguments row of a configured manager.
+    fn view_custom_args_input(
+        manager: &PackageManagerConfig,
+        lang: Lang,
+    ) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{row, text, text_input};
+
+        let manager_type = manager.manager_type;
+
+        row![
+            text(tr(lang, Key::ExtraArguments))
+                .size(14)
+                .color(app::colors::palette().on_surface_muted),
+            text_input("--flag, --option=value", &manager.custom_args.join(","))
+                .on_input(move |input| Message::SetCustomArgs(manager_type, input))
+                .padding(8)
+                .size(14),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
     /// Go binary configuration rows.
     fn view_go_bin_config(
         &self,
         pm_config: &updater_core::Config,
     ) -> impl Iterator<Item = iced::Element<'static, Message>> {
         use iced::Alignment;
-        use iced::widget::{row, text};
+        use iced::widget::{column, row, text, text_input};
 
-        let go_bin_info = pm_config
-            .go_bin_dir
-            .as_ref()
-            .map(|dir| format!("Binary Dir: {}", dir))
-            .unwrap_or_else(|| {
-                "Binary Dir: Auto Detect (GOBIN > GOPATH/bin > ~/go/bin)".to_string()
-            });
+        let lang = pm_config.language;
+        let dir_input = text_input(
+            tr(lang, Key::GoBinAutoDetect),
+            pm_config.go_bin_dir.as_deref().unwrap_or_default(),
+        )
+        .on_input(Message::SetGoBinDir)
+        .padding(8)
+        .size(13);
 
-        let info_elem = text(go_bin_info)
-            .size(13)
-            .color(app::colors::ON_SURFACE_ALT)
-            .into();
+        let info_elem = column![
+            row![
+                text(tr(lang, Key::GoBinDir))
+                    .size(13)
+                    .color(app::colors::palette().on_surface_alt),
+                dir_input,
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(4)
+        .push(self.go_bin_dir_error.as_ref().map(|error| {
+            text(error.clone())
+                .size(12)
+                .color(app::colors::palette().error)
+        }))
+        .into();
+
+        let change_btn = Self::secondary_button(
+            tr(lang, Key::ChooseBinaryDir),
+            13.0,
+            Some(Message::OpenGoBinDirDialog),
+        );
 
-        let change_btn =
-            Self::secondary_button("Choose Binary Dir", 13.0, Some(Message::OpenGoBinDirDialog));
+        let add_btn = Self::secondary_button(
+            tr(lang, Key::AddAnotherDir),
+            13.0,
+            Some(Message::OpenGoExtraBinDirDialog),
+        );
 
         let buttons = if pm_config.go_bin_dir.is_some() {
             row![
                 change_btn,
-                Self::secondary_button("Reset As Auto Detect", 13.0, Some(Message::ClearGoBinDir))
+                Self::secondary_button(
+                    tr(lang, Key::ResetAutoDetect),
+                    13.0,
+                    Some(Message::ClearGoBinDir)
+                ),
+                add_btn
             ]
             .spacing(10)
             .align_y(Alignment::Center)
         } else {
-            row![change_btn].spacing(10).align_y(Alignment::Center)
+            row![change_btn, add_btn]
+                .spacing(10)
+                .align_y(Alignment::Center)
         };
 
-        [info_elem, buttons.into()].into_iter()
+        let extra_dirs = pm_config
+            .go_extra_bin_dirs
+            .iter()
+            .enumerate()
+            .map(|(index, dir)| {
+                row![
+                    text(tr_args(lang, Key::AlsoScanned, &[dir]))
+                        .size(13)
+                        .color(app::colors::palette().on_surface_alt),
+                    Self::secondary_button(
+                        tr(lang, Key::Remove),
+                        12.0,
+                        Some(Message::RemoveGoExtraBinDir(index))
+                    )
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .into()
+            })
+            .collect::<Vec<_>>();
+
+        std::iter::once(info_elem)
+            .chain(extra_dirs)
+            .chain(std::iter::once(buttons.into()))
+    }
+
+    /// Parallel install configuration section.
+    fn view_install_section(
+        &self,
+        pm_config: &updater_core::Config,
+    ) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{checkbox, column, row, text, text_input};
+
+        let lang = pm_config.language;
+        let concurrency = pm_config.install_concurrency.max(1);
+        let honored_by = ALL_PACKAGE_MANAGERS
+            .iter()
+            .filter(|manager| manager.supports_concurrent_installs())
+            .map(|manager| manager.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let decrease_btn = Self::secondary_button(
+            "−",
+            14.0,
+            (concurrency > 1).then_some(Message::SetInstallConcurrency(concurrency - 1)),
+        );
+        let increase_btn = Self::secondary_button(
+            "+",
+            14.0,
+            (concurrency < MAX_INSTALL_CONCURRENCY)
+                .then_some(Message::SetInstallConcurrency(concurrency + 1)),
+        );
+
+        let cache_hours = pm_config.crate_cache_ttl_hours;
+        let cache_label = match cache_hours {
+            0 => tr(lang, Key::Off).to_owned(),
+            n => tr_args(lang, Key::Hours, &[&n]),
+        };
+        let decrease_cache_btn = Self::secondary_button(
+            "−",
+            14.0,
+            (cache_hours > 0).then_some(Message::SetCrateCacheTtl(
+                cache_hours.saturating_sub(CRATE_CACHE_TTL_STEP_HOURS),
+            )),
+        );
+        let increase_cache_btn = Self::secondary_button(
+            "+",
+            14.0,
+            Some(Message::SetCrateCacheTtl(
+                cache_hours.saturating_add(CRATE_CACHE_TTL_STEP_HOURS),
+            )),
+        );
+        let clear_cache_btn =
+            Self::secondary_button(tr(lang, Key::Clear), 14.0, Some(Message::ClearCrateCache));
+
+        let cargo_flag_toggles = column(COMMON_CARGO_INSTALL_FLAGS.iter().map(|(flag, label)| {
+            let enabled = pm_config
+                .cargo_install_flags
+                .iter()
+                .any(|existing| existing.trim() == *flag);
+
+            checkbox(enabled)
+                .label(*label)
+                .on_toggle(|enabled| Message::SetCargoInstallFlag(flag, enabled))
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false))
+                .into()
+        }))
+        .spacing(8);
+
+        let update_all_toggles = column(SharedUi::configured_managers(pm_config).into_iter().map(
+            |pm_type| {
+                checkbox(pm_config.exclude_from_update_all.contains(&pm_type))
+                    .label(tr_args(lang, Key::LeaveOutOfUpdateAll, &[&pm_type.name()]))
+                    .on_toggle(move |exclude| Message::SetExcludeFromUpdateAll(pm_type, exclude))
+                    .size(18)
+                    .spacing(8)
+                    .text_size(14)
+                    .style(SharedUi::checkbox_style(false))
+                    .into()
+            },
+        ))
+        .spacing(8);
+
+        let threshold = pm_config.confirm_threshold;
+        let threshold_label = match threshold {
+            0 => tr(lang, Key::Never).to_owned(),
+            n => n.to_string(),
+        };
+        let decrease_threshold_btn = Self::secondary_button(
+            "−",
+            14.0,
+            (threshold > 0).then_some(Message::SetConfirmThreshold(threshold - 1)),
+        );
+        let increase_threshold_btn = Self::secondary_button(
+            "+",
+            14.0,
+            Some(Message::SetConfirmThreshold(threshold.saturating_add(1))),
+        );
+
+        let timeout_minutes = pm_config.command_idle_timeout_secs.div_ceil(60);
+        let timeout_label = match timeout_minutes {
+            0 => tr(lang, Key::Never).to_owned(),
+            n => tr_args(lang, Key::Minutes, &[&n]),
+        };
+        let decrease_timeout_btn = Self::secondary_button(
+            "−",
+            14.0,
+            (timeout_minutes > 0).then_some(Message::SetCommandIdleTimeout(timeout_minutes - 1)),
+        );
+        let increase_timeout_btn = Self::secondary_button(
+            "+",
+            14.0,
+            Some(Message::SetCommandIdleTimeout(
+                timeout_minutes.saturating_add(1),
+            )),
+        );
+
+        let content = column![
+            row![
+                text(tr(lang, Key::ParallelInstalls))
+                    .size(16)
+                    .width(iced::Length::Fill),
+                decrease_btn,
+                text(concurrency.to_string()).size(16),
+                increase_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+            text(tr_args(lang, Key::ConcurrencyHonoredBy, &[&honored_by]))
+                .size(14)
+                .color(app::colors::palette().on_surface_muted),
+            checkbox(pm_config.include_prereleases)
+                .label(tr(pm_config.language, Key::IncludePrereleases))
+                .on_toggle(Message::SetIncludePrereleases)
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false)),
+            checkbox(pm_config.prefer_binstall)
+                .label(tr(pm_config.language, Key::PreferBinstall))
+                .on_toggle(Message::SetPreferBinstall)
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false)),
+            cargo_flag_toggles,
+            row![
+                text(tr(lang, Key::CrateCacheTtl))
+                    .size(16)
+                    .width(iced::Length::Fill),
+                decrease_cache_btn,
+                text(cache_label).size(16),
+                increase_cache_btn,
+                clear_cache_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+            row![
+                text(tr(lang, Key::ConfirmThreshold))
+                    .size(16)
+                    .width(iced::Length::Fill),
+                decrease_threshold_btn,
+                text(threshold_label).size(16),
+                increase_threshold_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+            checkbox(pm_config.confirm_system_always)
+                .label(tr(lang, Key::ConfirmSystemAlways))
+                .on_toggle(Message::SetConfirmSystemAlways)
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false)),
+            checkbox(pm_config.system_manager_readonly)
+                .label(tr(lang, Key::SystemManagerReadonly))
+                .on_toggle(Message::SetSystemManagerReadonly)
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false)),
+            checkbox(pm_config.confirm_close_during_operation)
+                .label(tr(lang, Key::ConfirmCloseDuringOperation))
+                .on_toggle(Message::SetConfirmCloseDuringOperation)
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false)),
+            checkbox(pm_config.dry_run)
+                .label(tr(lang, Key::DryRun))
+                .on_toggle(Message::SetDryRun)
+                .size(18)
+                .spacing(8)
+                .text_size(14)
+                .style(SharedUi::checkbox_style(false)),
+            row![
+                text(tr(lang, Key::IdleTimeout))
+                    .size(16)
+                    .width(iced::Length::Fill),
+                decrease_timeout_btn,
+                text(timeout_label).size(16),
+                increase_timeout_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+            text(tr(lang, Key::IdleTimeoutHint))
+                .size(14)
+                .color(app::colors::palette().on_surface_muted),
+            update_all_toggles,
+            text(tr(lang, Key::ExtraPath)).size(16),
+            text_input(
+                "/opt/homebrew/bin,~/.local/bin",
+                &pm_config.extra_path.join(",")
+            )
+            .on_input(Message::SetExtraPath)
+            .padding(10)
+            .size(15),
+            text(tr(lang, Key::ExtraPathHint))
+                .size(14)
+                .color(app::colors::palette().on_surface_muted),
+        ]
+        .spacing(8);
+
+        column![
+            Self::section_title(tr(lang, Key::Installation)),
+            Self::styled_container(content)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// UI language selection section.
+    fn view_language_section(&self, lang: Lang) -> iced::Element<'static, Message> {
+        use iced::widget::{column, radio};
+
+        let options = column(Lang::ALL.iter().map(|option| {
+            radio(option.name(), *option, Some(lang), Message::SetLanguage)
+                .size(15)
+                .spacing(10)
+                .text_size(15)
+                .style(SharedUi::radio_style)
+                .into()
+        }))
+        .spacing(12);
+
+        column![
+            Self::section_title(tr(lang, Key::Language)),
+            Self::styled_container(options)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Color scheme selection section.
+    fn view_appearance_section(
+        &self,
+        pm_config: &updater_core::Config,
+    ) -> iced::Element<'static, Message> {
+        use iced::widget::{column, radio, row, text};
+
+        let lang = pm_config.language;
+        let options = column(ColorScheme::ALL.iter().map(|scheme| {
+            let label = match scheme {
+                ColorScheme::Standard => Key::ColorStandard,
+                ColorScheme::HighContrast => Key::ColorHighContrast,
+            };
+
+            radio(
+                tr(lang, label),
+                *scheme,
+                Some(pm_config.color_scheme),
+                Message::SetColorScheme,
+            )
+            .size(15)
+            .spacing(10)
+            .text_size(15)
+            .style(SharedUi::radio_style)
+            .into()
+        }))
+        .spacing(12);
+
+        let density_options = row(ListDensity::ALL.iter().map(|density| {
+            let label = match density {
+                ListDensity::Comfortable => Key::DensityComfortable,
+                ListDensity::Compact => Key::DensityCompact,
+            };
+
+            radio(
+                tr(lang, label),
+                *density,
+                Some(pm_config.list_density),
+                Message::SetListDensity,
+            )
+            .size(15)
+            .spacing(10)
+            .text_size(15)
+            .style(SharedUi::radio_style)
+            .into()
+        }))
+        .spacing(24);
+
+        column![
+            Self::section_title(tr(lang, Key::Appearance)),
+            Self::styled_container(
+                column![
+                    options,
+                    text(tr(lang, Key::ListDensity)).size(16),
+                    density_options,
+                ]
+                .spacing(12)
+            )
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Broken/partial install diagnostics section.
+    fn view_diagnostics_section(&self, lang: Lang) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{column, row, text};
+
+        let run_btn = Self::secondary_button(
+            if self.is_diagnosing {
+                "Checking..."
+            } else {
+                "Run Diagnostics"
+            },
+            14.0,
+            (!self.is_diagnosing).then_some(Message::RunDiagnostics),
+        );
+
+        let mut content = column![
+            row![
+                text("Check installed packages for broken or partial installs")
+                    .size(14)
+                    .color(app::colors::palette().on_surface_muted)
+                    .width(iced::Length::Fill),
+                run_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(12);
+
+        if let Some(results) = &self.diagnostics {
+            let problem_count: usize = results
+                .iter()
+                .filter_map(|(_, result)| result.as_ref().ok())
+                .map(Vec::len)
+                .sum();
+
+            if problem_count == 0 && results.iter().all(|(_, result)| result.is_ok()) {
+                content = content.push(
+                    text("✓ No problems found")
+                        .size(14)
+                        .color(app::colors::palette().success),
+                );
+            }
+
+            for (pm_type, result) in results {
+                match result {
+                    Ok(problems) => {
+                        for problem in problems {
+                            content = content.push(Self::view_problem(*pm_type, problem));
+                        }
+                    }
+                    Err(e) => {
+                        content = content.push(
+                            text(format!("{}: diagnostics failed: {}", pm_type.name(), e))
+                                .size(13)
+                                .color(app::colors::palette().error),
+                        );
+                    }
+                }
+            }
+        }
+
+        column![
+            Self::section_title(tr(lang, Key::Diagnostics)),
+            Self::styled_container(content)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    fn view_problem(
+        pm_type: PackageManagerType,
+        problem: &Problem,
+    ) -> iced::Element<'static, Message> {
+        use iced::widget::{column, text};
+
+        let mut item = column![
+            text(format!("{} · {}", pm_type.name(), problem.package)).size(15),
+            text(problem.description.clone())
+                .size(13)
+                .color(app::colors::palette().error),
+        ]
+        .spacing(4);
+
+        if let Some(fix) = &problem.suggested_fix {
+            item = item.push(
+                text(format!("Suggested fix: {}", fix))
+                    .size(13)
+                    .color(app::colors::palette().on_surface_alt),
+            );
+        }
+
+        item.into()
+    }
+
+    /// Inventory snapshot export/compare section.
+    fn view_inventory_section(&self, lang: Lang) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{column, row, text};
+
+        let message = |msg| (!self.is_snapshotting).then_some(msg);
+        let export_btn =
+            Self::secondary_button("Export Snapshot", 14.0, message(Message::ExportInventory));
+        let compare_btn = Self::secondary_button(
+            "Compare With Snapshot",
+            14.0,
+            message(Message::CompareInventory),
+        );
+
+        let mut content = column![
+            row![
+                text(if self.is_snapshotting {
+                    "Reading installed packages..."
+                } else {
+                    "Save installed packages to a file, or see what changed since one"
+                })
+                .size(14)
+                .color(app::colors::palette().on_surface_muted)
+                .width(iced::Length::Fill),
+                export_btn,
+                compare_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(12);
+
+        if let Some(status) = &self.inventory_status {
+            let (message, color) = match status {
+                Ok(message) => (message.clone(), app::colors::palette().success),
+                Err(message) => (message.clone(), app::colors::palette().error),
+            };
+            content = content.push(text(message).size(13).color(color));
+        }
+
+        if let Some((created_at, diff)) = &self.inventory_diff {
+            content = content.push(
+                text(format!("Changes since snapshot from {}", created_at))
+                    .size(15)
+                    .color(app::colors::palette().on_surface),
+            );
+
+            if diff.is_empty() {
+                content = content.push(
+                    text("✓ No changes")
+                        .size(14)
+                        .color(app::colors::palette().success),
+                );
+            }
+
+            for (pm_type, changes) in &diff.managers {
+                content = content.push(
+                    text(pm_type.name())
+                        .size(14)
+                        .color(app::colors::palette().secondary),
+                );
+                for change in changes {
+                    content = content.push(Self::view_package_change(change));
+                }
+            }
+        }
+
+        column![
+            Self::section_title(tr(lang, Key::InventorySnapshots)),
+            Self::styled_container(content)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Status line of a finished manifest import: an error when a manager
+    /// failed, otherwise what was installed and skipped.
+    fn manifest_import_summary(
+        import: &ManifestImport,
+        pm_config: &updater_core::Config,
+    ) -> Result<String, String> {
+        let mut parts = vec![if pm_config.dry_run {
+            format!(
+                "Dry run: would install {} packages",
+                import.installed_count()
+            )
+        } else {
+            format!("Installed {} packages", import.installed_count())
+        }];
+        if import.already_installed > 0 {
+            parts.push(format!("{} already installed", import.already_installed));
+        }
+        if !import.skipped_managers.is_empty() {
+            let names: Vec<_> = import.skipped_managers.iter().map(|pm| pm.name()).collect();
+            parts.push(format!("skipped unconfigured {}", names.join(", ")));
+        }
+        for (pm_type, error) in &import.failed {
+            parts.push(format!("{} failed: {}", pm_type.name(), error));
+        }
+
+        let summary = parts.join("; ");
+        if import.failed.is_empty() {
+            Ok(summary)
+        } else {
+            Err(summary)
+        }
+    }
+
+    /// Package manifest export/import section.
+    fn view_manifest_section(&self, lang: Lang) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{column, row, text};
+
+        let message = |msg| (!self.is_manifest_busy).then_some(msg);
+        let export_btn =
+            Self::secondary_button("Export Manifest", 14.0, message(Message::ExportManifest));
+        let import_btn =
+            Self::secondary_button("Import Manifest", 14.0, message(Message::ImportManifest));
+
+        let mut content = column![
+            row![
+                text(if self.is_manifest_busy {
+                    "Working on the manifest..."
+                } else {
+                    "Save the names of installed packages, or install the missing ones from a saved list"
+                })
+                .size(14)
+                .color(app::colors::palette().on_surface_muted)
+                .width(iced::Length::Fill),
+                export_btn,
+                import_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(12);
+
+        if let Some(status) = &self.manifest_status {
+            let (message, color) = match status {
+                Ok(message) => (message.clone(), app::colors::palette().success),
+                Err(message) => (message.clone(), app::colors::palette().error),
+            };
+            content = content.push(text(message).size(13).color(color));
+        }
+
+        column![
+            Self::section_title(tr(lang, Key::PackageManifest)),
+            Self::styled_container(content)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Brewfile export/import through `brew bundle`; empty unless Homebrew
+    /// is configured.
+    fn view_brewfile_section(&self, pm_config: &Config) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{column, row, text};
+
+        let has_homebrew = pm_config
+            .app_managers
+            .iter()
+            .any(|manager| manager.manager_type == PackageManagerType::Homebrew);
+        if !has_homebrew {
+            return column![].into();
+        }
+
+        let message = |msg| (!self.is_brewfile_busy).then_some(msg);
+        let export_btn =
+            Self::secondary_button("Export Brewfile", 14.0, message(Message::ExportBrewfile));
+        let import_btn =
+            Self::secondary_button("Import Brewfile", 14.0, message(Message::ImportBrewfile));
+
+        let mut content = column![
+            row![
+                text(if self.is_brewfile_busy {
+                    "Running brew bundle..."
+                } else {
+                    "Save taps, formulae and casks as a Brewfile, or install one with brew bundle"
+                })
+                .size(14)
+                .color(app::colors::palette().on_surface_muted)
+                .width(iced::Length::Fill),
+                export_btn,
+                import_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(12);
+
+        if let Some(status) = &self.brewfile_status {
+            let (message, color) = match status {
+                Ok(message) => (message.clone(), app::colors::palette().success),
+                Err(message) => (message.clone(), app::colors::palette().error),
+            };
+            content = content.push(text(message).size(13).color(color));
+        }
+
+        column![
+            Self::section_title(tr(pm_config.language, Key::HomebrewBundle)),
+            Self::styled_container(content)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// DNF transaction history with rollback; empty unless DNF is the
+    /// configured system manager.
+    fn view_history_section(&self, pm_config: &Config) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{column, row, text};
+
+        let is_dnf = pm_config
+            .system_manager
+            .as_ref()
+            .is_some_and(|manager| manager.manager_type == PackageManagerType::Dnf);
+        if !is_dnf {
+            return column![].into();
+        }
+
+        let load_btn = Self::secondary_button(
+            if self.is_loading_history {
+                "Working..."
+            } else if self.transactions.is_some() {
+                "Reload History"
+            } else {
+                "Show History"
+            },
+            14.0,
+            (!self.is_loading_history).then_some(Message::LoadHistory),
+        );
+
+        let mut content = column![
+            row![
+                text("Roll the system back to the state after an earlier DNF transaction")
+                    .size(14)
+                    .color(app::colors::palette().on_surface_muted)
+                    .width(iced::Length::Fill),
+                load_btn,
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(12);
+
+        if let Some(status) = &self.rollback_status {
+            let (message, color) = match status {
+                Ok(message) => (message.clone(), app::colors::palette().success),
+                Err(message) => (message.clone(), app::colors::palette().error),
+            };
+            content = content.push(text(message).size(13).color(color));
+        }
+
+        match &self.transactions {
+            Some(Ok(transactions)) if transactions.is_empty() => {
+                content = content.push(
+                    text("No transactions recorded")
+                        .size(14)
+                        .color(app::colors::palette().on_surface_muted),
+                );
+            }
+            Some(Ok(transactions)) => {
+                for transaction in transactions.iter().take(HISTORY_LIMIT) {
+                    content = content.push(self.view_transaction(transaction));
+                }
+            }
+            Some(Err(e)) => {
+                content = content.push(
+                    text(format!("Failed to read DNF history: {}", e))
+                        .size(13)
+                        .color(app::colors::palette().error),
+                );
+            }
+            None => {}
+        }
+
+        column![
+            Self::section_title(tr(pm_config.language, Key::TransactionHistory)),
+            Self::styled_container(content)
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    fn view_transaction(&self, transaction: &DnfTransaction) -> iced::Element<'static, Message> {
+        use iced::Alignment;
+        use iced::widget::{column, row, text};
+
+        let mut details = format!("{} · {} packages", transaction.date, transaction.altered);
+        if !transaction.actions.is_empty() {
+            details.push_str(&format!(" · {}", transaction.actions));
+        }
+
+        let summary = column![
+            text(format!("#{} {}", transaction.id, transaction.command)).size(15),
+            text(details)
+                .size(13)
+                .color(app::colors::palette().on_surface_muted),
+        ]
+        .spacing(4)
+        .width(iced::Length::Fill);
+
+        let actions = if self.pending_rollback == Some(transaction.id) {
+            row![
+                text("Undo every later transaction?")
+                    .size(13)
+                    .color(app::colors::palette().warning),
+                Self::secondary_button("Cancel", 14.0, Some(Message::CancelRollback)),
+                Self::secondary_button("Roll Back", 14.0, Some(Message::ConfirmRollback)),
+            ]
+        } else {
+            row![Self::secondary_button(
+                "Roll back to here",
+                14.0,
+                (!self.is_loading_history).then_some(Message::RequestRollback(transaction.id)),
+            )]
+        };
+
+        row![summary, actions.spacing(8).align_y(Alignment::Center)]
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    fn view_package_change(change: &PackageChange) -> iced::Element<'static, Message> {
+        use iced::widget::text;
+
+        let (line, color) = match change {
+            PackageChange::Added { name, version } => (
+                format!("+ {} {}", name, version),
+                app::colors::palette().success,
+            ),
+            PackageChange::Removed { name, version } => (
+                format!("− {} {}", name, version),
+                app::colors::palette().error,
+            ),
+            PackageChange::Upgraded { name, from, to } => (
+                format!("↑ {} {} → {}", name, from, to),
+                app::colors::palette().on_surface,
+            ),
+            PackageChange::Downgraded { name, from, to } => (
+                format!("↓ {} {} → {}", name, from, to),
+                app::colors::palette().warning,
+            ),
+        };
+
+        text(line).size(13).color(color).into()
+    }
+
+    /// "Detected: ...; not found: ..." line for a finished `$PATH` scan.
+    fn detect_summary(detected: &[PackageManagerType]) -> String {
+        let names = |found: bool| {
+            ALL_APP_PACKAGE_MANAGERS
+                .iter()
+                .filter(|manager| detected.contains(manager) == found)
+                .map(|manager| manager.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match (names(true), names(false)) {
+            (found, missing) if found.is_empty() => {
+                format!("Detected nothing; not found: {}", missing)
+            }
+            (found, missing) if missing.is_empty() => format!("Detected: {}", found),
+            (found, missing) => format!("Detected: {}; not found: {}", found, missing),
+        }
     }
 
     /// Action buttons row.
-    fn view_buttons(&self) -> iced::Element<'static, Message> {
-        use iced::widget::{container, row, svg};
+    fn view_buttons(&self, lang: Lang) -> iced::Element<'static, Message> {
+        use iced::widget::{column, container, row, svg, text};
 
         let detect_msg = if self.is_detecting {
             None
@@ -658,9 +2124,9 @@ impl Settings {
         };
 
         let detect_label = if self.is_detecting {
-            "Scanning $PATH..."
+            tr(lang, Key::ScanningPath)
         } else {
-            "Scan $PATH"
+            tr(lang, Key::ScanPath)
         };
 
         let detect_btn = Self::icon_button(
@@ -676,9 +2142,9 @@ impl Settings {
             Some(Message::SaveConfig)
         };
         let save_label = if self.is_saving {
-            "Saving..."
+            tr(lang, Key::Saving)
         } else {
-            "Save Configuration"
+            tr(lang, Key::SaveConfiguration)
         };
 
         let save_btn = Self::icon_button(
@@ -688,9 +2154,25 @@ impl Settings {
             save_msg,
         );
 
-        container(row![detect_btn, save_btn].spacing(16))
-            .padding([0, 20])
-            .into()
+        let open_folder_btn = Self::secondary_button(
+            tr(lang, Key::OpenConfigFolder),
+            16.0,
+            Some(Message::OpenConfigFolder),
+        );
+
+        let summary = self.detect_summary.as_ref().map(|summary| {
+            text(summary.clone())
+                .size(14)
+                .color(app::colors::palette().on_surface_muted)
+        });
+
+        container(
+            column![row![detect_btn, save_btn, open_folder_btn].spacing(16)]
+                .push(summary)
+                .spacing(8),
+        )
+        .padding([0, 20])
+        .into()
     }
 
     /// Save status view.
@@ -704,13 +2186,13 @@ impl Settings {
             let (message, color, border_color) = match status {
                 SaveStatus::Success => (
                     "✓ Successfully Saved".to_string(),
-                    app::colors::SUCCESS,
-                    app::colors::SUCCESS,
+                    app::colors::palette().success,
+                    app::colors::palette().success,
                 ),
                 SaveStatus::Error(e) => (
                     format!("✗ Failed To Save: {}", e),
-                    app::colors::ERROR,
-                    app::colors::ERROR,
+                    app::colors::palette().error,
+                    app::colors::palette().error,
                 ),
             };
 
@@ -734,29 +2216,156 @@ impl Settings {
         }
     }
 
-    fn save_config(
-        &self,
-        system_manager: Option<&PackageManagerConfig>,
-        app_managers: &[PackageManagerConfig],
-        go_bin_dir: Option<&String>,
-    ) -> Action {
-        let system_manager = system_manager.cloned();
-        let app_managers = app_managers.to_vec();
-        let go_bin_dir = go_bin_dir.cloned();
+    fn save_config(&self, pm_config: &updater_core::Config) -> Action {
+        let config = pm_config.clone();
 
         let task = iced::Task::perform(
-            async move {
-                let config = updater_core::Config {
-                    system_manager,
-                    app_managers,
-                    go_bin_dir,
-                };
-
-                config.save().await.map_err(|e| e.to_string())
-            },
+            async move { config.save().await.map_err(|e| e.to_string()) },
             Message::SaveConfigResult,
         );
 
         Action::Run(task)
     }
 }
+
+/// Open the config directory with the platform file manager.
+async fn open_config_folder() -> Result<(), String> {
+    let dir = Config::config_dir().map_err(|e| e.to_string())?;
+    // The directory may not exist before the first save.
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    open_with_system(&dir).await
+}
+
+/// Save a snapshot of installed packages to a user-chosen file.
+async fn export_inventory(config: Config) -> Result<Option<String>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Export Inventory Snapshot")
+        .set_file_name("inventory.json")
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let inventory = Inventory::collect(&config).await;
+    inventory
+        .save(handle.path())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(handle.path().display().to_string()))
+}
+
+/// Save the names of installed packages to a user-chosen manifest file.
+async fn export_manifest(config: Config) -> Result<Option<String>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Export Package Manifest")
+        .set_file_name("packages.json")
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let manifest = config.export_manifest().await.map_err(|e| e.to_string())?;
+    manifest
+        .save(handle.path())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(handle.path().display().to_string()))
+}
+
+/// Install the packages of a user-chosen manifest file that are missing.
+async fn import_manifest(config: Config) -> ManifestImportResult {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Import Package Manifest")
+        .add_filter("JSON", &["json"])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let manifest = Manifest::load(handle.path())
+        .await
+        .map_err(|e| e.to_string())?;
+    let import = config
+        .import_manifest(&manifest, |progress| {
+            if let Some(message) = progress.command_message {
+                log::info!("[{}] {}", progress.manager.name(), message);
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(import))
+}
+
+/// Save `brew bundle dump` output to a user-chosen Brewfile.
+async fn export_brewfile(config: Config) -> Result<Option<String>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Export Brewfile")
+        .set_file_name("Brewfile")
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let brewfile = PackageManagerType::Homebrew
+        .export_brewfile(&config)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::fs::write(handle.path(), brewfile)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(handle.path().display().to_string()))
+}
+
+/// Install what a user-chosen Brewfile lists through `brew bundle`.
+async fn import_brewfile(config: Config) -> Result<Option<String>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Import Brewfile")
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    PackageManagerType::Homebrew
+        .import_brewfile(&config, handle.path(), |progress| {
+            if let Some(message) = progress.command_message {
+                log::info!("[Homebrew] {}", message);
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(handle.path().display().to_string()))
+}
+
+/// Diff a user-chosen snapshot file against the currently installed packages.
+async fn compare_inventory(config: Config) -> InventoryCompareResult {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Compare With Inventory Snapshot")
+        .add_filter("JSON", &["json"])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let snapshot = Inventory::load(handle.path())
+        .await
+        .map_err(|e| e.to_string())?;
+    let current = Inventory::collect(&config).await;
+
+    Ok(Some((snapshot.created_at.clone(), snapshot.diff(&current))))
+}
@@ -1,16 +1,66 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+    time::Instant,
+};
 
 use iced::widget::{column, container, text, text_input};
 use iced::{Border, Element};
-use updater_core::{Config, PackageManagerType};
+use updater_core::{
+    CancelToken, Config, PackageManagerType,
+    i18n::{Key, Lang, tr},
+};
 
 use crate::{
     app,
     content::errors::{ManagerErrors, joined_manager_names},
+    content::workflows::{CommandPreview, OperationError, OperationOutput},
 };
 
 pub type PackageSelectionKey = (PackageManagerType, String);
 
+/// Rows a manager section renders at first and adds per "Show more" click;
+/// building thousands of rows at once makes the page sluggish.
+pub const SECTION_PAGE_SIZE: usize = 100;
+
+/// Keyboard commands for the active page's package list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKey {
+    /// Focus the row above.
+    Previous,
+    /// Focus the row below.
+    Next,
+    /// Toggle the focused row's checkbox.
+    Toggle,
+}
+
+impl ListKey {
+    /// List command for a key press no widget consumed, so typing in a
+    /// search box never moves the focus.
+    pub fn from_event(event: iced::keyboard::Event) -> Option<Self> {
+        use iced::keyboard::{Event, Key, key::Named};
+
+        let Event::KeyPressed { key, modifiers, .. } = event else {
+            return None;
+        };
+        if modifiers.command() || modifiers.alt() {
+            return None;
+        }
+
+        match key.as_ref() {
+            Key::Named(Named::ArrowUp) => Some(Self::Previous),
+            Key::Named(Named::ArrowDown) => Some(Self::Next),
+            Key::Named(Named::Space | Named::Enter) => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+}
+
+/// Reference point for `SharedUi::spinner_text` frames.
+static SPINNER_START: LazyLock<Instant> = LazyLock::new(Instant::now);
+/// Duration of one spinner frame.
+const SPINNER_FRAME_MS: u128 = 300;
+
 /// Shared UI helpers for Installed/Updates pages.
 pub struct SharedUi;
 
@@ -19,19 +69,29 @@ impl SharedUi {
         (pm_type, package_name.to_owned())
     }
 
+    /// Configured managers in stable `PackageManagerType` order.
     pub fn configured_managers(pm_config: &Config) -> Vec<PackageManagerType> {
-        pm_config
+        let mut managers: Vec<_> = pm_config
             .system_manager
             .iter()
             .map(|pm| pm.manager_type)
             .chain(pm_config.app_managers.iter().map(|pm| pm.manager_type))
-            .collect()
+            .collect();
+        managers.sort();
+        managers
+    }
+
+    /// Selected managers in stable order, for rendering per-manager sections.
+    pub fn sorted_managers(managers: &HashSet<PackageManagerType>) -> Vec<PackageManagerType> {
+        let mut managers: Vec<_> = managers.iter().copied().collect();
+        managers.sort();
+        managers
     }
 
     pub fn section_title(text: &'static str) -> iced::widget::Text<'static> {
         iced::widget::text(text)
             .size(16)
-            .color(app::colors::ON_SURFACE)
+            .color(app::colors::palette().on_surface)
     }
 
     pub fn styled_container<'a, Message>(
@@ -41,9 +101,9 @@ impl SharedUi {
             .padding(16)
             .width(iced::Length::Fill)
             .style(|_theme: &iced::Theme| container::Style {
-                background: Some(app::colors::SURFACE.into()),
+                background: Some(app::colors::palette().surface.into()),
                 border: Border {
-                    color: app::colors::DIVIDER,
+                    color: app::colors::palette().divider,
                     width: 1.0,
                     radius: 8.0.into(),
                 },
@@ -63,16 +123,22 @@ impl SharedUi {
             match status {
                 iced::widget::checkbox::Status::Active { is_checked } => {
                     let (icon_color, border_color) = if is_checked {
-                        (app::colors::ON_PRIMARY, app::colors::SECONDARY)
+                        (
+                            app::colors::palette().on_primary,
+                            app::colors::palette().secondary,
+                        )
                     } else {
-                        (app::colors::ON_SURFACE_MUTED, app::colors::DIVIDER)
+                        (
+                            app::colors::palette().on_surface_muted,
+                            app::colors::palette().divider,
+                        )
                     };
 
                     Style {
                         background: if is_checked {
-                            app::colors::SECONDARY.into()
+                            app::colors::palette().secondary.into()
                         } else {
-                            app::colors::SURFACE.into()
+                            app::colors::palette().surface.into()
                         },
                         icon_color,
                         border: Border {
@@ -81,36 +147,36 @@ impl SharedUi {
                             radius: 4.0.into(),
                         },
                         text_color: if is_loading {
-                            Some(app::colors::ON_SURFACE_MUTED)
+                            Some(app::colors::palette().on_surface_muted)
                         } else {
-                            Some(app::colors::ON_SURFACE)
+                            Some(app::colors::palette().on_surface)
                         },
                     }
                 }
                 iced::widget::checkbox::Status::Hovered { is_checked } => {
                     if is_loading {
                         Style {
-                            background: app::colors::SURFACE.into(),
-                            icon_color: app::colors::ON_SURFACE_MUTED,
+                            background: app::colors::palette().surface.into(),
+                            icon_color: app::colors::palette().on_surface_muted,
                             border: Border {
-                                color: app::colors::DIVIDER,
+                                color: app::colors::palette().divider,
                                 width: 2.0,
                                 radius: 4.0.into(),
                             },
-                            text_color: Some(app::colors::ON_SURFACE_MUTED),
+                            text_color: Some(app::colors::palette().on_surface_muted),
                         }
                     } else {
                         let (icon_color, border_color, bg_color) = if is_checked {
                             (
-                                app::colors::ON_PRIMARY,
-                                app::colors::SECONDARY_HOVER,
-                                app::colors::SECONDARY_HOVER,
+                                app::colors::palette().on_primary,
+                                app::colors::palette().secondary_hover,
+                                app::colors::palette().secondary_hover,
                             )
                         } else {
                             (
-                                app::colors::ON_SURFACE_MUTED,
-                                app::colors::SECONDARY,
-                                app::colors::SURFACE,
+                                app::colors::palette().on_surface_muted,
+                                app::colors::palette().secondary,
+                                app::colors::palette().surface,
                             )
                         };
 
@@ -122,19 +188,19 @@ impl SharedUi {
                                 width: 2.0,
                                 radius: 4.0.into(),
                             },
-                            text_color: Some(app::colors::ON_SURFACE),
+                            text_color: Some(app::colors::palette().on_surface),
                         }
                     }
                 }
                 iced::widget::checkbox::Status::Disabled { .. } => Style {
-                    background: app::colors::SURFACE.into(),
-                    icon_color: app::colors::ON_SURFACE_MUTED,
+                    background: app::colors::palette().surface.into(),
+                    icon_color: app::colors::palette().on_surface_muted,
                     border: Border {
-                        color: app::colors::DIVIDER,
+                        color: app::colors::palette().divider,
                         width: 2.0,
                         radius: 4.0.into(),
                     },
-                    text_color: Some(app::colors::ON_SURFACE_MUTED),
+                    text_color: Some(app::colors::palette().on_surface_muted),
                 },
             }
         }
@@ -149,47 +215,89 @@ impl SharedUi {
         match status {
             iced::widget::radio::Status::Active { is_selected } => {
                 let (dot_color, border_color) = if is_selected {
-                    (app::colors::SECONDARY, app::colors::SECONDARY)
+                    (
+                        app::colors::palette().secondary,
+                        app::colors::palette().secondary,
+                    )
                 } else {
-                    (app::colors::ON_SURFACE_MUTED, app::colors::DIVIDER)
+                    (
+                        app::colors::palette().on_surface_muted,
+                        app::colors::palette().divider,
+                    )
                 };
 
                 Style {
-                    background: app::colors::SURFACE.into(),
+                    background: app::colors::palette().surface.into(),
                     dot_color,
                     border_width: 2.0,
                     border_color,
-                    text_color: Some(app::colors::ON_SURFACE),
+                    text_color: Some(app::colors::palette().on_surface),
                 }
             }
             iced::widget::radio::Status::Hovered { is_selected } => {
                 let (dot_color, border_color) = if is_selected {
-                    (app::colors::SECONDARY_HOVER, app::colors::SECONDARY_HOVER)
+                    (
+                        app::colors::palette().secondary_hover,
+                        app::colors::palette().secondary_hover,
+                    )
                 } else {
-                    (app::colors::ON_SURFACE_MUTED, app::colors::SECONDARY)
+                    (
+                        app::colors::palette().on_surface_muted,
+                        app::colors::palette().secondary,
+                    )
                 };
 
                 Style {
-                    background: app::colors::SURFACE.into(),
+                    background: app::colors::palette().surface.into(),
                     dot_color,
                     border_width: 2.0,
                     border_color,
-                    text_color: Some(app::colors::ON_SURFACE),
+                    text_color: Some(app::colors::palette().on_surface),
                 }
             }
         }
     }
 
+    /// `label` followed by dots that cycle while the view redraws. Redraws
+    /// come from the status panel's frame subscription, which only runs
+    /// while something is loading, so the dots stop with the work.
+    pub fn spinner_text(label: &str) -> String {
+        let frame = SPINNER_START.elapsed().as_millis() / SPINNER_FRAME_MS;
+        let dots = (frame % 4) as usize;
+        format!("{}{}{}", label, ".".repeat(dots), " ".repeat(3 - dots))
+    }
+
+    /// Centered `spinner_text`, for pages waiting on their first results.
+    pub fn spinner<'a, Message>(label: &str) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        container(
+            text(Self::spinner_text(label))
+                .size(16)
+                .color(app::colors::palette().on_surface_muted),
+        )
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .center_x(iced::Length::Fill)
+        .center_y(iced::Length::Fill)
+        .into()
+    }
+
     pub fn centered_message<'a, Message>(message: &'a str) -> Element<'a, Message>
     where
         Message: 'a,
     {
-        container(text(message).size(16).color(app::colors::ON_SURFACE_MUTED))
-            .width(iced::Length::Fill)
-            .height(iced::Length::Fill)
-            .center_x(iced::Length::Fill)
-            .center_y(iced::Length::Fill)
-            .into()
+        container(
+            text(message)
+                .size(16)
+                .color(app::colors::palette().on_surface_muted),
+        )
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .center_x(iced::Length::Fill)
+        .center_y(iced::Length::Fill)
+        .into()
     }
 
     pub fn filter_section<'a, Message>(
@@ -240,7 +348,7 @@ impl SharedUi {
                 joined_manager_names(init_errors)
             ))
             .size(13)
-            .color(app::colors::ERROR)
+            .color(app::colors::palette().error)
             .into()
         });
 
@@ -277,8 +385,12 @@ impl SharedUi {
         use iced::widget::{column, row};
 
         let header = row![
-            text(pm_type.name()).size(18).color(app::colors::SECONDARY),
-            text(subtitle).size(16).color(app::colors::ON_SURFACE_MUTED)
+            text(pm_type.name())
+                .size(18)
+                .color(app::colors::palette().secondary),
+            text(subtitle)
+                .size(16)
+                .color(app::colors::palette().on_surface_muted)
         ]
         .spacing(10)
         .align_y(iced::Alignment::Center);
@@ -289,7 +401,7 @@ impl SharedUi {
                 Self::styled_container(
                     text(format!("{}: {}", error_prefix, error))
                         .size(14)
-                        .color(app::colors::ERROR)
+                        .color(app::colors::palette().error)
                 )
             ]
             .spacing(12)
@@ -307,7 +419,7 @@ impl SharedUi {
 
     pub fn loading_manager_filter_view<'a, Message>(
         pm_config: &Config,
-        loading_text: &'static str,
+        loading_text: String,
     ) -> iced::widget::Column<'a, Message>
     where
         Message: 'a,
@@ -321,7 +433,7 @@ impl SharedUi {
         let mut col_items: Vec<iced::Element<'a, Message>> = vec![
             text(loading_text)
                 .size(13)
-                .color(app::colors::ON_SURFACE_MUTED)
+                .color(app::colors::palette().on_surface_muted)
                 .into(),
         ];
 
@@ -333,14 +445,14 @@ impl SharedUi {
                 .style(move |_theme, _status| {
                     use iced::widget::checkbox::Style;
                     Style {
-                        background: app::colors::SURFACE.into(),
-                        icon_color: app::colors::ON_SURFACE_MUTED,
+                        background: app::colors::palette().surface.into(),
+                        icon_color: app::colors::palette().on_surface_muted,
                         border: Border {
-                            color: app::colors::DIVIDER,
+                            color: app::colors::palette().divider,
                             width: 2.0,
                             radius: 4.0.into(),
                         },
-                        text_color: Some(app::colors::ON_SURFACE_MUTED),
+                        text_color: Some(app::colors::palette().on_surface_muted),
                     }
                 })
                 .into()
@@ -356,27 +468,40 @@ impl SharedUi {
     where
         Message: 'a,
     {
-        column![text(message).size(14).color(app::colors::ON_SURFACE_MUTED)].spacing(8)
+        column![
+            text(message)
+                .size(14)
+                .color(app::colors::palette().on_surface_muted)
+        ]
+        .spacing(8)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn active_manager_filter_view<'a, Message>(
         entries: Vec<(PackageManagerType, usize)>,
         selected_managers: &'a HashSet<PackageManagerType>,
         loading_managers: &'a HashSet<PackageManagerType>,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
         is_initializing: impl Fn(PackageManagerType) -> bool + Copy + 'a,
         on_toggle: impl Fn(PackageManagerType, bool) -> Message + Copy + 'a,
+        on_fix: Message,
     ) -> iced::widget::Column<'a, Message>
     where
-        Message: 'a,
+        Message: 'a + Clone,
     {
         column(entries.into_iter().map(move |(pm_type, count)| {
+            if unavailable_managers.contains(&pm_type) {
+                return Self::unavailable_manager_row(pm_type, on_fix.clone());
+            }
+
             let is_selected = selected_managers.contains(&pm_type);
             let is_loading = loading_managers.contains(&pm_type);
             let is_initializing = is_initializing(pm_type);
-            let is_disabled = is_loading || is_initializing;
+            // Loading managers stay toggleable so a slow load can be deselected.
+            let is_disabled = is_initializing;
 
             let label = if is_loading {
-                format!("{} (Loading...)", pm_type.name())
+                format!("{} ({})", pm_type.name(), Self::spinner_text("Loading"))
             } else if is_initializing {
                 format!("{} (Initializing...)", pm_type.name())
             } else {
@@ -400,8 +525,78 @@ impl SharedUi {
         .spacing(12)
     }
 
-    pub fn refresh_button_with_label<'a, Message>(
+    /// "Hide managers with a count of 0" checkbox under the manager filter.
+    pub fn hide_empty_managers_toggle<'a, Message>(
+        hide_empty_managers: bool,
         label: &'static str,
+        on_toggle: impl Fn(bool) -> Message + 'a,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        iced::widget::checkbox(hide_empty_managers)
+            .label(label)
+            .on_toggle(on_toggle)
+            .size(16)
+            .spacing(8)
+            .text_size(13)
+            .style(Self::checkbox_style(false))
+            .into()
+    }
+
+    /// Disabled filter entry for a manager whose binary is missing, with a
+    /// tooltip and a button leading to Settings to fix its path.
+    pub fn unavailable_manager_row<'a, Message>(
+        pm_type: PackageManagerType,
+        on_fix: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::{button, row, tooltip};
+
+        let checkbox = iced::widget::checkbox(false)
+            .label(format!("{} (unavailable)", pm_type.name()))
+            .spacing(10)
+            .text_size(15)
+            .style(Self::checkbox_style(true));
+
+        let hint = container(
+            text(format!(
+                "{} was not found on PATH or at its configured path",
+                pm_type.command()
+            ))
+            .size(13),
+        )
+        .padding([4, 8])
+        .style(|_theme: &iced::Theme| container::Style {
+            background: Some(app::colors::palette().surface.into()),
+            border: Border {
+                color: app::colors::palette().divider,
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            text_color: Some(app::colors::palette().on_surface),
+            ..Default::default()
+        });
+
+        let fix = button(
+            text("Fix in Settings")
+                .size(13)
+                .color(app::colors::palette().secondary),
+        )
+        .on_press(on_fix)
+        .padding([0, 4])
+        .style(|_theme, _status| iced::widget::button::Style::default());
+
+        row![tooltip(checkbox, hint, tooltip::Position::Top), fix]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    pub fn refresh_button_with_label<'a, Message>(
+        label: impl iced::widget::text::IntoFragment<'a>,
         message: Message,
     ) -> Element<'a, Message>
     where
@@ -413,10 +608,12 @@ impl SharedUi {
             .padding([8, 16])
             .style(|_theme, status| {
                 use iced::widget::button::Style;
-                let base_color = app::colors::SECONDARY;
+                let base_color = app::colors::palette().secondary;
                 match status {
                     button::Status::Hovered => Style {
-                        background: Some(iced::Background::Color(app::colors::SECONDARY_HOVER)),
+                        background: Some(iced::Background::Color(
+                            app::colors::palette().secondary_hover,
+                        )),
                         text_color: iced::Color::WHITE,
                         border: Border {
                             color: iced::Color::TRANSPARENT,
@@ -441,6 +638,321 @@ impl SharedUi {
             .into()
     }
 
+    /// Error line with a "Retry" button that re-dispatches the failed operation.
+    pub fn error_with_retry<'a, Message>(
+        error: String,
+        retry: Option<Message>,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::row;
+
+        let error = text(error)
+            .size(13)
+            .color(app::colors::palette().error)
+            .width(iced::Length::Fill);
+
+        match retry {
+            Some(retry) => row![error, Self::refresh_button_with_label("Retry", retry)]
+                .spacing(12)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            None => error.into(),
+        }
+    }
+
+    /// Outcome line of an unfinished batch operation: a muted note when the
+    /// user cancelled it, otherwise the error prefixed with `failed` and a
+    /// "Retry" button.
+    pub fn operation_error_view<'a, Message>(
+        lang: Lang,
+        error: &OperationError,
+        failed: &str,
+        retry: Option<Message>,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        match error {
+            OperationError::Cancelled => text(tr(lang, Key::OperationCancelled))
+                .size(13)
+                .color(app::colors::palette().on_surface_muted)
+                .into(),
+            OperationError::Failed(error) => {
+                Self::error_with_retry(format!("{}: {}", failed, error), retry)
+            }
+        }
+    }
+
+    /// "Cancel" button for a running operation; disabled once cancelled,
+    /// while the running command is being stopped.
+    pub fn cancel_operation_button<'a, Message>(
+        lang: Lang,
+        cancel: Option<&CancelToken>,
+        on_cancel: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::button;
+
+        let cancelling = cancel.is_none_or(CancelToken::is_cancelled);
+        let label = tr(
+            lang,
+            if cancelling {
+                Key::Cancelling
+            } else {
+                Key::Cancel
+            },
+        );
+
+        button(text(label).size(14))
+            .padding([8, 16])
+            .on_press_maybe((!cancelling).then_some(on_cancel))
+            .style(|_theme, status| button::Style {
+                background: Some(
+                    if matches!(status, button::Status::Hovered) {
+                        app::colors::palette().surface_hover
+                    } else {
+                        app::colors::palette().surface
+                    }
+                    .into(),
+                ),
+                text_color: app::colors::palette().on_surface,
+                border: Border {
+                    color: app::colors::palette().divider,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Commands of a previewed operation (see `Config::dry_run`), with
+    /// "Cancel" and "Run" buttons.
+    pub fn command_preview_view<'a, Message>(
+        preview: &CommandPreview,
+        on_run: Message,
+        on_cancel: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::{button, row};
+
+        let prompt = if preview.commands.is_empty() {
+            "Nothing to run.".to_owned()
+        } else {
+            format!("Run {} command(s)?", preview.commands.len())
+        };
+        let commands = column(preview.commands.iter().map(|command| {
+            text(command.clone())
+                .size(13)
+                .font(iced::Font::MONOSPACE)
+                .into()
+        }))
+        .spacing(4);
+
+        let cancel = button(text("Cancel").size(14))
+            .padding([8, 16])
+            .on_press(on_cancel)
+            .style(|_theme, status| button::Style {
+                background: Some(
+                    if matches!(status, button::Status::Hovered) {
+                        app::colors::palette().surface_hover
+                    } else {
+                        app::colors::palette().surface
+                    }
+                    .into(),
+                ),
+                text_color: app::colors::palette().on_surface,
+                border: Border {
+                    color: app::colors::palette().divider,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            });
+
+        let buttons = row![text(prompt).size(14).width(iced::Length::Fill), cancel]
+            .push(
+                (!preview.commands.is_empty())
+                    .then(|| Self::refresh_button_with_label("Run", on_run)),
+            )
+            .spacing(12)
+            .align_y(iced::Alignment::Center);
+
+        column![buttons, commands].spacing(8).into()
+    }
+
+    /// "Copy commands" / "Save script" buttons for the selection, with the
+    /// last export's status line (`Ok` is a confirmation, `Err` an error).
+    pub fn export_script_view<'a, Message>(
+        status: Option<&Result<String, String>>,
+        on_copy: Message,
+        on_save: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::row;
+
+        let status = status.map(|status| {
+            let (line, color) = match status {
+                Ok(line) => (line.clone(), app::colors::palette().on_surface_muted),
+                Err(e) => (
+                    format!("Export failed: {}", e),
+                    app::colors::palette().error,
+                ),
+            };
+            text(line).size(13).color(color)
+        });
+
+        row![
+            Self::refresh_button_with_label("Copy install commands", on_copy),
+            Self::refresh_button_with_label("Save as script", on_save),
+        ]
+        .push(status)
+        .spacing(12)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Rows currently shown in a manager section, see `SECTION_PAGE_SIZE`.
+    pub fn visible_rows(
+        visible_counts: &HashMap<PackageManagerType, usize>,
+        pm_type: PackageManagerType,
+    ) -> usize {
+        visible_counts
+            .get(&pm_type)
+            .copied()
+            .unwrap_or(SECTION_PAGE_SIZE)
+    }
+
+    /// The first `visible` of `items` as rows, followed by a "Show N more"
+    /// button when some are left out.
+    pub fn truncated_rows<'a, T, Message>(
+        items: Vec<T>,
+        visible: usize,
+        row_view: impl Fn(T) -> Element<'a, Message>,
+        on_show_more: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::button;
+
+        let hidden = items.len().saturating_sub(visible);
+        let mut rows = column(items.into_iter().take(visible).map(row_view)).spacing(8);
+
+        if hidden > 0 {
+            let show_more = button(
+                text(format!(
+                    "Show {} more ({} hidden)",
+                    hidden.min(SECTION_PAGE_SIZE),
+                    hidden
+                ))
+                .size(13)
+                .color(app::colors::palette().secondary),
+            )
+            .on_press(on_show_more)
+            .padding([4, 0])
+            .style(|_theme, _status| iced::widget::button::Style::default());
+            rows = rows.push(show_more);
+        }
+
+        rows.into()
+    }
+
+    /// `focused` moved by `key` within `len` rows. The first arrow press
+    /// focuses the first (or last) row; `Toggle` keeps the focus.
+    pub fn move_focus(focused: Option<usize>, len: usize, key: ListKey) -> Option<usize> {
+        let last = len.checked_sub(1)?;
+
+        match (focused.map(|index| index.min(last)), key) {
+            (None, ListKey::Previous) => Some(last),
+            (None, ListKey::Next) => Some(0),
+            (None, ListKey::Toggle) => None,
+            (Some(index), ListKey::Previous) => Some(index.saturating_sub(1)),
+            (Some(index), ListKey::Next) => Some((index + 1).min(last)),
+            (Some(index), ListKey::Toggle) => Some(index),
+        }
+    }
+
+    /// Scroll the list `id` so row `index` of `len` comes into view. Rows
+    /// are taken as evenly spread; section headers make this approximate.
+    pub fn scroll_to_row<Message>(
+        id: &'static str,
+        index: usize,
+        len: usize,
+    ) -> iced::Task<Message> {
+        use iced::widget::scrollable::RelativeOffset;
+
+        let y = if len > 1 {
+            index as f32 / (len - 1) as f32
+        } else {
+            0.0
+        };
+
+        iced::widget::operation::snap_to(iced::widget::Id::new(id), RelativeOffset { x: 0.0, y })
+    }
+
+    /// Human-readable byte count, e.g. `128 MB`.
+    pub fn format_size(bytes: u64) -> String {
+        const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+        let mut size = bytes as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+
+        match unit {
+            0 => format!("{} B", bytes),
+            _ if size >= 10.0 => format!("{:.0} {}", size, UNITS[unit]),
+            _ => format!("{:.1} {}", size, UNITS[unit]),
+        }
+    }
+
+    /// Rounded duration estimate, e.g. `~5 min`.
+    pub fn format_estimate(estimate: std::time::Duration) -> String {
+        let secs = estimate.as_secs();
+        match secs {
+            0..60 => "<1 min".to_string(),
+            60..3600 => format!("~{} min", secs.div_ceil(60)),
+            _ => format!("~{:.1} h", secs as f64 / 3600.0),
+        }
+    }
+
+    /// Package row with the keyboard focus highlight when `focused`.
+    pub fn focusable_row<'a, Message>(
+        row: impl Into<Element<'a, Message>>,
+        focused: bool,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        if !focused {
+            return row.into();
+        }
+
+        container(row)
+            .padding([0, 8])
+            .style(|_theme: &iced::Theme| container::Style {
+                background: Some(app::colors::palette().surface_hover.into()),
+                border: Border {
+                    color: app::colors::palette().focus,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+
     pub fn refresh_button<'a, Message>(message: Message) -> Element<'a, Message>
     where
         Message: 'a + Clone,
@@ -448,6 +960,214 @@ impl SharedUi {
         Self::refresh_button_with_label("Refresh", message)
     }
 
+    /// Wraps the disabled selection/action controls of a read-only system
+    /// manager's row, explaining on hover why they are grayed out.
+    pub fn read_only_hint<'a, Message>(
+        content: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        use iced::widget::tooltip;
+
+        tooltip(
+            content,
+            container(text("System packages are read-only (see Settings)").size(13))
+                .padding([4, 8])
+                .style(|_theme: &iced::Theme| container::Style {
+                    background: Some(app::colors::palette().surface.into()),
+                    border: Border {
+                        color: app::colors::palette().divider,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: Some(app::colors::palette().on_surface),
+                    ..Default::default()
+                }),
+            tooltip::Position::Top,
+        )
+        .into()
+    }
+
+    /// Version column text, shortened by `display_version`; hovering shows
+    /// the full version when it differs.
+    pub fn version_text<'a, Message>(
+        version: &'a str,
+        pm_type: PackageManagerType,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        Self::version_label(version, updater_core::display_version(version, pm_type))
+    }
+
+    /// Current and new version texts of an update. When shortening would
+    /// show the same version twice (a release-only bump such as
+    /// `1.2-1.fc40` → `1.2-2.fc40`), both show the full versions.
+    pub fn update_version_texts<'a, Message>(
+        current_version: &'a str,
+        new_version: &'a str,
+        pm_type: PackageManagerType,
+    ) -> [Element<'a, Message>; 2]
+    where
+        Message: 'a,
+    {
+        let (current, new) = update_display_versions(current_version, new_version, pm_type);
+        [
+            Self::version_label(current_version, current),
+            Self::version_label(new_version, new),
+        ]
+    }
+
+    /// `display` of `version`, with the full version on hover when they differ.
+    fn version_label<'a, Message>(version: &'a str, display: String) -> Element<'a, Message>
+    where
+        Message: 'a,
+    {
+        use iced::widget::tooltip;
+
+        let label = text(display.clone())
+            .size(14)
+            .color(app::colors::palette().on_surface_muted);
+
+        if display == version.trim() {
+            return label.into();
+        }
+
+        tooltip(
+            label,
+            container(text(version).size(13))
+                .padding([4, 8])
+                .style(|_theme: &iced::Theme| container::Style {
+                    background: Some(app::colors::palette().surface.into()),
+                    border: Border {
+                        color: app::colors::palette().divider,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: Some(app::colors::palette().on_surface),
+                    ..Default::default()
+                }),
+            tooltip::Position::Top,
+        )
+        .into()
+    }
+
+    /// Expandable "Output" section with the captured command output.
+    pub fn operation_output_view<'a, Message>(
+        output: &'a OperationOutput,
+        on_toggle: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::{button, scrollable};
+
+        let arrow = if output.expanded { "▾" } else { "▸" };
+        let header = button(
+            text(format!("{} Output ({} lines)", arrow, output.line_count()))
+                .size(13)
+                .color(app::colors::palette().on_surface_muted),
+        )
+        .on_press(on_toggle)
+        .padding([4, 0])
+        .style(|_theme, _status| iced::widget::button::Style::default());
+
+        if !output.expanded {
+            return header.into();
+        }
+
+        let mut body = column![].spacing(4);
+        if output.is_truncated() {
+            body = body.push(
+                text("Earlier output was dropped.")
+                    .size(12)
+                    .color(app::colors::palette().on_surface_muted),
+            );
+        }
+        body = body.push(
+            text(output.text())
+                .size(12)
+                .font(iced::Font::MONOSPACE)
+                .color(app::colors::palette().on_surface),
+        );
+
+        let panel = container(scrollable(body).height(iced::Length::Fixed(240.0)))
+            .padding(10)
+            .width(iced::Length::Fill)
+            .style(|_theme: &iced::Theme| container::Style {
+                background: Some(app::colors::palette().surface_muted.into()),
+                border: Border {
+                    color: app::colors::palette().divider,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            });
+
+        column![header, panel].spacing(6).into()
+    }
+
+    /// Name matcher for "Select matching": case-insensitive substring, or a
+    /// regex when `use_regex` is set. An invalid regex falls back to substring.
+    pub fn name_matcher(pattern: &str, use_regex: bool) -> Box<dyn Fn(&str) -> bool> {
+        let pattern = pattern.trim();
+
+        if use_regex {
+            match regex::RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(re) => return Box::new(move |name| re.is_match(name)),
+                Err(e) => log::debug!("Invalid selection regex {:?}: {}", pattern, e),
+            }
+        }
+
+        let needle = pattern.to_lowercase();
+        Box::new(move |name| name.to_lowercase().contains(&needle))
+    }
+
+    /// Pattern field, regex toggle and "Select matching" button.
+    pub fn select_matching_view<'a, Message>(
+        pattern: &str,
+        use_regex: bool,
+        on_input: impl Fn(String) -> Message + 'a,
+        on_toggle_regex: impl Fn(bool) -> Message + 'a,
+        on_select: Message,
+    ) -> Element<'a, Message>
+    where
+        Message: 'a + Clone,
+    {
+        use iced::widget::{checkbox, row};
+
+        let can_select = !pattern.trim().is_empty();
+        let input = text_input("Pattern, e.g. python3-", pattern)
+            .on_input(on_input)
+            .on_submit_maybe(can_select.then(|| on_select.clone()))
+            .padding(8)
+            .size(14);
+        let regex_toggle = checkbox(use_regex)
+            .label("Regex")
+            .on_toggle(on_toggle_regex)
+            .size(16)
+            .spacing(6)
+            .text_size(13)
+            .style(Self::checkbox_style(false));
+
+        let select_button: Element<'a, Message> = if can_select {
+            Self::refresh_button_with_label("Select matching", on_select)
+        } else {
+            iced::widget::button(text("Select matching").size(14))
+                .padding([8, 16])
+                .into()
+        };
+
+        row![input, regex_toggle, select_button]
+            .spacing(12)
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
     pub fn search_input_view<'a, Message>(
         label: &'static str,
         placeholder: &'static str,
@@ -488,3 +1208,40 @@ impl SharedUi {
             .into()
     }
 }
+
+/// Shortened current and new versions of an update, or the full ones when
+/// the shortened pair would be equal.
+fn update_display_versions(
+    current_version: &str,
+    new_version: &str,
+    pm_type: PackageManagerType,
+) -> (String, String) {
+    let current = updater_core::display_version(current_version, pm_type);
+    let new = updater_core::display_version(new_version, pm_type);
+
+    if current == new {
+        (
+            current_version.trim().to_owned(),
+            new_version.trim().to_owned(),
+        )
+    } else {
+        (current, new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_only_updates_show_full_versions() {
+        assert_eq!(
+            update_display_versions("1.2-1.fc40", "1.2-2.fc40", PackageManagerType::Dnf),
+            ("1.2-1.fc40".to_owned(), "1.2-2.fc40".to_owned())
+        );
+        assert_eq!(
+            update_display_versions("1.2-1.fc40", "1.3-1.fc40", PackageManagerType::Dnf),
+            ("1.2".to_owned(), "1.3".to_owned())
+        );
+    }
+}
@@ -1,24 +1,47 @@
 // Updates view with filtering, sorting, and search capabilities.
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use iced::{Border, Task};
-use updater_core::{PackageManagerType, PackageUpdate};
+use serde::{Deserialize, Serialize};
+use updater_core::{
+    CancelToken, PackageManagerType, PackageUpdate, RestartAdvice,
+    error::CoreError,
+    i18n::{Key, Lang, tr},
+};
 
 use crate::{
     app,
     content::errors::{ManagerErrors, apply_manager_counted_items_result},
-    content::shared::{PackageSelectionKey, SharedUi},
+    content::shared::{ListKey, PackageSelectionKey, SECTION_PAGE_SIZE, SharedUi},
     content::workflows::{
-        BatchProgress, PackageBatchAction, collect_selected_package_groups, push_command_log,
-        run_grouped_package_action,
+        BatchProgress, CommandPreview, OperationError, OperationOutput, PackageBatchAction,
+        PackageOperation, collect_selected_package_groups, preview_package_operation,
+        push_command_log, run_grouped_package_action,
     },
 };
 
+/// Cached repo metadata older than this gets a refresh banner.
+const STALE_METADATA_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Widget id of the package list, scrolled by keyboard navigation.
+const PACKAGE_LIST_ID: &str = "updates-packages";
+
 #[derive(Debug, Clone, Default)]
 pub struct Updates {
     /// Search text for filtering updates in UI.
     search_query: String,
+    /// Only list updates that fix security advisories.
+    security_only: bool,
+    /// Pattern for "Select matching".
+    select_pattern: String,
+    /// Treat `select_pattern` as a regex instead of a substring.
+    select_regex: bool,
+    /// Leave managers with no updates out of the filter and the list.
+    hide_empty_managers: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -26,17 +49,44 @@ pub enum Message {
     /// Package-manager selection message.
     SelectPackageManager(PackageManagerType, bool),
     /// Updates-load result message.
-    LoadUpdatesResult(PackageManagerType, Result<Vec<PackageUpdate>, String>),
+    LoadUpdatesResult(
+        PackageManagerType,
+        u64,
+        Result<Vec<PackageUpdate>, CoreError>,
+    ),
     /// Search-query change message.
     SearchQueryChanged(String),
     /// Sort-option change message.
     SortOptionChanged(SortOption),
+    /// Security-only filter toggle message.
+    SetSecurityOnly(bool),
+    /// Hide-empty-managers toggle message.
+    SetHideEmptyManagers(bool),
+    /// Hide one available version `(manager, package, version)` until a newer one appears.
+    SkipVersion(PackageManagerType, String, String),
+    /// Skipped-version save result message.
+    SkipVersionSaved(Result<(), String>),
     /// Package-selection toggle message.
     TogglePackageSelection(PackageManagerType, String, bool),
     /// Select-all toggle message.
     ToggleSelectAll(bool),
+    /// Select-matching pattern change message.
+    SelectPatternChanged(String),
+    /// Select-matching regex toggle message.
+    SetSelectRegex(bool),
+    /// Select every visible update matching the pattern.
+    SelectMatching,
     /// Update-selected message.
     UpdateSelectedPackages,
+    /// Update one package from its row, ignoring the selection.
+    UpdatePackage(PackageManagerType, String),
+    /// Update every listed update of the managers not excluded from
+    /// "update all" (see `Config::exclude_from_update_all`).
+    UpdateAll,
+    /// Expand or collapse a row's description, fetching it on first expand.
+    ToggleDetails(PackageManagerType, String),
+    /// Package description fetch result message.
+    DetailsResult(PackageManagerType, String, Result<Option<String>, String>),
     /// Update progress message.
     UpdateProgress {
         /// Number of finished packages.
@@ -51,7 +101,33 @@ pub enum Message {
         command_message: Option<String>,
     },
     /// Update result message.
-    UpdatePackagesResult(Result<(), String>),
+    UpdatePackagesResult(Result<(), OperationError>),
+    /// Re-run the last failed update.
+    RetryUpdate,
+    /// Stop the running update.
+    CancelOperation,
+    /// Commands listed for the update (see `Config::dry_run`).
+    CommandPreviewResult(Result<CommandPreview, String>),
+    /// Run the previewed commands.
+    ConfirmPreview,
+    /// Drop the previewed update without running it.
+    CancelPreview,
+    /// Expand or collapse the captured command output.
+    ToggleOutput,
+    /// Render another page of a manager's rows.
+    ShowMore(PackageManagerType),
+    /// Arrow/space/enter on the package list.
+    ListKey(ListKey),
+    /// Repo metadata age check result message.
+    MetadataAgeResult(PackageManagerType, Result<Option<Duration>, String>),
+    /// Download fresh repo metadata for a manager.
+    RefreshMetadata(PackageManagerType),
+    /// Metadata refresh result message.
+    RefreshMetadataResult(PackageManagerType, Result<(), String>),
+    /// Post-update restart check result message.
+    RestartAdviceResult(Result<RestartAdvice, String>),
+    /// Open Settings to fix an unavailable manager's path.
+    OpenSettings,
     /// Selected-managers refresh message.
     RefreshSelected,
     /// Full refresh message.
@@ -70,6 +146,8 @@ pub struct UpdatesInfo {
     pub selected_managers: HashSet<PackageManagerType>,
     /// Managers currently loading update list.
     pub loading_updates: HashSet<PackageManagerType>,
+    /// Latest load request per manager; results of older requests are stale.
+    pub load_generations: HashMap<PackageManagerType, u64>,
     /// Whether initial per-manager counts are loading.
     pub is_loading_count: bool,
     /// Whether counts have ever been loaded.
@@ -78,6 +156,8 @@ pub struct UpdatesInfo {
     pub init_progress: Option<(usize, usize)>,
     /// Initialization command logs.
     pub init_logs: Vec<String>,
+    /// Current initialization run; messages from older runs are dropped.
+    pub init_generation: u64,
     /// Current sort option.
     pub sort_by: SortOption,
     /// Selected package keys for batch operations.
@@ -88,8 +168,37 @@ pub struct UpdatesInfo {
     pub update_progress: Option<(usize, usize, PackageManagerType, String)>,
     /// Update command logs.
     pub update_logs: Vec<String>,
+    /// Captured output of the last update run.
+    pub update_output: OperationOutput,
+    /// Cancels the running update; `None` when idle.
+    pub update_cancel: Option<CancelToken>,
+    /// Update whose commands are waiting for confirmation (see `Config::dry_run`).
+    pub command_preview: Option<CommandPreview>,
+    /// Rows rendered per manager section, grown by "Show more".
+    pub visible_counts: HashMap<PackageManagerType, usize>,
+    /// Keyboard-focused row, an index into the rendered rows of all sections.
+    pub focused_index: Option<usize>,
     /// Last update error shown in UI.
-    pub last_update_error: Option<String>,
+    pub last_update_error: Option<OperationError>,
+    /// Last dispatched update, kept for retry after a failure.
+    pub last_update_operation: Option<PackageOperation>,
+    /// Restart advice reported after the last successful update.
+    pub restart_advice: Option<RestartAdvice>,
+    /// Age of each manager's cached repo metadata, checked after every load.
+    pub metadata_ages: HashMap<PackageManagerType, Duration>,
+    /// Managers currently refreshing their repo metadata.
+    pub refreshing_metadata: HashSet<PackageManagerType>,
+    /// Last metadata refresh failure per manager.
+    pub metadata_refresh_errors: ManagerErrors,
+    /// Rows expanded to show their package description.
+    pub expanded_packages: HashSet<PackageSelectionKey>,
+    /// Fetched descriptions, `None` when the manager has none to offer.
+    pub package_details: HashMap<PackageSelectionKey, Result<Option<String>, String>>,
+    /// Packages whose description is being fetched.
+    pub loading_details: HashSet<PackageSelectionKey>,
+    /// Managers whose last load failed on stale or locked repo metadata,
+    /// offered a "Refresh metadata and retry".
+    pub metadata_load_failures: HashSet<PackageManagerType>,
 }
 
 pub enum Action {
@@ -97,9 +206,14 @@ pub enum Action {
     None,
     /// Asynchronous task action.
     Run(iced::Task<Message>),
+    /// Record a skipped `(package, version)` in the config.
+    SkipVersion(String, String),
+    /// Settings page switch request action.
+    OpenSettings,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortOption {
     #[default]
     Name,
@@ -116,6 +230,12 @@ impl SortOption {
         }
     }
 
+    /// Option whose `name` is `name`, as saved in `Config::ui_preferences`
+    /// before options were saved by key.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|option| option.name() == name)
+    }
+
     pub const ALL: [SortOption; 3] = [
         SortOption::Name,
         SortOption::CurrentVersion,
@@ -146,30 +266,91 @@ impl Updates {
                         if *count == packages.len() {
                             Action::None
                         } else {
-                            info.loading_updates.insert(pm_type);
-                            Action::Run(Self::create_load_task(pm_config, pm_type, false))
+                            Action::Run(Self::start_load(pm_config, info, pm_type, false))
                         }
                     } else {
-                        info.loading_updates.insert(pm_type);
-                        Action::Run(Self::create_load_task(pm_config, pm_type, false))
+                        Action::Run(Self::start_load(pm_config, info, pm_type, false))
                     }
                 } else {
                     info.selected_managers.remove(&pm_type);
                     info.selected_packages
                         .retain(|(manager, _)| *manager != pm_type);
+                    // Deselected mid-load: invalidate the pending result.
+                    if info.loading_updates.remove(&pm_type) {
+                        *info.load_generations.entry(pm_type).or_default() += 1;
+                    }
                     Action::None
                 }
             }
-            Message::LoadUpdatesResult(pm_type, result) => {
+            Message::LoadUpdatesResult(pm_type, generation, result) => {
+                if info.load_generations.get(&pm_type) != Some(&generation) {
+                    log::debug!("Discarding stale update list for {}", pm_type.name());
+                    return Action::None;
+                }
                 info.loading_updates.remove(&pm_type);
+                if matches!(result, Err(CoreError::MetadataError(_))) {
+                    info.metadata_load_failures.insert(pm_type);
+                } else {
+                    info.metadata_load_failures.remove(&pm_type);
+                }
                 apply_manager_counted_items_result(
                     &mut info.updates_by_manager,
                     &mut info.load_errors,
                     pm_type,
-                    result,
+                    result.map_err(|e| {
+                        format!("Failed to load updates for {}: {}", pm_type.name(), e)
+                    }),
                 );
+                Action::Run(Self::create_metadata_age_task(pm_config, pm_type))
+            }
+            Message::MetadataAgeResult(pm_type, result) => {
+                match result {
+                    Ok(Some(age)) => {
+                        info.metadata_ages.insert(pm_type, age);
+                    }
+                    Ok(None) => {
+                        info.metadata_ages.remove(&pm_type);
+                    }
+                    Err(e) => {
+                        log::debug!("Metadata age unknown for {}: {}", pm_type.name(), e);
+                        info.metadata_ages.remove(&pm_type);
+                    }
+                }
                 Action::None
             }
+            Message::RefreshMetadata(pm_type) => {
+                if !info.refreshing_metadata.insert(pm_type) {
+                    return Action::None;
+                }
+                info.metadata_refresh_errors.remove(&pm_type);
+
+                let pm_config = pm_config.clone();
+                Action::Run(
+                    Task::future(async move {
+                        pm_type
+                            .refresh_metadata(&pm_config)
+                            .await
+                            .map_err(|e| e.to_string())
+                    })
+                    .then(move |result| {
+                        Task::done(Message::RefreshMetadataResult(pm_type, result))
+                    }),
+                )
+            }
+            Message::RefreshMetadataResult(pm_type, result) => {
+                info.refreshing_metadata.remove(&pm_type);
+                match result {
+                    Ok(()) => {
+                        info.metadata_ages.remove(&pm_type);
+                        Action::Run(Self::start_load(pm_config, info, pm_type, false))
+                    }
+                    Err(e) => {
+                        log::error!("Failed to refresh {} metadata: {}", pm_type.name(), e);
+                        info.metadata_refresh_errors.insert(pm_type, e);
+                        Action::None
+                    }
+                }
+            }
             Message::SearchQueryChanged(query) => {
                 self.search_query = query;
                 Action::None
@@ -178,8 +359,34 @@ impl Updates {
                 info.sort_by = sort_option;
                 Action::None
             }
+            Message::SetSecurityOnly(security_only) => {
+                self.security_only = security_only;
+                Action::None
+            }
+            Message::SetHideEmptyManagers(hide) => {
+                self.hide_empty_managers = hide;
+                Action::None
+            }
+            Message::SkipVersion(pm_type, package_name, version) => {
+                if let Some((count, packages)) = info.updates_by_manager.get_mut(&pm_type) {
+                    packages.retain(|pkg| pkg.name != package_name || pkg.new_version != version);
+                    *count = packages.len();
+                }
+                info.selected_packages
+                    .remove(&SharedUi::selection_key(pm_type, &package_name));
+                Action::SkipVersion(package_name, version)
+            }
+            Message::SkipVersionSaved(result) => {
+                if let Err(e) = result {
+                    log::error!("Failed to save skipped version: {}", e);
+                }
+                Action::None
+            }
             Message::TogglePackageSelection(pm_type, package_name, selected) => {
                 let key = SharedUi::selection_key(pm_type, &package_name);
+                if selected && pm_config.is_read_only(pm_type) {
+                    return Action::None;
+                }
                 if selected {
                     info.selected_packages.insert(key);
                 } else {
@@ -189,8 +396,11 @@ impl Updates {
             }
             Message::ToggleSelectAll(select_all) => {
                 if select_all {
-                    // Select all visible packages from selected managers.
+                    // Select all visible packages from selected, writable managers.
                     for pm_type in &info.selected_managers {
+                        if pm_config.is_read_only(*pm_type) {
+                            continue;
+                        }
                         if let Some((_, packages)) = info.updates_by_manager.get(pm_type) {
                             for pkg in packages {
                                 info.selected_packages
@@ -204,26 +414,146 @@ impl Updates {
                 }
                 Action::None
             }
+            Message::SelectPatternChanged(pattern) => {
+                self.select_pattern = pattern;
+                Action::None
+            }
+            Message::SetSelectRegex(use_regex) => {
+                self.select_regex = use_regex;
+                Action::None
+            }
+            Message::SelectMatching => {
+                let matches = SharedUi::name_matcher(&self.select_pattern, self.select_regex);
+                for pm_type in &info.selected_managers {
+                    if pm_config.is_read_only(*pm_type) {
+                        continue;
+                    }
+                    if let Some((_, packages)) = info.updates_by_manager.get(pm_type) {
+                        let keys: Vec<_> = self
+                            .filter_and_sort_updates(packages, info.sort_by)
+                            .into_iter()
+                            .filter(|pkg| matches(&pkg.name))
+                            .map(|pkg| SharedUi::selection_key(*pm_type, &pkg.name))
+                            .collect();
+                        info.selected_packages.extend(keys);
+                    }
+                }
+                Action::None
+            }
             Message::UpdateSelectedPackages => {
                 if info.selected_packages.is_empty() {
                     return Action::None;
                 }
-                info.is_updating = true;
-                info.last_update_error = None;
-                info.update_logs.clear();
-                let initial_manager = info
-                    .selected_packages
-                    .iter()
-                    .next()
-                    .map(|(pm_type, _)| *pm_type)
-                    .unwrap_or(PackageManagerType::Dnf);
-                info.update_progress = Some((
-                    0,
-                    info.selected_packages.len(),
-                    initial_manager,
-                    String::new(),
-                ));
-                Self::update_packages_action(pm_config, info)
+                let operation = Self::selected_update_operation(info);
+                Self::update_packages_action(pm_config, info, operation)
+            }
+            Message::UpdateAll => {
+                if info.is_updating {
+                    return Action::None;
+                }
+                let operation = Self::update_all_operation(pm_config, info);
+                if operation.manager_groups.is_empty() {
+                    return Action::None;
+                }
+                Self::update_packages_action(pm_config, info, operation)
+            }
+            Message::UpdatePackage(pm_type, package_name) => {
+                if info.is_updating {
+                    return Action::None;
+                }
+                let operation = PackageOperation {
+                    action: PackageBatchAction::Update,
+                    manager_groups: vec![(pm_type, vec![package_name])],
+                };
+                Self::update_packages_action(pm_config, info, operation)
+            }
+            Message::ToggleDetails(pm_type, package_name) => {
+                let key = SharedUi::selection_key(pm_type, &package_name);
+                if info.expanded_packages.remove(&key) {
+                    return Action::None;
+                }
+                info.expanded_packages.insert(key.clone());
+
+                // Cached descriptions re-expand instantly; failed fetches retry.
+                if matches!(info.package_details.get(&key), Some(Ok(_)))
+                    || !info.loading_details.insert(key)
+                {
+                    return Action::None;
+                }
+                Action::Run(Self::create_details_task(pm_config, pm_type, package_name))
+            }
+            Message::DetailsResult(pm_type, package_name, result) => {
+                let key = SharedUi::selection_key(pm_type, &package_name);
+                info.loading_details.remove(&key);
+                info.package_details.insert(key, result);
+                Action::None
+            }
+            Message::ListKey(key) => {
+                let rows = self.navigable_rows(info);
+                info.focused_index = SharedUi::move_focus(info.focused_index, rows.len(), key);
+
+                match (key, info.focused_index) {
+                    (ListKey::Toggle, Some(index)) => {
+                        let focused = rows[index].clone();
+                        if !info.selected_packages.remove(&focused)
+                            && !pm_config.is_read_only(focused.0)
+                        {
+                            info.selected_packages.insert(focused);
+                        }
+                        Action::None
+                    }
+                    (_, Some(index)) => {
+                        Action::Run(SharedUi::scroll_to_row(PACKAGE_LIST_ID, index, rows.len()))
+                    }
+                    (_, None) => Action::None,
+                }
+            }
+            Message::ShowMore(pm_type) => {
+                *info
+                    .visible_counts
+                    .entry(pm_type)
+                    .or_insert(SECTION_PAGE_SIZE) += SECTION_PAGE_SIZE;
+                Action::None
+            }
+            Message::ToggleOutput => {
+                info.update_output.expanded = !info.update_output.expanded;
+                Action::None
+            }
+            Message::RetryUpdate => {
+                if info.is_updating {
+                    return Action::None;
+                }
+                match info.last_update_operation.clone() {
+                    Some(operation) => Self::update_packages_action(pm_config, info, operation),
+                    None => Action::None,
+                }
+            }
+            Message::CommandPreviewResult(result) => {
+                match result {
+                    Ok(preview) => info.command_preview = Some(preview),
+                    Err(e) => info.last_update_error = Some(e.into()),
+                }
+                Action::None
+            }
+            Message::ConfirmPreview => match info.command_preview.take() {
+                Some(preview) if !info.is_updating => {
+                    let pm_config = updater_core::Config {
+                        dry_run: false,
+                        ..pm_config.clone()
+                    };
+                    Self::update_packages_action(&pm_config, info, preview.operation)
+                }
+                _ => Action::None,
+            },
+            Message::CancelPreview => {
+                info.command_preview = None;
+                Action::None
+            }
+            Message::CancelOperation => {
+                if let Some(cancel) = &info.update_cancel {
+                    cancel.cancel();
+                }
+                Action::None
             }
             Message::UpdateProgress {
                 completed,
@@ -234,6 +564,7 @@ impl Updates {
             } => {
                 info.update_progress = Some((completed, total, manager, current_package));
                 if let Some(command_message) = command_message {
+                    info.update_output.push_line(&command_message);
                     push_command_log(
                         &mut info.update_logs,
                         PackageBatchAction::Update,
@@ -249,31 +580,45 @@ impl Updates {
             Message::UpdatePackagesResult(result) => {
                 info.is_updating = false;
                 info.update_progress = None;
+                info.update_cancel = None;
                 match result {
                     Ok(_) => {
-                        info.selected_packages.clear();
+                        let operation = info.last_update_operation.take();
+                        let updated_managers: HashSet<PackageManagerType> = operation
+                            .as_ref()
+                            .map(|operation| operation.managers().collect())
+                            .unwrap_or_default();
+                        // 只取消已更新包的勾选，单独更新某一行时保留其他选择
+                        for (pm_type, packages) in operation
+                            .iter()
+                            .flat_map(|operation| &operation.manager_groups)
+                        {
+                            for package in packages {
+                                info.selected_packages
+                                    .remove(&SharedUi::selection_key(*pm_type, package));
+                            }
+                        }
                         info.last_update_error = None;
                         // Reload updates after a successful update run.
                         let pm_types: Vec<PackageManagerType> =
                             info.selected_managers.iter().copied().collect();
 
-                        if pm_types.is_empty() {
-                            return Action::None;
-                        }
-
-                        // Mark selected managers as loading.
-                        for pm_type in &pm_types {
-                            info.loading_updates.insert(*pm_type);
-                        }
-
-                        // Create load tasks for selected managers.
-                        let tasks: Vec<Task<Message>> = pm_types
+                        let mut tasks: Vec<Task<Message>> = pm_types
                             .into_iter()
-                            .map(|pm_type| Self::create_load_task(pm_config, pm_type, false))
+                            .map(|pm_type| Self::start_load(pm_config, info, pm_type, false))
                             .collect();
+                        tasks.push(Self::create_restart_check_task(
+                            pm_config,
+                            updated_managers.into_iter().collect(),
+                        ));
 
                         Action::Run(Task::batch(tasks))
                     }
+                    Err(OperationError::Cancelled) => {
+                        log::info!("Package update cancelled");
+                        info.last_update_error = Some(OperationError::Cancelled);
+                        Action::None
+                    }
                     Err(e) => {
                         log::error!("Failed to update packages: {}", e);
                         info.last_update_error = Some(e);
@@ -281,6 +626,14 @@ impl Updates {
                     }
                 }
             }
+            Message::RestartAdviceResult(result) => {
+                match result {
+                    Ok(advice) => info.restart_advice = Some(advice),
+                    Err(e) => log::warn!("Failed to check restart requirement: {}", e),
+                }
+                Action::None
+            }
+            Message::OpenSettings => Action::OpenSettings,
             Message::RefreshSelected => {
                 let pm_types: Vec<PackageManagerType> =
                     info.selected_managers.iter().copied().collect();
@@ -289,15 +642,9 @@ impl Updates {
                     return Action::None;
                 }
 
-                // Mark selected managers as loading.
-                for pm_type in &pm_types {
-                    info.loading_updates.insert(*pm_type);
-                }
-
-                // Create load tasks for selected managers.
                 let tasks: Vec<Task<Message>> = pm_types
                     .into_iter()
-                    .map(|pm_type| Self::create_load_task(pm_config, pm_type, true))
+                    .map(|pm_type| Self::start_load(pm_config, info, pm_type, true))
                     .collect();
 
                 Action::Run(Task::batch(tasks))
@@ -309,13 +656,9 @@ impl Updates {
                     return Action::None;
                 }
 
-                for pm_type in &pm_types {
-                    info.loading_updates.insert(*pm_type);
-                }
-
                 let tasks: Vec<Task<Message>> = pm_types
                     .into_iter()
-                    .map(|pm_type| Self::create_load_task(pm_config, pm_type, true))
+                    .map(|pm_type| Self::start_load(pm_config, info, pm_type, true))
                     .collect();
 
                 Action::Run(Task::batch(tasks))
@@ -327,20 +670,29 @@ impl Updates {
         &self,
         info: &'a UpdatesInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::Element<'a, Message> {
         use iced::widget::column;
 
         SharedUi::content_page_layout(
             column![
-                self.manager_filter_view(info, pm_config),
-                self.sort_order_view(info),
-                self.refresh_actions_view()
+                self.manager_filter_view(info, pm_config, unavailable_managers),
+                self.sort_order_view(info, pm_config.language),
+                self.security_filter_view(),
+                self.refresh_actions_view(pm_config.language)
             ]
             .spacing(24),
             column![
                 self.search_input_view(),
-                self.batch_actions_view(info),
-                self.updates_list_view(info)
+                SharedUi::select_matching_view(
+                    &self.select_pattern,
+                    self.select_regex,
+                    Message::SelectPatternChanged,
+                    Message::SetSelectRegex,
+                    Message::SelectMatching,
+                ),
+                self.batch_actions_view(info, pm_config),
+                self.updates_list_view(info, pm_config)
             ]
             .spacing(20),
         )
@@ -352,14 +704,15 @@ impl Updates {
         &self,
         info: &'a UpdatesInfo,
         pm_config: &updater_core::Config,
+        unavailable_managers: &'a HashSet<PackageManagerType>,
     ) -> iced::Element<'a, Message> {
         let filters_content = if !info.has_loading_count {
             SharedUi::loading_manager_filter_view(
                 pm_config,
                 if info.is_loading_count {
-                    "Loading update information..."
+                    SharedUi::spinner_text("Loading update information")
                 } else {
-                    "Waiting to load update information"
+                    "Waiting to load update information".to_owned()
                 },
             )
         } else {
@@ -367,7 +720,7 @@ impl Updates {
 
             if managers.is_empty() {
                 return SharedUi::filter_section(
-                    "Filter Package Managers",
+                    tr(pm_config.language, Key::FilterManagers),
                     SharedUi::empty_filter_view("No package managers detected"),
                 );
             }
@@ -381,38 +734,52 @@ impl Updates {
                         .map_or(0, |(count, _)| *count);
                     (*pm_type, count)
                 })
+                .filter(|(pm_type, count)| {
+                    unavailable_managers.contains(pm_type)
+                        || !self.is_hidden_manager(info, *pm_type, *count)
+                })
                 .collect();
 
             SharedUi::active_manager_filter_view(
                 entries,
                 &info.selected_managers,
                 &info.loading_updates,
+                unavailable_managers,
                 move |pm_type| {
                     info.is_loading_count && !info.updates_by_manager.contains_key(&pm_type)
                 },
                 Message::SelectPackageManager,
+                Message::OpenSettings,
             )
+            .push(SharedUi::hide_empty_managers_toggle(
+                self.hide_empty_managers,
+                tr(pm_config.language, Key::HideEmptyManagers),
+                Message::SetHideEmptyManagers,
+            ))
         };
 
         SharedUi::manager_filter_section(
-            "Filter Package Managers",
+            tr(pm_config.language, Key::FilterManagers),
             filters_content,
             &info.init_errors,
         )
     }
 
-    fn refresh_actions_view<'a>(&self) -> iced::Element<'a, Message> {
+    fn refresh_actions_view<'a>(&self, lang: Lang) -> iced::Element<'a, Message> {
         use iced::widget::row;
 
         row![
-            SharedUi::refresh_button_with_label("Refresh Selected", Message::RefreshSelected),
-            SharedUi::refresh_button_with_label("Refresh All", Message::RefreshAll),
+            SharedUi::refresh_button_with_label(
+                tr(lang, Key::RefreshSelected),
+                Message::RefreshSelected
+            ),
+            SharedUi::refresh_button_with_label(tr(lang, Key::RefreshAll), Message::RefreshAll),
         ]
         .spacing(8)
         .into()
     }
 
-    fn sort_order_view<'a>(&self, info: &'a UpdatesInfo) -> iced::Element<'a, Message> {
+    fn sort_order_view<'a>(&self, info: &'a UpdatesInfo, lang: Lang) -> iced::Element<'a, Message> {
         use iced::widget::column;
 
         let sort_options = column(SortOption::ALL.iter().map(|option| {
@@ -432,13 +799,32 @@ impl Updates {
         .spacing(12);
 
         column![
-            SharedUi::section_title("Sort By"),
+            SharedUi::section_title(tr(lang, Key::SortBy)),
             SharedUi::styled_container(sort_options)
         ]
         .spacing(12)
         .into()
     }
 
+    fn security_filter_view<'a>(&self) -> iced::Element<'a, Message> {
+        use iced::widget::{checkbox, column};
+
+        column![
+            SharedUi::section_title("Security"),
+            SharedUi::styled_container(
+                checkbox(self.security_only)
+                    .label("Security updates only")
+                    .on_toggle(Message::SetSecurityOnly)
+                    .size(18)
+                    .spacing(8)
+                    .text_size(15)
+                    .style(SharedUi::checkbox_style(false)),
+            )
+        ]
+        .spacing(12)
+        .into()
+    }
+
     fn search_input_view<'a>(&self) -> iced::Element<'a, Message> {
         SharedUi::search_input_view(
             "Search",
@@ -448,51 +834,53 @@ impl Updates {
         )
     }
 
-    fn updates_list_view<'a>(&self, info: &'a UpdatesInfo) -> iced::Element<'a, Message> {
+    fn updates_list_view<'a>(
+        &self,
+        info: &'a UpdatesInfo,
+        pm_config: &updater_core::Config,
+    ) -> iced::Element<'a, Message> {
         use iced::widget::{column, scrollable};
 
         if !info.has_loading_count {
-            return SharedUi::centered_message(if info.is_loading_count {
-                "Loading update information..."
+            return if info.is_loading_count {
+                SharedUi::spinner("Loading update information")
             } else {
-                "Waiting to load update information"
-            });
+                SharedUi::centered_message("Waiting to load update information")
+            };
         }
 
         if info.selected_managers.is_empty() {
             return SharedUi::centered_message("Please select a package manager to view");
         }
 
-        if info
-            .selected_managers
-            .iter()
-            .any(|pm_type| info.loading_updates.contains(pm_type))
-        {
-            return SharedUi::centered_message("Loading selected package manager updates...");
-        }
-
-        let filtered_managers: Vec<_> = info
-            .selected_managers
-            .iter()
-            .filter_map(|pm_type| {
-                info.updates_by_manager
-                    .get(pm_type)
-                    .map(|entry| (*pm_type, entry))
-            })
-            .collect();
+        // Each manager's section shows up as soon as its own load finishes;
+        // managers still loading without cached data get a placeholder section.
+        let filtered_managers: Vec<(PackageManagerType, usize, &[PackageUpdate])> =
+            SharedUi::sorted_managers(&info.selected_managers)
+                .into_iter()
+                .filter_map(|pm_type| match info.updates_by_manager.get(&pm_type) {
+                    Some((count, packages)) => Some((pm_type, *count, packages.as_slice())),
+                    None if info.loading_updates.contains(&pm_type) => Some((pm_type, 0, &[][..])),
+                    None => None,
+                })
+                .filter(|(pm_type, count, _)| !self.is_hidden_manager(info, *pm_type, *count))
+                .collect();
 
-        let total_updates: usize = filtered_managers.iter().map(|(_, (count, _))| *count).sum();
+        let total_updates: usize = filtered_managers.iter().map(|(_, count, _)| *count).sum();
         let has_visible_errors = filtered_managers
             .iter()
-            .any(|(pm_type, _)| info.load_errors.contains_key(pm_type));
+            .any(|(pm_type, _, _)| info.load_errors.contains_key(pm_type));
+        let is_any_loading = filtered_managers
+            .iter()
+            .any(|(pm_type, _, _)| info.loading_updates.contains(pm_type));
 
-        if total_updates == 0 && !has_visible_errors {
+        if total_updates == 0 && !has_visible_errors && !is_any_loading {
             return SharedUi::centered_message("No updates available");
         }
 
         let search_query = self.search_query.trim().to_lowercase();
-        if !search_query.is_empty() {
-            let has_any_match = filtered_managers.iter().any(|(_, (_, packages))| {
+        if !search_query.is_empty() && !is_any_loading {
+            let has_any_match = filtered_managers.iter().any(|(_, _, packages)| {
                 packages
                     .iter()
                     .any(|pkg| pkg.name.to_lowercase().contains(&search_query))
@@ -503,42 +891,76 @@ impl Updates {
             }
         }
 
+        let focused = info
+            .focused_index
+            .and_then(|index| self.navigable_rows(info).into_iter().nth(index));
         let updates_sections: Vec<iced::Element<'_, Message>> = filtered_managers
             .into_iter()
-            .map(|(pm_type, (count, packages))| {
-                self.package_manager_section(pm_type, *count, packages, info)
+            .map(|(pm_type, count, packages)| {
+                self.package_manager_section(
+                    pm_type,
+                    count,
+                    packages,
+                    info,
+                    focused.as_ref(),
+                    pm_config.is_read_only(pm_type),
+                )
             })
             .collect();
 
         scrollable(column(updates_sections).spacing(20))
+            .id(iced::widget::Id::new(PACKAGE_LIST_ID))
             .width(iced::Length::Fill)
             .height(iced::Length::Fill)
             .into()
     }
 
+    /// Whether `hide_empty_managers` leaves `pm_type` out. Checked on every
+    /// render, so a manager reappears as soon as a load reports updates;
+    /// loading and failed managers stay visible.
+    fn is_hidden_manager(
+        &self,
+        info: &UpdatesInfo,
+        pm_type: PackageManagerType,
+        count: usize,
+    ) -> bool {
+        self.hide_empty_managers
+            && count == 0
+            && !info.loading_updates.contains(&pm_type)
+            && !info.load_errors.contains_key(&pm_type)
+            && !(info.is_loading_count && !info.updates_by_manager.contains_key(&pm_type))
+    }
+
     fn package_manager_section<'a>(
         &self,
         pm_type: PackageManagerType,
         count: usize,
         packages: &'a [PackageUpdate],
         info: &'a UpdatesInfo,
+        focused: Option<&PackageSelectionKey>,
+        read_only: bool,
     ) -> iced::Element<'a, Message> {
         let is_loading = info.loading_updates.contains(&pm_type);
         let filtered_packages = self.filter_and_sort_updates(packages, info.sort_by);
         let subtitle = if is_loading {
-            "(Loading...)".to_owned()
+            format!("({})", SharedUi::spinner_text("Loading"))
         } else {
             format!("({} updates)", count)
         };
 
         let body = (!filtered_packages.is_empty()).then(|| {
-            iced::widget::column(
-                filtered_packages
-                    .into_iter()
-                    .map(|pkg| self.package_item_view(pm_type, pkg, info)),
+            SharedUi::truncated_rows(
+                filtered_packages,
+                SharedUi::visible_rows(&info.visible_counts, pm_type),
+                |pkg| {
+                    let is_focused = focused == Some(&SharedUi::selection_key(pm_type, &pkg.name));
+                    SharedUi::focusable_row(
+                        self.package_item_view(pm_type, pkg, info, read_only),
+                        is_focused,
+                    )
+                },
+                Message::ShowMore(pm_type),
             )
-            .spacing(8)
-            .into()
         });
 
         SharedUi::manager_section(
@@ -550,6 +972,24 @@ impl Updates {
         )
     }
 
+    /// Keys of the rendered rows in display order, what `focused_index` indexes.
+    fn navigable_rows(&self, info: &UpdatesInfo) -> Vec<PackageSelectionKey> {
+        SharedUi::sorted_managers(&info.selected_managers)
+            .into_iter()
+            .filter_map(|pm_type| {
+                info.updates_by_manager
+                    .get(&pm_type)
+                    .map(|(_, packages)| (pm_type, packages))
+            })
+            .flat_map(|(pm_type, packages)| {
+                self.filter_and_sort_updates(packages, info.sort_by)
+                    .into_iter()
+                    .take(SharedUi::visible_rows(&info.visible_counts, pm_type))
+                    .map(move |pkg| SharedUi::selection_key(pm_type, &pkg.name))
+            })
+            .collect()
+    }
+
     fn filter_and_sort_updates<'a>(
         &self,
         packages: &'a [PackageUpdate],
@@ -558,6 +998,7 @@ impl Updates {
         let query = self.search_query.trim().to_lowercase();
         let mut filtered: Vec<_> = packages
             .iter()
+            .filter(|pkg| !self.security_only || pkg.security)
             .filter(|pkg| {
                 if query.is_empty() {
                     true
@@ -587,44 +1028,204 @@ impl Updates {
         pm_type: PackageManagerType,
         package: &'a PackageUpdate,
         info: &'a UpdatesInfo,
+        read_only: bool,
     ) -> iced::Element<'a, Message> {
-        use iced::widget::{checkbox, row, text};
+        use iced::widget::{Space, button, checkbox, column, row, text};
 
         let package_name = package.name.clone();
-        let is_selected = info
-            .selected_packages
-            .contains(&SharedUi::selection_key(pm_type, &package.name));
+        let key = SharedUi::selection_key(pm_type, &package.name);
+        let is_selected = info.selected_packages.contains(&key);
+        let is_expanded = info.expanded_packages.contains(&key);
+        let metrics = app::density::metrics();
+        let [current_version, new_version] =
+            SharedUi::update_version_texts(&package.current_version, &package.new_version, pm_type);
+
+        let select_checkbox = checkbox(is_selected)
+            .on_toggle_maybe((!read_only).then(|| {
+                let package_name = package_name.clone();
+                move |selected| {
+                    Message::TogglePackageSelection(pm_type, package_name.clone(), selected)
+                }
+            }))
+            .size(18)
+            .spacing(8)
+            .style(SharedUi::checkbox_style(read_only));
+        let update_button = Self::update_button(pm_type, package, !info.is_updating && !read_only);
 
-        row![
-            checkbox(is_selected)
-                .on_toggle({
-                    let package_name = package_name.clone();
-                    move |selected| {
-                        Message::TogglePackageSelection(pm_type, package_name.clone(), selected)
-                    }
-                })
-                .size(18)
-                .spacing(8)
-                .style(SharedUi::checkbox_style(false)),
-            text(&package.name)
-                .size(15)
-                .color(app::colors::ON_SURFACE)
-                .width(iced::Length::Fill),
-            text(&package.current_version)
-                .size(14)
-                .color(app::colors::ON_SURFACE_MUTED),
-            text("→").size(14).color(app::colors::ON_SURFACE_MUTED),
-            text(&package.new_version)
+        let main_row = row![
+            if read_only {
+                SharedUi::read_only_hint(select_checkbox)
+            } else {
+                select_checkbox.into()
+            },
+            button(
+                text(format!(
+                    "{} {}",
+                    if is_expanded { "▾" } else { "▸" },
+                    package.name
+                ))
+                .size(metrics.name_size)
+                .color(app::colors::palette().on_surface),
+            )
+            .on_press(Message::ToggleDetails(pm_type, package_name.clone()))
+            .padding(0)
+            .style(|_theme, _status| button::Style::default()),
+            package.security.then(Self::security_badge),
+            Space::new().width(iced::Length::Fill),
+            current_version,
+            text("→")
                 .size(14)
-                .color(app::colors::ON_SURFACE_MUTED),
+                .color(app::colors::palette().on_surface_muted),
+            new_version,
+            Self::skip_button(pm_type, package),
+            if read_only {
+                SharedUi::read_only_hint(update_button)
+            } else {
+                update_button
+            },
         ]
         .spacing(12)
-        .align_y(iced::Alignment::Center)
-        .padding([8, 0])
-        .into()
+        .align_y(iced::Alignment::Center);
+
+        if !is_expanded {
+            return main_row.padding([metrics.row_padding, 0.0]).into();
+        }
+
+        column![main_row, Self::details_view(info, &key)]
+            .spacing(4)
+            .padding([metrics.row_padding, 0.0])
+            .into()
+    }
+
+    /// Description under an expanded row: a spinner while fetching, then
+    /// the cached result.
+    fn details_view<'a>(
+        info: &'a UpdatesInfo,
+        key: &PackageSelectionKey,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::text;
+
+        let (details, color) = if info.loading_details.contains(key) {
+            (
+                SharedUi::spinner_text("Loading description"),
+                app::colors::palette().on_surface_muted,
+            )
+        } else {
+            match info.package_details.get(key) {
+                Some(Ok(Some(details))) => (details.clone(), app::colors::palette().on_surface),
+                Some(Ok(None)) | None => (
+                    "No description available".to_owned(),
+                    app::colors::palette().on_surface_muted,
+                ),
+                Some(Err(e)) => (
+                    format!("Failed to load description: {}", e),
+                    app::colors::palette().error,
+                ),
+            }
+        };
+
+        text(details)
+            .size(app::density::metrics().detail_size)
+            .color(color)
+            .into()
+    }
+
+    /// Inline "Update" for one row; disabled while an update is running,
+    /// since progress and logs track a single operation.
+    fn update_button<'a>(
+        pm_type: PackageManagerType,
+        package: &PackageUpdate,
+        enabled: bool,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, text};
+
+        if enabled {
+            return SharedUi::refresh_button_with_label(
+                "Update",
+                Message::UpdatePackage(pm_type, package.name.clone()),
+            );
+        }
+
+        button(text("Update").size(14))
+            .padding([8, 16])
+            .style(|_theme, _status| button::Style {
+                background: Some(app::colors::palette().surface_muted.into()),
+                text_color: app::colors::palette().on_surface_muted,
+                border: Border {
+                    color: app::colors::palette().divider,
+                    width: 1.0,
+                    radius: 6.0.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+
+    fn skip_button<'a>(
+        pm_type: PackageManagerType,
+        package: &PackageUpdate,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{button, text};
+
+        button(text("Skip").size(13))
+            .on_press(Message::SkipVersion(
+                pm_type,
+                package.name.clone(),
+                package.new_version.clone(),
+            ))
+            .padding([4, 10])
+            .style(|_theme, status| button::Style {
+                background: matches!(status, button::Status::Hovered)
+                    .then(|| app::colors::palette().surface_hover.into()),
+                text_color: app::colors::palette().on_surface_muted,
+                border: Border {
+                    radius: 6.0.into(),
+                    ..Default::default()
+                },
+                shadow: iced::Shadow::default(),
+                snap: false,
+            })
+            .into()
+    }
+
+    fn security_badge<'a>() -> iced::Element<'a, Message> {
+        use iced::widget::{container, text};
+
+        container(text("Security").size(12).color(iced::Color::WHITE))
+            .padding([2, 8])
+            .style(|_theme: &iced::Theme| container::Style {
+                background: Some(app::colors::palette().error.into()),
+                border: Border {
+                    radius: 8.0.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .into()
     }
 
-    fn batch_actions_view<'a>(&self, info: &'a UpdatesInfo) -> iced::Element<'a, Message> {
+    /// Summed `updater_core::estimate_update_time` of the selected packages.
+    fn estimate_selected(info: &UpdatesInfo) -> std::time::Duration {
+        info.updates_by_manager
+            .iter()
+            .map(|(pm_type, (_, updates))| {
+                let selected: Vec<&PackageUpdate> = updates
+                    .iter()
+                    .filter(|update| {
+                        info.selected_packages
+                            .contains(&SharedUi::selection_key(*pm_type, &update.name))
+                    })
+                    .collect();
+                updater_core::estimate_update_time(*pm_type, &selected)
+            })
+            .sum()
+    }
+
+    fn batch_actions_view<'a>(
+        &self,
+        info: &'a UpdatesInfo,
+        pm_config: &updater_core::Config,
+    ) -> iced::Element<'a, Message> {
         use iced::widget::{button, checkbox, column, row, text};
 
         let selected_count = info.selected_packages.len();
@@ -673,17 +1274,19 @@ impl Updates {
         let update_button = button(text(button_text).size(14).color(if is_enabled {
             iced::Color::WHITE
         } else {
-            app::colors::ON_SURFACE_MUTED
+            app::colors::palette().on_surface_muted
         }))
         .padding([8, 16])
         .style(move |_theme, status| {
             use iced::widget::button::Style;
             if !is_enabled {
                 Style {
-                    background: Some(iced::Background::Color(app::colors::SURFACE_MUTED)),
-                    text_color: app::colors::ON_SURFACE_MUTED,
+                    background: Some(iced::Background::Color(
+                        app::colors::palette().surface_muted,
+                    )),
+                    text_color: app::colors::palette().on_surface_muted,
                     border: Border {
-                        color: app::colors::DIVIDER,
+                        color: app::colors::palette().divider,
                         width: 1.0,
                         radius: 6.0.into(),
                     },
@@ -724,55 +1327,358 @@ impl Updates {
             update_button
         };
 
-        let actions_row = row![select_all_checkbox, update_button]
+        let mut actions_row = row![select_all_checkbox, update_button]
             .spacing(12)
             .align_y(iced::Alignment::Center);
 
-        if let Some(error) = &info.last_update_error {
-            column![
-                actions_row,
-                text(format!("Update failed: {}", error))
-                    .size(13)
-                    .color(app::colors::ERROR)
-            ]
-            .spacing(8)
-            .into()
+        if info.is_updating {
+            actions_row = actions_row.push(SharedUi::cancel_operation_button(
+                pm_config.language,
+                info.update_cancel.as_ref(),
+                Message::CancelOperation,
+            ));
         } else {
-            actions_row.into()
+            let update_all_count = Self::update_all_operation(pm_config, info).package_count();
+            if update_all_count > 0 {
+                actions_row = actions_row.push(SharedUi::refresh_button_with_label(
+                    format!("Update All ({})", update_all_count),
+                    Message::UpdateAll,
+                ));
+            }
+        }
+
+        if is_enabled {
+            actions_row = actions_row.push(
+                text(format!(
+                    "Estimated: {}",
+                    SharedUi::format_estimate(Self::estimate_selected(info))
+                ))
+                .size(13)
+                .color(app::colors::palette().on_surface_muted),
+            );
+        }
+
+        let mut actions = column![actions_row].spacing(8);
+
+        if let Some(preview) = &info.command_preview {
+            actions = actions.push(SharedUi::command_preview_view(
+                preview,
+                Message::ConfirmPreview,
+                Message::CancelPreview,
+            ));
+        }
+
+        if let Some(error) = &info.last_update_error {
+            let retry = (!info.is_updating && info.last_update_operation.is_some())
+                .then_some(Message::RetryUpdate);
+            actions = actions.push(SharedUi::operation_error_view(
+                pm_config.language,
+                error,
+                "Update failed",
+                retry,
+            ));
+        }
+
+        for pm_type in SharedUi::sorted_managers(&info.selected_managers) {
+            if let Some(age) = info
+                .metadata_ages
+                .get(&pm_type)
+                .filter(|age| **age >= STALE_METADATA_AGE)
+            {
+                actions = actions.push(Self::stale_metadata_banner_view(
+                    pm_type,
+                    *age,
+                    info.refreshing_metadata.contains(&pm_type),
+                ));
+            }
+            if info.metadata_load_failures.contains(&pm_type)
+                && info.load_errors.contains_key(&pm_type)
+            {
+                actions = actions.push(Self::metadata_failure_banner_view(
+                    pm_type,
+                    info.refreshing_metadata.contains(&pm_type),
+                ));
+            }
+            if let Some(error) = info.metadata_refresh_errors.get(&pm_type) {
+                actions = actions.push(SharedUi::error_with_retry(
+                    format!("Refreshing {} metadata failed: {}", pm_type.name(), error),
+                    None,
+                ));
+            }
+        }
+
+        if let Some(advice) = info
+            .restart_advice
+            .as_ref()
+            .filter(|advice| advice.reboot_recommended)
+        {
+            actions = actions.push(Self::restart_banner_view(advice));
         }
+
+        if !info.update_output.is_empty() {
+            actions = actions.push(SharedUi::operation_output_view(
+                &info.update_output,
+                Message::ToggleOutput,
+            ));
+        }
+
+        actions.into()
+    }
+
+    fn stale_metadata_banner_view<'a>(
+        pm_type: PackageManagerType,
+        age: Duration,
+        is_refreshing: bool,
+    ) -> iced::Element<'a, Message> {
+        let days = age.as_secs() / (24 * 60 * 60);
+        Self::metadata_banner_view(
+            format!(
+                "{} repo metadata is {} days old, so updates may be missing. Refresh?",
+                pm_type.name(),
+                days
+            ),
+            "Refresh metadata",
+            pm_type,
+            is_refreshing,
+        )
+    }
+
+    /// Offer for a load that failed on repo metadata. Refreshing may prompt
+    /// for administrator rights, so it only runs when clicked.
+    fn metadata_failure_banner_view<'a>(
+        pm_type: PackageManagerType,
+        is_refreshing: bool,
+    ) -> iced::Element<'a, Message> {
+        Self::metadata_banner_view(
+            format!(
+                "{} could not read its repo metadata; it may be stale or locked.",
+                pm_type.name()
+            ),
+            "Refresh metadata and retry",
+            pm_type,
+            is_refreshing,
+        )
+    }
+
+    /// Warning banner with a button that refreshes `pm_type`'s metadata and
+    /// reloads its updates.
+    fn metadata_banner_view<'a>(
+        message: String,
+        button_label: &'static str,
+        pm_type: PackageManagerType,
+        is_refreshing: bool,
+    ) -> iced::Element<'a, Message> {
+        use iced::widget::{container, row, text};
+
+        let message = text(message)
+            .size(13)
+            .color(app::colors::palette().on_surface)
+            .width(iced::Length::Fill);
+
+        let refresh_button = if is_refreshing {
+            text("Refreshing...")
+                .size(13)
+                .color(app::colors::palette().on_surface_muted)
+                .into()
+        } else {
+            SharedUi::refresh_button_with_label(button_label, Message::RefreshMetadata(pm_type))
+        };
+
+        container(
+            row![message, refresh_button]
+                .spacing(12)
+                .align_y(iced::Alignment::Center),
+        )
+        .padding([8, 12])
+        .width(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| container::Style {
+            background: Some(app::colors::palette().surface_muted.into()),
+            border: Border {
+                color: app::colors::palette().warning,
+                width: 1.0,
+                radius: 6.0.into(),
+            },
+            text_color: None,
+            shadow: Default::default(),
+            snap: false,
+        })
+        .into()
     }
 
-    fn create_load_task(
+    fn restart_banner_view<'a>(advice: &RestartAdvice) -> iced::Element<'a, Message> {
+        use iced::widget::{container, text};
+
+        let message = if advice.packages.is_empty() {
+            "A reboot is recommended to finish applying updates".to_owned()
+        } else {
+            format!(
+                "A reboot is recommended to finish applying updates ({})",
+                advice.packages.join(", ")
+            )
+        };
+
+        container(
+            text(message)
+                .size(13)
+                .color(app::colors::palette().on_surface),
+        )
+        .padding([8, 12])
+        .width(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| container::Style {
+            background: Some(app::colors::palette().surface_muted.into()),
+            border: Border {
+                color: app::colors::palette().warning,
+                width: 1.0,
+                radius: 6.0.into(),
+            },
+            text_color: None,
+            shadow: Default::default(),
+            snap: false,
+        })
+        .into()
+    }
+
+    /// Mark `pm_type` as loading and start fetching its updates. Loads of
+    /// different managers run concurrently; a newer load supersedes older ones.
+    fn start_load(
         pm_config: &updater_core::Config,
+        info: &mut UpdatesInfo,
         pm_type: PackageManagerType,
         force_refresh: bool,
     ) -> Task<Message> {
+        let generation = info.load_generations.entry(pm_type).or_default();
+        *generation += 1;
+        let generation = *generation;
+        info.loading_updates.insert(pm_type);
+
         let pm_config = pm_config.clone();
 
         Task::future(async move {
             pm_type
                 .list_updates_with_refresh(&pm_config, force_refresh)
                 .await
-                .map_err(|e| format!("Failed to load updates for {}: {}", pm_type.name(), e))
         })
-        .then(move |result| Task::done(Message::LoadUpdatesResult(pm_type, result)))
+        .then(move |result| Task::done(Message::LoadUpdatesResult(pm_type, generation, result)))
+    }
+
+    fn create_details_task(
+        pm_config: &updater_core::Config,
+        pm_type: PackageManagerType,
+        package_name: String,
+    ) -> Task<Message> {
+        let pm_config = pm_config.clone();
+
+        Task::future(async move {
+            let result = pm_type
+                .get_package_details(&pm_config, &package_name)
+                .await
+                .map_err(|e| e.to_string());
+            Message::DetailsResult(pm_type, package_name, result)
+        })
+    }
+
+    fn create_metadata_age_task(
+        pm_config: &updater_core::Config,
+        pm_type: PackageManagerType,
+    ) -> Task<Message> {
+        let pm_config = pm_config.clone();
+
+        Task::future(async move {
+            pm_type
+                .metadata_age(&pm_config)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .then(move |result| Task::done(Message::MetadataAgeResult(pm_type, result)))
+    }
+
+    fn create_restart_check_task(
+        pm_config: &updater_core::Config,
+        pm_types: Vec<PackageManagerType>,
+    ) -> Task<Message> {
+        let pm_config = pm_config.clone();
+
+        Task::future(async move {
+            let mut advice = RestartAdvice::default();
+            for pm_type in pm_types {
+                let result = pm_type
+                    .needs_restarting(&pm_config)
+                    .await
+                    .map_err(|e| format!("{}: {}", pm_type.name(), e))?;
+                advice.reboot_recommended |= result.reboot_recommended;
+                advice.packages.extend(result.packages);
+            }
+            Ok(advice)
+        })
+        .then(|result| Task::done(Message::RestartAdviceResult(result)))
     }
 
-    fn update_packages_action(pm_config: &updater_core::Config, info: &UpdatesInfo) -> Action {
-        let manager_groups = collect_selected_package_groups(
-            info.selected_managers.iter().filter_map(|pm_type| {
+    fn selected_update_operation(info: &UpdatesInfo) -> PackageOperation {
+        PackageOperation {
+            action: PackageBatchAction::Update,
+            manager_groups: collect_selected_package_groups(
+                info.selected_managers.iter().filter_map(|pm_type| {
+                    info.updates_by_manager
+                        .get(pm_type)
+                        .map(|(_, packages)| (*pm_type, packages.as_slice()))
+                }),
+                &info.selected_packages,
+                |package| package.name.as_str(),
+            ),
+        }
+    }
+
+    /// Every loaded update of the writable managers "update all" includes.
+    fn update_all_operation(
+        pm_config: &updater_core::Config,
+        info: &UpdatesInfo,
+    ) -> PackageOperation {
+        PackageOperation {
+            action: PackageBatchAction::UpdateAll,
+            manager_groups: pm_config.update_all_groups(
                 info.updates_by_manager
-                    .get(pm_type)
-                    .map(|(_, packages)| (*pm_type, packages.as_slice()))
-            }),
-            &info.selected_packages,
-            |package| package.name.as_str(),
-        );
+                    .iter()
+                    .filter(|(pm_type, _)| !pm_config.is_read_only(**pm_type))
+                    .map(|(pm_type, (_, packages))| (*pm_type, packages.as_slice())),
+            ),
+        }
+    }
+
+    fn update_packages_action(
+        pm_config: &updater_core::Config,
+        info: &mut UpdatesInfo,
+        operation: PackageOperation,
+    ) -> Action {
+        if pm_config.dry_run {
+            info.command_preview = None;
+            info.last_update_error = None;
+            return Action::Run(preview_package_operation(
+                pm_config,
+                operation,
+                Message::CommandPreviewResult,
+            ));
+        }
+
+        info.is_updating = true;
+        info.last_update_error = None;
+        info.restart_advice = None;
+        info.update_logs.clear();
+        info.update_output.clear();
+        info.update_progress = Some((
+            0,
+            operation.package_count(),
+            operation.first_manager().unwrap_or(PackageManagerType::Dnf),
+            String::new(),
+        ));
+        info.last_update_operation = Some(operation.clone());
+        let cancel = CancelToken::new();
+        info.update_cancel = Some(cancel.clone());
 
         Action::Run(run_grouped_package_action(
             pm_config,
-            PackageBatchAction::Update,
-            manager_groups,
+            operation.action,
+            operation.manager_groups,
+            cancel,
             |BatchProgress {
                  completed,
                  total,
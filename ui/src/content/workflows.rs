@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use futures::channel::mpsc;
 use iced::Task;
-use updater_core::{Config, InstallProgress, PackageManagerType};
+use updater_core::{
+    CancelToken, Config, InstallProgress, PackageAction, PackageManagerType, PendingUpdates,
+    error::CoreError, install_script, with_cancel_token,
+};
 
 use crate::content::shared::PackageSelectionKey;
 
@@ -11,6 +14,9 @@ pub enum PackageBatchAction {
     Install,
     Remove,
     Update,
+    /// Update everything pending, with the manager's bulk upgrade command
+    /// where it has one.
+    UpdateAll,
 }
 
 impl PackageBatchAction {
@@ -18,7 +24,7 @@ impl PackageBatchAction {
         match self {
             Self::Install => "Install",
             Self::Remove => "Remove",
-            Self::Update => "Update",
+            Self::Update | Self::UpdateAll => "Update",
         }
     }
 
@@ -26,7 +32,16 @@ impl PackageBatchAction {
         match self {
             Self::Install => "install",
             Self::Remove => "remove",
-            Self::Update => "update",
+            Self::Update | Self::UpdateAll => "update",
+        }
+    }
+
+    fn package_action(self) -> PackageAction {
+        match self {
+            Self::Install => PackageAction::Install,
+            Self::Remove => PackageAction::Uninstall,
+            Self::Update => PackageAction::Update,
+            Self::UpdateAll => PackageAction::UpdateAll,
         }
     }
 
@@ -36,7 +51,7 @@ impl PackageBatchAction {
         pm_config: &Config,
         package_names: &[String],
         on_progress: F,
-    ) -> Result<(), String>
+    ) -> Result<(), OperationError>
     where
         F: FnMut(InstallProgress),
     {
@@ -56,19 +71,139 @@ impl PackageBatchAction {
                     .update_packages_with_progress(pm_config, package_names, on_progress)
                     .await
             }
+            Self::UpdateAll => {
+                pm_type
+                    .update_all_with_progress(pm_config, package_names, on_progress)
+                    .await
+            }
         };
 
-        result.map_err(|e| {
-            format!(
+        result.map_err(|e| match e {
+            CoreError::Cancelled => OperationError::Cancelled,
+            CoreError::ExecutableNotFound(program) => OperationError::Failed(format!(
+                "Failed to {} packages from {}: {} was not found, set its path in Settings",
+                self.error_verb(),
+                pm_type.name(),
+                program
+            )),
+            e => OperationError::Failed(format!(
                 "Failed to {} packages from {}: {}",
                 self.error_verb(),
                 pm_type.name(),
                 e
-            )
+            )),
         })
     }
 }
 
+/// Why a batch operation did not finish. A cancelled run is not a failure:
+/// it is reported without an error or a retry.
+#[derive(Debug, Clone)]
+pub enum OperationError {
+    Cancelled,
+    Failed(String),
+}
+
+impl std::fmt::Display for OperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("cancelled"),
+            Self::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+impl From<String> for OperationError {
+    fn from(error: String) -> Self {
+        Self::Failed(error)
+    }
+}
+
+/// A dispatched batch operation, kept so a failed run can be retried as-is.
+#[derive(Debug, Clone)]
+pub struct PackageOperation {
+    pub action: PackageBatchAction,
+    pub manager_groups: Vec<(PackageManagerType, Vec<String>)>,
+}
+
+impl PackageOperation {
+    pub fn package_count(&self) -> usize {
+        self.manager_groups
+            .iter()
+            .map(|(_, packages)| packages.len())
+            .sum()
+    }
+
+    pub fn first_manager(&self) -> Option<PackageManagerType> {
+        self.manager_groups.first().map(|(pm_type, _)| *pm_type)
+    }
+
+    pub fn managers(&self) -> impl Iterator<Item = PackageManagerType> + '_ {
+        self.manager_groups.iter().map(|(pm_type, _)| *pm_type)
+    }
+}
+
+/// Open a file, directory or URL with the platform's default handler.
+pub async fn open_with_system(target: impl AsRef<std::ffi::OsStr>) -> Result<(), String> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+
+    tokio::process::Command::new(opener)
+        .arg(target)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {}", opener, e))
+}
+
+/// Commands an operation would run, shown for confirmation first while
+/// `Config::dry_run` is on.
+#[derive(Debug, Clone)]
+pub struct CommandPreview {
+    pub operation: PackageOperation,
+    pub commands: Vec<String>,
+}
+
+/// List the commands `operation` would run, without running any of them.
+pub fn preview_package_operation<Message, DoneMessage>(
+    pm_config: &Config,
+    operation: PackageOperation,
+    done_message: DoneMessage,
+) -> Task<Message>
+where
+    Message: Send + 'static,
+    DoneMessage: Fn(Result<CommandPreview, String>) -> Message + Send + 'static,
+{
+    let pm_config = pm_config.clone();
+
+    Task::future(async move {
+        let mut commands = Vec::new();
+        for (pm_type, package_names) in &operation.manager_groups {
+            let manager_commands = pm_type
+                .dry_run_commands(operation.action.package_action(), &pm_config, package_names)
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Failed to {} packages from {}: {}",
+                        operation.action.error_verb(),
+                        pm_type.name(),
+                        e
+                    )
+                })?;
+            commands.extend(manager_commands);
+        }
+        Ok(CommandPreview {
+            operation,
+            commands,
+        })
+    })
+    .map(done_message)
+}
+
 #[derive(Debug, Clone)]
 pub struct BatchProgress {
     pub completed: usize,
@@ -81,7 +216,7 @@ pub struct BatchProgress {
 #[derive(Debug, Clone)]
 enum BatchActionEvent {
     Progress(BatchProgress),
-    Done(Result<(), String>),
+    Done(Result<(), OperationError>),
 }
 
 pub fn collect_selected_package_groups<'a, T: 'a, I, N>(
@@ -108,7 +243,7 @@ where
     }
 
     let mut manager_groups: Vec<_> = packages_by_manager.into_iter().collect();
-    manager_groups.sort_by_key(|(pm_type, _)| pm_type.name());
+    manager_groups.sort_by_key(|(pm_type, _)| *pm_type);
     for (_, package_names) in &mut manager_groups {
         package_names.sort();
     }
@@ -116,17 +251,20 @@ where
     manager_groups
 }
 
+/// Run `action` for every manager group in turn. Cancelling `cancel` stops
+/// the running command and skips the remaining groups.
 pub fn run_grouped_package_action<Message, ProgressMessage, DoneMessage>(
     pm_config: &Config,
     action: PackageBatchAction,
     manager_groups: Vec<(PackageManagerType, Vec<String>)>,
+    cancel: CancelToken,
     progress_message: ProgressMessage,
     done_message: DoneMessage,
 ) -> Task<Message>
 where
     Message: Send + 'static,
     ProgressMessage: Fn(BatchProgress) -> Message + Copy + Send + 'static,
-    DoneMessage: Fn(Result<(), String>) -> Message + Copy + Send + 'static,
+    DoneMessage: Fn(Result<(), OperationError>) -> Message + Copy + Send + 'static,
 {
     let total_packages: usize = manager_groups
         .iter()
@@ -141,9 +279,33 @@ where
     let runner_sender = sender.clone();
     let pm_config = pm_config.clone();
 
-    let runner_task = Task::future(async move {
+    // 在独立的 worker 运行时执行，进度和结果经由 channel 回到 UI
+    crate::worker::spawn(with_cancel_token(cancel, async move {
         let mut global_offset = 0usize;
 
+        let manager_groups = if matches!(action, PackageBatchAction::Update) {
+            let (manager_groups, up_to_date) =
+                drop_up_to_date_packages(&pm_config, manager_groups).await;
+
+            for (pm_type, package_name) in up_to_date {
+                global_offset += 1;
+                let _ = runner_sender.unbounded_send(BatchActionEvent::Progress(BatchProgress {
+                    completed: global_offset,
+                    total: total_packages,
+                    manager: pm_type,
+                    command_message: Some(format!(
+                        "{} is already up to date, skipped",
+                        package_name
+                    )),
+                    current_package: package_name,
+                }));
+            }
+
+            manager_groups
+        } else {
+            manager_groups
+        };
+
         for (pm_type, package_names) in manager_groups {
             let offset = global_offset;
             let progress_sender = runner_sender.clone();
@@ -173,15 +335,109 @@ where
         }
 
         let _ = runner_sender.unbounded_send(BatchActionEvent::Done(Ok(())));
-    })
-    .discard();
+    }));
 
-    let progress_task = Task::run(receiver, move |event| match event {
+    Task::run(receiver, move |event| match event {
         BatchActionEvent::Progress(progress) => progress_message(progress),
         BatchActionEvent::Done(result) => done_message(result),
-    });
+    })
+}
+
+/// Re-check every manager's requested updates at once and drop packages that
+/// are already current. A failed check keeps the group unchanged.
+async fn drop_up_to_date_packages(
+    pm_config: &Config,
+    manager_groups: Vec<(PackageManagerType, Vec<String>)>,
+) -> (
+    Vec<(PackageManagerType, Vec<String>)>,
+    Vec<(PackageManagerType, String)>,
+) {
+    let checks = futures::future::join_all(manager_groups.into_iter().map(
+        |(pm_type, package_names)| async move {
+            let result = pm_type.pending_updates(pm_config, &package_names).await;
+            (pm_type, package_names, result)
+        },
+    ))
+    .await;
+
+    let mut pending_groups = Vec::new();
+    let mut up_to_date = Vec::new();
+    for (pm_type, package_names, result) in checks {
+        let pending = match result {
+            Ok(PendingUpdates {
+                pending,
+                up_to_date: current,
+            }) => {
+                up_to_date.extend(current.into_iter().map(|name| (pm_type, name)));
+                pending
+            }
+            Err(e) => {
+                log::warn!("Skipping up-to-date check for {}: {}", pm_type.name(), e);
+                package_names
+            }
+        };
+
+        if !pending.is_empty() {
+            pending_groups.push((pm_type, pending));
+        }
+    }
+
+    (pending_groups, up_to_date)
+}
+
+/// Upper bound for captured operation output; older lines are dropped.
+const MAX_OPERATION_OUTPUT_BYTES: usize = 256 * 1024;
+
+/// Combined stdout/stderr of the last mutating operation, shown in the
+/// expandable "Output" section.
+#[derive(Debug, Clone, Default)]
+pub struct OperationOutput {
+    text: String,
+    truncated: bool,
+    /// Whether the "Output" section is expanded.
+    pub expanded: bool,
+}
+
+impl OperationOutput {
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.truncated = false;
+    }
+
+    pub fn push_line(&mut self, line: &str) {
+        self.text.push_str(line.trim_end());
+        self.text.push('\n');
+
+        if self.text.len() > MAX_OPERATION_OUTPUT_BYTES {
+            let mut overflow = self.text.len() - MAX_OPERATION_OUTPUT_BYTES;
+            while !self.text.is_char_boundary(overflow) {
+                overflow += 1;
+            }
+            // 从溢出位置之后的第一个换行处截断，保持整行
+            let cut = self.text[overflow..]
+                .find('\n')
+                .map_or(self.text.len(), |i| overflow + i + 1);
+            self.text.drain(..cut);
+            self.truncated = true;
+        }
+    }
 
-    Task::batch(vec![runner_task, progress_task])
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.lines().count()
+    }
+
+    /// Whether older lines were dropped to stay under the size cap.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
 }
 
 pub fn push_command_log(
@@ -216,3 +472,62 @@ pub fn push_command_log(
         logs.drain(0..overflow);
     }
 }
+
+/// Selected packages as an `install_script`, names sorted within each manager.
+pub fn selection_install_script(selected: &HashSet<PackageSelectionKey>) -> String {
+    let mut packages: BTreeMap<PackageManagerType, Vec<String>> = BTreeMap::new();
+    for (pm_type, name) in selected {
+        packages.entry(*pm_type).or_default().push(name.clone());
+    }
+    for names in packages.values_mut() {
+        names.sort();
+    }
+
+    install_script(&packages)
+}
+
+/// Write `script` to a user-chosen file; `Ok(None)` when the dialog was cancelled.
+pub async fn save_install_script(script: String) -> Result<Option<String>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .set_title("Save Install Script")
+        .set_file_name("install-packages.sh")
+        .add_filter("Shell script", &["sh"])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    tokio::fs::write(handle.path(), script)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(handle.path(), std::fs::Permissions::from_mode(0o755))
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(Some(handle.path().display().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operation_output_trims_non_ascii_lines_whole() {
+        let mut output = OperationOutput::default();
+        let line = "é".repeat(100);
+
+        for _ in 0..(MAX_OPERATION_OUTPUT_BYTES / line.len() + 10) {
+            output.push_line(&line);
+        }
+
+        assert!(output.is_truncated());
+        assert!(output.text().len() <= MAX_OPERATION_OUTPUT_BYTES);
+        assert!(output.text().lines().all(|l| l == line));
+    }
+}
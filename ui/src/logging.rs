@@ -0,0 +1,64 @@
+//! Logger setup: human-readable by default, JSON lines for automation.
+
+use std::io::Write;
+
+/// Environment variable selecting the log format when no flag is given.
+const LOG_FORMAT_ENV: &str = "UPDATER_LOG_FORMAT";
+
+/// Output format of log records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `env_logger`'s default colored format.
+    #[default]
+    Human,
+    /// One JSON object per line with timestamp, level, target and message.
+    Json,
+}
+
+impl LogFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "human" | "text" => Some(Self::Human),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Format from `--log-format <fmt>` / `--log-format=<fmt>`, else from
+    /// `UPDATER_LOG_FORMAT`, else human. Unknown values fall back to human.
+    pub fn from_env_and_args() -> Self {
+        let mut args = std::env::args().skip(1);
+        let mut from_args = None;
+        while let Some(arg) = args.next() {
+            if arg == "--log-format" {
+                from_args = args.next();
+            } else if let Some(value) = arg.strip_prefix("--log-format=") {
+                from_args = Some(value.to_owned());
+            }
+        }
+
+        from_args
+            .or_else(|| std::env::var(LOG_FORMAT_ENV).ok())
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+}
+
+/// Install the global logger; `RUST_LOG` still selects what gets logged.
+pub fn init(format: LogFormat) {
+    let mut builder = env_logger::Builder::from_default_env();
+
+    if format == LogFormat::Json {
+        builder.format(|buf, record| {
+            let line = serde_json::json!({
+                "timestamp": buf.timestamp_millis().to_string(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{}", line)
+        });
+    }
+
+    builder.init();
+}
@@ -1,4 +1,5 @@
 use iced::Theme;
+use updater_core::ColorScheme;
 
 use crate::app::App;
 
@@ -6,18 +7,23 @@ mod app;
 mod content;
 mod icon;
 mod init_workflows;
+mod logging;
 mod sidebar;
 mod status_panel;
+mod worker;
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-fn theme(_: &App) -> Theme {
-    Theme::CatppuccinLatte
+fn theme(app: &App) -> Theme {
+    match app.pm_config.color_scheme {
+        ColorScheme::Standard => Theme::CatppuccinLatte,
+        ColorScheme::HighContrast => Theme::Light,
+    }
 }
 
 fn main() -> iced::Result {
-    env_logger::init();
+    logging::init(logging::LogFormat::from_env_and_args());
 
     iced::application(app::App::new, app::App::update, app::App::view)
         .theme(theme)
@@ -25,6 +31,8 @@ fn main() -> iced::Result {
         .window(iced::window::Settings {
             size: iced::Size::new(1200.0, 800.0),
             min_size: Some(iced::Size::new(900.0, 600.0)),
+            // 关闭请求交给 App 处理，以便运行中的操作可以先确认
+            exit_on_close_request: false,
             ..Default::default()
         })
         .run()
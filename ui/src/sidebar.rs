@@ -1,13 +1,15 @@
 use iced::{
     Alignment, Length,
     border::Radius,
-    widget::{Container, Svg, Text, button, column, row, svg},
+    widget::{Container, Space, Svg, Text, button, column, container, row, svg},
 };
 
+use updater_core::i18n::{Key, Lang, tr};
+
 use crate::{
     app,
-    content::ActiveContentPage,
-    icon::{FIND_ICON, INSTALLED_ICON, SETTINGS_ICON, UPDATE_ICON},
+    content::{ActiveContentPage, SharedUi},
+    icon::{FIND_ICON, INSTALLED_ICON, REFRESH_ICON, SETTINGS_ICON, UPDATE_ICON},
 };
 
 #[derive(Debug, Clone, Default)]
@@ -30,10 +32,21 @@ pub enum Tab {
     Settings,
 }
 
+/// Long-running mutating operation, shown in the sidebar whichever tab is active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundOperation {
+    /// Tab that started the operation.
+    pub tab: Tab,
+    /// Short status line, e.g. `Updating 2/5`.
+    pub label: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
     /// Tab selection message.
     Select(Tab),
+    /// Reload installed and update counts of every manager.
+    RefreshAll,
 }
 
 #[derive(Debug)]
@@ -42,6 +55,8 @@ pub enum Action {
     None,
     /// Content page switch action.
     ChangeContent(ActiveContentPage),
+    /// Global refresh request action.
+    RefreshAll,
 }
 
 impl From<Tab> for ActiveContentPage {
@@ -67,33 +82,58 @@ impl SideBar {
                     Action::ChangeContent(sidebar.into())
                 }
             }
+            Message::RefreshAll => Action::RefreshAll,
         }
     }
 
-    pub fn view(&self) -> iced::Element<'_, Message> {
-        column(
-            Tab::ALL
-                .iter()
-                .map(|&tab| sidebar_button(tab, self.active_tab, tab.icon())),
-        )
-        .spacing(8)
-        .padding(8)
-        .into()
+    pub fn view(
+        &self,
+        lang: Lang,
+        operations: &[BackgroundOperation],
+        refreshing: bool,
+    ) -> iced::Element<'_, Message> {
+        let tabs = column(Tab::ALL.iter().map(|&tab| {
+            let busy = operations.iter().any(|operation| operation.tab == tab);
+            sidebar_button(tab, self.active_tab, tab.label(lang), tab.icon(), busy)
+        }))
+        .spacing(8);
+
+        column![tabs, Space::new().height(Length::Fill)]
+            .push((!operations.is_empty()).then(|| operations_view(lang, operations)))
+            .push(refresh_all_button(lang, refreshing))
+            .spacing(8)
+            .padding(8)
+            .into()
     }
 }
 
 impl Tab {
     const ALL: [Tab; 4] = [Tab::Finding, Tab::Updates, Tab::Installed, Tab::Settings];
 
-    fn label(self) -> &'static str {
+    /// Stable name saved as `UiPreferences::last_tab`.
+    pub fn key(self) -> &'static str {
         match self {
-            Tab::Finding => "Finding",
-            Tab::Updates => "Updates",
-            Tab::Installed => "Installed",
-            Tab::Settings => "Settings",
+            Tab::Finding => "finding",
+            Tab::Updates => "updates",
+            Tab::Installed => "installed",
+            Tab::Settings => "settings",
         }
     }
 
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tab| tab.key() == key)
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let key = match self {
+            Tab::Finding => Key::TabFinding,
+            Tab::Updates => Key::TabUpdates,
+            Tab::Installed => Key::TabInstalled,
+            Tab::Settings => Key::TabSettings,
+        };
+        tr(lang, key)
+    }
+
     fn icon(self) -> svg::Handle {
         match self {
             Tab::Finding => FIND_ICON.clone(),
@@ -104,18 +144,129 @@ impl Tab {
     }
 }
 
-fn sidebar_button(tab: Tab, active: Tab, icon: svg::Handle) -> iced::Element<'static, Message> {
+/// Footer listing running operations; clicking one jumps to its tab.
+fn operations_view(
+    lang: Lang,
+    operations: &[BackgroundOperation],
+) -> iced::Element<'static, Message> {
+    let items = column(operations.iter().map(|operation| {
+        button(
+            Text::new(operation.label.clone())
+                .size(13)
+                .color(app::colors::palette().on_surface),
+        )
+        .on_press(Message::Select(operation.tab))
+        .padding([4, 8])
+        .width(Length::Fill)
+        .style(|_theme, status| button::Style {
+            background: matches!(status, button::Status::Hovered)
+                .then(|| app::colors::palette().surface_hover.into()),
+            text_color: app::colors::palette().on_surface,
+            border: iced::Border {
+                radius: Radius::new(6.0),
+                ..Default::default()
+            },
+            shadow: iced::Shadow::default(),
+            snap: false,
+        })
+        .into()
+    }))
+    .spacing(2);
+
+    container(
+        column![
+            Text::new(tr(lang, Key::InProgress))
+                .size(12)
+                .color(app::colors::palette().on_surface_muted),
+            items
+        ]
+        .spacing(4),
+    )
+    .padding(8)
+    .width(Length::Fill)
+    .style(|_theme: &iced::Theme| container::Style {
+        background: Some(app::colors::palette().accent_muted.into()),
+        border: iced::Border {
+            radius: Radius::new(10.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+/// Footer button reloading every manager; shows a spinner while it runs.
+fn refresh_all_button(lang: Lang, refreshing: bool) -> iced::Element<'static, Message> {
+    let label = if refreshing {
+        SharedUi::spinner_text(tr(lang, Key::Refreshing))
+    } else {
+        tr(lang, Key::RefreshEverything).to_string()
+    };
+
+    let content = row![
+        Svg::new(REFRESH_ICON.clone()).width(14).height(14),
+        Text::new(label).size(14)
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    button(content)
+        .on_press_maybe((!refreshing).then_some(Message::RefreshAll))
+        .padding([10, 16])
+        .width(Length::Fill)
+        .style(|_theme, status| button::Style {
+            background: matches!(status, button::Status::Hovered)
+                .then(|| app::colors::palette().surface_hover.into()),
+            text_color: match status {
+                button::Status::Disabled => app::colors::palette().on_surface_muted,
+                _ => app::colors::palette().on_surface_idle,
+            },
+            border: iced::Border {
+                color: app::colors::palette().divider,
+                width: 1.0,
+                radius: Radius::new(10.0),
+            },
+            shadow: iced::Shadow::default(),
+            snap: false,
+        })
+        .into()
+}
+
+fn sidebar_button(
+    tab: Tab,
+    active: Tab,
+    label: &'static str,
+    icon: svg::Handle,
+    busy: bool,
+) -> iced::Element<'static, Message> {
     let is_active = tab == active;
 
-    let text = Text::new(tab.label()).size(16);
+    let text = Text::new(label).size(16);
 
     let icon = Svg::new(icon).width(16).height(16);
 
-    let content = Container::new(row![icon, text].spacing(12).align_y(Alignment::Center))
-        .padding([14, 16])
-        .width(Length::Fill)
-        .align_y(Alignment::Center)
-        .align_x(Alignment::Start);
+    // 后台操作进行中的标记
+    let busy_marker = busy.then(|| {
+        container(Space::new().width(8).height(8)).style(|_theme: &iced::Theme| container::Style {
+            background: Some(app::colors::palette().secondary.into()),
+            border: iced::Border {
+                radius: Radius::new(4.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    });
+
+    let content = Container::new(
+        row![icon, text, Space::new().width(Length::Fill)]
+            .push(busy_marker)
+            .spacing(12)
+            .align_y(Alignment::Center),
+    )
+    .padding([14, 16])
+    .width(Length::Fill)
+    .align_y(Alignment::Center)
+    .align_x(Alignment::Start);
 
     button(content)
         .on_press(Message::Select(tab))
@@ -125,8 +276,8 @@ fn sidebar_button(tab: Tab, active: Tab, icon: svg::Handle) -> iced::Element<'st
 
             let (background, text_color, shadow) = match (is_active, status) {
                 (true, button::Status::Hovered) => (
-                    app::colors::PRIMARY_HOVER.into(),
-                    app::colors::ON_PRIMARY,
+                    app::colors::palette().primary_hover.into(),
+                    app::colors::palette().on_primary,
                     Shadow {
                         color: iced::Color::from_rgba(0.3, 0.4, 0.9, 0.25),
                         offset: Vector::new(0.0, 1.0),
@@ -134,8 +285,8 @@ fn sidebar_button(tab: Tab, active: Tab, icon: svg::Handle) -> iced::Element<'st
                     },
                 ),
                 (true, _) => (
-                    app::colors::PRIMARY.into(),
-                    app::colors::ON_PRIMARY,
+                    app::colors::palette().primary.into(),
+                    app::colors::palette().on_primary,
                     Shadow {
                         color: iced::Color::from_rgba(0.4, 0.5, 0.95, 0.3),
                         offset: Vector::new(0.0, 2.0),
@@ -143,8 +294,8 @@ fn sidebar_button(tab: Tab, active: Tab, icon: svg::Handle) -> iced::Element<'st
                     },
                 ),
                 (_, button::Status::Pressed) => (
-                    app::colors::SURFACE_PRESSED.into(),
-                    app::colors::ON_SURFACE,
+                    app::colors::palette().surface_pressed.into(),
+                    app::colors::palette().on_surface,
                     Shadow {
                         color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.05),
                         offset: Vector::new(0.0, 1.0),
@@ -152,8 +303,8 @@ fn sidebar_button(tab: Tab, active: Tab, icon: svg::Handle) -> iced::Element<'st
                     },
                 ),
                 (_, button::Status::Hovered) => (
-                    app::colors::SURFACE_HOVER.into(),
-                    app::colors::ON_SURFACE,
+                    app::colors::palette().surface_hover.into(),
+                    app::colors::palette().on_surface,
                     Shadow {
                         color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.08),
                         offset: Vector::new(0.0, 2.0),
@@ -161,8 +312,8 @@ fn sidebar_button(tab: Tab, active: Tab, icon: svg::Handle) -> iced::Element<'st
                     },
                 ),
                 _ => (
-                    app::colors::SURFACE.into(),
-                    app::colors::ON_SURFACE_IDLE,
+                    app::colors::palette().surface.into(),
+                    app::colors::palette().on_surface_idle,
                     Shadow::default(),
                 ),
             };
@@ -6,6 +6,7 @@
 use std::time::{Duration, Instant};
 
 use iced::{Animation, Border, Length, Subscription};
+use updater_core::i18n::{Key, Lang, tr};
 
 use crate::{
     app::colors,
@@ -124,8 +125,8 @@ impl StatusPanel {
     }
 
     /// Renders the status panel view.
-    pub fn view<'a>(&'a self) -> iced::Element<'a, Message> {
-        render(self)
+    pub fn view<'a>(&'a self, lang: Lang) -> iced::Element<'a, Message> {
+        render(self, lang)
     }
 
     fn sync_progress_animation(
@@ -158,6 +159,11 @@ fn has_active_work(
         || updates_info.is_loading_count
         || !installed_info.loading_installed.is_empty()
         || !updates_info.loading_updates.is_empty()
+        || !updates_info.loading_details.is_empty()
+        || installed_info
+            .selected_package
+            .as_ref()
+            .is_some_and(|selected| selected.fetched.is_none())
         || !finding_info.searching_managers.is_empty()
         || finding_info.is_installing
         || updates_info.is_updating
@@ -359,7 +365,10 @@ fn operation_status_label(
     fallback.to_string()
 }
 
-fn render<'a, Message: 'a + 'static>(panel: &'a StatusPanel) -> iced::Element<'a, Message> {
+fn render<'a, Message: 'a + 'static>(
+    panel: &'a StatusPanel,
+    lang: Lang,
+) -> iced::Element<'a, Message> {
     use iced::widget::{column, container, row, scrollable, text};
 
     let progress_widget = determinate_capsule_bar(panel.progress);
@@ -371,8 +380,12 @@ fn render<'a, Message: 'a + 'static>(panel: &'a StatusPanel) -> iced::Element<'a
 
     let mut panel_content = column![
         row![
-            text(&panel.status_label).size(14).color(colors::ON_SURFACE),
-            text(status_right).size(13).color(colors::ON_SURFACE_MUTED)
+            text(&panel.status_label)
+                .size(14)
+                .color(colors::palette().on_surface),
+            text(status_right)
+                .size(13)
+                .color(colors::palette().on_surface_muted)
         ]
         .align_y(iced::Alignment::Center)
         .spacing(12),
@@ -385,7 +398,7 @@ fn render<'a, Message: 'a + 'static>(panel: &'a StatusPanel) -> iced::Element<'a
         let lines = panel.command_logs.iter().map(|line| {
             text(line)
                 .size(12)
-                .color(colors::ON_SURFACE_ALT)
+                .color(colors::palette().on_surface_alt)
                 .width(Length::Fill)
                 .into()
         });
@@ -396,9 +409,9 @@ fn render<'a, Message: 'a + 'static>(panel: &'a StatusPanel) -> iced::Element<'a
 
         panel_content = panel_content
             .push(
-                text("Command Output")
+                text(tr(lang, Key::CommandOutput))
                     .size(12)
-                    .color(colors::ON_SURFACE_MUTED),
+                    .color(colors::palette().on_surface_muted),
             )
             .push(log_list);
     }
@@ -414,9 +427,9 @@ fn render<'a, Message: 'a + 'static>(panel: &'a StatusPanel) -> iced::Element<'a
         .height(Length::Fixed(panel_height))
         .width(Length::Fill)
         .style(|_theme: &iced::Theme| container::Style {
-            background: Some(colors::SURFACE_ALT.into()),
+            background: Some(colors::palette().surface_alt.into()),
             border: Border {
-                color: colors::DIVIDER,
+                color: colors::palette().divider,
                 width: 1.0,
                 radius: 8.0.into(),
             },
@@ -443,12 +456,12 @@ fn determinate_capsule_bar<Message: 'static>(progress: f32) -> iced::Element<'st
         })
         .height(Length::Fixed(8.0))
         .style(|_theme: &iced::Theme| container::Style {
-            background: Some(colors::SECONDARY_SOFT.into()),
+            background: Some(colors::palette().secondary_soft.into()),
             border: Border {
                 color: iced::Color::from_rgba(
-                    colors::SECONDARY_SOFT.r,
-                    colors::SECONDARY_SOFT.g,
-                    colors::SECONDARY_SOFT.b,
+                    colors::palette().secondary_soft.r,
+                    colors::palette().secondary_soft.g,
+                    colors::palette().secondary_soft.b,
                     0.6,
                 ),
                 width: 1.0,
@@ -457,9 +470,9 @@ fn determinate_capsule_bar<Message: 'static>(progress: f32) -> iced::Element<'st
             text_color: None,
             shadow: iced::Shadow {
                 color: iced::Color::from_rgba(
-                    colors::SECONDARY_SOFT.r,
-                    colors::SECONDARY_SOFT.g,
-                    colors::SECONDARY_SOFT.b,
+                    colors::palette().secondary_soft.r,
+                    colors::palette().secondary_soft.g,
+                    colors::palette().secondary_soft.b,
                     0.28,
                 ),
                 offset: iced::Vector::new(0.0, 0.0),
@@ -482,9 +495,9 @@ fn determinate_capsule_bar<Message: 'static>(progress: f32) -> iced::Element<'st
         .padding([4, 6])
         .width(Length::Fill)
         .style(|_theme: &iced::Theme| container::Style {
-            background: Some(colors::SURFACE_HOVER.into()),
+            background: Some(colors::palette().surface_hover.into()),
             border: Border {
-                color: colors::DIVIDER_LIGHT,
+                color: colors::palette().divider_light,
                 width: 1.0,
                 radius: 999.0.into(),
             },
@@ -0,0 +1,24 @@
+use std::{future::Future, sync::LazyLock};
+
+use tokio::runtime::{Builder, Runtime};
+
+/// Threads running package operations; subprocess orchestration is mostly
+/// waiting, so a couple are plenty.
+const WORKER_THREADS: usize = 2;
+
+/// Runtime for install/update/remove runs, kept apart from the GUI executor
+/// so a burst of subprocess work cannot delay rendering.
+static WORKER: LazyLock<Runtime> = LazyLock::new(|| {
+    Builder::new_multi_thread()
+        .worker_threads(WORKER_THREADS)
+        .thread_name("package-worker")
+        .enable_all()
+        .build()
+        .expect("failed to start the package worker runtime")
+});
+
+/// Run `future` on the worker runtime. Results go back to the UI through
+/// whatever channel the future writes to.
+pub fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    WORKER.spawn(future);
+}